@@ -0,0 +1,79 @@
+// accessibility.rs - Expose the painted canvas to screen readers through egui's AccessKit tree
+use eframe::egui::{self, accesskit};
+
+/// One element's text as painted on screen
+pub struct TextRun {
+    pub rect: egui::Rect,
+    pub text: String,
+    pub rope_start: usize,
+    pub rope_len: usize,       // Chars of the element in the rope, which a redaction placeholder replaces
+    pub document_start: usize, // Where `text` starts in the published document
+}
+
+/// (run index, char index in run) for a rope position; gaps between runs snap to the previous run's end
+fn locate(runs: &[TextRun], rope_pos: usize) -> Option<(usize, usize)> {
+    let idx = runs.iter().rposition(|run| run.rope_start <= rope_pos).unwrap_or(0);
+    let run = runs.get(idx)?;
+    let len = run.text.chars().count();
+    Some((idx, rope_pos.saturating_sub(run.rope_start).min(len)))
+}
+
+/// Rope position as a char offset into the published document, whose redacted runs have another length
+fn document_position(runs: &[TextRun], rope_pos: usize) -> usize {
+    let Some(run) = runs.iter().rfind(|run| run.rope_start <= rope_pos) else { return rope_pos };
+    let offset = rope_pos - run.rope_start;
+    let len = run.text.chars().count();
+    if offset < run.rope_len {
+        run.document_start + offset.min(len)
+    } else {
+        run.document_start + len + (offset - run.rope_len)
+    }
+}
+
+/// Publish the document as a multi-line text field with one inline text box per run,
+/// so VoiceOver/NVDA can read the text, follow the cursor and announce the selection
+pub fn publish(ui: &egui::Ui, response: &egui::Response, document: &str, runs: &[TextRun],
+               cursor: usize, selection: Option<(usize, usize)>) {
+    let (anchor, focus) = match selection {
+        Some((start, end)) if start < end => (start, end),
+        _ => (cursor, cursor),
+    };
+    let selected = document_position(runs, anchor)..=document_position(runs, focus);
+    response.widget_info(|| egui::WidgetInfo::text_selection_changed(true, selected, document));
+
+    let ctx = ui.ctx();
+    let parent = response.id;
+    ctx.with_accessibility_parent(parent, || {
+        for (i, run) in runs.iter().enumerate() {
+            ctx.accesskit_node_builder(parent.with(("text_run", i)), |builder| {
+                builder.set_role(accesskit::Role::InlineTextBox);
+                builder.set_bounds(accesskit::Rect {
+                    x0: run.rect.min.x as f64,
+                    y0: run.rect.min.y as f64,
+                    x1: run.rect.max.x as f64,
+                    y1: run.rect.max.y as f64,
+                });
+                builder.set_text_direction(accesskit::TextDirection::LeftToRight);
+                let character_lengths: Vec<u8> = run.text.chars().map(|c| c.len_utf8() as u8).collect();
+                let word_lengths: Vec<u8> = run.text.split_inclusive(char::is_whitespace)
+                    .map(|word| word.chars().count().min(u8::MAX as usize) as u8)
+                    .collect();
+                builder.set_value(run.text.clone());
+                builder.set_character_lengths(character_lengths);
+                builder.set_word_lengths(word_lengths);
+            });
+        }
+    });
+
+    ctx.accesskit_node_builder(parent, |builder| {
+        builder.set_role(accesskit::Role::MultilineTextInput);
+        builder.set_value(document.to_string());
+        let position = |rope_pos: usize| locate(runs, rope_pos).map(|(idx, character_index)| accesskit::TextPosition {
+            node: parent.with(("text_run", idx)).accesskit_id(),
+            character_index,
+        });
+        if let (Some(anchor), Some(focus)) = (position(anchor), position(focus)) {
+            builder.set_text_selection(accesskit::TextSelection { anchor, focus });
+        }
+    });
+}
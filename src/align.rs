@@ -0,0 +1,49 @@
+// align.rs - Snap a group of elements onto one shared column edge
+use eframe::egui;
+
+/// Which edge of each element lines up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAlign {
+    Left,
+    Right,
+    Decimal,  // The decimal point, or just after the last digit of whole numbers
+}
+
+impl ColumnAlign {
+    pub const ALL: [ColumnAlign; 3] = [ColumnAlign::Left, ColumnAlign::Right, ColumnAlign::Decimal];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColumnAlign::Left => "⇤ Align left",
+            ColumnAlign::Right => "⇥ Align right",
+            ColumnAlign::Decimal => "⋅ Align decimal points",
+        }
+    }
+}
+
+/// Char index the decimal anchor sits before: the last `.`/`,` followed by 1-2 digits
+/// (cents, not a thousands group), else just after the last digit
+pub fn decimal_index(text: &str) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let last_digit = match chars.iter().rposition(|c| c.is_ascii_digit()) {
+        Some(i) => i,
+        None => return chars.len(),
+    };
+    let separator = chars[..=last_digit].iter().rposition(|&c| c == '.' || c == ',');
+    match separator {
+        Some(at) if (1..=2).contains(&(last_digit - at)) && chars[at + 1..=last_digit].iter().all(|c| c.is_ascii_digit()) => at,
+        _ => last_digit + 1,
+    }
+}
+
+/// The shared x: the median anchor, so one badly placed OCR box doesn't drag the column
+pub fn column_x(anchors: &[f32]) -> Option<f32> {
+    let mut sorted = anchors.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    sorted.get(sorted.len() / 2).copied()
+}
+
+/// `bounds` shifted horizontally so `anchor` lands on `target`
+pub fn shifted(bounds: egui::Rect, anchor: f32, target: f32) -> egui::Rect {
+    bounds.translate(egui::vec2(target - anchor, 0.0))
+}
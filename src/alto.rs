@@ -0,0 +1,88 @@
+// alto.rs - Write current (edited) elements back out as ALTO XML
+use crate::metadata::DocumentMetadata;
+use crate::tags::ColorTag;
+use crate::{layout, SpatialElement};
+use std::fmt::Write;
+
+fn escape_attr(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// OtherTag ID for a color tag, e.g. `TAG_RED`
+fn tag_id(tag: ColorTag) -> String {
+    format!("TAG_{}", tag.label().to_uppercase())
+}
+
+/// ALTO v3 with one TextBlock per page and one TextLine per detected line
+pub fn write(elements: &[SpatialElement]) -> String {
+    write_with_metadata(elements, None)
+}
+
+/// Same, with document metadata as metadata-typed OtherTags.
+/// Color tags become color-typed OtherTags that tagged Strings point to with TAGREFS
+pub fn write_with_metadata(elements: &[SpatialElement], metadata: Option<&DocumentMetadata>) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<alto xmlns=\"http://www.loc.gov/standards/alto/ns-v3#\">\n");
+    xml.push_str("  <Description>\n    <MeasurementUnit>pixel</MeasurementUnit>\n  </Description>\n");
+    let entries = metadata.map(|m| m.entries()).unwrap_or_default();
+    let color_tags: Vec<ColorTag> = ColorTag::ALL.into_iter()
+        .filter(|&tag| elements.iter().any(|e| e.tag == Some(tag)))
+        .collect();
+    if !entries.is_empty() || !color_tags.is_empty() {
+        xml.push_str("  <Tags>\n");
+        for (i, (label, value)) in entries.iter().enumerate() {
+            let _ = writeln!(xml, "    <OtherTag ID=\"META{}\" TYPE=\"metadata\" LABEL=\"{}\" DESCRIPTION=\"{}\"/>",
+                             i + 1, label, escape_attr(value));
+        }
+        for tag in &color_tags {
+            let _ = writeln!(xml, "    <OtherTag ID=\"{}\" TYPE=\"color\" LABEL=\"{}\"/>", tag_id(*tag), tag.label());
+        }
+        xml.push_str("  </Tags>\n");
+    }
+    xml.push_str("  <Layout>\n");
+
+    let mut pages: Vec<usize> = elements.iter().map(|e| e.page).collect();
+    pages.sort();
+    pages.dedup();
+    for page in pages {
+        let page_elements: Vec<&SpatialElement> = elements.iter().filter(|e| e.page == page).collect();
+        let _ = writeln!(xml, "    <Page ID=\"PAGE{}\" PHYSICAL_IMG_NR=\"{}\">", page, page);
+        xml.push_str("      <PrintSpace>\n");
+        let _ = writeln!(xml, "        <TextBlock ID=\"p{}_b1\">", page);
+        for (line_idx, line) in layout::group_into_lines(&page_elements).iter().enumerate() {
+            let _ = writeln!(xml, "          <TextLine ID=\"p{}_l{}\">", page, line_idx + 1);
+            for (i, element) in line.iter().enumerate() {
+                if i > 0 {
+                    xml.push_str("            <SP/>\n");
+                }
+                let _ = write!(
+                    xml,
+                    "            <String CONTENT=\"{}\" HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+                    escape_attr(&element.content), element.hpos, element.vpos, element.width, element.height
+                );
+                if let Some(style_refs) = &element.style_refs {
+                    let _ = write!(xml, " STYLEREFS=\"{}\"", escape_attr(style_refs));
+                }
+                if let Some(confidence) = element.confidence {
+                    let _ = write!(xml, " WC=\"{}\"", confidence);
+                }
+                if let Some(tag) = element.tag {
+                    let _ = write!(xml, " TAGREFS=\"{}\"", tag_id(tag));
+                }
+                xml.push_str("/>\n");
+            }
+            xml.push_str("          </TextLine>\n");
+        }
+        xml.push_str("        </TextBlock>\n");
+        xml.push_str("      </PrintSpace>\n");
+        xml.push_str("    </Page>\n");
+    }
+
+    xml.push_str("  </Layout>\n");
+    xml.push_str("</alto>\n");
+    xml
+}
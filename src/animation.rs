@@ -0,0 +1,25 @@
+// animation.rs - Eased viewport transitions for jumps
+use eframe::egui;
+
+const DURATION: f64 = 0.35; // Seconds per jump
+
+/// Scroll offset easing from where the viewport was to where a jump lands
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollAnimation {
+    from: egui::Vec2,
+    to: egui::Vec2,
+    started: f64,
+}
+
+impl ScrollAnimation {
+    pub fn new(from: egui::Vec2, to: egui::Vec2, now: f64) -> Self {
+        Self { from, to, started: now }
+    }
+    
+    /// Offset at time `now` (ease-out cubic) and whether the jump has landed
+    pub fn offset_at(&self, now: f64) -> (egui::Vec2, bool) {
+        let t = ((now - self.started) / DURATION).clamp(0.0, 1.0) as f32;
+        let eased = 1.0 - (1.0 - t).powi(3);
+        (self.from + (self.to - self.from) * eased, t >= 1.0)
+    }
+}
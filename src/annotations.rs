@@ -0,0 +1,57 @@
+// annotations.rs - Free-text comments on elements or regions, saved next to the PDF
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A comment anchored to a document-space rectangle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub page: usize,
+    pub rect: [f32; 4], // x0, y0, x1, y1 in PDF points
+    pub text: String,
+    pub created_at: u64, // Unix seconds
+}
+
+impl Annotation {
+    pub fn new(page: usize, rect: egui::Rect, text: String) -> Self {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            page,
+            rect: [rect.min.x, rect.min.y, rect.max.x, rect.max.y],
+            text,
+            created_at,
+        }
+    }
+    
+    pub fn rect(&self) -> egui::Rect {
+        egui::Rect::from_min_max(egui::pos2(self.rect[0], self.rect[1]), egui::pos2(self.rect[2], self.rect[3]))
+    }
+}
+
+fn sidecar_path(pdf_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.chonker-annotations", pdf_path))
+}
+
+/// Comments saved for a PDF, in the order they were added
+pub fn load(pdf_path: &str) -> Vec<Annotation> {
+    std::fs::read_to_string(sidecar_path(pdf_path))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Save comments; with none left the sidecar is removed
+pub fn save(pdf_path: &str, annotations: &[Annotation]) -> Result<(), Box<dyn std::error::Error>> {
+    let path = sidecar_path(pdf_path);
+    if annotations.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    std::fs::write(path, serde_json::to_string_pretty(annotations)?)?;
+    Ok(())
+}
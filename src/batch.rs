@@ -0,0 +1,310 @@
+// batch.rs - Headless conversion of many PDFs with predictable output names
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const USAGE: &str = "usage: chonker9 batch <pdf, folder or 'glob/**/*.pdf'>... [--out DIR] [--format txt,alto] [--jobs N]
+                      [--template NAME] [--per-page | --join-pages] [--zones TEMPLATE]
+  --format: comma-separated output formats (default txt); the template's extension follows the format
+  --jobs: worker threads (default: one per CPU)
+  --join-pages: strip headers/footers and continue paragraphs across page breaks
+  --zones: also write {stem}_zones.json with the fields of a saved zone template
+  NAME placeholders: {stem} (file name without extension), {page} (page number, or \"all\")
+  default NAME: {stem}.txt, or {stem}_p{page}.txt with --per-page
+  outputs of PDFs found through a folder glob keep their subfolder under --out (in/**/*.pdf: in/a/x.pdf -> OUT/a/x.txt)";
+
+/// Output written for each document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Txt,   // Readable text through the pipeline
+    Alto,  // ALTO XML of the extracted elements
+}
+
+impl Format {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "txt" | "text" => Some(Format::Txt),
+            "alto" | "xml" => Some(Format::Alto),
+            _ => None,
+        }
+    }
+    
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Txt => "txt",
+            Format::Alto => "xml",
+        }
+    }
+}
+
+/// What to convert and how to name the results
+#[derive(Debug)]
+pub struct BatchOptions {
+    pub inputs: Vec<PathBuf>,
+    pub out_dir: PathBuf,
+    pub formats: Vec<Format>,
+    pub jobs: Option<usize>, // Worker threads; None = rayon's default
+    pub template: String,
+    pub per_page: bool, // One file per page instead of one per document
+    pub join_pages: bool, // Paragraphs continue across page breaks (combined output only)
+    pub zones: Option<String>, // Zone template to extract alongside the text
+}
+
+impl BatchOptions {
+    /// Parse `batch ...` (or `--batch ...`); None when batch mode was not requested
+    pub fn from_args(args: &[String]) -> Option<Result<Self, String>> {
+        let start = match args.get(1) {
+            Some(command) if command == "batch" => 1,
+            _ => args.iter().position(|a| a == "--batch")?,
+        };
+        let mut options = BatchOptions {
+            inputs: Vec::new(),
+            out_dir: PathBuf::from("."),
+            formats: vec![Format::Txt],
+            jobs: None,
+            template: String::new(),
+            per_page: false,
+            join_pages: false,
+            zones: None,
+        };
+        let mut rest = args[start + 1..].iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--out" | "--out-dir" => match rest.next() {
+                    Some(dir) => options.out_dir = PathBuf::from(dir),
+                    None => return Some(Err("--out-dir needs a directory".to_string())),
+                },
+                "--format" => match rest.next() {
+                    Some(list) => {
+                        let formats: Option<Vec<Format>> = list.split(',').map(Format::parse).collect();
+                        match formats {
+                            Some(formats) if !formats.is_empty() => options.formats = formats,
+                            _ => return Some(Err(format!("unknown format in {} (expected txt, alto)", list))),
+                        }
+                    }
+                    None => return Some(Err("--format needs a list like txt,alto".to_string())),
+                },
+                "--jobs" => match rest.next().and_then(|n| n.parse().ok()).filter(|&n: &usize| n > 0) {
+                    Some(jobs) => options.jobs = Some(jobs),
+                    None => return Some(Err("--jobs needs a positive number".to_string())),
+                },
+                "--template" => match rest.next() {
+                    Some(template) => options.template = template.clone(),
+                    None => return Some(Err("--template needs a name".to_string())),
+                },
+                "--zones" => match rest.next() {
+                    Some(name) => options.zones = Some(name.clone()),
+                    None => return Some(Err("--zones needs a template name".to_string())),
+                },
+                "--per-page" => options.per_page = true,
+                "--join-pages" => options.join_pages = true,
+                flag if flag.starts_with("--") => return Some(Err(format!("unknown option {}", flag))),
+                input => options.inputs.push(PathBuf::from(input)),
+            }
+        }
+        if options.inputs.is_empty() {
+            return Some(Err("no input PDFs given".to_string()));
+        }
+        if options.template.is_empty() {
+            options.template = if options.per_page { "{stem}_p{page}.txt" } else { "{stem}.txt" }.to_string();
+        }
+        if options.per_page && options.join_pages {
+            return Some(Err("--join-pages joins pages into one file and can't be combined with --per-page".to_string()));
+        }
+        if options.per_page && !options.template.contains("{page}") {
+            return Some(Err("--per-page needs {page} in the template, or pages would overwrite each other".to_string()));
+        }
+        Some(Ok(options))
+    }
+    
+    /// Folder a document's outputs go to: its subfolder mirrored under the output directory
+    pub fn output_dir(&self, input: &BatchInput) -> PathBuf {
+        self.out_dir.join(&input.relative_dir)
+    }
+    
+    /// Output path for one document (page None = combined) in one format. The template's own
+    /// extension gives way to the format's, which is appended so dotted stems (report.v2) survive
+    pub fn output_path(&self, input: &BatchInput, page: Option<usize>, format: Format) -> PathBuf {
+        let stem = input.pdf.file_stem().map_or("document".into(), |s| s.to_string_lossy());
+        let page = page.map_or("all".to_string(), |p| p.to_string());
+        let template = Path::new(&self.template).with_extension("");
+        let name = template.to_string_lossy().replace("{stem}", &stem).replace("{page}", &page);
+        self.output_dir(input).join(format!("{}.{}", name, format.extension()))
+    }
+}
+
+/// One PDF to convert and where under the output directory its results go
+#[derive(Debug)]
+pub struct BatchInput {
+    pub pdf: PathBuf,
+    pub relative_dir: PathBuf, // Subfolder below the glob's fixed prefix; empty otherwise
+}
+
+fn is_glob(input: &Path) -> bool {
+    input.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Leading folders of a pattern before the first wildcard ('in/**/*.pdf' -> 'in')
+fn glob_root(pattern: &Path) -> PathBuf {
+    pattern.components()
+        .take_while(|component| !is_glob(Path::new(component.as_os_str())))
+        .collect()
+}
+
+/// Input PDFs: files as given, folders expanded to their *.pdf files in name order,
+/// globs (quoted, e.g. 'in/**/*.pdf') expanded to the PDFs they match
+pub fn collect_pdfs(inputs: &[PathBuf]) -> Vec<BatchInput> {
+    let flat = |pdf: PathBuf| BatchInput { pdf, relative_dir: PathBuf::new() };
+    let mut pdfs = Vec::new();
+    for input in inputs {
+        if is_glob(input) {
+            match glob::glob(&input.to_string_lossy()) {
+                Ok(paths) => {
+                    let mut found: Vec<PathBuf> = paths.filter_map(|path| path.ok())
+                        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf")))
+                        .collect();
+                    found.sort();
+                    let root = glob_root(input);
+                    pdfs.extend(found.into_iter().map(|pdf| {
+                        let relative_dir = pdf.parent()
+                            .and_then(|parent| parent.strip_prefix(&root).ok())
+                            .map_or_else(PathBuf::new, Path::to_path_buf);
+                        BatchInput { pdf, relative_dir }
+                    }));
+                }
+                Err(e) => eprintln!("❌ Bad pattern {}: {}", input.display(), e),
+            }
+        } else if input.is_dir() {
+            let mut found: Vec<PathBuf> = std::fs::read_dir(input)
+                .map(|entries| {
+                    entries.filter_map(|entry| entry.ok().map(|e| e.path()))
+                        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf")))
+                        .collect()
+                })
+                .unwrap_or_default();
+            found.sort();
+            pdfs.extend(found.into_iter().map(flat));
+        } else {
+            pdfs.push(flat(input.clone()));
+        }
+    }
+    pdfs
+}
+
+/// Output files claimed by more than one input (e.g. the same name given from two folders),
+/// which parallel workers would otherwise overwrite silently
+pub fn output_clashes(options: &BatchOptions, inputs: &[BatchInput]) -> Vec<PathBuf> {
+    let mut seen = std::collections::HashSet::new();
+    let mut clashes = Vec::new();
+    for input in inputs {
+        let path = options.output_path(input, None, options.formats[0]);
+        if !seen.insert(path.clone()) && !clashes.contains(&path) {
+            clashes.push(path);
+        }
+    }
+    clashes
+}
+
+/// How one PDF went
+pub struct Outcome {
+    pub pdf: PathBuf,
+    pub result: Result<usize, String>,  // Files written, or why it failed
+    pub elapsed: Duration,
+}
+
+/// Table of every document's result plus totals
+pub fn summary_table(outcomes: &[Outcome], wall: Duration) -> String {
+    let width = outcomes.iter().map(|o| o.pdf.display().to_string().chars().count()).max().unwrap_or(0).max(8);
+    let mut table = format!("{:<width$}  {:<6}  {:>5}  {:>8}\n", "Document", "Status", "Files", "Time", width = width);
+    for outcome in outcomes {
+        let (status, files, detail) = match &outcome.result {
+            Ok(files) => ("ok", files.to_string(), String::new()),
+            Err(e) => ("FAILED", "-".to_string(), format!("  {}", e)),
+        };
+        table.push_str(&format!("{:<width$}  {:<6}  {:>5}  {:>7.1}s{}\n",
+                                outcome.pdf.display(), status, files, outcome.elapsed.as_secs_f32(), detail, width = width));
+    }
+    let failed = outcomes.iter().filter(|o| o.result.is_err()).count();
+    let files: usize = outcomes.iter().filter_map(|o| o.result.as_ref().ok()).sum();
+    let busy: f32 = outcomes.iter().map(|o| o.elapsed.as_secs_f32()).sum();
+    table.push_str(&format!("{} succeeded, {} failed, {} file(s) written in {:.1}s ({:.1}s of work)\n",
+                            outcomes.len() - failed, failed, files, wall.as_secs_f32(), busy));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<BatchOptions, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        BatchOptions::from_args(&args).expect("batch mode requested")
+    }
+
+    #[test]
+    fn not_batch_mode_without_the_command() {
+        let args: Vec<String> = ["chonker9", "file.pdf"].iter().map(|a| a.to_string()).collect();
+        assert!(BatchOptions::from_args(&args).is_none());
+    }
+
+    #[test]
+    fn formats_parse_as_a_comma_separated_list() {
+        let options = parse(&["chonker9", "batch", "a.pdf", "--format", "txt, ALTO"]).unwrap();
+        assert_eq!(options.formats, vec![Format::Txt, Format::Alto]);
+        assert_eq!(parse(&["chonker9", "batch", "a.pdf"]).unwrap().formats, vec![Format::Txt]);
+        assert!(parse(&["chonker9", "batch", "a.pdf", "--format", "txt,pdf"]).is_err());
+        assert!(parse(&["chonker9", "batch", "a.pdf", "--format"]).is_err());
+    }
+
+    #[test]
+    fn jobs_must_be_a_positive_number() {
+        assert_eq!(parse(&["chonker9", "--batch", "a.pdf", "--jobs", "4"]).unwrap().jobs, Some(4));
+        assert_eq!(parse(&["chonker9", "batch", "a.pdf"]).unwrap().jobs, None);
+        assert!(parse(&["chonker9", "batch", "a.pdf", "--jobs", "0"]).is_err());
+        assert!(parse(&["chonker9", "batch", "a.pdf", "--jobs", "many"]).is_err());
+    }
+
+    #[test]
+    fn per_page_needs_a_page_placeholder() {
+        let options = parse(&["chonker9", "batch", "a.pdf", "--per-page"]).unwrap();
+        assert_eq!(options.template, "{stem}_p{page}.txt");
+        assert!(parse(&["chonker9", "batch", "a.pdf", "--per-page", "--template", "{stem}.txt"]).is_err());
+        assert!(parse(&["chonker9", "batch", "a.pdf", "--per-page", "--join-pages"]).is_err());
+    }
+
+    #[test]
+    fn glob_expands_to_pdfs_keeping_subfolders() {
+        let root = crate::temp_path("chonker9_batch_test");
+        for file in ["a/x.pdf", "a/notes.txt", "b/c/y.PDF", "z.pdf"] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        let pattern = root.join("**").join("*.*");
+        let found = collect_pdfs(&[pattern]);
+        let _ = std::fs::remove_dir_all(&root);
+
+        let found: Vec<(PathBuf, PathBuf)> = found.into_iter()
+            .map(|input| (input.pdf.strip_prefix(&root).unwrap().to_path_buf(), input.relative_dir))
+            .collect();
+        assert_eq!(found, vec![
+            (PathBuf::from("a/x.pdf"), PathBuf::from("a")),
+            (PathBuf::from("b/c/y.PDF"), PathBuf::from("b/c")),
+            (PathBuf::from("z.pdf"), PathBuf::new()),
+        ]);
+    }
+
+    #[test]
+    fn summary_table_lists_failures_and_totals() {
+        let outcomes = vec![
+            Outcome { pdf: PathBuf::from("good.pdf"), result: Ok(2), elapsed: Duration::from_millis(1500) },
+            Outcome { pdf: PathBuf::from("bad.pdf"), result: Err("pdfalto failed".to_string()), elapsed: Duration::from_millis(500) },
+        ];
+        let table = summary_table(&outcomes, Duration::from_secs(1));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Document"));
+        assert!(lines[1].starts_with("good.pdf") && lines[1].contains("ok") && lines[1].contains("1.5s"));
+        assert!(lines[2].starts_with("bad.pdf ") && lines[2].contains("FAILED") && lines[2].ends_with("pdfalto failed"));
+        assert_eq!(lines[3], "1 succeeded, 1 failed, 2 file(s) written in 1.0s (2.0s of work)");
+    }
+}
@@ -0,0 +1,121 @@
+// bates.rs - Bates number detection: the repeating prefix+number stamps of legal productions
+use crate::SpatialElement;
+use eframe::egui;
+use std::collections::HashMap;
+
+/// Fraction of a page's text extent, top and bottom, where stamps are looked for
+const BAND_FRACTION: f32 = 0.12;
+
+/// Stamps carry at least this many digits (usually zero-padded to 6-8)
+const MIN_DIGITS: usize = 4;
+
+/// One page's stamp
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatesStamp {
+    pub page: usize,
+    pub text: String,
+    pub prefix: String,
+    pub number: u64,
+    pub indices: Vec<usize>,  // Elements making up the stamp
+    pub bounds: egui::Rect,
+}
+
+/// ("ABC", 1234) from "ABC0001234", "ABC-0001234" or "ABC_0001234"
+fn split_stamp(text: &str) -> Option<(String, u64)> {
+    let digits_at = text.len() - text.chars().rev().take_while(|c| c.is_ascii_digit()).map(char::len_utf8).sum::<usize>();
+    let (prefix, digits) = text.split_at(digits_at);
+    let prefix = prefix.trim_end_matches(['-', '_', ' ']);
+    let valid_prefix = !prefix.is_empty() && prefix.len() <= 12
+        && prefix.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || "-_ ".contains(c))
+        && prefix.chars().next().map_or(false, |c| c.is_ascii_uppercase());
+    if !valid_prefix || digits.len() < MIN_DIGITS {
+        return None;
+    }
+    Some((prefix.to_string(), digits.parse().ok()?))
+}
+
+fn bounds_of(elements: &[SpatialElement], indices: &[usize]) -> egui::Rect {
+    indices.iter()
+        .map(|&i| {
+            let e = &elements[i];
+            egui::Rect::from_min_size(egui::pos2(e.hpos, e.vpos), egui::vec2(e.width, e.height))
+        })
+        .reduce(|a, b| a.union(b))
+        .unwrap_or(egui::Rect::NOTHING)
+}
+
+/// Stamp candidates in a page's top and bottom bands: single tokens, or a prefix token
+/// followed on the same line by a number ("ABC" "0001234")
+fn candidates(elements: &[SpatialElement], page: usize) -> Vec<BatesStamp> {
+    let indices: Vec<usize> = (0..elements.len()).filter(|&i| elements[i].page == page).collect();
+    let top = indices.iter().map(|&i| elements[i].vpos).fold(f32::MAX, f32::min);
+    let bottom = indices.iter().map(|&i| elements[i].vpos).fold(0.0, f32::max);
+    let band = (bottom - top) * BAND_FRACTION;
+    let in_band: Vec<usize> = indices.into_iter()
+        .filter(|&i| elements[i].vpos <= top + band || elements[i].vpos >= bottom - band)
+        .collect();
+
+    let mut found = Vec::new();
+    for (n, &i) in in_band.iter().enumerate() {
+        let text = elements[i].content.trim();
+        let mut stamp_indices = vec![i];
+        let mut stamp_text = text.to_string();
+        if split_stamp(text).is_none() {
+            // Prefix and number extracted as separate words
+            let next = in_band[n + 1..].iter().copied().find(|&j| {
+                (elements[j].vpos - elements[i].vpos).abs() < 4.0 && elements[j].hpos > elements[i].hpos
+            });
+            match next {
+                Some(j) => {
+                    stamp_text = format!("{} {}", text, elements[j].content.trim());
+                    stamp_indices.push(j);
+                }
+                None => continue,
+            }
+        }
+        if let Some((prefix, number)) = split_stamp(&stamp_text) {
+            let bounds = bounds_of(elements, &stamp_indices);
+            found.push(BatesStamp { page, text: stamp_text, prefix, number, indices: stamp_indices, bounds });
+        }
+    }
+    found
+}
+
+/// Pages a prefix must be stamped on before it counts; a lone "FORM 1040" is just text
+const MIN_PAGES: usize = 2;
+
+/// One stamp per page, for the prefix found on the most pages (at least two, and at least
+/// half of them), so nothing is detected from a single page
+pub fn detect(elements: &[SpatialElement]) -> Vec<BatesStamp> {
+    let mut pages: Vec<usize> = elements.iter().map(|e| e.page).collect();
+    pages.sort();
+    pages.dedup();
+    let per_page: Vec<Vec<BatesStamp>> = pages.iter().map(|&page| candidates(elements, page)).collect();
+
+    let mut prefix_pages: HashMap<&str, usize> = HashMap::new();
+    for stamps in &per_page {
+        let mut prefixes: Vec<&str> = stamps.iter().map(|s| s.prefix.as_str()).collect();
+        prefixes.sort();
+        prefixes.dedup();
+        for prefix in prefixes {
+            *prefix_pages.entry(prefix).or_default() += 1;
+        }
+    }
+    // Ties go to the lexicographically first prefix, so the pick doesn't depend on hash order
+    let prefix = match prefix_pages.iter().max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then_with(|| b.cmp(a))) {
+        Some((&prefix, &count)) if count >= MIN_PAGES && count * 2 >= pages.len() => prefix.to_string(),
+        _ => return Vec::new(),
+    };
+    per_page.into_iter()
+        .filter_map(|stamps| stamps.into_iter().find(|s| s.prefix == prefix))
+        .collect()
+}
+
+/// `page,bates` rows
+pub fn to_csv(stamps: &[BatesStamp]) -> String {
+    let mut csv = String::from("page,bates\n");
+    for stamp in stamps {
+        csv.push_str(&format!("{},\"{}\"\n", stamp.page, stamp.text.replace('"', "\"\"")));
+    }
+    csv
+}
@@ -0,0 +1,169 @@
+// bidi.rs - Visual order for right-to-left and mixed-direction text, from cosmic-text's BiDi layout
+use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Strong RTL letters: Hebrew, Arabic, Syriac, Thaana, NKo and their presentation forms
+pub fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32, 0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF)
+}
+
+pub fn has_rtl(text: &str) -> bool {
+    text.chars().any(is_rtl_char)
+}
+
+/// Paragraph direction from the first strong letter, as the Unicode BiDi algorithm picks it
+pub fn is_rtl_base(text: &str) -> bool {
+    text.chars().find(|c| c.is_alphabetic()).is_some_and(is_rtl_char)
+}
+
+/// Left-to-right order of a single line's chars, by logical index
+#[derive(Debug, Clone)]
+pub struct VisualOrder {
+    order: Vec<usize>,   // Visual slot -> logical char
+    slots: Vec<usize>,   // Logical char -> visual slot
+    rtl: Vec<bool>,      // Per logical char: laid out right to left
+}
+
+impl VisualOrder {
+    fn identity(len: usize) -> Self {
+        Self { order: (0..len).collect(), slots: (0..len).collect(), rtl: vec![false; len] }
+    }
+
+    fn from_order(order: Vec<usize>, rtl: Vec<bool>) -> Self {
+        let mut slots = vec![0; order.len()];
+        for (slot, &logical) in order.iter().enumerate() {
+            slots[logical] = slot;
+        }
+        Self { order, slots, rtl }
+    }
+
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn slot(&self, logical: usize) -> usize {
+        self.slots.get(logical).copied().unwrap_or(logical)
+    }
+
+    /// Logical char drawn in a visual slot
+    pub fn logical(&self, slot: usize) -> usize {
+        self.order.get(slot).copied().unwrap_or(slot)
+    }
+
+    /// Chars of `text` as they appear on screen, left to right
+    pub fn display(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        self.order.iter().filter_map(|&i| chars.get(i)).collect()
+    }
+
+    /// Visual boundary (0..=len) where a caret before logical char `logical` is drawn
+    pub fn caret_to_visual(&self, logical: usize) -> usize {
+        let len = self.len();
+        if logical < len {
+            self.slots[logical] + self.rtl[logical] as usize
+        } else if len > 0 {
+            self.slots[len - 1] + !self.rtl[len - 1] as usize
+        } else {
+            0
+        }
+    }
+
+    /// Logical caret for a visual boundary, judged by the char to its right
+    pub fn caret_to_logical(&self, visual: usize) -> usize {
+        let len = self.len();
+        if visual < len {
+            let logical = self.order[visual];
+            logical + self.rtl[logical] as usize
+        } else if len > 0 {
+            let logical = self.order[len - 1];
+            logical + !self.rtl[logical] as usize
+        } else {
+            0
+        }
+    }
+
+    /// One visual step left or right; None once the caret would leave the line
+    pub fn step(&self, logical: usize, right: bool) -> Option<usize> {
+        let mut visual = self.caret_to_visual(logical);
+        loop {
+            visual = if right {
+                if visual >= self.len() { return None; }
+                visual + 1
+            } else {
+                visual.checked_sub(1)?
+            };
+            // Direction changes put two logical carets on one boundary; skip to a new one
+            let next = self.caret_to_logical(visual);
+            if next != logical {
+                return Some(next);
+            }
+        }
+    }
+}
+
+/// Shared shaper; scanning system fonts is slow, so lines are cached by text
+fn shaper() -> &'static Mutex<(FontSystem, HashMap<String, VisualOrder>)> {
+    static SHAPER: OnceLock<Mutex<(FontSystem, HashMap<String, VisualOrder>)>> = OnceLock::new();
+    SHAPER.get_or_init(|| Mutex::new((FontSystem::new(), HashMap::new())))
+}
+
+/// Visual order of one line; pure LTR text skips shaping entirely
+pub fn visual_order(line: &str) -> VisualOrder {
+    let len = line.chars().count();
+    if !has_rtl(line) || line.contains('\n') {
+        return VisualOrder::identity(len);
+    }
+    let mut guard = shaper().lock().unwrap_or_else(|e| e.into_inner());
+    let (font_system, cache) = &mut *guard;
+    if let Some(order) = cache.get(line) {
+        return order.clone();
+    }
+
+    let mut buffer = Buffer::new(font_system, Metrics::new(12.0, 14.0));
+    buffer.set_size(font_system, None, None);
+    buffer.set_text(font_system, line, Attrs::new(), Shaping::Advanced);
+    buffer.shape_until_scroll(font_system, false);
+
+    let char_starts: Vec<usize> = line.char_indices().map(|(byte, _)| byte).collect();
+    let mut order = Vec::with_capacity(len);
+    let mut rtl = vec![false; len];
+    let mut placed = vec![false; len];
+    for run in buffer.layout_runs() {
+        let mut glyphs: Vec<_> = run.glyphs.iter().collect();
+        glyphs.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap_or(std::cmp::Ordering::Equal));
+        for glyph in glyphs {
+            // A glyph may cover a cluster of chars (ligatures, Arabic joining)
+            let first = char_starts.partition_point(|&byte| byte < glyph.start);
+            let last = char_starts.partition_point(|&byte| byte < glyph.end);
+            let mut cluster: Vec<usize> = (first..last).collect();
+            if glyph.level.is_rtl() {
+                cluster.reverse();
+            }
+            for i in cluster {
+                if !placed[i] {
+                    placed[i] = true;
+                    rtl[i] = glyph.level.is_rtl();
+                    order.push(i);
+                }
+            }
+        }
+    }
+    // Anything the layout dropped (e.g. trailing spaces) keeps logical order at the end
+    order.extend((0..len).filter(|&i| !placed[i]));
+
+    let visual = VisualOrder::from_order(order, rtl);
+    if cache.len() > 4096 {
+        cache.clear();
+    }
+    cache.insert(line.to_string(), visual.clone());
+    visual
+}
+
+/// Text reordered for painting, line by line
+pub fn display(text: &str) -> String {
+    if !has_rtl(text) {
+        return text.to_string();
+    }
+    text.split('\n').map(|line| visual_order(line).display(line)).collect::<Vec<_>>().join("\n")
+}
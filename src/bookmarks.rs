@@ -0,0 +1,99 @@
+// bookmarks.rs - Named cursor bookmarks persisted next to the PDF
+use ropey::Rope;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub name: String,
+    pub rope_pos: usize,
+}
+
+/// Bookmarks kept sorted by rope position
+#[derive(Debug)]
+pub struct Bookmarks {
+    pub items: Vec<Bookmark>,
+    next_number: usize,
+}
+
+impl Bookmarks {
+    pub fn new() -> Self {
+        Self { items: Vec::new(), next_number: 1 }
+    }
+    
+    /// Add a bookmark at a rope position, returning its index
+    pub fn add(&mut self, rope_pos: usize) -> usize {
+        let name = format!("Bookmark {}", self.next_number);
+        self.next_number += 1;
+        let index = self.items.partition_point(|b| b.rope_pos <= rope_pos);
+        self.items.insert(index, Bookmark { name, rope_pos });
+        index
+    }
+    
+    /// First bookmark after a position, wrapping to the start
+    pub fn next_after(&self, rope_pos: usize) -> Option<&Bookmark> {
+        self.items.iter().find(|b| b.rope_pos > rope_pos).or(self.items.first())
+    }
+    
+    /// Last bookmark before a position, wrapping to the end
+    pub fn previous_before(&self, rope_pos: usize) -> Option<&Bookmark> {
+        self.items.iter().rev().find(|b| b.rope_pos < rope_pos).or(self.items.last())
+    }
+    
+    /// Keep bookmarks attached to their text when characters are inserted
+    pub fn shift_for_insert(&mut self, pos: usize, len: usize) {
+        for bookmark in &mut self.items {
+            if bookmark.rope_pos > pos {
+                bookmark.rope_pos += len;
+            }
+        }
+    }
+    
+    /// Keep bookmarks attached to their text when a range is deleted
+    pub fn shift_for_delete(&mut self, start: usize, end: usize) {
+        for bookmark in &mut self.items {
+            if bookmark.rope_pos >= end {
+                bookmark.rope_pos -= end - start;
+            } else if bookmark.rope_pos > start {
+                bookmark.rope_pos = start;
+            }
+        }
+    }
+    
+    /// One line of context around a bookmark for the side panel
+    pub fn snippet(rope: &Rope, rope_pos: usize) -> String {
+        let start = rope_pos.saturating_sub(20);
+        let end = (rope_pos + 40).min(rope.len_chars());
+        if start >= end {
+            return String::new();
+        }
+        rope.slice(start..end).to_string().replace('\n', " ")
+    }
+    
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-bookmarks", pdf_path))
+    }
+    
+    /// Load bookmarks saved for a PDF (`position<TAB>name` per line)
+    pub fn load(pdf_path: &str) -> Self {
+        let mut bookmarks = Self::new();
+        if let Ok(content) = std::fs::read_to_string(Self::sidecar_path(pdf_path)) {
+            for line in content.lines() {
+                if let Some((pos, name)) = line.split_once('\t') {
+                    if let Ok(rope_pos) = pos.parse() {
+                        bookmarks.items.push(Bookmark { name: name.to_string(), rope_pos });
+                    }
+                }
+            }
+        }
+        bookmarks.items.sort_by_key(|b| b.rope_pos);
+        bookmarks.next_number = bookmarks.items.len() + 1;
+        bookmarks
+    }
+    
+    pub fn save(&self, pdf_path: &str) -> std::io::Result<()> {
+        let content: String = self.items.iter()
+            .map(|b| format!("{}\t{}\n", b.rope_pos, b.name.replace(['\t', '\n'], " ")))
+            .collect();
+        std::fs::write(Self::sidecar_path(pdf_path), content)
+    }
+}
@@ -0,0 +1,48 @@
+// change_report.rs - Per-element edit report (CSV/JSON) for audit trails
+use serde::Serialize;
+
+/// One modified element as it stands at export time
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeRecord {
+    pub element_id: usize,
+    pub page: usize,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub original: String,
+    pub corrected: String,
+    pub modified_at: u64, // Unix seconds
+}
+
+pub fn to_json(records: &[ChangeRecord]) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(records)
+}
+
+pub fn to_csv(records: &[ChangeRecord]) -> String {
+    let mut csv = String::from("element_id,page,x,y,width,height,original,corrected,modified_at\n");
+    for r in records {
+        csv.push_str(&format!(
+            "{},{},{:.2},{:.2},{:.2},{:.2},{},{},{}\n",
+            r.element_id, r.page, r.x, r.y, r.width, r.height,
+            quote(&r.original), quote(&r.corrected), r.modified_at
+        ));
+    }
+    csv
+}
+
+/// Quote a field when it holds a delimiter, quote or line break
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Write `<stem>.csv` and `<stem>.json`; returns the number of records
+pub fn write(stem: &str, records: &[ChangeRecord]) -> Result<usize, Box<dyn std::error::Error>> {
+    std::fs::write(format!("{}.csv", stem), to_csv(records))?;
+    std::fs::write(format!("{}.json", stem), to_json(records)?)?;
+    Ok(records.len())
+}
@@ -0,0 +1,38 @@
+// cjk.rs - East Asian wide characters and vertically set text
+use eframe::egui;
+
+/// Wide and full-width ranges from UAX #11: Hangul, kana, CJK ideographs, full-width forms
+pub fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF |
+        0x4E00..=0x9FFF | 0xA000..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF |
+        0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x20000..=0x3FFFD)
+}
+
+/// Monospace cells a char occupies: two for wide glyphs, one otherwise
+pub fn cell_width(c: char) -> usize {
+    if is_wide(c) { 2 } else { 1 }
+}
+
+/// Cell offset of every char boundary in `text`
+pub fn cell_offsets(text: &str) -> Vec<usize> {
+    let mut offsets = vec![0];
+    let mut cells = 0;
+    for c in text.chars() {
+        cells += cell_width(c);
+        offsets.push(cells);
+    }
+    offsets
+}
+
+/// Tall, narrow box of mostly CJK text: typeset top to bottom (Japanese tategaki)
+pub fn is_vertical(bounds: egui::Rect, text: &str) -> bool {
+    let chars = text.trim().chars().count();
+    let wide = text.chars().filter(|&c| is_wide(c)).count();
+    chars > 1 && bounds.height() > bounds.width() * 1.5 && wide * 2 >= chars
+}
+
+/// One char per line, for painting a vertical block with a horizontal text painter
+pub fn stack_vertically(text: &str) -> String {
+    text.trim_end().chars().map(String::from).collect::<Vec<_>>().join("\n")
+}
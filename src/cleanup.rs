@@ -0,0 +1,115 @@
+// cleanup.rs - Unicode normalization and typographic cleanup of extracted text
+use unicode_normalization::UnicodeNormalization;
+
+/// Options live here, one `key=value` per line
+pub const SETTINGS_FILE: &str = "chonker9_cleanup.txt";
+
+const SOFT_HYPHEN: char = '\u{00AD}';
+const ZERO_WIDTH: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+const SINGLE_QUOTES: &[char] = &['\u{2018}', '\u{2019}', '\u{201A}', '\u{201B}', '\u{2032}', '\u{02BC}'];
+const DOUBLE_QUOTES: &[char] = &['\u{201C}', '\u{201D}', '\u{201E}', '\u{201F}', '\u{2033}', '\u{00AB}', '\u{00BB}'];
+const DASHES: &[char] = &['\u{2010}', '\u{2011}', '\u{2012}', '\u{2013}', '\u{2014}', '\u{2015}', '\u{2212}'];
+
+/// Unicode normalization form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    None,
+    Nfc,    // Canonical composition: é as one codepoint
+    Nfkc,   // Also folds compatibility forms: ligatures, full-width letters, superscripts
+}
+
+impl Normalization {
+    pub const ALL: [Normalization; 3] = [Normalization::None, Normalization::Nfc, Normalization::Nfkc];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Normalization::None => "none",
+            Normalization::Nfc => "NFC",
+            Normalization::Nfkc => "NFKC",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CleanupOptions {
+    pub normalization: Normalization,
+    pub unify_quotes: bool,        // Curly quotes → ' and "
+    pub unify_dashes: bool,        // En/em dashes, minus → -
+    pub remove_soft_hyphens: bool,
+    pub remove_zero_width: bool,
+    pub apply_on_load: bool,
+}
+
+impl Default for CleanupOptions {
+    fn default() -> Self {
+        Self {
+            normalization: Normalization::Nfc,
+            unify_quotes: false,
+            unify_dashes: false,
+            remove_soft_hyphens: true,
+            remove_zero_width: true,
+            apply_on_load: false,
+        }
+    }
+}
+
+impl CleanupOptions {
+    pub fn load() -> Self {
+        let mut options = Self::default();
+        let content = std::fs::read_to_string(SETTINGS_FILE).unwrap_or_default();
+        for line in content.lines() {
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+            let flag = value == "true";
+            match key {
+                "normalization" => {
+                    options.normalization = Normalization::ALL.iter().copied()
+                        .find(|n| n.label().eq_ignore_ascii_case(value))
+                        .unwrap_or(options.normalization);
+                }
+                "unify_quotes" => options.unify_quotes = flag,
+                "unify_dashes" => options.unify_dashes = flag,
+                "remove_soft_hyphens" => options.remove_soft_hyphens = flag,
+                "remove_zero_width" => options.remove_zero_width = flag,
+                "apply_on_load" => options.apply_on_load = flag,
+                _ => {}
+            }
+        }
+        options
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let content = format!(
+            "normalization={}\nunify_quotes={}\nunify_dashes={}\nremove_soft_hyphens={}\nremove_zero_width={}\napply_on_load={}\n",
+            self.normalization.label(), self.unify_quotes, self.unify_dashes,
+            self.remove_soft_hyphens, self.remove_zero_width, self.apply_on_load,
+        );
+        std::fs::write(SETTINGS_FILE, content)
+    }
+}
+
+/// Apply the enabled cleanups; invisible characters go first so normalization sees clean input
+pub fn clean(text: &str, options: &CleanupOptions) -> String {
+    let stripped: String = text.chars()
+        .filter(|&c| !(options.remove_soft_hyphens && c == SOFT_HYPHEN))
+        .filter(|c| !(options.remove_zero_width && ZERO_WIDTH.contains(c)))
+        .map(|c| {
+            if options.unify_quotes && SINGLE_QUOTES.contains(&c) {
+                '\''
+            } else if options.unify_quotes && DOUBLE_QUOTES.contains(&c) {
+                '"'
+            } else if options.unify_dashes && DASHES.contains(&c) {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    match options.normalization {
+        Normalization::None => stripped,
+        Normalization::Nfc => stripped.nfc().collect(),
+        Normalization::Nfkc => stripped.nfkc().collect(),
+    }
+}
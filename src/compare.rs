@@ -0,0 +1,84 @@
+// compare.rs - This document beside another version (a PDF or an earlier export), word by word
+use crate::{diff, ChonkerApp};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+
+/// The other document and how both panes are scrolled
+pub struct Comparison {
+    pub path: String,
+    pub ops: Vec<diff::DiffOp>, // This document → the other one
+    revision: u64,              // Buffer revision of this document when the diff was made
+    other: String,
+    pub scroll: f32,            // Shared position, as a fraction of each pane's scroll range
+    pub max_scroll: [f32; 2],   // Scroll range of each pane at the last frame
+    pub sync: [bool; 2],        // Pane should jump to `scroll` this frame
+}
+
+/// Readable text of a PDF, every page through the same pipeline passes as this document,
+/// or the contents of anything else (a previous text/markdown export)
+pub fn load_text(path: &str, passes: Vec<(String, bool)>) -> Result<String, Box<dyn std::error::Error>> {
+    let is_pdf = Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        return Ok(std::fs::read_to_string(path)?);
+    }
+    let mut app = ChonkerApp::default();
+    app.pdf_path = path.to_string();
+    app.load_pdf()?;
+    app.pipeline.profile_mut().passes = passes;
+    app.poll_extractor_sampling(true);
+    app.parse_all_pages()?;
+    Ok(app.pipeline_text())
+}
+
+/// Load the other document on a worker thread
+pub fn load_in_background(path: &str, passes: Vec<(String, bool)>) -> Receiver<Result<String, String>> {
+    let path = path.to_string();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        // The receiver is gone when another comparison was started meanwhile
+        let _ = sender.send(load_text(&path, passes).map_err(|e| e.to_string()));
+    });
+    receiver
+}
+
+impl Comparison {
+    pub fn new(path: &str, revision: u64, current: &str, other: String) -> Self {
+        Comparison {
+            path: path.to_string(),
+            ops: diff::diff_words(current, &other),
+            revision,
+            other,
+            scroll: 0.0,
+            max_scroll: [0.0; 2],
+            sync: [false; 2],
+        }
+    }
+
+    /// Re-diff after this document was edited; `current` is only rendered when the revision moved
+    pub fn refresh(&mut self, revision: u64, current: impl FnOnce() -> String) {
+        if self.revision != revision {
+            self.ops = diff::diff_words(&current(), &self.other);
+            self.revision = revision;
+        }
+    }
+
+    /// Offsets the panes should jump to this frame, for panes following the other one
+    pub fn take_sync(&mut self) -> [Option<f32>; 2] {
+        let jumps = [0, 1].map(|pane| self.sync[pane].then(|| self.scroll * self.max_scroll[pane]));
+        self.sync = [false; 2];
+        jumps
+    }
+
+    /// Record a pane's scroll offset; when the user moved it, the other pane follows next frame
+    pub fn scrolled(&mut self, pane: usize, offset: f32, max_scroll: f32, jumped: bool) {
+        self.max_scroll[pane] = max_scroll;
+        if jumped {
+            return;
+        }
+        let fraction = if max_scroll > 0.0 { (offset / max_scroll).clamp(0.0, 1.0) } else { 0.0 };
+        if (fraction - self.scroll).abs() > 0.001 {
+            self.scroll = fraction;
+            self.sync[1 - pane] = true;
+        }
+    }
+}
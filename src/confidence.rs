@@ -0,0 +1,31 @@
+// confidence.rs - OCR word and character confidence: ALTO CC parsing and heatmap tints
+use eframe::egui;
+
+/// Words below this count as low confidence until the user changes it
+pub const DEFAULT_THRESHOLD: f32 = 0.8;
+
+/// Mean confidence (0.0-1.0) from an ALTO CC list: one value per char, 0 = sure, 9 = unsure
+pub fn from_cc(cc: &str) -> Option<f32> {
+    let digits: Vec<u32> = cc.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    let sum: f32 = digits.iter().map(|&d| 1.0 - d.min(9) as f32 / 9.0).sum();
+    Some(sum / digits.len() as f32)
+}
+
+pub fn is_low(confidence: Option<f32>, threshold: f32) -> bool {
+    confidence.is_some_and(|c| c < threshold)
+}
+
+/// Red well under the threshold, yellow just under it, nothing for confident or unscored words
+pub fn tint(confidence: Option<f32>, threshold: f32) -> Option<egui::Color32> {
+    let confidence = confidence?;
+    if confidence < threshold * 0.75 {
+        Some(egui::Color32::from_rgba_unmultiplied(230, 40, 40, 90))
+    } else if confidence < threshold {
+        Some(egui::Color32::from_rgba_unmultiplied(240, 200, 0, 80))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,61 @@
+// dictionary.rs - Word list for dictionary-backed heuristics
+use std::collections::HashSet;
+
+/// System word lists tried in order
+const SYSTEM_WORD_LISTS: &[&str] = &[
+    "/usr/share/dict/words",
+    "/usr/dict/words",
+];
+
+/// Lowercased word set used for hit-rate scoring and validation
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    /// Load the first available system word list (empty if none exists)
+    pub fn load_system() -> Self {
+        let mut dictionary = Self::default();
+        for path in SYSTEM_WORD_LISTS {
+            if let Ok(content) = std::fs::read_to_string(path) {
+                dictionary.add_words(content.lines());
+                break;
+            }
+        }
+        dictionary
+    }
+    
+    pub fn add_words<'a>(&mut self, words: impl Iterator<Item = &'a str>) {
+        for word in words {
+            let word = word.trim();
+            if !word.is_empty() {
+                self.words.insert(word.to_lowercase());
+            }
+        }
+    }
+    
+    pub fn words(&self) -> impl Iterator<Item = &String> {
+        self.words.iter()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+    
+    /// Whether a token (surrounding punctuation ignored) is a known word
+    pub fn contains(&self, token: &str) -> bool {
+        let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+        self.words.contains(&word.to_lowercase())
+    }
+}
+
+/// Strip punctuation and return the token if it is worth checking (has letters)
+pub fn checkable_word(token: &str) -> Option<&str> {
+    let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+    if word.chars().any(|c| c.is_alphabetic()) && !word.chars().any(|c| c.is_numeric()) {
+        Some(word)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,85 @@
+// diff.rs - Word-level diff between original and edited text
+/// One run of words in the diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffOp {
+    Equal(String),
+    Delete(String), // Only in the original
+    Insert(String), // Only in the edited text
+}
+
+/// Diff two texts word by word (longest common subsequence), merging adjacent runs
+pub fn diff_words(original: &str, edited: &str) -> Vec<DiffOp> {
+    let a: Vec<&str> = original.split_whitespace().collect();
+    let b: Vec<&str> = edited.split_whitespace().collect();
+    
+    // Trim the common prefix and suffix so the table only covers the changed middle
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    
+    // lcs[i][j] = LCS length of a_mid[i..] and b_mid[j..]
+    let (n, m) = (a_mid.len(), b_mid.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a_mid[i] == b_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    
+    let mut ops = Vec::new();
+    for word in &a[..prefix] {
+        push(&mut ops, DiffOp::Equal(word.to_string()));
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a_mid[i] == b_mid[j] {
+            push(&mut ops, DiffOp::Equal(a_mid[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            push(&mut ops, DiffOp::Insert(b_mid[j].to_string()));
+            j += 1;
+        } else {
+            push(&mut ops, DiffOp::Delete(a_mid[i].to_string()));
+            i += 1;
+        }
+    }
+    for word in &a[a.len() - suffix..] {
+        push(&mut ops, DiffOp::Equal(word.to_string()));
+    }
+    ops
+}
+
+/// Diff each (original, edited) pair on its own and chain the results, so words can't
+/// be matched across pairs
+pub fn diff_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Vec<DiffOp> {
+    let mut ops = Vec::new();
+    for (original, edited) in pairs {
+        for op in diff_words(original, edited) {
+            push(&mut ops, op);
+        }
+    }
+    ops
+}
+
+/// Append a word, extending the last run when it is the same kind
+fn push(ops: &mut Vec<DiffOp>, op: DiffOp) {
+    match (ops.last_mut(), op) {
+        (Some(DiffOp::Equal(run)), DiffOp::Equal(word))
+        | (Some(DiffOp::Delete(run)), DiffOp::Delete(word))
+        | (Some(DiffOp::Insert(run)), DiffOp::Insert(word)) => {
+            run.push(' ');
+            run.push_str(&word);
+        }
+        (_, op) => ops.push(op),
+    }
+}
+
+/// Number of changed runs (deletions and insertions)
+pub fn change_count(ops: &[DiffOp]) -> usize {
+    ops.iter().filter(|op| !matches!(op, DiffOp::Equal(_))).count()
+}
@@ -0,0 +1,60 @@
+// discrepancy.rs - Embedded text layer vs OCR disagreement detection
+use crate::spellcheck::edit_distance;
+use crate::SpatialElement;
+use eframe::egui;
+
+/// A text-layer word whose OCR reading disagrees
+#[derive(Debug, Clone)]
+pub struct Discrepancy {
+    pub page: usize,
+    pub bounds: egui::Rect,  // Text-layer bounds in PDF points
+    pub text_layer: String,
+    pub ocr: String,
+}
+
+fn element_rect(element: &SpatialElement) -> egui::Rect {
+    egui::Rect::from_min_size(egui::pos2(element.hpos, element.vpos), egui::vec2(element.width, element.height))
+}
+
+/// Lowercased alphanumerics, so punctuation and case noise don't count as disagreement
+fn normalized(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Compare each text-layer element with the OCR words whose centers fall inside it
+pub fn find_discrepancies(text_layer: &[SpatialElement], ocr: &[SpatialElement]) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+    
+    for element in text_layer {
+        let bounds = element_rect(element);
+        // OCR boxes are often a little taller than text-layer boxes
+        let search_bounds = bounds.expand2(egui::vec2(1.0, 3.0));
+        let mut overlapping: Vec<&SpatialElement> = ocr.iter()
+            .filter(|o| o.page == element.page && search_bounds.contains(element_rect(o).center()))
+            .collect();
+        overlapping.sort_by(|a, b| a.hpos.partial_cmp(&b.hpos).unwrap());
+        let ocr_text = overlapping.iter().map(|o| o.content.as_str()).collect::<Vec<&str>>().join(" ");
+        
+        let expected = normalized(&element.content);
+        let actual = normalized(&ocr_text);
+        if expected.is_empty() && actual.is_empty() {
+            continue;
+        }
+        
+        // Disagree when more than a quarter of the characters differ
+        let distance = edit_distance(&expected, &actual);
+        if distance as f32 > expected.len().max(actual.len()) as f32 * 0.25 {
+            discrepancies.push(Discrepancy {
+                page: element.page,
+                bounds,
+                text_layer: element.content.clone(),
+                ocr: ocr_text,
+            });
+        }
+    }
+    
+    discrepancies
+}
@@ -0,0 +1,76 @@
+// docx.rs - Word export of reconstructed paragraphs, with the document metadata as core properties
+use crate::metadata::DocumentMetadata;
+use std::fmt::Write;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="xml" ContentType="application/xml"/>
+  <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+  <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
+</Types>
+"#;
+
+const RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
+</Relationships>
+"#;
+
+/// One `w:p` per paragraph; a line break inside a paragraph stays a break
+fn document_xml(paragraphs: &[String]) -> String {
+    let mut body = String::new();
+    for paragraph in paragraphs {
+        body.push_str("    <w:p><w:r>");
+        for (i, line) in paragraph.lines().enumerate() {
+            if i > 0 {
+                body.push_str("<w:br/>");
+            }
+            let _ = write!(body, "<w:t xml:space=\"preserve\">{}</w:t>", escape(line));
+        }
+        body.push_str("</w:r></w:p>\n");
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\n  <w:body>\n{}  </w:body>\n</w:document>\n",
+        body
+    )
+}
+
+/// Title, author, subject and creation date as Dublin Core core properties
+fn core_xml(metadata: &DocumentMetadata, pdf_path: &str) -> String {
+    let mut properties = String::new();
+    let _ = writeln!(properties, "  <dc:title>{}</dc:title>", escape(&metadata.title_or_file(pdf_path)));
+    if !metadata.author.trim().is_empty() {
+        let _ = writeln!(properties, "  <dc:creator>{}</dc:creator>", escape(metadata.author.trim()));
+    }
+    if !metadata.subject.trim().is_empty() {
+        let _ = writeln!(properties, "  <dc:subject>{}</dc:subject>", escape(metadata.subject.trim()));
+    }
+    if let Some(date) = metadata.iso_creation_date() {
+        let _ = writeln!(properties, "  <dcterms:created xsi:type=\"dcterms:W3CDTF\">{}</dcterms:created>", date);
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:dcterms=\"http://purl.org/dc/terms/\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\n{}</cp:coreProperties>\n",
+        properties
+    )
+}
+
+/// Write the document; the title falls back to the PDF file name
+pub fn write(path: &str, metadata: &DocumentMetadata, pdf_path: &str, paragraphs: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let files: Vec<(String, Vec<u8>)> = vec![
+        ("[Content_Types].xml".to_string(), CONTENT_TYPES.as_bytes().to_vec()),
+        ("_rels/.rels".to_string(), RELS.as_bytes().to_vec()),
+        ("word/document.xml".to_string(), document_xml(paragraphs).into_bytes()),
+        ("docProps/core.xml".to_string(), core_xml(metadata, pdf_path).into_bytes()),
+    ];
+    std::fs::write(path, crate::zip::write_stored(&files))?;
+    Ok(())
+}
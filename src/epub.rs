@@ -0,0 +1,118 @@
+// epub.rs - EPUB 3 export, one chapter per bookmark or top-level heading
+use crate::links;
+use crate::metadata::DocumentMetadata;
+use std::fmt::Write;
+
+/// A chapter of reconstructed paragraphs
+pub struct Chapter {
+    pub title: String,
+    pub paragraphs: Vec<String>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn chapter_xhtml(chapter: &Chapter) -> String {
+    let mut body = String::new();
+    let _ = writeln!(body, "<h1>{}</h1>", escape(&chapter.title));
+    for paragraph in &chapter.paragraphs {
+        let text = links::markup(paragraph, escape, |text, target| format!("<a href=\"{}\">{}</a>", escape(target), escape(text)));
+        let _ = writeln!(body, "<p>{}</p>", text);
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\">\n<head><title>{}</title></head>\n<body>\n{}</body>\n</html>\n",
+        escape(&chapter.title), body
+    )
+}
+
+fn nav_xhtml(chapters: &[Chapter]) -> String {
+    let mut items = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        let _ = writeln!(items, "<li><a href=\"chapter_{:03}.xhtml\">{}</a></li>", i + 1, escape(&chapter.title));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n<head><title>Contents</title></head>\n<body>\n<nav epub:type=\"toc\"><ol>\n{}</ol></nav>\n</body>\n</html>\n",
+        items
+    )
+}
+
+fn content_opf(metadata: &DocumentMetadata, identifier: &str, chapters: &[Chapter]) -> String {
+    let mut described = String::new();
+    if !metadata.author.trim().is_empty() {
+        let _ = writeln!(described, "    <dc:creator>{}</dc:creator>", escape(metadata.author.trim()));
+    }
+    if !metadata.subject.trim().is_empty() {
+        let _ = writeln!(described, "    <dc:subject>{}</dc:subject>", escape(metadata.subject.trim()));
+    }
+    if let Some(date) = metadata.iso_creation_date() {
+        let _ = writeln!(described, "    <dc:date>{}</dc:date>", date);
+    }
+    let mut manifest = String::new();
+    let mut spine = String::new();
+    for i in 1..=chapters.len() {
+        let _ = writeln!(manifest, "    <item id=\"chapter_{i:03}\" href=\"chapter_{i:03}.xhtml\" media-type=\"application/xhtml+xml\"/>");
+        let _ = writeln!(spine, "    <itemref idref=\"chapter_{i:03}\"/>");
+    }
+    let modified = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="uid">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="uid">{}</dc:identifier>
+    <dc:title>{}</dc:title>
+{}    <dc:language>en</dc:language>
+    <meta property="dcterms:modified">{}</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+{}  </manifest>
+  <spine>
+{}  </spine>
+</package>
+"#,
+        escape(identifier), escape(&metadata.title_or_file(identifier)), described, iso8601(modified), manifest, spine
+    )
+}
+
+/// UTC timestamp in the CCYY-MM-DDThh:mm:ssZ form EPUB requires
+pub fn iso8601(unix: u64) -> String {
+    let (days, secs) = (unix / 86400, unix % 86400);
+    // Civil-from-days (Howard Hinnant)
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, secs / 3600, secs % 3600 / 60, secs % 60)
+}
+
+/// Write the book; `identifier` should be stable for the source (e.g. the PDF path), and names it when
+/// the metadata has no title
+pub fn write(path: &str, metadata: &DocumentMetadata, identifier: &str, chapters: &[Chapter]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("mimetype".to_string(), b"application/epub+zip".to_vec()),
+        ("META-INF/container.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>
+"#.to_vec()),
+        ("OEBPS/content.opf".to_string(), content_opf(metadata, identifier, chapters).into_bytes()),
+        ("OEBPS/nav.xhtml".to_string(), nav_xhtml(chapters).into_bytes()),
+    ];
+    for (i, chapter) in chapters.iter().enumerate() {
+        files.push((format!("OEBPS/chapter_{:03}.xhtml", i + 1), chapter_xhtml(chapter).into_bytes()));
+    }
+    std::fs::write(path, crate::zip::write_stored(&files))?;
+    Ok(())
+}
@@ -0,0 +1,211 @@
+// export.rs - Document model and user-defined template exports
+use crate::table_regions::{self, TableRegion};
+use crate::{layout, links, reading_order, tags::ColorTag, SpatialElement};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Directory scanned for user export templates
+pub const TEMPLATE_DIR: &str = "chonker9_templates";
+
+#[derive(Debug, Serialize)]
+pub struct ElementModel {
+    pub content: String,
+    pub hpos: f32,
+    pub vpos: f32,
+    pub width: f32,
+    pub height: f32,
+    pub modified: bool,
+    pub tag: Option<ColorTag>,
+    pub link: Option<String>,  // PDF link annotation, else the first URL/email in the text
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockModel {
+    pub text: String,
+    pub html: String,      // Escaped text with <a href> links
+    pub markdown: String,  // Text with [text](target) links
+    pub lines: Vec<String>,
+    pub elements: Vec<ElementModel>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableModel {
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageModel {
+    pub number: usize,
+    pub blocks: Vec<BlockModel>,
+    pub tables: Vec<TableModel>,
+}
+
+/// Everything a template can see: pages > blocks > elements, plus tables
+#[derive(Debug, Serialize)]
+pub struct DocumentModel {
+    pub source: String,
+    pub pages: Vec<PageModel>,
+}
+
+/// Flat per-element record for data pipelines (JSON / JSONL export)
+#[derive(Debug, Serialize)]
+pub struct ElementRecord {
+    pub page: usize,
+    pub text: String,
+    pub bbox: [f32; 4],        // x, y, width, height in PDF points
+    pub style: Option<String>, // ALTO STYLEREFS
+    pub modified: bool,
+    pub confidence: Option<f32>,
+    pub tag: Option<ColorTag>,
+}
+
+impl ElementRecord {
+    pub fn from_element(element: &SpatialElement) -> Self {
+        Self {
+            page: element.page,
+            text: element.content.clone(),
+            bbox: [element.hpos, element.vpos, element.width, element.height],
+            style: element.style_refs.clone(),
+            modified: element.modified,
+            confidence: element.confidence,
+            tag: element.tag,
+        }
+    }
+}
+
+/// Write every element as one JSON array, or one object per line when `lines` is set
+pub fn write_element_records(path: &str, elements: &[SpatialElement], lines: bool) -> Result<usize, Box<dyn std::error::Error>> {
+    let records: Vec<ElementRecord> = elements.iter().map(ElementRecord::from_element).collect();
+    let output = if lines {
+        let mut output = String::new();
+        for record in &records {
+            output.push_str(&serde_json::to_string(record)?);
+            output.push('\n');
+        }
+        output
+    } else {
+        serde_json::to_string_pretty(&records)?
+    };
+    std::fs::write(path, output)?;
+    Ok(records.len())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Backslash-escape brackets and parens so link text can't close the markdown link early
+fn escape_link_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '(' | ')') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// One element as markup: wholly linked when a PDF annotation covers it, else its detected URLs/emails
+fn element_markup(element: &SpatialElement, pdf_links: &[links::PdfLink], plain: &dyn Fn(&str) -> String, link: &dyn Fn(&str, &str) -> String) -> String {
+    match links::annotation_target(element, pdf_links) {
+        Some(target) => link(&element.content, target),
+        None => links::markup(&element.content, plain, link),
+    }
+}
+
+impl ElementModel {
+    fn from_element(element: &SpatialElement, pdf_links: &[links::PdfLink]) -> Self {
+        let link = links::annotation_target(element, pdf_links).map(str::to_string)
+            .or_else(|| links::detect(&element.content).into_iter().next().map(|found| found.target));
+        Self {
+            content: element.content.clone(),
+            hpos: element.hpos,
+            vpos: element.vpos,
+            width: element.width,
+            height: element.height,
+            modified: element.modified,
+            tag: element.tag,
+            link,
+        }
+    }
+}
+
+impl DocumentModel {
+    /// Build the model from current (edited) elements of a single page, blocks in manual order if set;
+    /// hand-drawn table regions, when present, each become one table
+    pub fn from_elements(source: &str, page_number: usize, elements: &[SpatialElement], order: &[String],
+                         pdf_links: &[links::PdfLink], regions: &[TableRegion]) -> Self {
+        let (table_elements, text_elements): (Vec<&SpatialElement>, Vec<&SpatialElement>) =
+            elements.iter().partition(|e| table_regions::is_table_element(regions, e));
+
+        let mut blocks = layout::group_into_blocks(layout::group_into_lines(&text_elements));
+        if !order.is_empty() {
+            blocks.sort_by_key(|block| reading_order::rank(order, &reading_order::block_key(block[0][0])));
+        }
+        let blocks = blocks
+            .into_iter()
+            .map(|block| {
+                let lines: Vec<String> = block.iter().map(|line| layout::line_text(line)).collect();
+                let markup = |plain: &dyn Fn(&str) -> String, link: &dyn Fn(&str, &str) -> String| {
+                    block.iter().flatten()
+                        .map(|e| element_markup(e, pdf_links, plain, link))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                BlockModel {
+                    text: lines.join(" "),
+                    html: markup(&escape_html, &|text, target| format!("<a href=\"{}\">{}</a>", escape_html(target), escape_html(text))),
+                    markdown: markup(&|plain| plain.to_string(), &|text, target| format!("[{}]({})", escape_link_text(text), target)),
+                    lines,
+                    elements: block.iter().flatten().map(|e| ElementModel::from_element(e, pdf_links)).collect(),
+                }
+            })
+            .collect();
+
+        let mut tables = Vec::new();
+        let page_regions: Vec<&TableRegion> = regions.iter().filter(|region| region.page == page_number).collect();
+        if !page_regions.is_empty() {
+            tables.extend(page_regions.iter().map(|region| TableModel { rows: region.cells(&table_elements) }));
+        } else if !table_elements.is_empty() {
+            let rows = layout::group_into_lines(&table_elements)
+                .iter()
+                .map(|line| line.iter().map(|e| e.content.clone()).collect())
+                .collect();
+            tables.push(TableModel { rows });
+        }
+
+        Self {
+            source: source.to_string(),
+            pages: vec![PageModel { number: page_number, blocks, tables }],
+        }
+    }
+}
+
+/// Templates available in the template directory (`name.ext.j2`)
+pub fn list_templates() -> Vec<PathBuf> {
+    let mut templates: Vec<PathBuf> = std::fs::read_dir(TEMPLATE_DIR)
+        .map(|entries| {
+            entries.filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().map_or(false, |ext| ext == "j2"))
+                .collect()
+        })
+        .unwrap_or_default();
+    templates.sort();
+    templates
+}
+
+/// Render a template against the document model; `name.md.j2` writes `chonker9_name.md`
+pub fn render_template(template_path: &Path, model: &DocumentModel) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let source = std::fs::read_to_string(template_path)?;
+    let env = minijinja::Environment::new();
+    let rendered = env.render_str(&source, minijinja::context! {
+        document => model,
+        pages => &model.pages,
+    })?;
+
+    let stem = template_path.file_stem().and_then(|s| s.to_str()).unwrap_or("template");
+    let output_path = PathBuf::from(format!("chonker9_{}", stem));
+    std::fs::write(&output_path, rendered)?;
+    Ok(output_path)
+}
@@ -0,0 +1,209 @@
+// extraction_cache.rs - Parsed pages kept on disk by PDF content, so an unchanged PDF skips extraction
+use crate::extractors::Extractor;
+use crate::ocr::OcrEngine;
+use crate::{layout, units, ParsedAlto, SpatialElement};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const CACHE_DIR: &str = "chonker9_cache";
+/// Size cap as a `limit_mb=` line
+const SETTINGS_FILE: &str = "chonker9_cache.txt";
+const DEFAULT_LIMIT_MB: u64 = 256;
+
+/// 64-bit FNV-1a of the file's bytes; stable across runs, unlike std's hasher
+pub fn content_hash(path: &str) -> std::io::Result<u64> {
+    let bytes = std::fs::read(path)?;
+    Ok(bytes.iter().fold(0xcbf29ce484222325u64, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3)))
+}
+
+/// Backend part of the key: OCR results also depend on the engine
+pub fn source_key(extractor: Extractor, engine: &OcrEngine) -> String {
+    match (extractor, engine) {
+        (Extractor::TextLayer, _) => extractor.key().to_string(),
+        (Extractor::Ocr, OcrEngine::Tesseract) => format!("{}-tesseract", extractor.key()),
+        (Extractor::Ocr, OcrEngine::Remote(url)) => format!("{}-{}", extractor.key(), layout::slugify(url)),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedElement {
+    content: String,
+    bbox: [f32; 4],  // hpos, vpos, width, height
+    confidence: Option<f32>,
+    style_refs: Option<String>,
+    font_size: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedPage {
+    unit: String,  // MeasurementUnit label
+    dpi: f32,
+    description: Vec<(String, String)>,
+    elements: Vec<CachedElement>,
+}
+
+fn entry_path(hash: u64, page: usize, source: &str) -> PathBuf {
+    PathBuf::from(CACHE_DIR).join(format!("{:016x}_p{}_{}.json", hash, page, source))
+}
+
+/// Cache files with their size and last use, oldest first
+fn entries(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let mut entries: Vec<(PathBuf, u64, SystemTime)> = std::fs::read_dir(dir)
+        .map(|dir| {
+            dir.filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let metadata = entry.metadata().ok()?;
+                    Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by_key(|&(_, _, used)| used);
+    entries
+}
+
+pub fn size_bytes() -> u64 {
+    entries(Path::new(CACHE_DIR)).iter().map(|&(_, bytes, _)| bytes).sum()
+}
+
+/// Delete every cached page, returning how many were removed
+pub fn clear() -> std::io::Result<usize> {
+    let count = entries(Path::new(CACHE_DIR)).len();
+    match std::fs::remove_dir_all(CACHE_DIR) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(count),
+    }
+}
+
+#[derive(Clone)]
+pub struct ExtractionCache {
+    pub limit_mb: u64,
+}
+
+impl ExtractionCache {
+    pub fn load() -> Self {
+        let content = std::fs::read_to_string(SETTINGS_FILE).unwrap_or_default();
+        let limit_mb = content.lines()
+            .find_map(|line| line.strip_prefix("limit_mb=")?.trim().parse().ok())
+            .unwrap_or(DEFAULT_LIMIT_MB);
+        ExtractionCache { limit_mb }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        std::fs::write(SETTINGS_FILE, format!("limit_mb={}\n", self.limit_mb))
+    }
+
+    /// A page extracted earlier (without its XML); a hit counts as a use for eviction
+    pub fn get(&self, hash: u64, page: usize, source: &str) -> Option<ParsedAlto> {
+        let path = entry_path(hash, page, source);
+        let cached: CachedPage = serde_json::from_str(&std::fs::read_to_string(&path).ok()?).ok()?;
+        if let Ok(file) = std::fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        let elements = cached.elements.into_iter()
+            .map(|e| SpatialElement {
+                content: e.content,
+                hpos: e.bbox[0],
+                vpos: e.bbox[1],
+                width: e.bbox[2],
+                height: e.bbox[3],
+                page,
+                confidence: e.confidence,
+                modified: false,
+                tag: None,
+                style_refs: e.style_refs,
+                font_size: e.font_size,
+                extracted: None,
+            })
+            .collect();
+        Some(ParsedAlto {
+            elements,
+            units: units::SourceUnits { unit: units::MeasurementUnit::from_name(&cached.unit), dpi: cached.dpi },
+            description: cached.description,
+            raw_xml: None,
+        })
+    }
+
+    pub fn put(&self, hash: u64, page: usize, source: &str, parsed: &ParsedAlto) -> Result<(), Box<dyn std::error::Error>> {
+        let cached = CachedPage {
+            unit: parsed.units.unit.label().to_string(),
+            dpi: parsed.units.dpi,
+            description: parsed.description.clone(),
+            elements: parsed.elements.iter()
+                .map(|e| CachedElement {
+                    content: e.content.clone(),
+                    bbox: [e.hpos, e.vpos, e.width, e.height],
+                    confidence: e.confidence,
+                    style_refs: e.style_refs.clone(),
+                    font_size: e.font_size,
+                })
+                .collect(),
+        };
+        std::fs::create_dir_all(CACHE_DIR)?;
+        std::fs::write(entry_path(hash, page, source), serde_json::to_string(&cached)?)?;
+        self.evict();
+        Ok(())
+    }
+
+    /// Drop least recently used pages until the cache fits its cap
+    pub fn evict(&self) {
+        evict_to(Path::new(CACHE_DIR), self.limit_mb * 1024 * 1024);
+    }
+}
+
+/// Delete the least recently used files in `dir` until at most `limit` bytes remain
+fn evict_to(dir: &Path, limit: u64) {
+    let entries = entries(dir);
+    let mut total: u64 = entries.iter().map(|&(_, bytes, _)| bytes).sum();
+    for (path, bytes, _) in entries {
+        if total <= limit {
+            break;
+        }
+        // Another batch worker may have removed it already
+        let _ = std::fs::remove_file(path);
+        total = total.saturating_sub(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn eviction_drops_least_recently_used_first() {
+        let dir = crate::temp_path("chonker9_cache_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        // Written newest first, so file order alone can't pass the test
+        for (name, age) in [("newest", 0), ("middle", 60), ("oldest", 120)] {
+            let file = std::fs::File::create(dir.join(name)).unwrap();
+            file.set_len(100).unwrap();
+            file.set_modified(now - Duration::from_secs(age)).unwrap();
+        }
+
+        evict_to(&dir, 250);
+        let mut left: Vec<String> = entries(&dir).into_iter()
+            .map(|(path, _, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        evict_to(&dir, 0);
+        let emptied = entries(&dir).is_empty();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        left.sort();
+        assert_eq!(left, vec!["middle", "newest"]);
+        assert!(emptied);
+    }
+
+    #[test]
+    fn under_the_limit_nothing_is_evicted() {
+        let dir = crate::temp_path("chonker9_cache_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("page"), [0u8; 10]).unwrap();
+        evict_to(&dir, 10);
+        let kept = entries(&dir).len();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(kept, 1);
+    }
+}
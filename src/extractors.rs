@@ -0,0 +1,294 @@
+// extractors.rs - Extraction backends and per-page source selection
+use crate::dictionary::Dictionary;
+use crate::ocr::{OcrBackend, OcrEngine};
+use crate::{alto, diff, parse_alto_stream, quality, units, ParsedAlto, SpatialElement};
+use std::io::{BufReader, Read};
+use std::process::{Command, Stdio};
+
+/// Pages compared with both backends before the rest follow the majority
+pub const SAMPLE_SIZE: usize = 5;
+
+/// Where a page's text comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extractor {
+    TextLayer,  // Embedded PDF text via pdfalto
+    Ocr,        // Rendered page through the document's OCR engine
+}
+
+impl Extractor {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Extractor::TextLayer => "Text layer",
+            Extractor::Ocr => "OCR",
+        }
+    }
+    
+    pub fn key(&self) -> &'static str {
+        match self {
+            Extractor::TextLayer => "text",
+            Extractor::Ocr => "ocr",
+        }
+    }
+    
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "text" => Some(Extractor::TextLayer),
+            "ocr" => Some(Extractor::Ocr),
+            _ => None,
+        }
+    }
+    
+    /// Elements for a page in PDF points; the ALTO XML itself only when `retain_xml` (debug panel)
+    pub fn extract_page(&self, pdf_path: &str, page: usize, ocr: &dyn OcrBackend, retain_xml: bool) -> Result<ParsedAlto, Box<dyn std::error::Error>> {
+        let page_arg = page.to_string();
+        match self {
+            Extractor::TextLayer => {
+                // Parsed straight off the pipe rather than collected into a string first
+                let mut child = Command::new("pdfalto")
+                    .args([
+                        "-f", &page_arg, "-l", &page_arg,
+                        "-readingOrder",        // Follow visual reading order
+                        "-noImage",            // Skip image extraction for speed
+                        "-noLineNumbers",      // Clean output without line numbers
+                        pdf_path,
+                        "/dev/stdout"
+                    ])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()?;
+                let stdout = child.stdout.take().ok_or("pdfalto produced no output")?;
+                let mut reader = BufReader::new(Tee { inner: stdout, copy: retain_xml.then(Vec::new) });
+                let parsed = parse_alto_stream(&mut reader);
+                // Closing the pipe first, so a pdfalto still writing after a parse error can't block the wait
+                let copy = reader.into_inner().copy;
+                let status = child.wait()?;
+                let mut parsed = parsed?;
+                if !status.success() {
+                    return Err("pdfalto failed".into());
+                }
+                for element in &mut parsed.elements {
+                    element.page = page;
+                }
+                parsed.raw_xml = copy.map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+                Ok(parsed)
+            }
+            Extractor::Ocr => {
+                // Rasterize at OCR_DPI, then hand the image to the chosen engine
+                let prefix = crate::temp_path(&format!("chonker9_ocr_p{}", page));
+                let prefix_str = prefix.to_string_lossy().to_string();
+                let status = Command::new("pdftoppm")
+                    .args(["-r", &OCR_DPI.to_string(), "-f", &page_arg, "-l", &page_arg, "-png", "-singlefile", pdf_path, &prefix_str])
+                    .status()?;
+                if !status.success() {
+                    return Err("pdftoppm failed".into());
+                }
+                let image_path = format!("{}.png", prefix_str);
+                let words = ocr.recognize(std::path::Path::new(&image_path));
+                let _ = std::fs::remove_file(&image_path);
+                
+                // Engines report pixels of the rasterized page
+                let scale = 72.0 / OCR_DPI as f32;
+                let elements: Vec<SpatialElement> = words?.into_iter()
+                    .filter(|word| !word.text.is_empty())
+                    .map(|word| SpatialElement {
+                        content: word.text,
+                        hpos: word.x * scale,
+                        vpos: word.y * scale,
+                        width: word.width * scale,
+                        height: word.height * scale,
+                        page,
+                        confidence: word.confidence,
+                        modified: false,
+                        tag: None,
+                        style_refs: None,
+                        font_size: None,
+                        extracted: None,
+                    })
+                    .collect();
+                Ok(ParsedAlto {
+                    raw_xml: retain_xml.then(|| alto::write(&elements)),
+                    elements,
+                    units: units::SourceUnits::default(),  // Converted to points above
+                    description: Vec::new(),
+                })
+            }
+        }
+    }
+}
+
+/// Passes reads through, keeping a copy of the bytes when asked to
+struct Tee<R> {
+    inner: R,
+    copy: Option<Vec<u8>>,
+}
+
+impl<R: Read> Read for Tee<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if let Some(copy) = &mut self.copy {
+            copy.extend_from_slice(&buf[..n]);
+        }
+        Ok(n)
+    }
+}
+
+/// Rasterization resolution for OCR
+const OCR_DPI: u32 = 300;
+
+/// Which backend a page uses and why
+#[derive(Debug, Clone)]
+pub struct ExtractorDecision {
+    pub page: usize,
+    pub chosen: Extractor,
+    pub text_layer_score: Option<f32>,
+    pub ocr_score: Option<f32>,
+    pub overridden: bool,  // Chosen by the user rather than by score
+}
+
+/// Pages to compare both backends on, spread evenly across the document
+pub fn sample_pages(pages: &[usize]) -> Vec<usize> {
+    if pages.len() <= SAMPLE_SIZE {
+        return pages.to_vec();
+    }
+    let step = pages.len() as f32 / SAMPLE_SIZE as f32;
+    (0..SAMPLE_SIZE).map(|i| pages[(i as f32 * step) as usize]).collect()
+}
+
+/// Score both backends on a page and keep the better one
+pub fn compare_page(pdf_path: &str, page: usize, dictionary: &Dictionary, ocr: &dyn OcrBackend) -> Result<ExtractorDecision, Box<dyn std::error::Error>> {
+    let text_elements = Extractor::TextLayer.extract_page(pdf_path, page, ocr, false)?.elements;
+    let text_refs: Vec<&SpatialElement> = text_elements.iter().collect();
+    let text_score = quality::score_page(page, &text_refs, dictionary).score;
+    
+    let ocr_score = match Extractor::Ocr.extract_page(pdf_path, page, ocr, false) {
+        Ok(ParsedAlto { elements: ocr_elements, .. }) => {
+            let ocr_refs: Vec<&SpatialElement> = ocr_elements.iter().collect();
+            Some(quality::score_page(page, &ocr_refs, dictionary).score)
+        }
+        Err(e) => {
+            eprintln!("OCR comparison failed on page {}: {}", page, e);
+            None
+        }
+    };
+    
+    let chosen = match ocr_score {
+        Some(score) if score > text_score => Extractor::Ocr,
+        _ => Extractor::TextLayer,
+    };
+    
+    Ok(ExtractorDecision {
+        page,
+        chosen,
+        text_layer_score: Some(text_score),
+        ocr_score,
+        overridden: false,
+    })
+}
+
+/// A backend for the comparison view: an extractor, plus the engine when it's OCR
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    pub extractor: Extractor,
+    pub engine: OcrEngine,
+}
+
+impl Source {
+    pub fn label(&self) -> String {
+        match (self.extractor, &self.engine) {
+            (Extractor::TextLayer, _) => "Text layer (pdfalto)".to_string(),
+            (Extractor::Ocr, OcrEngine::Tesseract) => "OCR (Tesseract)".to_string(),
+            (Extractor::Ocr, OcrEngine::Remote(url)) => format!("OCR ({})", url),
+        }
+    }
+}
+
+/// Backends that can be compared; the remote engine only once a service URL is known
+pub fn sources(remote_url: &str) -> Vec<Source> {
+    let mut sources = vec![
+        Source { extractor: Extractor::TextLayer, engine: OcrEngine::Tesseract },
+        Source { extractor: Extractor::Ocr, engine: OcrEngine::Tesseract },
+    ];
+    if !remote_url.trim().is_empty() {
+        sources.push(Source { extractor: Extractor::Ocr, engine: OcrEngine::Remote(remote_url.trim().to_string()) });
+    }
+    sources
+}
+
+/// Two backends run on one page: their quality scores and where their text differs
+pub struct BackendComparison {
+    pub page: usize,
+    pub sources: [Source; 2],
+    pub scores: [f32; 2],
+    pub ops: Vec<diff::DiffOp>,  // First backend's text → second's
+}
+
+/// Run both sources on a page; `render` turns a page's elements into readable text
+pub fn compare_sources(
+    pdf_path: &str,
+    page: usize,
+    sources: [Source; 2],
+    dictionary: &Dictionary,
+    render: impl Fn(Vec<SpatialElement>) -> String,
+) -> Result<BackendComparison, Box<dyn std::error::Error>> {
+    let mut scores = [0.0; 2];
+    let mut texts = [String::new(), String::new()];
+    for (i, source) in sources.iter().enumerate() {
+        let backend = source.engine.backend();
+        if source.extractor == Extractor::Ocr && !backend.is_available() {
+            return Err(format!("{} unavailable", backend.name()).into());
+        }
+        let elements = source.extractor.extract_page(pdf_path, page, backend.as_ref(), false)?.elements;
+        let refs: Vec<&SpatialElement> = elements.iter().collect();
+        scores[i] = quality::score_page(page, &refs, dictionary).score;
+        texts[i] = render(elements);
+    }
+    Ok(BackendComparison { page, ops: diff::diff_words(&texts[0], &texts[1]), sources, scores })
+}
+
+/// Backend most sampled pages preferred, used for pages outside the sample
+pub fn majority_choice(decisions: &[ExtractorDecision]) -> Extractor {
+    let ocr_votes = decisions.iter().filter(|d| d.chosen == Extractor::Ocr).count();
+    if ocr_votes * 2 > decisions.len() { Extractor::Ocr } else { Extractor::TextLayer }
+}
+
+fn decisions_path(pdf_path: &str) -> String {
+    format!("{}.chonker-extractors", pdf_path)
+}
+
+fn format_score(score: Option<f32>) -> String {
+    score.map_or("-".to_string(), |s| format!("{:.1}", s))
+}
+
+/// Load recorded decisions (`page<TAB>backend<TAB>overridden<TAB>text score<TAB>ocr score`)
+pub fn load_decisions(pdf_path: &str) -> Vec<ExtractorDecision> {
+    let content = std::fs::read_to_string(decisions_path(pdf_path)).unwrap_or_default();
+    content.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 5 {
+                return None;
+            }
+            Some(ExtractorDecision {
+                page: fields[0].parse().ok()?,
+                chosen: Extractor::from_key(fields[1])?,
+                overridden: fields[2] == "1",
+                text_layer_score: fields[3].parse().ok(),
+                ocr_score: fields[4].parse().ok(),
+            })
+        })
+        .collect()
+}
+
+pub fn save_decisions(pdf_path: &str, decisions: &[ExtractorDecision]) -> std::io::Result<()> {
+    let content: String = decisions.iter()
+        .map(|d| format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            d.page,
+            d.chosen.key(),
+            if d.overridden { "1" } else { "0" },
+            format_score(d.text_layer_score),
+            format_score(d.ocr_score),
+        ))
+        .collect();
+    std::fs::write(decisions_path(pdf_path), content)
+}
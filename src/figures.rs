@@ -0,0 +1,164 @@
+// figures.rs - Illustration and graphic regions, cut from the page raster and drawn under the text
+use crate::rotation::{self, Rotation};
+use crate::{tile_cache, units};
+use eframe::egui;
+use quick_xml::{events::Event, Reader};
+use std::process::Command;
+
+/// Raster resolution figures are cropped from
+pub const FIGURE_DPI: f32 = 150.0;
+
+/// Vector graphics smaller than this (rules, underlines, table borders) aren't figures
+const MIN_GRAPHIC_SIZE: f32 = 24.0;
+
+/// A picture region on a page, in points
+#[derive(Debug, Clone, PartialEq)]
+pub struct Figure {
+    pub page: usize,
+    pub rect: egui::Rect,
+}
+
+/// Run pdfalto with image extraction on and collect the page's figure regions; the
+/// extracted image files themselves are discarded, the raster crop stands in for them
+pub fn detect(pdf_path: &str, page: usize) -> Result<Vec<Figure>, Box<dyn std::error::Error>> {
+    let dir = crate::temp_path(&format!("chonker9_figures_p{}", page));
+    std::fs::create_dir_all(&dir)?;
+    let output_path = dir.join("page.xml");
+    let page_arg = page.to_string();
+    let status = Command::new("pdfalto")
+        .args(["-f", &page_arg, "-l", &page_arg, "-noLineNumbers", pdf_path])
+        .arg(&output_path)
+        .status()?;
+    let xml = std::fs::read_to_string(&output_path).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&dir);
+    if !status.success() {
+        return Err("pdfalto failed".into());
+    }
+    Ok(parse(&xml, page))
+}
+
+/// Figure crops for a page, placed like its elements: pdfalto's unrotated boxes turned by the
+/// page's /Rotate and the view rotation, with redacted areas painted black
+pub fn load_page(pdf_path: &str, page: usize, view_rotation: Rotation, redacted: &[egui::Rect])
+    -> Result<Vec<(Figure, egui::ColorImage)>, Box<dyn std::error::Error>> {
+    let found = detect(pdf_path, page)?;
+    if found.is_empty() {
+        return Ok(Vec::new());
+    }
+    let raster = tile_cache::render_page(pdf_path, page, FIGURE_DPI)?;
+    let detected = rotation::page_geometry(pdf_path, page).map_or(Rotation::None, |(detected, _)| detected);
+    // The raster is already upright, so its size is the displayed page
+    let scale = FIGURE_DPI / 72.0;
+    let displayed = (raster.size[0] as f32 / scale, raster.size[1] as f32 / scale);
+    let unrotated = rotation::rotated_size(displayed, detected);
+    Ok(found.into_iter()
+        .filter_map(|figure| {
+            let upright = rotation::rotate_rect(figure.rect, detected, unrotated);
+            let mut image = rotate_image(crop(&raster, upright, FIGURE_DPI)?, view_rotation);
+            let rect = rotation::rotate_rect(upright, view_rotation, displayed);
+            redact(&mut image, rect, redacted, FIGURE_DPI);
+            Some((Figure { page, rect }, image))
+        })
+        .collect())
+}
+
+/// Illustration elements, plus GraphicalElements big enough to be pictures, in points
+pub fn parse(xml: &str, page: usize) -> Vec<Figure> {
+    let mut reader = Reader::from_str(xml);
+    let mut figures = Vec::new();
+    let mut source_units = units::SourceUnits::default();
+    let mut in_unit = false;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                match e.name().as_ref() {
+                    b"MeasurementUnit" => in_unit = true,
+                    b"Page" => source_units.dpi = units::page_dpi(&e).unwrap_or(source_units.dpi),
+                    _ => {}
+                }
+                let illustration = e.name().as_ref() == b"Illustration";
+                if !illustration && e.name().as_ref() != b"GraphicalElement" {
+                    continue;
+                }
+                let mut bounds = [0.0f32; 4];
+                for attr in e.attributes().flatten() {
+                    let slot = match attr.key.as_ref() {
+                        b"HPOS" => 0,
+                        b"VPOS" => 1,
+                        b"WIDTH" => 2,
+                        b"HEIGHT" => 3,
+                        _ => continue,
+                    };
+                    bounds[slot] = String::from_utf8_lossy(&attr.value).parse().unwrap_or(0.0);
+                }
+                let [x, y, width, height] = bounds.map(|value| value * source_units.points_per_unit());
+                let big_enough = width >= MIN_GRAPHIC_SIZE && height >= MIN_GRAPHIC_SIZE;
+                if (illustration && width > 0.0 && height > 0.0) || big_enough {
+                    figures.push(Figure { page, rect: egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(width, height)) });
+                }
+            }
+            Ok(Event::Text(text)) if in_unit => {
+                source_units.unit = units::MeasurementUnit::from_name(&String::from_utf8_lossy(&text));
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"MeasurementUnit" => in_unit = false,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    figures
+}
+
+/// The figure's part of a page raster rendered at `dpi`
+pub fn crop(page: &egui::ColorImage, rect: egui::Rect, dpi: f32) -> Option<egui::ColorImage> {
+    let scale = dpi / 72.0;
+    let [page_width, page_height] = page.size;
+    let x0 = ((rect.min.x * scale).max(0.0) as usize).min(page_width);
+    let y0 = ((rect.min.y * scale).max(0.0) as usize).min(page_height);
+    let x1 = ((rect.max.x * scale).ceil().max(0.0) as usize).min(page_width);
+    let y1 = ((rect.max.y * scale).ceil().max(0.0) as usize).min(page_height);
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+    let mut pixels = Vec::with_capacity((x1 - x0) * (y1 - y0));
+    for y in y0..y1 {
+        pixels.extend_from_slice(&page.pixels[y * page_width + x0..y * page_width + x1]);
+    }
+    Some(egui::ColorImage { size: [x1 - x0, y1 - y0], pixels })
+}
+
+/// An image turned clockwise by `rotation`
+pub fn rotate_image(image: egui::ColorImage, rotation: Rotation) -> egui::ColorImage {
+    let [width, height] = image.size;
+    let size = match rotation {
+        Rotation::None => return image,
+        Rotation::Cw180 => [width, height],
+        Rotation::Cw90 | Rotation::Cw270 => [height, width],
+    };
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..size[1] {
+        for x in 0..size[0] {
+            let (from_x, from_y) = match rotation {
+                Rotation::Cw90 => (y, height - 1 - x),
+                Rotation::Cw180 => (width - 1 - x, height - 1 - y),
+                _ => (width - 1 - y, x),
+            };
+            pixels.push(image.pixels[from_y * width + from_x]);
+        }
+    }
+    egui::ColorImage { size, pixels }
+}
+
+/// Paint the parts of redacted rectangles that fall on a figure placed at `rect` black
+fn redact(image: &mut egui::ColorImage, rect: egui::Rect, redacted: &[egui::Rect], dpi: f32) {
+    let scale = dpi / 72.0;
+    let [width, height] = image.size;
+    for covered in redacted.iter().map(|r| r.intersect(rect)).filter(|r| r.is_positive()) {
+        let x0 = (((covered.min.x - rect.min.x) * scale).floor().max(0.0) as usize).min(width);
+        let x1 = (((covered.max.x - rect.min.x) * scale).ceil().max(0.0) as usize).min(width);
+        let y0 = (((covered.min.y - rect.min.y) * scale).floor().max(0.0) as usize).min(height);
+        let y1 = (((covered.max.y - rect.min.y) * scale).ceil().max(0.0) as usize).min(height);
+        for y in y0..y1 {
+            image.pixels[y * width + x0..y * width + x1].fill(egui::Color32::BLACK);
+        }
+    }
+}
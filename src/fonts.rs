@@ -0,0 +1,172 @@
+// fonts.rs - Editor font family and fallback chain (from cosmic-text's font database) and text size
+use cosmic_text::fontdb;
+use eframe::egui;
+
+pub const MIN_SIZE: f32 = 6.0;
+pub const MAX_SIZE: f32 = 48.0;
+pub const DEFAULT_SIZE: f32 = 12.0;
+const SIZE_STEP: f32 = 1.0;
+
+/// Chosen family, size and fallbacks: `family=...`, `size=...`, `fallback=A, B, ...` lines
+pub const SETTINGS_FILE: &str = "chonker9_font.txt";
+
+/// Tried in order for glyphs the editor font lacks (Greek, Cyrillic, math, CJK, symbols);
+/// families that aren't installed are skipped
+pub const DEFAULT_FALLBACKS: &[&str] = &[
+    "DejaVu Sans",
+    "Noto Sans",
+    "Noto Sans Math",
+    "STIX Two Math",
+    "Cambria Math",
+    "Noto Sans Symbols",
+    "Noto Sans Symbols 2",
+    "Segoe UI Symbol",
+    "Noto Sans CJK JP",
+    "Noto Sans Arabic",
+    "Noto Sans Hebrew",
+    "Arial Unicode MS",
+];
+
+/// egui family name the picked font is registered under
+pub const EDITOR_FAMILY: &str = "chonker9-editor";
+
+/// Editor text settings, independent of canvas zoom
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorFont {
+    pub family: Option<String>,   // None = egui's built-in fonts
+    pub size: f32,
+    pub fallbacks: Vec<String>,
+}
+
+impl Default for EditorFont {
+    fn default() -> Self {
+        Self {
+            family: None,
+            size: DEFAULT_SIZE,
+            fallbacks: DEFAULT_FALLBACKS.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+impl EditorFont {
+    pub fn load() -> Self {
+        let mut font = Self::default();
+        let content = std::fs::read_to_string(SETTINGS_FILE).unwrap_or_default();
+        for line in content.lines() {
+            match line.split_once('=') {
+                Some(("family", value)) if !value.trim().is_empty() => font.family = Some(value.trim().to_string()),
+                Some(("size", value)) => {
+                    if let Ok(size) = value.trim().parse::<f32>() {
+                        font.size = size.clamp(MIN_SIZE, MAX_SIZE);
+                    }
+                }
+                Some(("fallback", value)) => {
+                    font.fallbacks = value.split(',').map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect();
+                }
+                _ => {}
+            }
+        }
+        font
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let content = format!("family={}\nsize={}\nfallback={}\n",
+                              self.family.as_deref().unwrap_or(""), self.size, self.fallbacks.join(", "));
+        std::fs::write(SETTINGS_FILE, content)
+    }
+
+    /// Ctrl+= / Ctrl+- step; returns whether the size changed
+    pub fn step(&mut self, larger: bool) -> bool {
+        let size = if larger { self.size + SIZE_STEP } else { self.size - SIZE_STEP };
+        let size = size.clamp(MIN_SIZE, MAX_SIZE);
+        let changed = size != self.size;
+        self.size = size;
+        changed
+    }
+}
+
+/// Installed font families, loaded once on demand (scanning the system takes a moment)
+pub struct FontCatalog {
+    db: fontdb::Database,
+    pub families: Vec<(String, bool)>,   // (family, monospaced)
+}
+
+impl FontCatalog {
+    pub fn load_system() -> Self {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+        let mut families: Vec<(String, bool)> = Vec::new();
+        for face in db.faces() {
+            if let Some((name, _)) = face.families.first() {
+                match families.iter_mut().find(|(family, _)| family == name) {
+                    Some(entry) => entry.1 |= face.monospaced,
+                    None => families.push((name.clone(), face.monospaced)),
+                }
+            }
+        }
+        families.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+        Self { db, families }
+    }
+
+    /// Regular face of a family if there is one, otherwise any face
+    fn face_data(&self, family: &str) -> Option<(Vec<u8>, u32)> {
+        let faces: Vec<&fontdb::FaceInfo> = self.db.faces()
+            .filter(|face| face.families.iter().any(|(name, _)| name == family))
+            .collect();
+        let face = faces.iter()
+            .find(|face| face.style == fontdb::Style::Normal && face.weight == fontdb::Weight::NORMAL)
+            .or(faces.first())?;
+        self.db.with_face_data(face.id, |data, index| (data.to_vec(), index))
+    }
+}
+
+fn add_font_data(fonts: &mut egui::FontDefinitions, catalog: &FontCatalog, family: &str) -> bool {
+    match catalog.face_data(family) {
+        Some((data, index)) => {
+            let mut font_data = egui::FontData::from_owned(data);
+            font_data.index = index;
+            fonts.font_data.insert(family.to_string(), font_data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Register the editor family with egui: the picked font first, built-in monospace next,
+/// then every installed fallback (also appended to egui's own families so panels get them).
+/// Returns the fallbacks that were found.
+pub fn install(ctx: &egui::Context, catalog: Option<&FontCatalog>, family: Option<&str>,
+               fallbacks: &[String]) -> Result<Vec<String>, String> {
+    let mut fonts = egui::FontDefinitions::default();
+    let mut chain = fonts.families.get(&egui::FontFamily::Monospace).cloned().unwrap_or_default();
+    if let Some(family) = family {
+        let catalog = catalog.ok_or("font catalog not loaded")?;
+        if !add_font_data(&mut fonts, catalog, family) {
+            return Err(format!("font family '{}' not found", family));
+        }
+        chain.insert(0, family.to_string());
+    }
+    let mut installed = Vec::new();
+    if let Some(catalog) = catalog {
+        for fallback in fallbacks {
+            if Some(fallback.as_str()) != family && add_font_data(&mut fonts, catalog, fallback) {
+                installed.push(fallback.clone());
+            }
+        }
+    }
+    chain.extend(installed.iter().cloned());
+    for builtin in [egui::FontFamily::Monospace, egui::FontFamily::Proportional] {
+        fonts.families.entry(builtin).or_default().extend(installed.iter().cloned());
+    }
+    fonts.families.insert(egui::FontFamily::Name(EDITOR_FAMILY.into()), chain);
+    ctx.set_fonts(fonts);
+    Ok(installed)
+}
+
+/// Distinct printable chars of `text` that no font in `font`'s chain can draw
+pub fn missing_glyphs(ctx: &egui::Context, font: &egui::FontId, text: impl Iterator<Item = char>) -> Vec<char> {
+    let mut chars: Vec<char> = text.filter(|c| !c.is_whitespace() && !c.is_control()).collect();
+    chars.sort_unstable();
+    chars.dedup();
+    ctx.fonts(|f| chars.into_iter().filter(|&c| !f.has_glyph(font, c)).collect())
+}
@@ -0,0 +1,116 @@
+// garbled.rs - Statistical checks for text that is unlikely to be real words
+use crate::cjk;
+
+/// Why an element's text looks like an extraction failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GarbleReason {
+    NoVowels,         // Long Latin word without a vowel
+    ImprobableLetters, // Letter pairs or consonant runs that don't occur in words
+    MixedScripts,     // Latin, Greek and Cyrillic letters in one word
+    SymbolDense,      // Mostly symbols rather than letters, digits or punctuation
+}
+
+impl GarbleReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GarbleReason::NoVowels => "no vowels",
+            GarbleReason::ImprobableLetters => "improbable letters",
+            GarbleReason::MixedScripts => "mixed scripts",
+            GarbleReason::SymbolDense => "symbol density",
+        }
+    }
+}
+
+const VOWELS: &str = "aeiouyàáâãäåæèéêëìíîïòóôõöøùúûüýÿœ";
+
+/// Letter pairs (lowercase) that practically never occur inside English or Romance words
+const RARE_BIGRAMS: &[&str] = &[
+    "bx", "cj", "cx", "dx", "fq", "fx", "fz", "gq", "gx", "hx", "jb", "jc", "jd", "jf", "jg", "jh",
+    "jk", "jl", "jm", "jq", "jv", "jw", "jx", "jz", "kq", "kx", "kz", "lx", "mx", "px", "pz", "qb",
+    "qc", "qd", "qf", "qg", "qh", "qj", "qk", "ql", "qm", "qn", "qp", "qq", "qr", "qs", "qt", "qv",
+    "qw", "qx", "qy", "qz", "sx", "tq", "vb", "vf", "vh", "vj", "vk", "vm", "vp", "vq", "vw", "vx",
+    "vz", "wq", "wx", "xj", "xk", "xq", "xz", "yq", "yx", "zf", "zj", "zq", "zx",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+}
+
+fn script(c: char) -> Option<Script> {
+    match c as u32 {
+        0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F => Some(Script::Latin),
+        0x370..=0x3FF => Some(Script::Greek),
+        0x400..=0x4FF => Some(Script::Cyrillic),
+        _ => None,
+    }
+}
+
+fn no_vowels(word: &str) -> bool {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    // Short all-caps tokens are usually acronyms (NYC, HTML5, PDF)
+    let acronym = letters.len() <= 5 && letters.iter().all(|c| c.is_uppercase());
+    letters.len() >= 4 && !acronym
+        && letters.iter().all(|&c| script(c) == Some(Script::Latin))
+        && !letters.iter().any(|c| c.to_lowercase().any(|l| VOWELS.contains(l)))
+}
+
+fn improbable_letters(word: &str) -> bool {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_lowercase()).collect();
+    if letters.len() < 4 {
+        return false;
+    }
+    let rare = letters.windows(2)
+        .filter(|pair| RARE_BIGRAMS.contains(&pair.iter().collect::<String>().as_str()))
+        .count();
+    let mut run = 0;
+    let mut longest_run = 0;
+    for &c in &letters {
+        run = if VOWELS.contains(c) { 0 } else { run + 1 };
+        longest_run = longest_run.max(run);
+    }
+    rare >= 2 || (rare == 1 && letters.len() <= 6) || longest_run >= 6
+}
+
+fn mixed_scripts(word: &str) -> bool {
+    let mut seen: Option<Script> = None;
+    for script in word.chars().filter_map(script) {
+        match seen {
+            Some(previous) if previous != script => return true,
+            _ => seen = Some(script),
+        }
+    }
+    false
+}
+
+fn symbol_dense(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.len() < 3 {
+        return false;
+    }
+    let symbols = chars.iter()
+        .filter(|&&c| !c.is_alphanumeric() && !cjk::is_wide(c) && !".,;:'\"-()[]!?%$€£¥&/#*+=@".contains(c))
+        .count();
+    symbols * 5 > chars.len() * 2
+}
+
+/// Every reason `text` looks garbled; empty when it reads like words, numbers or punctuation
+pub fn detect(text: &str) -> Vec<GarbleReason> {
+    let mut reasons = Vec::new();
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.iter().any(|w| no_vowels(w)) {
+        reasons.push(GarbleReason::NoVowels);
+    }
+    if words.iter().any(|w| improbable_letters(w)) {
+        reasons.push(GarbleReason::ImprobableLetters);
+    }
+    if words.iter().any(|w| mixed_scripts(w)) {
+        reasons.push(GarbleReason::MixedScripts);
+    }
+    if symbol_dense(text) {
+        reasons.push(GarbleReason::SymbolDense);
+    }
+    reasons
+}
@@ -0,0 +1,81 @@
+// glyph_map.rs - Per-document glyph-to-Unicode substitutions for broken PDF encodings
+use std::path::PathBuf;
+
+/// Characters substituted at parse time, persisted as `U+XXXX<TAB>replacement` lines
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlyphMap {
+    pub entries: Vec<(char, String)>,
+}
+
+impl GlyphMap {
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-glyphmap", pdf_path))
+    }
+
+    pub fn load(pdf_path: &str) -> Self {
+        let content = std::fs::read_to_string(Self::sidecar_path(pdf_path)).unwrap_or_default();
+        let entries = content.lines()
+            .filter_map(|line| {
+                let (source, replacement) = line.split_once('\t')?;
+                Some((parse_char(source)?, replacement.to_string()))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Write the table, removing the sidecar when it is empty
+    pub fn save(&self, pdf_path: &str) -> std::io::Result<()> {
+        let path = Self::sidecar_path(pdf_path);
+        if self.entries.is_empty() {
+            return match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        let content: String = self.entries.iter()
+            .map(|(source, replacement)| format!("U+{:04X}\t{}\n", *source as u32, replacement))
+            .collect();
+        std::fs::write(path, content)
+    }
+
+    /// Add or update a substitution
+    pub fn set(&mut self, source: char, replacement: &str) {
+        match self.entries.iter_mut().find(|(c, _)| *c == source) {
+            Some(entry) => entry.1 = replacement.to_string(),
+            None => self.entries.push((source, replacement.to_string())),
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        if self.entries.is_empty() {
+            return text.to_string();
+        }
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            match self.entries.iter().find(|(source, _)| *source == c) {
+                Some((_, replacement)) => result.push_str(replacement),
+                None => result.push(c),
+            }
+        }
+        result
+    }
+}
+
+/// A single character (a space included), or a codepoint written as `U+XXXX`
+pub fn parse_char(input: &str) -> Option<char> {
+    let code = input.trim();
+    if let Some(hex) = code.strip_prefix("U+").or_else(|| code.strip_prefix("u+")) {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+    let mut chars = input.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Characters that usually mean a broken font encoding: private use area, ligature
+/// presentation forms, and symbol-font ranges
+pub fn is_suspicious(c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0x10FFFF | 0xFB00..=0xFB06 | 0xFFFD) || (c.is_control() && !c.is_whitespace())
+}
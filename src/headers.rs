@@ -0,0 +1,113 @@
+// headers.rs - Running header, footer and page number detection
+use crate::SpatialElement;
+use std::collections::HashSet;
+
+/// Fraction of a page's text extent treated as the header / footer band
+const BAND_FRACTION: f32 = 0.08;
+
+/// A line (element indices) inside the top or bottom band of its page
+struct BandLine {
+    page: usize,
+    vpos: f32,
+    indices: Vec<usize>,
+    signature: String,  // Text with digits folded, so "Page 3" matches "Page 4"
+}
+
+/// Whether a whole token is a well-formed lowercase roman numeral below 400 ("xiv", not "civil",
+/// "iiii" or words like "mix" that a d or m would let through)
+fn is_roman(token: &str) -> bool {
+    const NUMERALS: [(u32, &str); 9] = [(100, "c"), (90, "xc"), (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i")];
+    let digit = |c: char| NUMERALS.iter().find(|(_, n)| n.len() == 1 && n.starts_with(c)).map(|&(v, _)| v);
+    let values: Option<Vec<u32>> = token.chars().map(digit).collect();
+    let Some(values) = values.filter(|v| !v.is_empty()) else { return false };
+    let mut total = 0;
+    for (i, &value) in values.iter().enumerate() {
+        if values.get(i + 1).is_some_and(|&next| next > value) {
+            total -= value as i64;
+        } else {
+            total += value as i64;
+        }
+    }
+    // Only the canonical spelling of the value counts
+    let mut rest = total.max(0) as u32;
+    let mut canonical = String::new();
+    for (value, numeral) in NUMERALS {
+        while rest >= value {
+            canonical.push_str(numeral);
+            rest -= value;
+        }
+    }
+    (1..400).contains(&total) && canonical == token
+}
+
+/// Lone page numbers: "12", "- 12 -", "Page 12", "12 of 40", roman numerals
+fn is_page_number(text: &str) -> bool {
+    let lower = text.trim().trim_matches('-').trim().to_lowercase();
+    let lower = lower.strip_prefix("page")
+        .filter(|rest| rest.starts_with(|c: char| c.is_whitespace() || c.is_ascii_digit()))
+        .map(|rest| rest.trim())
+        .unwrap_or(&lower);
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    match lower.split_once(" of ") {
+        Some((page, total)) => is_number(page.trim()) && is_number(total.trim()),
+        None => is_number(lower) || is_roman(lower),
+    }
+}
+
+fn band_lines(elements: &[SpatialElement]) -> Vec<BandLine> {
+    let mut pages: Vec<usize> = elements.iter().map(|e| e.page).collect();
+    pages.sort();
+    pages.dedup();
+
+    let mut result = Vec::new();
+    for page in pages {
+        let indices: Vec<usize> = (0..elements.len()).filter(|&i| elements[i].page == page).collect();
+        let top = indices.iter().map(|&i| elements[i].vpos).fold(f32::MAX, f32::min);
+        let bottom = indices.iter().map(|&i| elements[i].vpos).fold(0.0, f32::max);
+        let band = (bottom - top) * BAND_FRACTION;
+
+        // Cluster band elements into lines (within 8 pixels vertically)
+        let mut lines: Vec<Vec<usize>> = Vec::new();
+        for &i in &indices {
+            let vpos = elements[i].vpos;
+            if vpos > top + band && vpos < bottom - band {
+                continue;
+            }
+            match lines.iter_mut().find(|line| (elements[line[0]].vpos - vpos).abs() < 8.0) {
+                Some(line) => line.push(i),
+                None => lines.push(vec![i]),
+            }
+        }
+
+        for mut line in lines {
+            line.sort_by(|&a, &b| elements[a].hpos.partial_cmp(&elements[b].hpos).unwrap());
+            let text = line.iter().map(|&i| elements[i].content.as_str()).collect::<Vec<&str>>().join(" ");
+            let signature = text.chars().map(|c| if c.is_ascii_digit() { '#' } else { c.to_ascii_lowercase() }).collect();
+            result.push(BandLine { page, vpos: elements[line[0]].vpos, indices: line, signature });
+        }
+    }
+    result
+}
+
+/// Indices of elements that are running headers/footers or page numbers.
+/// A band line counts when it repeats (same band, same folded text) on at
+/// least half the pages, or is a bare page number.
+pub fn detect(elements: &[SpatialElement]) -> HashSet<usize> {
+    let lines = band_lines(elements);
+    let page_count = lines.iter().map(|l| l.page).collect::<HashSet<usize>>().len();
+
+    let mut running = HashSet::new();
+    for line in &lines {
+        let text = line.indices.iter().map(|&i| elements[i].content.as_str()).collect::<Vec<&str>>().join(" ");
+        let repeats = lines.iter()
+            .filter(|other| other.signature == line.signature && (other.vpos - line.vpos).abs() < 5.0)
+            .map(|other| other.page)
+            .collect::<HashSet<usize>>()
+            .len();
+        let is_running = page_count >= 2 && repeats * 2 >= page_count && repeats >= 2;
+        if is_running || is_page_number(&text) {
+            running.extend(line.indices.iter().copied());
+        }
+    }
+    running
+}
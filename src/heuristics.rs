@@ -0,0 +1,180 @@
+// heuristics.rs - Tunable thresholds for line, paragraph and spacing reconstruction
+use crate::SpatialElement;
+use std::path::PathBuf;
+
+/// Defaults for every document, one `key=value` per line
+pub const SETTINGS_FILE: &str = "chonker9_heuristics.txt";
+
+/// Typical advance of a text glyph, as a fraction of the font size
+const AVERAGE_ADVANCE_EM: f32 = 0.5;
+
+/// Word spacing of the editor's own text paths (readable text, positioned paragraphs,
+/// spaced elements): gaps over 3px become one space per 8px, 1 to 10 of them
+pub const EDITOR_WIDE_GAP: f32 = 3.0;
+pub const EDITOR_SPACE_WIDTH: f32 = 8.0;
+pub const EDITOR_MIN_SPACES: usize = 1;
+pub const EDITOR_MAX_SPACES: usize = 10;
+
+/// Layout reconstruction thresholds, in ALTO units (px)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heuristics {
+    pub line_tolerance: f32,     // Max vertical offset for words on one line
+    pub section_gap: f32,        // Vertical gap that starts a new paragraph/section
+    pub blank_line_height: f32,  // Vertical gap per extra blank line at a section break
+    pub wide_gap: f32,           // Horizontal gap above which a run of spaces is inferred
+    pub space_width: f32,        // Horizontal px per inferred space
+    pub max_spaces: usize,       // Cap on an inferred run
+    pub min_spaces: usize,       // Floor on an inferred run; not saved, each path keeps its own
+    pub use_font_metrics: bool,  // Scale spacing by each word's font size or measured advance
+}
+
+impl Default for Heuristics {
+    fn default() -> Self {
+        Self {
+            line_tolerance: 8.0,
+            section_gap: 15.0,
+            blank_line_height: 12.0,
+            wide_gap: 6.0,
+            space_width: 6.0,
+            max_spaces: 8,
+            min_spaces: 2,
+            use_font_metrics: true,
+        }
+    }
+}
+
+impl Heuristics {
+    /// (key, label, range) for each float setting, in display order
+    pub const FIELDS: [(&'static str, &'static str, std::ops::RangeInclusive<f32>); 5] = [
+        ("line_tolerance", "Line tolerance", 1.0..=30.0),
+        ("section_gap", "Section gap", 5.0..=60.0),
+        ("blank_line_height", "Blank line height", 4.0..=40.0),
+        ("wide_gap", "Wide gap", 1.0..=30.0),
+        ("space_width", "Space width", 2.0..=20.0),
+    ];
+
+    pub fn field_mut(&mut self, key: &str) -> Option<&mut f32> {
+        match key {
+            "line_tolerance" => Some(&mut self.line_tolerance),
+            "section_gap" => Some(&mut self.section_gap),
+            "blank_line_height" => Some(&mut self.blank_line_height),
+            "wide_gap" => Some(&mut self.wide_gap),
+            "space_width" => Some(&mut self.space_width),
+            _ => None,
+        }
+    }
+
+    /// Average advance of one char of `element`: half its ALTO font size, else its width
+    /// spread over its chars, else the fixed `space_width`
+    pub fn char_width(&self, element: &SpatialElement) -> f32 {
+        if !self.use_font_metrics {
+            return self.space_width;
+        }
+        if let Some(size) = element.font_size.filter(|&size| size > 0.0) {
+            return size * AVERAGE_ADVANCE_EM;
+        }
+        let chars = element.content.chars().count();
+        if chars > 0 && element.width > 0.0 {
+            element.width / chars as f32
+        } else {
+            self.space_width
+        }
+    }
+
+    /// Spaces standing in for the horizontal gap between two neighbouring words; with font
+    /// metrics the thresholds scale with the words' char width, so small print and large
+    /// headings reconstruct alike
+    pub fn spaces_between(&self, left: &SpatialElement, right: &SpatialElement) -> usize {
+        let gap = right.hpos - (left.hpos + left.width);
+        let char_width = (self.char_width(left) + self.char_width(right)) / 2.0;
+        let scale = char_width / self.space_width;
+        if gap > self.wide_gap * scale {
+            ((gap / char_width) as usize).clamp(self.min_spaces, self.max_spaces.max(self.min_spaces))
+        } else {
+            1
+        }
+    }
+
+    /// These settings for the editor's text paths: spacing left at the pipeline defaults
+    /// falls back to the editor's own, tuned values apply to both
+    pub fn for_editor(&self) -> Self {
+        let defaults = Self::default();
+        let pick = |value: f32, default: f32, editor: f32| if value == default { editor } else { value };
+        Self {
+            wide_gap: pick(self.wide_gap, defaults.wide_gap, EDITOR_WIDE_GAP),
+            space_width: pick(self.space_width, defaults.space_width, EDITOR_SPACE_WIDTH),
+            max_spaces: if self.max_spaces == defaults.max_spaces { EDITOR_MAX_SPACES } else { self.max_spaces },
+            min_spaces: EDITOR_MIN_SPACES,
+            ..*self
+        }
+    }
+
+    /// Blank lines standing in for a vertical gap at a section break
+    pub fn blank_lines_for_gap(&self, gap: f32) -> usize {
+        ((gap / self.blank_line_height) as usize).clamp(1, 3)
+    }
+
+    /// Global defaults, then the document's overrides on top
+    pub fn load(pdf_path: &str) -> Self {
+        let mut heuristics = Self::load_defaults();
+        if !pdf_path.is_empty() {
+            heuristics.apply(&std::fs::read_to_string(Self::sidecar_path(pdf_path)).unwrap_or_default());
+        }
+        heuristics
+    }
+
+    pub fn load_defaults() -> Self {
+        let mut heuristics = Self::default();
+        heuristics.apply(&std::fs::read_to_string(SETTINGS_FILE).unwrap_or_default());
+        heuristics
+    }
+
+    pub fn has_override(pdf_path: &str) -> bool {
+        Self::sidecar_path(pdf_path).exists()
+    }
+
+    pub fn save_defaults(&self) -> std::io::Result<()> {
+        std::fs::write(SETTINGS_FILE, self.serialize())
+    }
+
+    pub fn save_override(&self, pdf_path: &str) -> std::io::Result<()> {
+        std::fs::write(Self::sidecar_path(pdf_path), self.serialize())
+    }
+
+    pub fn clear_override(pdf_path: &str) -> std::io::Result<()> {
+        match std::fs::remove_file(Self::sidecar_path(pdf_path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-heuristics", pdf_path))
+    }
+
+    fn apply(&mut self, content: &str) {
+        for line in content.lines() {
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+            if key == "use_font_metrics" {
+                self.use_font_metrics = value == "true";
+            } else if key == "max_spaces" {
+                if let Ok(max) = value.parse() {
+                    self.max_spaces = max;
+                }
+            } else if let (Some(field), Ok(value)) = (self.field_mut(key), value.parse::<f32>()) {
+                *field = value;
+            }
+        }
+    }
+
+    fn serialize(&self) -> String {
+        format!(
+            "line_tolerance={}\nsection_gap={}\nblank_line_height={}\nwide_gap={}\nspace_width={}\nmax_spaces={}\nuse_font_metrics={}\n",
+            self.line_tolerance, self.section_gap, self.blank_line_height, self.wide_gap, self.space_width, self.max_spaces,
+            self.use_font_metrics,
+        )
+    }
+}
@@ -0,0 +1,114 @@
+// iiif.rs - IIIF manifests as page underlays, corrections out as Web Annotations
+use eframe::egui;
+use serde_json::{json, Value};
+use std::process::Command;
+
+/// A page of a IIIF manifest with its full-size image
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    pub id: String,
+    pub width: f32,
+    pub height: f32,
+    pub image_url: String,
+}
+
+/// One correction or comment to publish against a canvas
+pub struct CanvasNote {
+    pub rect: egui::Rect, // PDF points
+    pub text: String,
+    pub commenting: bool, // Comment rather than a transcription correction
+}
+
+/// Read a manifest from a URL (via curl) or a local file
+pub fn load_manifest(source: &str) -> Result<Vec<Canvas>, Box<dyn std::error::Error>> {
+    let json = if source.starts_with("http://") || source.starts_with("https://") {
+        let output = Command::new("curl").args(["-sS", "--fail", "-L", source]).output()?;
+        if !output.status.success() {
+            return Err(format!("fetching manifest failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+        }
+        String::from_utf8(output.stdout)?
+    } else {
+        std::fs::read_to_string(source)?
+    };
+    let manifest: Value = serde_json::from_str(&json)?;
+    let canvases = parse_canvases(&manifest);
+    if canvases.is_empty() {
+        return Err("manifest has no canvases with images".into());
+    }
+    Ok(canvases)
+}
+
+/// Canvases from a Presentation 3 (`items`) or 2 (`sequences`) manifest
+fn parse_canvases(manifest: &Value) -> Vec<Canvas> {
+    let size = |canvas: &Value| (
+        canvas["width"].as_f64().unwrap_or(0.0) as f32,
+        canvas["height"].as_f64().unwrap_or(0.0) as f32,
+    );
+    if let Some(items) = manifest["items"].as_array() {
+        items.iter()
+            .filter_map(|canvas| {
+                let (width, height) = size(canvas);
+                let image_url = canvas["items"][0]["items"][0]["body"]["id"].as_str()?.to_string();
+                Some(Canvas { id: canvas["id"].as_str()?.to_string(), width, height, image_url })
+            })
+            .collect()
+    } else {
+        manifest["sequences"][0]["canvases"].as_array().into_iter().flatten()
+            .filter_map(|canvas| {
+                let (width, height) = size(canvas);
+                let image_url = canvas["images"][0]["resource"]["@id"].as_str()?.to_string();
+                Some(Canvas { id: canvas["@id"].as_str()?.to_string(), width, height, image_url })
+            })
+            .collect()
+    }
+}
+
+/// Download a canvas image and decode it (ImageMagick converts whatever the server sends to PPM)
+pub fn fetch_image(canvas: &Canvas) -> Result<egui::ColorImage, Box<dyn std::error::Error>> {
+    let download = crate::temp_path("chonker9_iiif_image");
+    let status = Command::new("curl")
+        .args(["-sS", "--fail", "-L", "-o"])
+        .arg(&download)
+        .arg(&canvas.image_url)
+        .status()?;
+    if !status.success() {
+        return Err("fetching canvas image failed".into());
+    }
+    let convert = |program: &str| Command::new(program).arg(&download).arg("ppm:-").output();
+    let output = convert("magick").or_else(|_| convert("convert"));
+    let _ = std::fs::remove_file(&download);
+    let output = output?;
+    if !output.status.success() {
+        return Err("ImageMagick could not decode the canvas image".into());
+    }
+    crate::tile_cache::parse_ppm(&output.stdout)
+}
+
+/// AnnotationPage of Web Annotations; rectangles are scaled from PDF points to canvas pixels
+pub fn annotation_page(canvas: &Canvas, page_size: egui::Vec2, notes: &[CanvasNote]) -> Value {
+    let scale = egui::vec2(canvas.width / page_size.x.max(1.0), canvas.height / page_size.y.max(1.0));
+    let items: Vec<Value> = notes.iter().enumerate()
+        .map(|(i, note)| {
+            let (x, y) = (note.rect.min.x * scale.x, note.rect.min.y * scale.y);
+            let (w, h) = (note.rect.width() * scale.x, note.rect.height() * scale.y);
+            json!({
+                "id": format!("{}/annotation/chonker9-{}", canvas.id, i + 1),
+                "type": "Annotation",
+                "motivation": if note.commenting { "commenting" } else { "supplementing" },
+                "body": {
+                    "type": "TextualBody",
+                    "value": note.text,
+                    "format": "text/plain",
+                    "purpose": if note.commenting { "commenting" } else { "transcribing" },
+                },
+                "target": format!("{}#xywh={:.0},{:.0},{:.0},{:.0}", canvas.id, x, y, w, h),
+            })
+        })
+        .collect();
+    json!({
+        "@context": "http://iiif.io/api/presentation/3/context.json",
+        "id": format!("{}/annotations/chonker9", canvas.id),
+        "type": "AnnotationPage",
+        "items": items,
+    })
+}
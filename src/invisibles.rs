@@ -0,0 +1,126 @@
+// invisibles.rs - Visible marks for spaces, tabs and line breaks, telling real characters from inferred spacing
+use crate::scripts::{self, ScriptPosition};
+use eframe::egui;
+use egui::text::LayoutJob;
+use std::collections::HashSet;
+use std::ops::Range;
+
+pub const SPACE: char = '·';
+pub const INFERRED_SPACE: char = '␣';   // Spacing the layout heuristic made up from a gap
+pub const TAB: char = '→';
+pub const NEWLINE: char = '¶';
+
+/// How a painted char should look
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Text,
+    Mark,
+    Inferred,
+}
+
+/// Mark standing in for a whitespace char; None for everything else
+fn mark(c: char, inferred: bool) -> Option<char> {
+    match c {
+        ' ' | '\u{00A0}' if inferred => Some(INFERRED_SPACE),
+        ' ' | '\u{00A0}' => Some(SPACE),
+        '\t' => Some(TAB),
+        '\n' => Some(NEWLINE),
+        _ => None,
+    }
+}
+
+/// Plain-string version for single-color painting; a trailing separator space is marked as inferred
+pub fn show(text: &str, trailing_inferred: bool) -> String {
+    let count = text.chars().count();
+    text.chars().enumerate()
+        .map(|(i, c)| mark(c, trailing_inferred && i + 1 == count).unwrap_or(c))
+        .collect()
+}
+
+/// Colors for text, real whitespace and inferred spacing
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub text: egui::Color32,
+    pub mark: egui::Color32,
+    pub inferred: egui::Color32,
+}
+
+/// Builds a layout job char by char, merging neighbours of the same kind and script position into one section
+struct JobBuilder {
+    job: LayoutJob,
+    font: egui::FontId,
+    palette: Palette,
+    pending: String,
+    kind: Kind,
+    position: ScriptPosition,
+}
+
+impl JobBuilder {
+    fn new(font: egui::FontId, palette: Palette) -> Self {
+        Self { job: LayoutJob::default(), font, palette, pending: String::new(), kind: Kind::Text, position: ScriptPosition::Normal }
+    }
+
+    fn push(&mut self, c: char, inferred: bool, position: ScriptPosition) {
+        let (shown, kind) = match mark(c, inferred) {
+            Some(m) => (m, if inferred { Kind::Inferred } else { Kind::Mark }),
+            None => (c, Kind::Text),
+        };
+        if kind != self.kind || position != self.position {
+            self.flush();
+            self.kind = kind;
+            self.position = position;
+        }
+        self.pending.push(shown);
+        if c == '\n' {
+            self.pending.push('\n');
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let color = match self.kind {
+            Kind::Text => self.palette.text,
+            Kind::Mark => self.palette.mark,
+            Kind::Inferred => self.palette.inferred,
+        };
+        let text = std::mem::take(&mut self.pending);
+        self.job.append(&text, 0.0, scripts::text_format(self.position, &self.font, color));
+    }
+
+    fn finish(mut self) -> LayoutJob {
+        self.flush();
+        self.job
+    }
+}
+
+/// `text` with whitespace marked; `inferred` are sorted char ranges of made-up spacing
+pub fn layout_job(text: &str, inferred: &[Range<usize>], font: egui::FontId, palette: Palette) -> LayoutJob {
+    let mut builder = JobBuilder::new(font, palette);
+    let mut ranges = inferred.iter().peekable();
+    for (i, c) in text.chars().enumerate() {
+        while ranges.peek().is_some_and(|r| r.end <= i) {
+            ranges.next();
+        }
+        builder.push(c, ranges.peek().is_some_and(|r| r.contains(&i)), ScriptPosition::Normal);
+    }
+    builder.finish()
+}
+
+/// Like `layout_job` for text already in visual order: each slot names the logical char drawn
+/// there, None breaks the line. `positions` raise and lower chars like the plain view does
+pub fn layout_job_reordered(chars: &[char], slots: impl Iterator<Item = Option<usize>>, inferred: &HashSet<usize>,
+                            positions: Option<&[ScriptPosition]>, font: egui::FontId, palette: Palette) -> LayoutJob {
+    let mut builder = JobBuilder::new(font, palette);
+    for slot in slots {
+        match slot.and_then(|i| chars.get(i).map(|&c| (i, c))) {
+            Some((i, c)) => {
+                let position = positions.and_then(|p| p.get(i)).copied().unwrap_or(ScriptPosition::Normal);
+                builder.push(c, inferred.contains(&i), position);
+            }
+            None => builder.pending.push('\n'),
+        }
+    }
+    builder.finish()
+}
@@ -0,0 +1,162 @@
+// key_values.rs - "Label: value" pairs on forms, and reusable form templates
+use crate::{layout, SpatialElement};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Directory of saved form templates (`name.json`)
+pub const FORM_DIR: &str = "chonker9_forms";
+
+/// Keys longer than this are prose with a colon ("as follows:"), not form labels
+const MAX_KEY_WORDS: usize = 5;
+/// Horizontal gap (points) that separates a label column from a value column
+const COLUMN_GAP: f32 = 20.0;
+/// Column starts within this distance count as aligned
+const ALIGN_TOLERANCE: f32 = 4.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+    pub page: usize,
+    pub bbox: [f32; 4],  // Label and value together: x, y, width, height in points
+}
+
+fn join(words: &[&SpatialElement]) -> String {
+    words.iter().map(|e| e.content.trim()).collect::<Vec<&str>>().join(" ")
+}
+
+fn pair(key_words: &[&SpatialElement], value_words: &[&SpatialElement]) -> Option<KeyValue> {
+    let key = join(key_words).trim_end_matches(':').trim().to_string();
+    if key.is_empty() || key_words.len() > MAX_KEY_WORDS {
+        return None;
+    }
+    let all = key_words.iter().chain(value_words);
+    let left = all.clone().map(|e| e.hpos).fold(f32::MAX, f32::min);
+    let top = all.clone().map(|e| e.vpos).fold(f32::MAX, f32::min);
+    let right = all.clone().map(|e| e.hpos + e.width).fold(f32::MIN, f32::max);
+    let bottom = all.map(|e| e.vpos + e.height).fold(f32::MIN, f32::max);
+    Some(KeyValue {
+        key,
+        value: join(value_words),
+        page: key_words[0].page,
+        bbox: [left, top, right - left, bottom - top],
+    })
+}
+
+/// Index just after the widest gap between neighbouring words (0 when there's one word)
+fn widest_gap(words: &[&SpatialElement]) -> usize {
+    (1..words.len())
+        .max_by(|&a, &b| {
+            let gap = |i: usize| words[i].hpos - (words[i - 1].hpos + words[i - 1].width);
+            gap(a).partial_cmp(&gap(b)).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap_or(0)
+}
+
+/// Pairs from words ending in a colon: "Name: Jane Doe    Date: 1 May". Words between two
+/// labels are split at their widest gap into the first value and the second label
+fn colon_pairs(line: &[&SpatialElement]) -> Vec<KeyValue> {
+    let colons: Vec<usize> = (0..line.len()).filter(|&i| line[i].content.trim_end().ends_with(':')).collect();
+    let mut pairs = Vec::new();
+    let mut key_start = 0;
+    for (n, &colon) in colons.iter().enumerate() {
+        let value_end = match colons.get(n + 1) {
+            // Between colon and the next label word: value words, then the next label
+            Some(&next) => colon + 1 + widest_gap(&line[colon + 1..=next]),
+            None => line.len(),
+        };
+        if let Some(kv) = pair(&line[key_start..=colon], &line[colon + 1..value_end]) {
+            pairs.push(kv);
+        }
+        key_start = value_end;
+    }
+    pairs
+}
+
+/// Pairs from two-column layouts without colons: a label group and a value group split by a
+/// wide gap, kept only when at least three lines share both column starts
+fn aligned_pairs(lines: &[Vec<&SpatialElement>]) -> Vec<KeyValue> {
+    let candidates: Vec<(usize, usize)> = lines.iter().enumerate()
+        .filter(|(_, line)| line.len() >= 2 && !line.iter().any(|e| e.content.trim_end().ends_with(':')))
+        .filter_map(|(l, line)| {
+            let split = widest_gap(line);
+            let gap = line[split].hpos - (line[split - 1].hpos + line[split - 1].width);
+            (gap >= COLUMN_GAP).then_some((l, split))
+        })
+        .collect();
+    let aligned = |a: f32, b: f32| (a - b).abs() <= ALIGN_TOLERANCE;
+    candidates.iter()
+        .filter(|&&(l, split)| {
+            let (label_x, value_x) = (lines[l][0].hpos, lines[l][split].hpos);
+            candidates.iter()
+                .filter(|&&(o, o_split)| aligned(lines[o][0].hpos, label_x) && aligned(lines[o][o_split].hpos, value_x))
+                .count() >= 3
+        })
+        .filter_map(|&(l, split)| pair(&lines[l][..split], &lines[l][split..]))
+        .collect()
+}
+
+/// Label/value pairs in reading order
+pub fn detect(elements: &[SpatialElement]) -> Vec<KeyValue> {
+    let refs: Vec<&SpatialElement> = elements.iter().filter(|e| !e.content.trim().is_empty()).collect();
+    let lines = layout::group_into_lines(&refs);
+    let mut pairs: Vec<KeyValue> = lines.iter().flat_map(|line| colon_pairs(line)).collect();
+    pairs.extend(aligned_pairs(&lines));
+    pairs.sort_by(|a, b| (a.page, a.bbox[1], a.bbox[0]).partial_cmp(&(b.page, b.bbox[1], b.bbox[0])).unwrap_or(std::cmp::Ordering::Equal));
+    pairs
+}
+
+/// Labels expected on a recurring form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormTemplate {
+    pub name: String,
+    pub keys: Vec<String>,
+}
+
+fn normalize_key(key: &str) -> String {
+    key.trim().trim_end_matches(':').trim().to_lowercase()
+}
+
+impl FormTemplate {
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from(FORM_DIR).join(format!("{}.json", layout::slugify(name)))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(FORM_DIR)?;
+        std::fs::write(Self::path(&self.name), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Every template in the form directory, by name
+    pub fn list() -> Vec<FormTemplate> {
+        let mut templates: Vec<FormTemplate> = std::fs::read_dir(FORM_DIR)
+            .map(|entries| {
+                entries.filter_map(|entry| entry.ok())
+                    .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+                    .filter_map(|json| serde_json::from_str(&json).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+
+    /// The template's keys with the value found for each (case and colon insensitive)
+    pub fn apply(&self, pairs: &[KeyValue]) -> Vec<(String, Option<String>)> {
+        self.keys.iter()
+            .map(|key| {
+                let found = pairs.iter().find(|kv| normalize_key(&kv.key) == normalize_key(key));
+                (key.clone(), found.map(|kv| kv.value.clone()))
+            })
+            .collect()
+    }
+}
+
+/// `[{ "key": "...", "value": "..." }, ...]` in template order, null for missing fields
+pub fn template_json(fields: &[(String, Option<String>)]) -> Result<String, serde_json::Error> {
+    let fields: Vec<serde_json::Value> = fields.iter()
+        .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+        .collect();
+    serde_json::to_string_pretty(&fields)
+}
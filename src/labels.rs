@@ -0,0 +1,66 @@
+// labels.rs - Semantic labels for training data and extraction pipelines
+use crate::tags::TagKind;
+use eframe::egui;
+use serde::Serialize;
+
+/// Category assigned to an element in labeling mode (hotkeys 1-5, 0 clears)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Label {
+    Name,
+    Amount,
+    Date,
+    Address,
+    Redacted,
+}
+
+impl Label {
+    pub const ALL: [Label; 5] = [Label::Name, Label::Amount, Label::Date, Label::Address, Label::Redacted];
+    
+    /// Label bound to a digit key in labeling mode
+    pub fn for_hotkey(digit: usize) -> Option<Label> {
+        digit.checked_sub(1).and_then(|i| Label::ALL.get(i).copied())
+    }
+    
+    pub fn color(&self) -> egui::Color32 {
+        match self {
+            Label::Name => egui::Color32::from_rgb(90, 160, 255),
+            Label::Amount => egui::Color32::from_rgb(90, 210, 120),
+            Label::Date => egui::Color32::from_rgb(240, 170, 60),
+            Label::Address => egui::Color32::from_rgb(200, 110, 230),
+            Label::Redacted => egui::Color32::from_rgb(120, 120, 120),
+        }
+    }
+}
+
+impl TagKind for Label {
+    const SIDECAR: &'static str = "labels";
+    
+    fn label(&self) -> &'static str {
+        match self {
+            Label::Name => "name",
+            Label::Amount => "amount",
+            Label::Date => "date",
+            Label::Address => "address",
+            Label::Redacted => "redacted",
+        }
+    }
+    
+    fn from_label(label: &str) -> Option<Self> {
+        Label::ALL.into_iter().find(|l| l.label() == label)
+    }
+}
+
+/// One labeled element in the export
+#[derive(Debug, Serialize)]
+pub struct LabeledElement {
+    pub label: Label,
+    pub text: String,
+    pub page: usize,
+    pub bbox: [f32; 4], // x, y, width, height in PDF points
+}
+
+pub fn write_json(path: &str, labeled: &[LabeledElement]) -> Result<usize, Box<dyn std::error::Error>> {
+    std::fs::write(path, serde_json::to_string_pretty(labeled)?)?;
+    Ok(labeled.len())
+}
@@ -0,0 +1,208 @@
+// layout.rs - Line, heading and section reconstruction from spatial elements
+use crate::SpatialElement;
+
+/// A heading line detected from relative text height
+#[derive(Debug, Clone)]
+pub struct Heading {
+    pub text: String,
+    pub level: usize,     // 1 = top-level section heading
+    pub line_index: usize, // Index into the grouped lines
+}
+
+/// A run of lines that starts at a top-level heading
+#[derive(Debug)]
+pub struct Section<'a> {
+    pub heading: Option<String>,        // None for text before the first heading
+    pub lines: Vec<Vec<&'a SpatialElement>>,
+}
+
+/// Group elements into lines (within 8 pixels vertically), sorted left to right
+pub fn group_into_lines<'a>(elements: &[&'a SpatialElement]) -> Vec<Vec<&'a SpatialElement>> {
+    let mut lines: Vec<Vec<&SpatialElement>> = Vec::new();
+    let mut sorted_elements: Vec<&SpatialElement> = elements.to_vec();
+    sorted_elements.sort_by(|a, b| a.vpos.partial_cmp(&b.vpos).unwrap());
+
+    for element in sorted_elements {
+        let found_line = lines.iter_mut().find(|line| {
+            if let Some(first) = line.first() {
+                (element.vpos - first.vpos).abs() < 8.0
+            } else {
+                false
+            }
+        });
+
+        if let Some(line) = found_line {
+            line.push(element);
+        } else {
+            lines.push(vec![element]);
+        }
+    }
+
+    for line in &mut lines {
+        line.sort_by(|a, b| a.hpos.partial_cmp(&b.hpos).unwrap());
+    }
+
+    lines
+}
+
+/// Plain text of a line with single spaces between elements
+pub fn line_text(line: &[&SpatialElement]) -> String {
+    line.iter()
+        .map(|e| e.content.as_str())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Detect heading lines: noticeably taller than body text and short
+pub fn detect_headings(lines: &[Vec<&SpatialElement>]) -> Vec<Heading> {
+    let mut heights: Vec<f32> = lines.iter().flatten().map(|e| e.height).collect();
+    if heights.is_empty() {
+        return Vec::new();
+    }
+    heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let median_height = heights[heights.len() / 2];
+
+    let mut candidates: Vec<(usize, f32)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() || line.len() > 10 {
+            continue;
+        }
+        let line_height = line.iter().map(|e| e.height).sum::<f32>() / line.len() as f32;
+        let has_letters = line.iter().any(|e| e.content.chars().any(|c| c.is_alphabetic()));
+        if has_letters && line_height >= median_height * 1.15 {
+            candidates.push((i, line_height));
+        }
+    }
+
+    // Largest heading size band is level 1, everything else nests below it
+    let max_height = candidates.iter().map(|(_, h)| *h).fold(0.0, f32::max);
+    candidates.into_iter()
+        .map(|(i, h)| Heading {
+            text: line_text(&lines[i]),
+            level: if max_height - h < 1.0 { 1 } else { 2 },
+            line_index: i,
+        })
+        .collect()
+}
+
+/// Split lines into top-level sections using the detected heading outline
+pub fn split_sections<'a>(elements: &[&'a SpatialElement]) -> Vec<Section<'a>> {
+    let lines = group_into_lines(elements);
+    let headings = detect_headings(&lines);
+
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current = Section { heading: None, lines: Vec::new() };
+
+    for (i, line) in lines.into_iter().enumerate() {
+        if let Some(heading) = headings.iter().find(|h| h.line_index == i && h.level == 1) {
+            if current.heading.is_some() || !current.lines.is_empty() {
+                sections.push(current);
+            }
+            current = Section { heading: Some(heading.text.clone()), lines: Vec::new() };
+        }
+        current.lines.push(line);
+    }
+    if current.heading.is_some() || !current.lines.is_empty() {
+        sections.push(current);
+    }
+
+    sections
+}
+
+/// Derive a filesystem-safe name from heading text
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(|c| c.to_lowercase()) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.ends_with('_') && !slug.is_empty() {
+            slug.push('_');
+        }
+    }
+    let slug: String = slug.trim_end_matches('_').chars().take(48).collect();
+    if slug.is_empty() { "section".to_string() } else { slug.trim_end_matches('_').to_string() }
+}
+
+/// Table heuristic: currency, percentages, N/A and years inside the known table region
+pub fn is_table_element(element: &SpatialElement) -> bool {
+    let content = element.content.trim();
+    let is_in_table_region = element.vpos >= 409.0 && element.vpos <= 517.0;
+    let is_table_content = content.contains('$') ||
+                          content == "N/A" ||
+                          content.contains('%') ||
+                          (content.chars().all(|c| c.is_numeric()) && content.len() == 4);
+    is_in_table_region && is_table_content
+}
+
+/// Split lines into blocks wherever the vertical gap marks a section break
+pub fn group_into_blocks<'a>(lines: Vec<Vec<&'a SpatialElement>>) -> Vec<Vec<Vec<&'a SpatialElement>>> {
+    let mut blocks: Vec<Vec<Vec<&SpatialElement>>> = Vec::new();
+    let mut last_vpos: Option<f32> = None;
+
+    for line in lines {
+        let current_vpos = match line.first() {
+            Some(first) => first.vpos,
+            None => continue,
+        };
+        let starts_block = match last_vpos {
+            Some(last) => current_vpos - last > 15.0,
+            None => true,
+        };
+        if starts_block {
+            blocks.push(Vec::new());
+        }
+        blocks.last_mut().unwrap().push(line);
+        last_vpos = Some(current_vpos);
+    }
+
+    blocks
+}
+
+/// Column x-ranges found from gaps in the horizontal coverage histogram
+pub fn detect_columns(elements: &[&SpatialElement]) -> Vec<(f32, f32)> {
+    const BIN_WIDTH: f32 = 5.0;
+    const MIN_GUTTER: f32 = 15.0;
+
+    if elements.is_empty() {
+        return Vec::new();
+    }
+    let min_x = elements.iter().map(|e| e.hpos).fold(f32::MAX, f32::min);
+    let max_x = elements.iter().map(|e| e.hpos + e.width).fold(0.0, f32::max);
+    let bin_count = (((max_x - min_x) / BIN_WIDTH).ceil() as usize).max(1);
+
+    // How many elements cover each horizontal bin
+    let mut coverage = vec![0usize; bin_count];
+    for element in elements {
+        let first = ((element.hpos - min_x) / BIN_WIDTH) as usize;
+        let last = (((element.hpos + element.width - min_x) / BIN_WIDTH) as usize).min(bin_count - 1);
+        for bin in coverage.iter_mut().take(last + 1).skip(first) {
+            *bin += 1;
+        }
+    }
+
+    // Gutters: wide runs of near-empty bins (stray full-width words are tolerated)
+    let threshold = coverage.iter().max().copied().unwrap_or(0) / 10;
+    let mut columns = Vec::new();
+    let mut column_start = min_x;
+    let mut run_start: Option<usize> = None;
+    for (bin, &count) in coverage.iter().enumerate() {
+        if count <= threshold {
+            run_start.get_or_insert(bin);
+        } else if let Some(start) = run_start.take() {
+            let gutter_width = (bin - start) as f32 * BIN_WIDTH;
+            if gutter_width >= MIN_GUTTER && start > 0 {
+                columns.push((column_start, min_x + start as f32 * BIN_WIDTH));
+                column_start = min_x + bin as f32 * BIN_WIDTH;
+            }
+        }
+    }
+    columns.push((column_start, max_x));
+    columns
+}
+
+/// Index of the column an element starts in
+pub fn column_of(element: &SpatialElement, columns: &[(f32, f32)]) -> usize {
+    columns.iter()
+        .position(|&(_, end)| element.hpos < end)
+        .unwrap_or(columns.len().saturating_sub(1))
+}
@@ -0,0 +1,182 @@
+// links.rs - URL/email detection in text and PDF link annotations
+use crate::SpatialElement;
+use eframe::egui;
+use quick_xml::{events::Event, Reader};
+use std::process::Command;
+
+/// A link found in text, as char offsets into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLink {
+    pub start: usize,
+    pub end: usize,
+    pub target: String,  // Openable target: https://…, mailto:…
+}
+
+/// A PDF URI link annotation, its rectangle in points from the page's top left
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfLink {
+    pub page: usize,
+    pub rect: egui::Rect,
+    pub target: String,
+}
+
+const URL_PREFIXES: &[&str] = &["https://", "http://", "ftp://", "www."];
+
+fn target_for(word: &str) -> Option<String> {
+    let lower = word.to_lowercase();
+    if let Some(prefix) = URL_PREFIXES.iter().find(|p| lower.starts_with(*p)) {
+        if word.len() <= prefix.len() {
+            return None;
+        }
+        return Some(if *prefix == "www." { format!("https://{}", word) } else { word.to_string() });
+    }
+    if lower.starts_with("mailto:") {
+        return Some(word.to_string());
+    }
+    // name@domain.tld, with exactly one @ and a dot somewhere after it
+    let (local, domain) = word.split_once('@')?;
+    let valid = |c: char| c.is_alphanumeric() || "._%+-".contains(c);
+    let looks_like_email = !local.is_empty() && local.chars().all(valid)
+        && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        && domain.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '-');
+    looks_like_email.then(|| format!("mailto:{}", word))
+}
+
+/// URLs and emails in `text`, with surrounding brackets and sentence punctuation trimmed
+pub fn detect(text: &str) -> Vec<TextLink> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut links = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let mut start = i;
+        let mut end = i;
+        while end < chars.len() && !chars[end].is_whitespace() {
+            end += 1;
+        }
+        i = end;
+        while start < end && "(<[\"'".contains(chars[start]) {
+            start += 1;
+        }
+        while end > start && ".,;:!?)]}>\"'".contains(chars[end - 1]) {
+            end -= 1;
+        }
+        let word: String = chars[start..end].iter().collect();
+        if let Some(target) = target_for(&word) {
+            links.push(TextLink { start, end, target });
+        }
+    }
+    links
+}
+
+/// The annotation covering the centre of an element, if any
+pub fn annotation_target<'a>(element: &SpatialElement, pdf_links: &'a [PdfLink]) -> Option<&'a str> {
+    let centre = egui::pos2(element.hpos + element.width / 2.0, element.vpos + element.height / 2.0);
+    pdf_links.iter()
+        .find(|link| link.page == element.page && link.rect.contains(centre))
+        .map(|link| link.target.as_str())
+}
+
+/// Rebuild `text` with every detected link passed through `link(text, target)` and the rest through `plain`
+pub fn markup(text: &str, plain: impl Fn(&str) -> String, link: impl Fn(&str, &str) -> String) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::new();
+    let mut last = 0;
+    for found in detect(text) {
+        output.push_str(&plain(&chars[last..found.start].iter().collect::<String>()));
+        output.push_str(&link(&chars[found.start..found.end].iter().collect::<String>(), &found.target));
+        last = found.end;
+    }
+    output.push_str(&plain(&chars[last..].iter().collect::<String>()));
+    output
+}
+
+/// Hand a target to the platform's default handler (browser, mail client)
+pub fn open(target: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command.arg(target).spawn()?;
+    Ok(())
+}
+
+/// Run pdfalto with -annotation on one page and keep its URI links
+pub fn extract_annotations(pdf_path: &str, page: usize) -> Result<Vec<PdfLink>, Box<dyn std::error::Error>> {
+    let dir = crate::temp_path(&format!("chonker9_links_p{}", page));
+    std::fs::create_dir_all(&dir)?;
+    let page_arg = page.to_string();
+    let status = Command::new("pdfalto")
+        .args(["-annotation", "-noImage", "-f", &page_arg, "-l", &page_arg, pdf_path])
+        .arg(dir.join("links.xml"))
+        .status()?;
+    if !status.success() {
+        return Err("pdfalto failed".into());
+    }
+    let xml = std::fs::read_to_string(dir.join("links_annot.xml")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&dir);
+    Ok(parse_annotations(&xml, page))
+}
+
+/// `<ANNOTATION><ACTION type="uri"><DEST>url</DEST></ACTION><QUADPOINTS>…<POINT HPOS VPOS/>…</QUADPOINTS></ANNOTATION>`
+pub fn parse_annotations(xml: &str, page: usize) -> Vec<PdfLink> {
+    let mut reader = Reader::from_str(xml);
+    let mut links = Vec::new();
+    let mut target = String::new();
+    let mut is_uri = false;
+    let mut in_dest = false;
+    let mut points: Vec<egui::Pos2> = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"ANNOTATION" => {
+                    target.clear();
+                    is_uri = false;
+                    points.clear();
+                }
+                b"ACTION" => {
+                    is_uri = e.attributes().flatten()
+                        .any(|attr| attr.key.as_ref() == b"type" && attr.value.eq_ignore_ascii_case(b"uri"));
+                }
+                b"DEST" => in_dest = true,
+                b"POINT" => {
+                    let (mut x, mut y) = (None, None);
+                    for attr in e.attributes().flatten() {
+                        let value: Option<f32> = String::from_utf8_lossy(&attr.value).parse().ok();
+                        match attr.key.as_ref() {
+                            b"HPOS" | b"x" => x = value,
+                            b"VPOS" | b"y" => y = value,
+                            _ => {}
+                        }
+                    }
+                    if let (Some(x), Some(y)) = (x, y) {
+                        points.push(egui::pos2(x, y));
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Text(text)) if in_dest => target.push_str(String::from_utf8_lossy(&text).trim()),
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"DEST" => in_dest = false,
+                b"ANNOTATION" => {
+                    if is_uri && !target.is_empty() && !points.is_empty() {
+                        let rect = egui::Rect::from_points(&points);
+                        links.push(PdfLink { page, rect, target: target.clone() });
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    links
+}
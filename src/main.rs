@@ -1,7 +1,83 @@
 use eframe::egui;
-use std::{process::Command, sync::{Arc, Mutex}, thread, time::Duration};
+use std::{borrow::Cow, sync::{Arc, Mutex}, thread, time::Duration};
 
 mod spatial_text;
+mod layout;
+mod export;
+mod bookmarks;
+mod pipeline;
+mod search;
+mod dictionary;
+mod quality;
+mod extractors;
+mod spellcheck;
+mod ocr_fix;
+mod discrepancy;
+mod mojibake;
+mod speech;
+mod headers;
+mod zoom;
+mod animation;
+mod svg;
+mod tags;
+mod reading_order;
+mod review;
+mod alto;
+mod ocr;
+mod tile_cache;
+mod snap;
+mod diff;
+mod change_report;
+mod annotations;
+mod labels;
+mod ner;
+mod redaction;
+mod png;
+mod print;
+mod epub;
+mod batch;
+mod zip;
+mod xlsx;
+mod mets;
+mod iiif;
+mod tabs;
+mod panels;
+mod theme;
+mod fonts;
+mod accessibility;
+mod bidi;
+mod cjk;
+mod cleanup;
+mod glyph_map;
+mod invisibles;
+mod heuristics;
+mod units;
+mod rotation;
+mod thumbnails;
+mod outline;
+mod metadata;
+mod docx;
+mod figures;
+mod confidence;
+mod garbled;
+mod links;
+mod numbers;
+mod align;
+mod markdown;
+mod table_regions;
+mod table_check;
+mod bates;
+mod scripts;
+mod key_values;
+mod zones;
+mod watch;
+mod compare;
+mod extraction_cache;
+mod page_window;
+mod track_changes;
+use extractors::{Extractor, ExtractorDecision};
+use ocr::OcrBackend;
+use tags::TagKind;
 use spatial_text::{SpatialTextBuffer, SpatialCursor, ElementRange};
 
 #[derive(Debug, Clone)]
@@ -11,9 +87,19 @@ struct SpatialElement {
     vpos: f32,
     width: f32,
     height: f32,
+    page: usize,              // 1-based page number
+    confidence: Option<f32>,  // ALTO word confidence (WC, else mean of CC), 0.0-1.0
+    modified: bool,           // Current text differs from the extracted CONTENT
+    tag: Option<tags::ColorTag>, // User color tag
+    style_refs: Option<String>,  // ALTO STYLEREFS
+    font_size: Option<f32>,      // FONTSIZE of the referenced TextStyle
+    extracted: Option<String>,   // CONTENT as extracted, when load-time cleanup changed it
 }
 
-#[derive(Debug, Clone)]
+/// Character cell size for terminal-targeted exports, as `cell_width=` / `cell_height=` lines
+const TERMINAL_METRICS_FILE: &str = "chonker9_terminal.txt";
+
+#[derive(Debug, Clone, PartialEq)]
 struct TerminalMetrics {
     cell_width_pts: f32,
     cell_height_pts: f32,
@@ -27,24 +113,118 @@ impl TerminalMetrics {
         }
     }
     
+    fn load() -> Self {
+        let mut metrics = Self::new();
+        let content = std::fs::read_to_string(TERMINAL_METRICS_FILE).unwrap_or_default();
+        for line in content.lines() {
+            match line.split_once('=').map(|(key, value)| (key.trim(), value.trim().parse::<f32>())) {
+                Some(("cell_width", Ok(width))) if width > 0.0 => metrics.cell_width_pts = width,
+                Some(("cell_height", Ok(height))) if height > 0.0 => metrics.cell_height_pts = height,
+                _ => {}
+            }
+        }
+        metrics
+    }
+    
+    fn save(&self) -> std::io::Result<()> {
+        std::fs::write(TERMINAL_METRICS_FILE, format!("cell_width={}\ncell_height={}\n", self.cell_width_pts, self.cell_height_pts))
+    }
+    
     fn pdf_to_terminal(&self, pdf_x: f32, pdf_y: f32) -> (u16, u16) {
         let col = (pdf_x / self.cell_width_pts) as u16;
         let row = (pdf_y / self.cell_height_pts) as u16;
         (col, row)
     }
+    
+    /// Document rect covered by a character cell
+    fn cell_rect(&self, col: u16, row: u16) -> egui::Rect {
+        egui::Rect::from_min_size(
+            egui::pos2(col as f32 * self.cell_width_pts, row as f32 * self.cell_height_pts),
+            egui::vec2(self.cell_width_pts, self.cell_height_pts),
+        )
+    }
+    
+    /// Columnar plain text (like `pdftotext -layout`): each word lands on the character
+    /// cell under its PDF position, pages separated by form feeds
+    fn layout_text(&self, elements: &[SpatialElement]) -> String {
+        let mut pages: std::collections::BTreeMap<usize, Vec<&SpatialElement>> = std::collections::BTreeMap::new();
+        for element in elements {
+            pages.entry(element.page).or_default().push(element);
+        }
+        
+        let mut output = Vec::new();
+        for page_elements in pages.into_values() {
+            let mut cells: Vec<(u16, u16, &str)> = page_elements.iter()
+                .map(|e| {
+                    // Row from the vertical center so words on one baseline share a row
+                    let (col, row) = self.pdf_to_terminal(e.hpos, e.vpos + e.height / 2.0);
+                    (col, row, e.content.trim())
+                })
+                .collect();
+            // Cells fill rows left to right, so order by the grid position rather than the raw top edge
+            cells.sort_by_key(|&(col, row, _)| (row, col));
+            let left = cells.iter().map(|&(col, _, _)| col).min().unwrap_or(0);
+            let top = cells.iter().map(|&(_, row, _)| row).min().unwrap_or(0);
+            
+            let mut rows: Vec<Vec<char>> = Vec::new();
+            for &(col, row, text) in &cells {
+                let row = (row - top) as usize;
+                if rows.len() <= row {
+                    rows.resize(row + 1, Vec::new());
+                }
+                let line = &mut rows[row];
+                // Keep at least one space from the previous word when cells collide
+                let mut col = (col - left) as usize;
+                if !line.is_empty() {
+                    col = col.max(line.len() + 1);
+                }
+                line.resize(col, ' ');
+                line.extend(text.chars());
+            }
+            let page_text: Vec<String> = rows.iter().map(|line| line.iter().collect()).collect();
+            output.push(page_text.join("\n"));
+        }
+        output.join("\n\x0c")
+    }
+}
+
+/// Editor split into two panes over the same buffer
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SplitView {
+    SideBySide,
+    Stacked,
+}
+
+/// Zoom and scroll of the second pane; swapped into the app while that pane renders
+struct PaneView {
+    zoom_mode: zoom::ZoomMode,
+    scroll_offset: egui::Vec2,
+    scroll_animation: Option<animation::ScrollAnimation>,
 }
 
 struct ChonkerApp {
     pdf_path: String,
-    raw_xml: String,
+    raw_xml: Option<String>,  // pdfalto/OCR output, kept only while the XML debug panel is open
     spatial_elements: Vec<SpatialElement>,
     terminal_metrics: TerminalMetrics,
+    show_terminal_grid: bool,  // Overlay the character grid used by layout text export
+    view_rotation: rotation::Rotation,  // Manual rotate-view on top of the detected /Rotate
+    source_units: units::SourceUnits,  // Coordinate unit the ALTO declared (elements are stored in points)
+    display_unit: units::DisplayUnit,  // Unit of the inspector's coordinate readout
     show_xml_debug: bool,
+    xml_detached: bool,              // XML panel in its own OS window
+    panel_layout: panels::PanelLayout,
+    theme: theme::ThemeConfig,
+    show_theme_editor: bool,
+    font_settings: fonts::EditorFont,
+    font_catalog: Option<fonts::FontCatalog>,
+    fonts_dirty: bool,               // Editor family needs (re)installing into egui
+    editor_family_ready: bool,
+    missing_glyphs: Vec<char>,       // Document chars no installed font covers
+    glyph_check_len: Option<usize>,  // Rope length at the last coverage check; None = check next frame
     xml_scroll: usize,
     terminal_output: Arc<Mutex<String>>,
     // Text editing capabilities
-    rope: ropey::Rope,
-    cursor_pos: usize,
     selection_start: Option<usize>,
     selection_end: Option<usize>,
     modified: bool,
@@ -55,20 +235,217 @@ struct ChonkerApp {
     spatial_buffer: SpatialTextBuffer,
     spatial_cursor: SpatialCursor,
     wysiwyg_mode: bool,              // Toggle between old and new system
+    // Go to line / offset dialog
+    goto_open: bool,
+    goto_input: String,
+    goto_by_line: bool,              // Line number vs character offset
+    scroll_to_cursor: bool,          // Scroll cursor into view on next frame
+    scroll_offset: egui::Vec2,       // Canvas scroll offset last frame
+    scroll_animation: Option<animation::ScrollAnimation>,
+    // Split view: second pane's view, which pane is drawing and which takes typing
+    split_view: Option<SplitView>,
+    second_pane: PaneView,
+    pane_zoom: [f32; 2],            // Resolved zoom of each pane last frame
+    rendering_pane: usize,
+    focused_pane: usize,
+    // Named bookmarks
+    bookmarks: bookmarks::Bookmarks,
+    show_bookmarks: bool,
+    show_outline: bool,
+    show_metadata: bool,
+    show_figures: bool,
+    show_confidence: bool,     // Heatmap of OCR word confidence
+    low_confidence: f32,       // Threshold for the heatmap and F8 jumps
+    figures: Option<(Vec<egui::Rect>, Vec<(figures::Figure, egui::TextureHandle)>)>,  // (redactions applied, crops) cut from the page raster on first show
+    figures_loading: Option<(usize, Vec<egui::Rect>, std::sync::mpsc::Receiver<Result<Vec<(figures::Figure, egui::ColorImage)>, String>>)>, // Crops made off the UI thread
+    metadata: metadata::DocumentMetadata,
+    outline: Option<Vec<outline::OutlineEntry>>,  // Read from the PDF the first time the panel opens
+    // Text reconstruction passes
+    pipeline: pipeline::Pipeline,
+    show_pipeline: bool,
+    // Ctrl+F find bar
+    search: search::SearchState,
+    // Extraction quality
+    dictionary: Arc<dictionary::Dictionary>,
+    page_quality: Vec<quality::PageQuality>,
+    // Per-page extraction backend
+    auto_extractor: bool,
+    ocr_available: bool,
+    ocr_engine: ocr::OcrEngine,      // Recognizer used for this document
+    ocr_url_input: String,           // Remote engine URL being edited
+    extractor_decisions: Vec<ExtractorDecision>,
+    extractor_sampling: Option<std::sync::mpsc::Receiver<Vec<ExtractorDecision>>>, // OCR comparison running off the UI thread
+    show_extractors: bool,
+    extraction_cache: extraction_cache::ExtractionCache,
+    page_window: page_window::PageWindow,  // Viewed page plus parsed neighbours
+    // Two backends run on one page, text differences highlighted
+    show_backend_compare: bool,
+    backend_compare_page: usize,
+    backend_compare_pick: [usize; 2],  // Indices into extractors::sources()
+    backend_comparison: Option<extractors::BackendComparison>,
+    backend_comparing: Option<std::sync::mpsc::Receiver<Result<extractors::BackendComparison, String>>>, // Both backends running off the UI thread
+    // Spellchecking
+    spellcheck_enabled: bool,
+    project_words: spellcheck::ProjectWords,
+    spell_menu: Option<spellcheck::SpellMenu>,
+    misspelling_cache: Option<(u64, Vec<spellcheck::Misspelling>)>, // (buffer revision, misspellings)
+    spell_suggestions: std::collections::HashMap<String, Vec<String>>, // Per word, computed when one is right-clicked
+    // OCR confusion corrections
+    ocr_rules: Vec<ocr_fix::CorrectionRule>,
+    show_ocr_fixes: bool,
+    new_rule: ocr_fix::CorrectionRule,
+    // Text layer vs OCR disagreements
+    discrepancies: Vec<discrepancy::Discrepancy>,
+    // Encoding damage review
+    show_encoding: bool,
+    // URLs/emails in text plus PDF link annotations (loaded on first use)
+    show_links: bool,
+    pdf_links: Option<Vec<links::PdfLink>>,
+    links_loading: Option<(usize, std::sync::mpsc::Receiver<Result<Vec<links::PdfLink>, String>>)>, // Annotations read off the UI thread
+    link_cache: Option<(u64, Vec<(usize, usize, String)>)>, // (buffer revision, link spans)
+    // Statistically garbled elements: underlines plus a review list
+    garbled_check: bool,
+    show_garbled: bool,
+    garbled_dismissed: std::collections::HashSet<usize>,  // Element ids reviewed and kept as-is
+    show_cleanup: bool,
+    show_normalize: bool,
+    normalize_options: numbers::NormalizeOptions,
+    show_glyph_map: bool,
+    show_heuristics: bool,
+    glyph_map: glyph_map::GlyphMap,
+    glyph_map_input: (String, String),  // (source char or U+XXXX, replacement) being added
+    glyph_map_dirty: bool,              // Edited since last saved; written when an edit is finished
+    cleanup: cleanup::CleanupOptions,
+    // Text-to-speech proofreading
+    speech: Option<std::process::Child>,
+    // Paragraphs wrapped to the window instead of PDF line breaks
+    reflow_mode: bool,
+    // Original extraction beside the edited text with word-level changes
+    diff_mode: bool,
+    diff_cache: Option<(u64, Vec<diff::DiffOp>)>, // (buffer revision, ops)
+    // Another version of the document beside this one
+    compare_mode: bool,
+    compare_input: String,
+    comparison: Option<compare::Comparison>,
+    comparison_loading: Option<(String, std::sync::mpsc::Receiver<Result<String, String>>)>, // Other document extracting off the UI thread
+    watch_pdf: bool,                          // Re-extract when the PDF changes on disk
+    pdf_watch: Option<watch::FileWatch>,
+    reload_prompt: Option<Vec<diff::DiffOp>>, // Edited text vs. the changed PDF, awaiting a decision
+    prefetching: Option<(String, usize, std::sync::mpsc::Receiver<Result<ParsedAlto, String>>)>, // (PDF, page) parsing on a worker
+    saved_edits_revision: u64, // Buffer revision whose pending tracked edits are on disk
+    reload_extraction: Option<std::sync::mpsc::Receiver<Result<Vec<SpatialElement>, String>>>, // Changed PDF re-extracting off the UI thread
+    confirm_reload: bool,                      // Load PDF pressed with edits on some parsed page
+    pending_rotation: Option<rotation::Rotation>, // Rotate-view waiting on the reload confirmation
+    // Review mode: Tab walks elements in reading order
+    review_mode: bool,
+    review_element: Option<usize>,
+    auto_accept: Option<f32>,        // Confidence at which words skip the review queue
+    // Color-code detected text columns on the canvas
+    show_columns: bool,
+    // Mark spaces and line breaks, with inferred spacing set apart from real characters
+    show_invisibles: bool,
+    // Numbered badges and arrows in extraction order
+    show_reading_order: bool,
+    // Stroke original and current element bounds
+    show_bounds: bool,
+    // Docked list of modified elements
+    show_changes: bool,
+    // Element inspector with editable geometry
+    show_inspector: bool,
+    show_thumbnails: bool,
+    selected_element: Option<usize>, // Clicked element: inspector target, gets resize handles
+    selection_group: Vec<usize>,     // Shift+click multi-selection for merging
+    drag_origin: Option<(egui::Rect, egui::Vec2)>, // Bounds at drag start and offset so far
+    snap: snap::SnapSettings,
+    // Insert-text-box tool: drag a rectangle, then type its content
+    text_box_tool: bool,
+    text_box_drag: Option<egui::Pos2>,   // Drag start in document coordinates (text box or redact tool)
+    pending_text_box: Option<egui::Rect>, // Drawn box waiting for content
+    text_box_input: String,
+    inspector_text: String,          // CONTENT being edited in the inspector
+    // Comments on elements or regions, shown as margin markers
+    annotations: Vec<annotations::Annotation>,
+    annotation_draft: Option<(Option<usize>, egui::Rect, String)>, // (existing comment, anchor, text)
+    // Color tags with legend and view filter
+    element_tags: tags::ElementTags,
+    // Labeling mode: digit hotkeys assign categories to the selection
+    element_labels: tags::ElementTags<labels::Label>,
+    labeling_mode: bool,
+    // Recognizer suggestions awaiting confirmation
+    show_ner: bool,
+    ner_command: String,
+    ner_suggestions: Vec<ner::Suggestion>,
+    // Redaction: drawn regions plus elements labeled redacted
+    redactions: redaction::Redactions,
+    redact_tool: bool,
+    table_tool: bool,            // Drag to declare a table region
+    table_tool_columns: usize,   // Column count for the next region
+    zone_tool: bool,             // Drag to define a named zone
+    zone_tool_name: String,      // Name for the next zone (blank = "Zone N")
+    zones: Vec<zones::Zone>,     // Kept across PDFs so a template applies to the next one
+    zone_template_name: String,
+    show_zones: bool,
+    show_table_check: bool,
+    show_bates: bool,
+    exclude_bates: bool,         // Drop Bates stamps from text exports
+    show_fields: bool,
+    form_template_name: String,          // Name for "Save as template"
+    form_template: Option<String>,       // Template applied in the fields window
+    form_templates: Vec<key_values::FormTemplate>, // Saved templates, re-read when one is saved
+    script_encoding: scripts::ScriptEncoding,  // How superscripts/subscripts are written in exports
+    script_cache: Option<(u64, Vec<scripts::ScriptPosition>, Option<Vec<scripts::ScriptPosition>>)>, // (buffer revision, per element, per rope char when any are scripts)
+    context_pos: Option<egui::Pos2>, // Document point of the last right-click
+    // PNG snapshot of the canvas, cropped from a viewport screenshot
+    canvas_rect: egui::Rect,
+    screenshot_requested: bool,
+    print_layout: print::PrintLayout,
+    // IIIF manifest: first canvas image as underlay, corrections exported as Web Annotations
+    show_iiif: bool,
+    iiif_source: String,
+    iiif_canvases: Vec<iiif::Canvas>,
+    iiif_texture: Option<egui::TextureHandle>,
+    iiif_loading: Option<std::sync::mpsc::Receiver<Result<(Vec<iiif::Canvas>, egui::ColorImage), String>>>, // Manifest and image fetch off the UI thread
+    show_tags: bool,
+    tag_filter: std::collections::HashSet<tags::ColorTag>,
+    context_element: Option<usize>,  // Element under the last right-click
+    // Manual paragraph order
+    show_reading_order_list: bool,
+    order_selected: Option<usize>,   // Paragraph selected in the order list
+    order_cache: Option<(Vec<String>, Vec<String>)>, // (keys, texts) of the listed paragraphs
+    // Canvas zoom preset, remembered per document
+    zoom_mode: zoom::ZoomMode,
+    // Rasterized page behind the text
+    show_page_image: bool,
+    tile_cache: tile_cache::TileCache,
 }
 
 impl Default for ChonkerApp {
     fn default() -> Self {
+        let dictionary = Arc::new(dictionary::Dictionary::load_system());
+        let panel_layout = panels::PanelLayout::load();
+        
         Self {
             pdf_path: "/Users/jack/Documents/chonker_test.pdf".to_string(),
-            raw_xml: String::new(),
+            raw_xml: None,
             spatial_elements: Vec::new(),
-            terminal_metrics: TerminalMetrics::new(),
+            terminal_metrics: TerminalMetrics::load(),
+            show_terminal_grid: false,
+            view_rotation: rotation::Rotation::None,
+            source_units: units::SourceUnits::default(),
+            display_unit: units::DisplayUnit::Points,
             show_xml_debug: false,
+            xml_detached: panel_layout.xml_detached,
+            panel_layout,
+            theme: theme::ThemeConfig::load(),
+            show_theme_editor: false,
+            font_settings: fonts::EditorFont::load(),
+            font_catalog: None,
+            fonts_dirty: true,
+            editor_family_ready: false,
+            missing_glyphs: Vec::new(),
+            glyph_check_len: None,
             xml_scroll: 0,
             terminal_output: Arc::new(Mutex::new(String::new())),
-            rope: ropey::Rope::new(),
-            cursor_pos: 0,
             selection_start: None,
             selection_end: None,
             modified: false,
@@ -77,8 +454,406 @@ impl Default for ChonkerApp {
             spatial_buffer: SpatialTextBuffer::new(),
             spatial_cursor: SpatialCursor::new(),
             wysiwyg_mode: false,
+            goto_open: false,
+            goto_input: String::new(),
+            goto_by_line: true,
+            scroll_to_cursor: false,
+            scroll_offset: egui::Vec2::ZERO,
+            scroll_animation: None,
+            split_view: None,
+            second_pane: PaneView {
+                zoom_mode: zoom::ZoomMode::Actual,
+                scroll_offset: egui::Vec2::ZERO,
+                scroll_animation: None,
+            },
+            pane_zoom: [1.0; 2],
+            rendering_pane: 0,
+            focused_pane: 0,
+            bookmarks: bookmarks::Bookmarks::new(),
+            show_bookmarks: false,
+            show_outline: false,
+            show_metadata: false,
+            show_figures: false,
+            show_confidence: false,
+            low_confidence: confidence::DEFAULT_THRESHOLD,
+            figures: None,
+            figures_loading: None,
+            metadata: metadata::DocumentMetadata::default(),
+            outline: None,
+            pipeline: {
+                let mut pipeline = pipeline::Pipeline::new(dictionary.clone());
+                pipeline.heuristics = heuristics::Heuristics::load_defaults();
+                pipeline
+            },
+            show_pipeline: false,
+            search: search::SearchState::new(),
+            dictionary,
+            page_quality: Vec::new(),
+            auto_extractor: true,
+            ocr_available: ocr::TesseractBackend.is_available(),
+            ocr_engine: ocr::OcrEngine::Tesseract,
+            ocr_url_input: String::new(),
+            extractor_decisions: Vec::new(),
+            extractor_sampling: None,
+            show_extractors: false,
+            extraction_cache: extraction_cache::ExtractionCache::load(),
+            page_window: page_window::PageWindow::new("", None, 1, 1),
+            show_backend_compare: false,
+            backend_compare_page: 1,
+            backend_compare_pick: [0, 1],
+            backend_comparison: None,
+            backend_comparing: None,
+            spellcheck_enabled: true,
+            project_words: spellcheck::ProjectWords::default(),
+            spell_menu: None,
+            misspelling_cache: None,
+            spell_suggestions: std::collections::HashMap::new(),
+            ocr_rules: ocr_fix::load_rules(),
+            show_ocr_fixes: false,
+            new_rule: ocr_fix::CorrectionRule {
+                pattern: String::new(),
+                replacement: String::new(),
+                context: ocr_fix::RuleContext::Word,
+            },
+            discrepancies: Vec::new(),
+            show_encoding: false,
+            show_links: true,
+            pdf_links: None,
+            links_loading: None,
+            link_cache: None,
+            garbled_check: true,
+            show_garbled: false,
+            garbled_dismissed: std::collections::HashSet::new(),
+            show_cleanup: false,
+            show_normalize: false,
+            normalize_options: numbers::NormalizeOptions::default(),
+            show_glyph_map: false,
+            show_heuristics: false,
+            glyph_map: glyph_map::GlyphMap::default(),
+            glyph_map_input: (String::new(), String::new()),
+            glyph_map_dirty: false,
+            cleanup: cleanup::CleanupOptions::load(),
+            speech: None,
+            reflow_mode: false,
+            diff_mode: false,
+            diff_cache: None,
+            compare_mode: false,
+            compare_input: String::new(),
+            comparison: None,
+            comparison_loading: None,
+            watch_pdf: false,
+            pdf_watch: None,
+            reload_prompt: None,
+            prefetching: None,
+            saved_edits_revision: 0,
+            reload_extraction: None,
+            confirm_reload: false,
+            pending_rotation: None,
+            review_mode: false,
+            review_element: None,
+            auto_accept: None,
+            show_columns: false,
+            show_invisibles: false,
+            show_reading_order: false,
+            show_bounds: false,
+            show_changes: false,
+            show_inspector: false,
+            show_thumbnails: false,
+            selected_element: None,
+            selection_group: Vec::new(),
+            drag_origin: None,
+            snap: snap::SnapSettings::default(),
+            text_box_tool: false,
+            text_box_drag: None,
+            pending_text_box: None,
+            text_box_input: String::new(),
+            inspector_text: String::new(),
+            annotations: Vec::new(),
+            annotation_draft: None,
+            element_tags: tags::ElementTags::default(),
+            element_labels: tags::ElementTags::default(),
+            labeling_mode: false,
+            show_ner: false,
+            ner_command: ner::configured_command(),
+            ner_suggestions: Vec::new(),
+            redactions: redaction::Redactions::default(),
+            redact_tool: false,
+            table_tool: false,
+            table_tool_columns: 3,
+            zone_tool: false,
+            zone_tool_name: String::new(),
+            zones: Vec::new(),
+            zone_template_name: String::new(),
+            show_zones: false,
+            show_table_check: false,
+            show_bates: false,
+            exclude_bates: false,
+            show_fields: false,
+            form_template_name: String::new(),
+            form_template: None,
+            form_templates: key_values::FormTemplate::list(),
+            script_encoding: scripts::ScriptEncoding::Plain,
+            script_cache: None,
+            context_pos: None,
+            canvas_rect: egui::Rect::NOTHING,
+            screenshot_requested: false,
+            print_layout: print::PrintLayout::Readable,
+            show_iiif: false,
+            iiif_source: String::new(),
+            iiif_canvases: Vec::new(),
+            iiif_texture: None,
+            iiif_loading: None,
+            show_tags: false,
+            tag_filter: std::collections::HashSet::new(),
+            context_element: None,
+            show_reading_order_list: false,
+            order_selected: None,
+            order_cache: None,
+            zoom_mode: zoom::ZoomMode::Actual,
+            show_page_image: false,
+            tile_cache: tile_cache::TileCache::new(256),
+        }
+    }
+}
+
+/// Everything extracting a page depends on, detached from the app so workers can extract pages
+#[derive(Clone)]
+struct PageSource {
+    pdf_path: String,
+    pdf_hash: Option<u64>,
+    ocr_engine: ocr::OcrEngine,
+    decisions: Vec<ExtractorDecision>,
+    view_rotation: rotation::Rotation,
+    glyph_map: glyph_map::GlyphMap,
+    cleanup: cleanup::CleanupOptions,
+    cache: extraction_cache::ExtractionCache,
+}
+
+impl PageSource {
+    /// One page through its chosen backend (text layer if that fails), turned upright, with the
+    /// glyph map and load-time cleanup applied
+    fn extract(&self, page: usize, retain_xml: bool) -> Result<ParsedAlto, Box<dyn std::error::Error>> {
+        let ocr_backend = self.ocr_engine.backend();
+        let chosen = self.decisions.iter().find(|d| d.page == page).map_or(Extractor::TextLayer, |d| d.chosen);
+        let (used, mut parsed) = match self.extract_cached(chosen, page, ocr_backend.as_ref(), retain_xml) {
+            Ok(result) => (chosen, result),
+            Err(e) if chosen != Extractor::TextLayer => {
+                eprintln!("{} failed on page {} ({}), using text layer", chosen.label(), page, e);
+                (Extractor::TextLayer, self.extract_cached(Extractor::TextLayer, page, ocr_backend.as_ref(), retain_xml)?)
+            }
+            Err(e) => return Err(e),
+        };
+        
+        // pdfalto reports the unrotated page; rasterized OCR pages are already upright
+        let (detected, mut page_size) = rotation::page_geometry(&self.pdf_path, page)
+            .unwrap_or_else(|| (rotation::Rotation::None, rotation::element_extent(&parsed.elements)));
+        if used == Extractor::TextLayer {
+            rotation::rotate(&mut parsed.elements, detected, page_size);
+        }
+        if matches!(detected, rotation::Rotation::Cw90 | rotation::Rotation::Cw270) {
+            page_size = (page_size.1, page_size.0);
+        }
+        rotation::rotate(&mut parsed.elements, self.view_rotation, page_size);
+        
+        // Broken encodings are fixed first, so cleanup sees the intended characters
+        for element in &mut parsed.elements {
+            element.content = self.glyph_map.apply(&element.content);
+        }
+        // Cleanup counts as an edit, so the extracted text stays revertible
+        if self.cleanup.apply_on_load {
+            for element in &mut parsed.elements {
+                let cleaned = cleanup::clean(&element.content, &self.cleanup);
+                if cleaned != element.content {
+                    element.extracted = Some(std::mem::replace(&mut element.content, cleaned));
+                    element.modified = true;
+                }
+            }
+        }
+        Ok(parsed)
+    }
+    
+    /// A page from the extraction cache when this exact PDF was extracted before, else extracted and cached.
+    /// The cache holds no XML, so retaining it always extracts
+    fn extract_cached(&self, extractor: Extractor, page: usize, ocr_backend: &dyn ocr::OcrBackend, retain_xml: bool)
+        -> Result<ParsedAlto, Box<dyn std::error::Error>> {
+        let source = extraction_cache::source_key(extractor, &self.ocr_engine);
+        if let Some(hit) = self.pdf_hash.filter(|_| !retain_xml).and_then(|hash| self.cache.get(hash, page, &source)) {
+            return Ok(hit);
+        }
+        let parsed = extractor.extract_page(&self.pdf_path, page, ocr_backend, retain_xml)?;
+        if let Some(hash) = self.pdf_hash {
+            if let Err(e) = self.cache.put(hash, page, &source, &parsed) {
+                eprintln!("Error caching page {}: {}", page, e);
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Scratch path in the temp dir, unique to this process and call, so concurrent workers and
+/// other running instances never share a file
+fn temp_path(name: &str) -> std::path::PathBuf {
+    static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    std::env::temp_dir().join(format!("{}_{}_{}", name, std::process::id(), n))
+}
+
+/// An ALTO document reduced to what is kept: elements in points plus header details
+struct ParsedAlto {
+    elements: Vec<SpatialElement>,
+    units: units::SourceUnits,            // Unit the file declared, before conversion to points
+    description: Vec<(String, String)>,   // metadata::ALTO_SOURCE_FIELDS found in the Description
+    raw_xml: Option<String>,              // The XML itself, only when retained for the debug panel
+}
+
+/// Parse ALTO String elements (with page numbers) from pdfalto or OCR output
+fn parse_alto(xml: &str) -> Result<Vec<SpatialElement>, Box<dyn std::error::Error>> {
+    Ok(parse_alto_stream(xml.as_bytes())?.elements)
+}
+
+/// Parse ALTO as it is read (e.g. from pdfalto's stdout), so the XML is never held whole
+fn parse_alto_stream<R: std::io::BufRead>(input: R) -> Result<ParsedAlto, Box<dyn std::error::Error>> {
+    use quick_xml::{Reader, events::Event};
+    
+    let mut reader = Reader::from_reader(input);
+    let mut buf = Vec::new();
+    let mut elements = Vec::new();
+    
+    let mut in_page = false;
+    let mut page_number = 0;
+    let mut font_sizes: std::collections::HashMap<String, f32> = std::collections::HashMap::new();
+    let mut source_units = units::SourceUnits::default();
+    let mut in_unit = false;
+    let mut in_description = false;
+    let mut description = Vec::new();
+    let mut description_field: Option<String> = None;
+    
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let tag_bytes = e.name().as_ref().to_vec();
+                let tag_name = String::from_utf8_lossy(&tag_bytes);
+                
+                if tag_name == "Description" {
+                    in_description = true;
+                } else if in_description && metadata::ALTO_SOURCE_FIELDS.contains(&tag_name.as_ref()) {
+                    description_field = Some(tag_name.to_string());
+                } else if tag_name == "MeasurementUnit" {
+                    in_unit = true;
+                } else if tag_name == "Page" {
+                    in_page = true;
+                    page_number += 1;
+                    if page_number == 1 {
+                        if let Some(dpi) = units::page_dpi(&e) {
+                            source_units.dpi = dpi;
+                        }
+                    }
+                } else if tag_name == "TextStyle" {
+                    let mut id = None;
+                    let mut size = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"ID" => id = Some(String::from_utf8_lossy(&attr.value).to_string()),
+                            b"FONTSIZE" => size = String::from_utf8_lossy(&attr.value).parse::<f32>().ok(),
+                            _ => {}
+                        }
+                    }
+                    if let (Some(id), Some(size)) = (id, size) {
+                        font_sizes.insert(id, size);
+                    }
+                } else if tag_name == "String" && in_page {
+                    let mut content = String::new();
+                    let mut hpos = 0.0;
+                    let mut vpos = 0.0;
+                    let mut width = 0.0;
+                    let mut height = 0.0;
+                    let mut confidence = None;
+                    let mut char_confidence = None;
+                    let mut style_refs = None;
+                    
+                    for attr in e.attributes() {
+                        if let Ok(attr) = attr {
+                            let key = String::from_utf8_lossy(attr.key.as_ref());
+                            let value = String::from_utf8_lossy(&attr.value);
+                            
+                            match key.as_ref() {
+                                "CONTENT" => content = value.to_string(),
+                                "HPOS" => hpos = value.parse().unwrap_or(0.0),
+                                "VPOS" => vpos = value.parse().unwrap_or(0.0),
+                                "WIDTH" => width = value.parse().unwrap_or(0.0),
+                                "HEIGHT" => height = value.parse().unwrap_or(0.0),
+                                "WC" => confidence = value.parse().ok(),
+                                "CC" => char_confidence = confidence::from_cc(&value),
+                                "STYLEREFS" => style_refs = Some(value.to_string()),
+                                _ => {}
+                            }
+                        }
+                    }
+                    
+                    if !content.is_empty() {
+                        elements.push(SpatialElement {
+                            content,
+                            hpos,
+                            vpos,
+                            width,
+                            height,
+                            page: page_number,
+                            confidence: confidence.or(char_confidence),
+                            modified: false,
+                            tag: None,
+                            style_refs,
+                            font_size: None,
+                            extracted: None,
+                        });
+                    }
+                }
+            }
+            Ok(Event::Text(text)) => {
+                if in_unit {
+                    source_units.unit = units::MeasurementUnit::from_name(&String::from_utf8_lossy(&text));
+                }
+                if let Some(field) = description_field.take() {
+                    description.push((field, String::from_utf8_lossy(&text).trim().to_string()));
+                }
+            }
+            Ok(Event::End(e)) => {
+                let tag_bytes = e.name().as_ref().to_vec();
+                let tag_name = String::from_utf8_lossy(&tag_bytes);
+                
+                match tag_name.as_ref() {
+                    "Page" => in_page = false,
+                    "MeasurementUnit" => in_unit = false,
+                    "Description" => in_description = false,
+                    _ => {}
+                }
+                description_field = None;
+            }
+            Ok(Event::Eof) => break,
+            // A broken pipe or truncated output; don't keep reading a stream that won't recover
+            Err(e) => return Err(e.into()),
+            _ => {}
+        }
+        buf.clear();
+    }
+    
+    // STYLEREFS may list several IDs; the first TextStyle among them gives the size
+    for element in &mut elements {
+        element.font_size = element.style_refs.as_deref()
+            .and_then(|refs| refs.split_whitespace().find_map(|id| font_sizes.get(id).copied()));
+    }
+    
+    // Everything downstream works in points; FONTSIZE is already in points
+    let scale = source_units.points_per_unit();
+    if scale != 1.0 {
+        for element in &mut elements {
+            element.hpos *= scale;
+            element.vpos *= scale;
+            element.width *= scale;
+            element.height *= scale;
         }
     }
+    
+    Ok(ParsedAlto { elements, units: source_units, description, raw_xml: None })
 }
 
 impl ChonkerApp {
@@ -88,184 +863,496 @@ impl ChonkerApp {
             return Err(format!("PDF file not found: {}", self.pdf_path).into());
         }
         
-        // Extract PDF using pdfalto
-        let output = Command::new("pdfalto")
-            .args([
-                "-f", "1", "-l", "1",   // Just page 1 for now
-                "-readingOrder",        // Follow visual reading order
-                "-noImage",            // Skip image extraction for speed
-                "-noLineNumbers",      // Clean output without line numbers
-                &self.pdf_path,
-                "/dev/stdout"
-            ])
-            .output()?;
-        
-        if !output.status.success() {
-            return Err("pdfalto failed".into());
+        // Choose a backend per page, then extract each page with it
+        self.tile_cache.clear();
+        self.outline = None;
+        self.figures = None;
+        self.figures_loading = None;
+        self.garbled_dismissed.clear();
+        self.pdf_links = None;
+        self.links_loading = None;
+        self.link_cache = None;
+        self.reload_extraction = None;
+        self.prefetching = None;
+        self.backend_comparing = None;
+        self.ocr_engine = ocr::OcrEngine::load(&self.pdf_path);
+        if let ocr::OcrEngine::Remote(url) = &self.ocr_engine {
+            self.ocr_url_input = url.clone();
+        }
+        let ocr_backend = self.ocr_engine.backend();
+        self.ocr_available = ocr_backend.is_available();
+        // Only the viewed page is parsed now; neighbours are prefetched and distant pages evicted
+        let page_count = page_window::page_count(&self.pdf_path).unwrap_or(1);
+        let page = if self.page_window.pdf_path == self.pdf_path { self.page_window.current } else { 1 };
+        let pdf_hash = extraction_cache::content_hash(&self.pdf_path).ok();
+        self.page_window = page_window::PageWindow::new(&self.pdf_path, pdf_hash, page_count, page);
+        let pages: Vec<usize> = (1..=page_count).collect();
+        self.extractor_decisions = self.decide_extractors(&pages);
+        // Provisional choices aren't recorded, or the sampling would never run again
+        if self.extractor_sampling.is_none() {
+            if let Err(e) = extractors::save_decisions(&self.pdf_path, &self.extractor_decisions) {
+                eprintln!("Error saving extractor decisions: {}", e);
+            }
         }
         
-        self.raw_xml = String::from_utf8_lossy(&output.stdout).to_string();
-        self.parse_spatial_elements()?;
-        self.build_rope_from_elements();
+        self.view_rotation = rotation::load_manual(&self.pdf_path);
+        self.glyph_map = glyph_map::GlyphMap::load(&self.pdf_path);
+        let retain_xml = self.show_xml_debug;
+        let parsed = self.extract_view_page(self.page_window.current, retain_xml)?;
+        self.source_units = parsed.units;
+        self.raw_xml = parsed.raw_xml;
+        self.metadata = metadata::DocumentMetadata::load(&self.pdf_path, parsed.description);
+        let elements = parsed.elements;
+        self.spatial_elements = elements;
+        self.pipeline.heuristics = heuristics::Heuristics::load(&self.pdf_path);
+        self.modified = false;
         
         // Initialize WYSIWYG spatial buffer
-        let elements_for_spatial: Vec<(String, f32, f32, f32, f32)> = self.spatial_elements.iter()
-            .map(|e| (e.content.clone(), e.hpos, e.vpos, e.width, e.height))
-            .collect();
-        self.spatial_buffer = SpatialTextBuffer::from_alto_elements(&elements_for_spatial);
+        let tracking = self.spatial_buffer.track_changes;
+        self.spatial_buffer = self.spatial_buffer_from_elements();
+        self.spatial_buffer.track_changes = tracking;
+        track_changes::restore(&mut self.spatial_buffer, &track_changes::load(&self.page_sidecar_key()));
+        self.bookmarks = bookmarks::Bookmarks::load(&self.pdf_path);
+        self.project_words = spellcheck::ProjectWords::load(&self.pdf_path);
+        self.misspelling_cache = None;
+        self.zoom_mode = zoom::ZoomMode::load(&self.pdf_path);
+        self.ner_suggestions.clear();
+        self.load_page_sidecars();
+        self.auto_accept = review::ReviewAudit::load(&self.pdf_path).map(|audit| audit.threshold);
+        self.page_quality = quality::score_pages(&self.spatial_elements, &self.dictionary);
         
         Ok(())
     }
     
-    fn parse_spatial_elements(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        use quick_xml::{Reader, events::Event};
-        
-        let mut reader = Reader::from_str(&self.raw_xml);
-        let mut buf = Vec::new();
-        self.spatial_elements.clear();
+    /// One page through its chosen backend (text layer if that fails), turned upright, with the
+    /// glyph map and load-time cleanup applied
+    fn extract_view_page(&self, page: usize, retain_xml: bool) -> Result<ParsedAlto, Box<dyn std::error::Error>> {
+        self.page_source().extract(page, retain_xml)
+    }
+    
+    /// What extracting a page depends on, copied out so a worker thread can extract pages too
+    fn page_source(&self) -> PageSource {
+        PageSource {
+            pdf_path: self.pdf_path.clone(),
+            pdf_hash: self.page_window.pdf_hash,
+            ocr_engine: self.ocr_engine.clone(),
+            decisions: self.extractor_decisions.clone(),
+            view_rotation: self.view_rotation,
+            glyph_map: self.glyph_map.clone(),
+            cleanup: self.cleanup.clone(),
+            cache: self.extraction_cache.clone(),
+        }
+    }
+    
+    fn spatial_buffer_from_elements(&self) -> SpatialTextBuffer {
+        Self::spatial_buffer_for(&self.spatial_elements)
+    }
+    
+    /// Buffer over the extracted text, with load-time cleanup applied as edits on top
+    fn spatial_buffer_for(elements: &[SpatialElement]) -> SpatialTextBuffer {
+        let elements_for_spatial: Vec<(String, f32, f32, f32, f32)> = elements.iter()
+            .map(|e| (e.extracted.clone().unwrap_or_else(|| e.content.clone()), e.hpos, e.vpos, e.width, e.height))
+            .collect();
+        let mut buffer = SpatialTextBuffer::from_alto_elements(&elements_for_spatial);
+        for (i, element) in elements.iter().enumerate().filter(|(_, e)| e.extracted.is_some()) {
+            buffer.edit_element(i, &element.content);
+        }
+        buffer
+    }
+    
+    /// Page sidecars (tags, labels, redactions, comments, order, tables) are kept per page
+    fn page_sidecar_key(&self) -> String {
+        page_window::sidecar_key(&self.pdf_path, self.page_window.current)
+    }
+    
+    /// Everything drawn on or attached to the viewed page: tags, labels, redactions,
+    /// comments, reading order and table regions
+    fn load_page_sidecars(&mut self) {
+        self.set_page_sidecars(page_window::PageSidecars::load(&self.pdf_path, self.page_window.current));
+    }
+    
+    fn set_page_sidecars(&mut self, sidecars: page_window::PageSidecars) {
+        self.element_tags = sidecars.tags;
+        self.element_labels = sidecars.labels;
+        self.redactions = sidecars.redactions;
+        self.annotations = sidecars.annotations;
+        self.annotation_draft = None;
+        self.pipeline.reading_order = sidecars.reading_order;
+        self.pipeline.table_regions = sidecars.table_regions;
+        self.order_cache = None; // Listed paragraphs belong to the previous page
+    }
+    
+    /// The viewed page's sidecars, moved out as it is swapped for another page
+    fn take_page_sidecars(&mut self) -> page_window::PageSidecars {
+        page_window::PageSidecars {
+            tags: std::mem::take(&mut self.element_tags),
+            labels: std::mem::take(&mut self.element_labels),
+            redactions: std::mem::take(&mut self.redactions),
+            annotations: std::mem::take(&mut self.annotations),
+            reading_order: std::mem::take(&mut self.pipeline.reading_order),
+            table_regions: std::mem::take(&mut self.pipeline.table_regions),
+            pending_edits: Vec::new(), // Live in the buffer while the page is viewed
+        }
+    }
+    
+    /// View another page. The current one stays parsed, with its edits, until it leaves the
+    /// prefetch window unedited
+    fn show_page(&mut self, page: usize) {
+        let page = page.clamp(1, self.page_window.page_count);
+        if page == self.page_window.current {
+            return;
+        }
+        let incoming = match self.page_window.resident.remove(&page) {
+            Some(resident) => resident,
+            None => match self.extract_view_page(page, false) {
+                Ok(parsed) => page_window::ResidentPage {
+                    elements: parsed.elements,
+                    buffer: None,
+                    sidecars: page_window::PageSidecars::load(&self.pdf_path, page),
+                },
+                Err(e) => {
+                    eprintln!("Error loading page {}: {}", page, e);
+                    return;
+                }
+            },
+        };
+        self.page_window.failed.remove(&page);
         
-        let mut in_page = false;
+        let outgoing_elements = std::mem::replace(&mut self.spatial_elements, incoming.elements);
+        let mut buffer = incoming.buffer.unwrap_or_else(|| Self::page_buffer(&self.spatial_elements, &incoming.sidecars.pending_edits));
+        // View settings carry over; text, cursor and selection belong to the page
+        buffer.transform = self.spatial_buffer.transform;
+        buffer.proportional = self.spatial_buffer.proportional;
+        buffer.vertical_cjk = self.spatial_buffer.vertical_cjk;
+        buffer.track_changes = self.spatial_buffer.track_changes;
+        buffer.glyph_advances = self.spatial_buffer.glyph_advances.clone();
+        let outgoing_buffer = std::mem::replace(&mut self.spatial_buffer, buffer);
+        let previous = self.page_window.current;
+        let outgoing_sidecars = self.take_page_sidecars();
+        self.page_window.resident.insert(previous, page_window::ResidentPage {
+            elements: outgoing_elements,
+            buffer: Some(outgoing_buffer),
+            sidecars: outgoing_sidecars,
+        });
+        self.page_window.current = page;
+        self.page_window.evict();
         
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                    let tag_bytes = e.name().as_ref().to_vec();
-                    let tag_name = String::from_utf8_lossy(&tag_bytes);
-                    
-                    if tag_name == "Page" {
-                        in_page = true;
-                    } else if tag_name == "String" && in_page {
-                        let mut content = String::new();
-                        let mut hpos = 0.0;
-                        let mut vpos = 0.0;
-                        let mut width = 0.0;
-                        let mut height = 0.0;
-                        
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                let key = String::from_utf8_lossy(attr.key.as_ref());
-                                let value = String::from_utf8_lossy(&attr.value);
-                                
-                                match key.as_ref() {
-                                    "CONTENT" => content = value.to_string(),
-                                    "HPOS" => hpos = value.parse().unwrap_or(0.0),
-                                    "VPOS" => vpos = value.parse().unwrap_or(0.0),
-                                    "WIDTH" => width = value.parse().unwrap_or(0.0),
-                                    "HEIGHT" => height = value.parse().unwrap_or(0.0),
-                                    _ => {}
-                                }
-                            }
-                        }
-                        
-                        if !content.is_empty() {
-                            self.spatial_elements.push(SpatialElement {
-                                content,
-                                hpos,
-                                vpos,
-                                width,
-                                height,
-                            });
-                        }
-                    }
+        self.spatial_cursor.rope_pos = 0;
+        self.selection_group.clear();
+        self.review_element = None;
+        self.garbled_dismissed.clear();
+        self.discrepancies.clear();
+        self.ner_suggestions.clear();
+        self.pdf_links = None;
+        self.links_loading = None;
+        self.link_cache = None;
+        self.figures = None;
+        self.figures_loading = None;
+        self.set_page_sidecars(incoming.sidecars);
+        let scores = quality::score_pages(&self.spatial_elements, &self.dictionary);
+        self.record_quality(scores);
+        if self.show_xml_debug {
+            self.capture_raw_xml();
+        }
+    }
+    
+    /// Keep the scores of every page parsed so far, a page's newer score replacing its older one
+    fn record_quality(&mut self, scores: Vec<quality::PageQuality>) {
+        for score in scores {
+            self.page_quality.retain(|known| known.page != score.page);
+            self.page_quality.push(score);
+        }
+        self.page_quality.sort_by_key(|known| known.page);
+    }
+    
+    /// Parse every page and keep them all resident, for conversions that write the whole document
+    fn parse_all_pages(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.page_window.prefetch = self.page_window.page_count;
+        while let Some(page) = self.page_window.next_to_prefetch() {
+            let parsed = self.extract_view_page(page, false)?;
+            let scores = quality::score_pages(&parsed.elements, &self.dictionary);
+            self.record_quality(scores);
+            let sidecars = page_window::PageSidecars::load(&self.pdf_path, page);
+            self.page_window.resident.insert(page, page_window::ResidentPage { elements: parsed.elements, buffer: None, sidecars });
+        }
+        Ok(())
+    }
+    
+    /// Parse the nearest unparsed page in the prefetch window on a worker, one page at a time;
+    /// returns whether a page is still being parsed
+    fn prefetch_page(&mut self) -> bool {
+        if let Some((pdf_path, page, receiver)) = &self.prefetching {
+            let parsed = match receiver.try_recv() {
+                Ok(parsed) => parsed,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return true,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => Err("prefetch worker stopped".to_string()),
+            };
+            let (pdf_path, page) = (pdf_path.clone(), *page);
+            self.prefetching = None;
+            // A page viewed meanwhile was parsed on the spot, and another PDF has its own pages
+            let wanted = pdf_path == self.pdf_path && page != self.page_window.current && !self.page_window.resident.contains_key(&page);
+            match parsed {
+                Ok(parsed) if wanted => {
+                    let scores = quality::score_pages(&parsed.elements, &self.dictionary);
+                    self.record_quality(scores);
+                    let sidecars = page_window::PageSidecars::load(&self.pdf_path, page);
+                    self.page_window.resident.insert(page, page_window::ResidentPage { elements: parsed.elements, buffer: None, sidecars });
                 }
-                Ok(Event::End(e)) => {
-                    let tag_bytes = e.name().as_ref().to_vec();
-                    let tag_name = String::from_utf8_lossy(&tag_bytes);
-                    
-                    if tag_name == "Page" {
-                        in_page = false;
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("Error prefetching page {}: {}", page, e);
+                    if wanted {
+                        self.page_window.failed.insert(page);
                     }
                 }
-                Ok(Event::Eof) => break,
-                _ => {}
             }
-            buf.clear();
         }
-        
-        Ok(())
+        let Some(page) = self.page_window.next_to_prefetch() else { return false };
+        let source = self.page_source();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            // The receiver is gone when another PDF was loaded meanwhile
+            let _ = sender.send(source.extract(page, false).map_err(|e| e.to_string()));
+        });
+        self.prefetching = Some((self.pdf_path.clone(), page, receiver));
+        true
     }
     
-    fn generate_readable_text(&self) -> String {
-        // Group elements into lines and create readable text with proper spacing
-        let mut lines: Vec<Vec<&SpatialElement>> = Vec::new();
-        
-        // Sort elements by vertical position first
-        let mut sorted_elements: Vec<&SpatialElement> = self.spatial_elements.iter().collect();
-        sorted_elements.sort_by(|a, b| a.vpos.partial_cmp(&b.vpos).unwrap());
+    /// A page from the extraction cache when this exact PDF was extracted before, else extracted and cached
+    fn extract_page_cached(&self, pdf_hash: Option<u64>, extractor: Extractor, page: usize, ocr_backend: &dyn ocr::OcrBackend, retain_xml: bool)
+        -> Result<ParsedAlto, Box<dyn std::error::Error>> {
+        PageSource { pdf_hash, ..self.page_source() }.extract_cached(extractor, page, ocr_backend, retain_xml)
+    }
+    
+    /// Extract again just for the XML debug panel; elements and edits are untouched
+    fn capture_raw_xml(&mut self) {
+        let ocr_backend = self.ocr_engine.backend();
+        let page = self.page_window.current;
+        let chosen = self.extractor_decisions.iter().find(|d| d.page == page).map_or(Extractor::TextLayer, |d| d.chosen);
+        match chosen.extract_page(&self.pdf_path, page, ocr_backend.as_ref(), true) {
+            Ok(parsed) => self.raw_xml = parsed.raw_xml,
+            Err(e) => eprintln!("Error capturing XML for page {}: {}", page, e),
+        }
+    }
+    
+    /// Per-page backend: recorded decisions are reused, sampled pages compare
+    /// quality scores, and unsampled pages follow the sample's majority.
+    /// Sampling runs OCR, so it happens on a worker thread; until it reports back
+    /// (see poll_extractor_sampling) undecided pages use the text layer
+    fn decide_extractors(&mut self, pages: &[usize]) -> Vec<ExtractorDecision> {
+        let recorded = extractors::load_decisions(&self.pdf_path);
+        let undecided: Vec<usize> = pages.iter().copied()
+            .filter(|page| !recorded.iter().any(|d| d.page == *page))
+            .collect();
         
-        // Group into lines (within 8 pixels vertically)
-        for element in sorted_elements {
-            let found_line = lines.iter_mut().find(|line| {
-                if let Some(first) = line.first() {
-                    (element.vpos - first.vpos).abs() < 8.0
-                } else {
-                    false
-                }
+        self.extractor_sampling = None;
+        if self.auto_extractor && self.ocr_available && !undecided.is_empty() {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let pdf_path = self.pdf_path.clone();
+            let dictionary = Arc::clone(&self.dictionary);
+            let engine = self.ocr_engine.clone();
+            let sample = extractors::sample_pages(&undecided);
+            thread::spawn(move || {
+                let ocr_backend = engine.backend();
+                let sampled: Vec<ExtractorDecision> = sample.into_iter()
+                    .filter_map(|page| match extractors::compare_page(&pdf_path, page, &dictionary, ocr_backend.as_ref()) {
+                        Ok(decision) => Some(decision),
+                        Err(e) => {
+                            eprintln!("Extractor comparison failed on page {}: {}", page, e);
+                            None
+                        }
+                    })
+                    .collect();
+                // The receiver is gone when another PDF was loaded meanwhile
+                let _ = sender.send(sampled);
             });
-            
-            if let Some(line) = found_line {
-                line.push(element);
-            } else {
-                lines.push(vec![element]);
-            }
+            self.extractor_sampling = Some(receiver);
         }
+        Self::combine_decisions(pages, &recorded, &[])
+    }
+    
+    /// Take the worker's sampled decisions once they are in (`wait` blocks for them), record them,
+    /// and re-extract when a parsed page's backend changed and nothing is edited yet
+    fn poll_extractor_sampling(&mut self, wait: bool) {
+        let Some(receiver) = &self.extractor_sampling else { return };
+        let sampled = if wait {
+            receiver.recv().ok()
+        } else {
+            match receiver.try_recv() {
+                Ok(sampled) => Some(sampled),
+                Err(std::sync::mpsc::TryRecvError::Empty) => return,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => None,
+            }
+        };
+        self.extractor_sampling = None;
+        let Some(sampled) = sampled else { return };
         
-        // Sort words within each line by horizontal position
-        for line in &mut lines {
-            line.sort_by(|a, b| a.hpos.partial_cmp(&b.hpos).unwrap());
+        let pages: Vec<usize> = (1..=self.page_window.page_count).collect();
+        let decisions = Self::combine_decisions(&pages, &extractors::load_decisions(&self.pdf_path), &sampled);
+        let chosen = |decisions: &[ExtractorDecision], page: usize| decisions.iter().find(|d| d.page == page).map(|d| d.chosen);
+        let parsed = std::iter::once(self.page_window.current).chain(self.page_window.resident.keys().copied());
+        let changed: Vec<usize> = parsed.filter(|&page| chosen(&decisions, page) != chosen(&self.extractor_decisions, page)).collect();
+        self.extractor_decisions = decisions;
+        if let Err(e) = extractors::save_decisions(&self.pdf_path, &self.extractor_decisions) {
+            eprintln!("Error saving extractor decisions: {}", e);
         }
+        if changed.is_empty() {
+            return;
+        }
+        if self.edited_element_count() > 0 {
+            println!("⚠️ OCR reads better on page(s) {:?}; re-evaluate in 🤖 Extractors to re-extract", changed);
+        } else if let Err(e) = self.load_pdf() {
+            eprintln!("Error loading PDF: {}", e);
+        }
+    }
+    
+    /// Recorded decisions first, then sampled ones; other pages follow the sample's majority
+    fn combine_decisions(pages: &[usize], recorded: &[ExtractorDecision], sampled: &[ExtractorDecision]) -> Vec<ExtractorDecision> {
+        let majority = extractors::majority_choice(sampled);
         
-        // Reconstruct readable text with section spacing
-        let mut output = String::new();
-        let mut last_vpos = 0.0;
-        
-        for line in lines {
-            if !line.is_empty() {
-                let current_vpos = line[0].vpos;
+        pages.iter()
+            .map(|&page| {
+                recorded.iter().chain(sampled.iter())
+                    .find(|d| d.page == page)
+                    .cloned()
+                    .unwrap_or(ExtractorDecision {
+                        page,
+                        chosen: majority,
+                        text_layer_score: None,
+                        ocr_score: None,
+                        overridden: false,
+                    })
+            })
+            .collect()
+    }
+    
+    fn render_extractor_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_extractors;
+        let mut reload = false;
+        egui::Window::new("🤖 Extractors")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.auto_extractor, "Auto-select by quality score");
+                if self.extractor_sampling.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Comparing OCR on sample pages…");
+                    });
+                }
                 
-                // Add extra spacing for large vertical gaps (section breaks)
-                if last_vpos > 0.0 {
-                    let vertical_gap = current_vpos - last_vpos;
-                    if vertical_gap > 15.0 {  // Large gap - add extra line breaks
-                        let extra_lines = ((vertical_gap / 12.0) as usize).min(3).max(1);
-                        output.push_str(&"\n".repeat(extra_lines));
+                // OCR engine for this document
+                let mut engine = self.ocr_engine.clone();
+                ui.horizontal(|ui| {
+                    ui.label("OCR engine:");
+                    if ui.radio(engine == ocr::OcrEngine::Tesseract, "Tesseract").clicked() {
+                        engine = ocr::OcrEngine::Tesseract;
+                    }
+                    if ui.radio(matches!(engine, ocr::OcrEngine::Remote(_)), "Remote").clicked() {
+                        engine = ocr::OcrEngine::Remote(self.ocr_url_input.clone());
+                    }
+                });
+                if let ocr::OcrEngine::Remote(_) = engine {
+                    ui.horizontal(|ui| {
+                        ui.label("URL:");
+                        let response = ui.text_edit_singleline(&mut self.ocr_url_input);
+                        if response.lost_focus() {
+                            engine = ocr::OcrEngine::Remote(self.ocr_url_input.trim().to_string());
+                        }
+                    });
+                }
+                if engine != self.ocr_engine {
+                    if let Err(e) = engine.save(&self.pdf_path) {
+                        eprintln!("Error saving OCR engine: {}", e);
                     }
+                    self.ocr_available = engine.backend().is_available();
+                    self.ocr_engine = engine;
                 }
+                if !self.ocr_available {
+                    ui.label(format!("{} unavailable - OCR disabled", self.ocr_engine.backend().name()));
+                }
+                ui.separator();
                 
-                let mut line_text = String::new();
-                let mut last_end_pos = 0.0;
-                
-                for element in line {
-                    if !line_text.is_empty() {
-                        // Better spacing calculation for good kerning
-                        let gap = element.hpos - last_end_pos;
-                        if gap > 6.0 {  // Large gap - multiple spaces
-                            let spaces = ((gap / 6.0) as usize).min(8).max(2);
-                            line_text.push_str(&" ".repeat(spaces));
-                        } else {
-                            line_text.push(' '); // Normal single space
+                egui::Grid::new("extractor_decisions").striped(true).show(ui, |ui| {
+                    ui.label("Page");
+                    ui.label("Text layer");
+                    ui.label("OCR");
+                    ui.label("Source");
+                    ui.end_row();
+                    
+                    let format_score = |score: Option<f32>| score.map_or("-".to_string(), |s| format!("{:.1}", s));
+                    for decision in &mut self.extractor_decisions {
+                        ui.label(decision.page.to_string());
+                        ui.label(format_score(decision.text_layer_score));
+                        ui.label(format_score(decision.ocr_score));
+                        
+                        let before = decision.chosen;
+                        egui::ComboBox::from_id_source(("extractor", decision.page))
+                            .selected_text(decision.chosen.label())
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut decision.chosen, Extractor::TextLayer, Extractor::TextLayer.label());
+                                ui.selectable_value(&mut decision.chosen, Extractor::Ocr, Extractor::Ocr.label());
+                            });
+                        if decision.chosen != before {
+                            decision.overridden = true;
+                            reload = true;
                         }
+                        if decision.overridden {
+                            ui.label("manual");
+                        }
+                        ui.end_row();
                     }
-                    
-                    line_text.push_str(&element.content);
-                    last_end_pos = element.hpos + element.width;
-                }
+                });
                 
-                output.push_str(&line_text);
-                output.push('\n');
-                last_vpos = current_vpos;
+                ui.horizontal(|ui| {
+                    if ui.button("🔄 Re-evaluate automatic choices").clicked() {
+                        self.extractor_decisions.retain(|d| d.overridden);
+                        reload = true;
+                    }
+                    if ui.button("🆚 Compare backends").on_hover_text("Run two backends on one page and see where their text differs").clicked() {
+                        self.show_backend_compare = true;
+                    }
+                });
+                
+                // Unchanged PDFs reopen from the cache without running pdfalto or OCR
+                ui.horizontal(|ui| {
+                    ui.label(format!("Cache: {:.1} MB of", extraction_cache::size_bytes() as f32 / (1024.0 * 1024.0)));
+                    if ui.add(egui::DragValue::new(&mut self.extraction_cache.limit_mb).range(1..=65536).suffix(" MB")).changed() {
+                        if let Err(e) = self.extraction_cache.save() {
+                            eprintln!("Error saving cache settings: {}", e);
+                        }
+                        self.extraction_cache.evict();
+                    }
+                    if ui.button("🗑 Clear cache").clicked() {
+                        match extraction_cache::clear() {
+                            Ok(count) => println!("✅ Cleared {} cached page(s)", count),
+                            Err(e) => eprintln!("Error clearing cache: {}", e),
+                        }
+                    }
+                });
+            });
+        self.show_extractors = open;
+        
+        if reload {
+            if let Err(e) = extractors::save_decisions(&self.pdf_path, &self.extractor_decisions) {
+                eprintln!("Error saving extractor decisions: {}", e);
+            }
+            if let Err(e) = self.load_pdf() {
+                eprintln!("Error loading PDF: {}", e);
             }
         }
-        
-        output
     }
     
-    fn build_rope_from_elements(&mut self) {
-        // Build rope text buffer from spatial elements
-        let readable_text = self.generate_readable_text();
-        self.rope = ropey::Rope::from_str(&readable_text);
-        self.cursor_pos = 0;
-        self.modified = false;
+    fn generate_readable_text(&self) -> String {
+        // Run the active reconstruction profile and lay out its lines and paragraphs
+        let ctx = self.pipeline.run(self.spatial_elements.clone());
+        pipeline::render_readable_text(&ctx)
+    }
+    
+    /// Switch to the reflowed text after a reconstruction setting changes; the canvas keeps
+    /// the extracted layout, so the pipeline's result is only visible there and in exports
+    fn show_pipeline_result(&mut self) {
+        self.reflow_mode = true;
+        self.order_cache = None;
     }
     
     fn render_hybrid_smart(&mut self, ui: &mut egui::Ui) {
@@ -286,16 +1373,7 @@ impl ChonkerApp {
         let mut paragraph_elements = Vec::new();
         
         for element in &self.spatial_elements {
-            let content = element.content.trim();
-            
-            // More precise table detection: actual table region VPOS 409-517
-            let is_in_table_region = element.vpos >= 409.0 && element.vpos <= 517.0;
-            let is_table_content = content.contains('$') ||           // Currency values
-                                  content == "N/A" ||                // Table placeholders  
-                                  content.contains('%') ||           // Percentages
-                                  (content.chars().all(|c| c.is_numeric()) && content.len() == 4); // Years like 2011, 2012
-            
-            if is_in_table_region && is_table_content {
+            if table_regions::is_table_element(&self.pipeline.table_regions, element) {
                 table_elements.push(element);
             } else {
                 paragraph_elements.push(element);
@@ -303,19 +1381,21 @@ impl ChonkerApp {
         }
         
         // Render table elements with exact positioning (good for tables)
+        let theme = self.theme.active();
         for element in table_elements {
             let pos = egui::Pos2::new(
                 element.hpos * scale_x,
                 element.vpos * scale_y
             );
             
-            painter.text(
+            let rect = painter.text(
                 pos,
                 egui::Align2::LEFT_TOP,
                 &element.content,
-                egui::FontId::monospace(12.0),
-                egui::Color32::from_rgb(150, 255, 150) // Green for tables
+                self.editor_font(),
+                theme.table_text
             );
+            theme.paint_cue(&painter, rect, theme::StatusCue::Table);
         }
         
         // Render paragraph elements with automatic spacing to prevent jumbling
@@ -332,8 +1412,8 @@ impl ChonkerApp {
                 pos,
                 egui::Align2::LEFT_TOP,
                 &spaced_content,
-                egui::FontId::monospace(12.0),
-                egui::Color32::WHITE
+                self.editor_font(),
+                self.theme.active().text
             );
         }
         
@@ -362,7 +1442,7 @@ impl ChonkerApp {
         for element in sorted_elements {
             let found_line = lines.iter_mut().find(|line| {
                 if let Some(first) = line.first() {
-                    (element.vpos - first.vpos).abs() < 8.0
+                    (element.vpos - first.vpos).abs() < self.pipeline.heuristics.line_tolerance
                 } else {
                     false
                 }
@@ -390,28 +1470,23 @@ impl ChonkerApp {
                 // Add section spacing
                 if last_vpos > 0.0 {
                     let vertical_gap = current_vpos - last_vpos;
-                    if vertical_gap > 15.0 {
-                        let extra_lines = ((vertical_gap / 12.0) as usize).min(3).max(1);
+                    if vertical_gap > self.pipeline.heuristics.section_gap {
+                        let extra_lines = self.pipeline.heuristics.blank_lines_for_gap(vertical_gap);
                         output.push_str(&"\n".repeat(extra_lines));
                     }
                 }
                 
                 let mut line_text = String::new();
-                let mut last_end_pos = 0.0;
+                let mut previous: Option<&SpatialElement> = None;
                 
                 for element in line {
-                    if !line_text.is_empty() {
-                        let gap = element.hpos - last_end_pos;
-                        if gap > 3.0 {
-                            let spaces = ((gap / 8.0) as usize).min(10).max(1);
-                            line_text.push_str(&" ".repeat(spaces));
-                        } else {
-                            line_text.push(' ');
-                        }
+                    if let Some(previous) = previous {
+                        let spaces = self.pipeline.heuristics.for_editor().spaces_between(previous, element);
+                        line_text.push_str(&" ".repeat(spaces));
                     }
                     
                     line_text.push_str(&element.content);
-                    last_end_pos = element.hpos + element.width;
+                    previous = Some(element);
                 }
                 
                 output.push_str(&line_text);
@@ -433,7 +1508,7 @@ impl ChonkerApp {
         for element in sorted_elements {
             let found_line = lines.iter_mut().find(|line| {
                 if let Some(first) = line.first() {
-                    (element.vpos - first.vpos).abs() < 8.0
+                    (element.vpos - first.vpos).abs() < self.pipeline.heuristics.line_tolerance
                 } else {
                     false
                 }
@@ -459,21 +1534,16 @@ impl ChonkerApp {
             
             // Build line text with proper spacing
             let mut line_text = String::new();
-            let mut last_end_pos = 0.0;
+            let mut previous: Option<&SpatialElement> = None;
             
             for element in sorted_line {
-                if !line_text.is_empty() {
-                    let gap = element.hpos - last_end_pos;
-                    if gap > 3.0 {
-                        let spaces = ((gap / 8.0) as usize).min(10).max(1);
-                        line_text.push_str(&" ".repeat(spaces));
-                    } else {
-                        line_text.push(' ');
-                    }
+                if let Some(previous) = previous {
+                    let spaces = self.pipeline.heuristics.for_editor().spaces_between(previous, element);
+                    line_text.push_str(&" ".repeat(spaces));
                 }
                 
                 line_text.push_str(&element.content);
-                last_end_pos = element.hpos + element.width;
+                previous = Some(element);
             }
             
             // Render the line at its proper horizontal position
@@ -481,8 +1551,8 @@ impl ChonkerApp {
                 egui::Pos2::new(line_x, line_y),
                 egui::Align2::LEFT_TOP,
                 &line_text,
-                egui::FontId::monospace(12.0),
-                egui::Color32::WHITE
+                self.editor_font(),
+                self.theme.active().text
             );
         }
     }
@@ -509,15 +1579,8 @@ impl ChonkerApp {
                 // Add space after element if there's a significant gap to the next element
                 if i < line_elements.len() - 1 {
                     let next_element = line_elements[i + 1];
-                    let gap = next_element.hpos - (element.hpos + element.width);
-                    
-                    // If there's a significant gap (>3 pixels), add spaces
-                    if gap > 3.0 {
-                        let spaces_needed = ((gap / 8.0) as usize).min(10).max(1);
-                        display_content.push_str(&" ".repeat(spaces_needed));
-                    } else {
-                        display_content.push(' '); // Single space for normal word separation
-                    }
+                    let spaces_needed = self.pipeline.heuristics.for_editor().spaces_between(element, next_element);
+                    display_content.push_str(&" ".repeat(spaces_needed));
                 }
                 
                 // Render at exact ALTO position
@@ -530,7 +1593,7 @@ impl ChonkerApp {
                     pos,
                     egui::Align2::LEFT_TOP,
                     &display_content,
-                    egui::FontId::monospace(12.0),
+                    self.editor_font(),
                     color
                 );
             }
@@ -589,7 +1652,9 @@ impl ChonkerApp {
         }
         
         // Render each element using current rope content at exact ALTO positions
-        for (_i, element_range) in self.spatial_buffer.element_ranges.iter().enumerate() {
+        self.refresh_script_positions();
+        let theme = self.theme.active();
+        for (i, element_range) in self.spatial_buffer.element_ranges.iter().enumerate() {
             // Get current text from rope (this is the key - live text, not original)
             let current_text = if element_range.rope_start < self.spatial_buffer.rope.len_chars() {
                 self.spatial_buffer.rope.slice(element_range.rope_start..element_range.rope_end.min(self.spatial_buffer.rope.len_chars())).to_string()
@@ -605,30 +1670,30 @@ impl ChonkerApp {
             
             // Render text at spatial position
             if !current_text.is_empty() {
-                painter.text(
+                let rect = painter.text(
                     pos,
                     egui::Align2::LEFT_TOP,
-                    &current_text,
-                    egui::FontId::monospace(12.0),
-                    if element_range.modified { 
-                        egui::Color32::from_rgb(255, 200, 100) // Orange for modified
-                    } else { 
-                        egui::Color32::WHITE 
-                    }
+                    &self.display_text(element_range, &current_text),
+                    scripts::font(self.element_script_position(i), self.editor_font()),
+                    if element_range.modified { theme.modified } else { theme.text }
                 );
+                if element_range.modified {
+                    theme.paint_cue(&painter, rect, theme::StatusCue::Modified);
+                }
             }
             
             // Show bounds if element is overflowing
             if element_range.overflow {
                 let bounds_rect = egui::Rect::from_min_size(pos, 
                     egui::Vec2::new(element_range.visual_bounds.width(), 15.0));
-                painter.rect_stroke(bounds_rect, 0.0, egui::Stroke::new(1.0, egui::Color32::RED));
+                painter.rect_stroke(bounds_rect, 0.0, egui::Stroke::new(1.0, theme.overflow));
+                theme.paint_cue(&painter, bounds_rect.expand(2.0), theme::StatusCue::Overflow);
             }
         }
         
         // Update and render cursor
         self.spatial_cursor.update_position(&self.spatial_buffer);
-        self.spatial_cursor.render(&painter);
+        self.spatial_cursor.render(&painter, self.theme.active().cursor);
         
         // Handle keyboard input for text editing
         ui.input(|i| {
@@ -637,28 +1702,18 @@ impl ChonkerApp {
                 match event {
                     egui::Event::Text(text) => {
                         // Insert text at current cursor position
-                        self.spatial_buffer.insert_text(self.spatial_cursor.rope_pos, text);
-                        self.spatial_cursor.rope_pos += text.chars().count();
-                        self.modified = true;
+                        self.insert_at_cursor(text);
                     }
-                    egui::Event::Key { key, pressed: true, .. } => {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => {
                         match key {
                             egui::Key::Backspace => {
-                                if self.spatial_cursor.rope_pos > 0 {
-                                    self.spatial_buffer.delete_range(self.spatial_cursor.rope_pos - 1, self.spatial_cursor.rope_pos);
-                                    self.spatial_cursor.rope_pos -= 1;
-                                    self.modified = true;
-                                }
+                                self.delete_before_cursor();
                             }
                             egui::Key::ArrowLeft => {
-                                if self.spatial_cursor.rope_pos > 0 {
-                                    self.spatial_cursor.rope_pos -= 1;
-                                }
+                                self.move_cursor_visually(false, modifiers.shift);
                             }
                             egui::Key::ArrowRight => {
-                                if self.spatial_cursor.rope_pos < self.spatial_buffer.rope.len_chars() {
-                                    self.spatial_cursor.rope_pos += 1;
-                                }
+                                self.move_cursor_visually(true, modifiers.shift);
                             }
                             _ => {}
                         }
@@ -673,82 +1728,353 @@ impl ChonkerApp {
         // Combine readable paragraph rendering with WYSIWYG cursor positioning
         let canvas_width = 3000.0;
         let canvas_height = 2000.0;
+        let zoom = self.zoom_mode.resolve(self.page_size(), ui.clip_rect().size());
         
         let (response, painter) = ui.allocate_painter(
-            egui::Vec2::new(canvas_width, canvas_height), 
+            egui::Vec2::new(canvas_width, canvas_height) * zoom, 
             egui::Sense::click_and_drag()
         );
         
+        // Painting and hit-testing both go through this transform
+        self.spatial_buffer.transform = spatial_text::CoordinateTransform {
+            zoom,
+            pan: response.rect.min.to_vec2(),
+        };
+        self.canvas_rect = response.rect.intersect(ui.clip_rect());
+        
         let scale_x = 1.2;
         let scale_y = 1.0;
         
+        // Page image underneath everything
+        if self.show_page_image {
+            self.render_page_image(ui.ctx(), &painter);
+        }
+        if self.show_figures {
+            self.render_figures(ui.ctx(), &painter);
+        }
+        self.refresh_script_positions();
+        
         // Use the readable paragraph rendering approach
         let mut table_elements = Vec::new();
         let mut paragraph_elements = Vec::new();
         
         for element in &self.spatial_elements {
-            let content = element.content.trim();
-            let is_in_table_region = element.vpos >= 409.0 && element.vpos <= 517.0;
-            let is_table_content = content.contains('$') ||
-                                  content == "N/A" ||
-                                  content.contains('%') ||
-                                  (content.chars().all(|c| c.is_numeric()) && content.len() == 4);
-            
-            if is_in_table_region && is_table_content {
+            if table_regions::is_table_element(&self.pipeline.table_regions, element) {
                 table_elements.push(element);
             } else {
                 paragraph_elements.push(element);
             }
         }
         
-        // Render table elements (green)
+        // Render table elements in the theme's table color
+        let theme = self.theme.active();
         for element in table_elements {
-            let pos = egui::Pos2::new(element.hpos * scale_x, element.vpos * scale_y);
-            painter.text(pos, egui::Align2::LEFT_TOP, &element.content, 
-                        egui::FontId::monospace(12.0), egui::Color32::from_rgb(150, 255, 150));
+            let pos = self.spatial_buffer.transform.to_screen(egui::Pos2::new(element.hpos * scale_x, element.vpos * scale_y));
+            let rect = painter.text(pos, egui::Align2::LEFT_TOP, &element.content, 
+                        egui::FontId::new(self.font_settings.size * zoom, self.editor_font().family), theme.table_text);
+            theme.paint_cue(&painter, rect, theme::StatusCue::Table);
+        }
+        
+        // Column tints sit underneath everything else
+        if self.show_columns {
+            self.render_column_overlay(&painter);
+        }
+        if self.show_confidence {
+            self.render_confidence_heatmap(&painter);
+        }
+        if self.show_terminal_grid {
+            self.render_terminal_grid(&painter);
+        }
+        
+        // Search matches sit underneath the text
+        if self.search.open {
+            self.render_search_highlights(&painter);
+        }
+        
+        // Keyboard selection, split into visual pieces across RTL runs
+        if let Some((start, end)) = self.spatial_buffer.selection {
+            let color = ui.visuals().selection.bg_fill.gamma_multiply(0.6);
+            for rect in self.spatial_buffer.selection_rects(start, end) {
+                painter.rect_filled(self.spatial_buffer.document_rect_to_screen(rect), 0.0, color);
+            }
         }
         
         // Render live editable text in readable format (not individual elements)
         self.render_live_readable_paragraphs(&painter, scale_x, scale_y);
         
+        // Edited and overflowing elements get shape cues on accessible themes
+        for range in &self.spatial_buffer.element_ranges {
+            let rect = self.spatial_buffer.document_rect_to_screen(range.visual_bounds);
+            if range.modified {
+                theme.paint_cue(&painter, rect, theme::StatusCue::Modified);
+            }
+            if range.overflow {
+                theme.paint_cue(&painter, rect.expand(2.0), theme::StatusCue::Overflow);
+            }
+        }
+        
+        // Redactions cover the text completely
+        for rect in self.redacted_rects() {
+            painter.rect_filled(self.spatial_buffer.document_rect_to_screen(rect), 0.0, egui::Color32::BLACK);
+        }
+        
+        // Tracked edits: replaced text struck through above the new text
+        self.render_pending_changes(&painter);
+        
+        // Tag outlines, with the filter dimming everything else
+        self.render_tag_overlay(&painter);
+        if self.labeling_mode {
+            self.render_label_overlay(&painter);
+        }
+        for suggestion in &self.ner_suggestions {
+            if let Some(range) = self.spatial_buffer.element_ranges.get(suggestion.element_idx) {
+                let rect = self.spatial_buffer.document_rect_to_screen(range.visual_bounds).expand(2.0);
+                painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, suggestion.label.color()));
+            }
+        }
+        
+        // Bounding boxes for debugging coordinates
+        if self.show_bounds {
+            self.render_bounds_overlay(&painter);
+        }
+        
+        // Extraction order drawn over the text
+        if self.show_reading_order {
+            self.render_reading_order(&painter);
+        }
+        
+        // Outline the element under review
+        if let Some(range) = self.review_element.and_then(|i| self.spatial_buffer.element_ranges.get(i)) {
+            let rect = self.spatial_buffer.document_rect_to_screen(range.visual_bounds).expand(2.0);
+            painter.rect_stroke(rect, 2.0, egui::Stroke::new(2.0, egui::Color32::from_rgb(80, 160, 255)));
+        }
+        
+        // Multi-selection outlines, then drag handles on the selected element
+        for range in self.selection_group.iter().filter_map(|&i| self.spatial_buffer.element_ranges.get(i)) {
+            let rect = self.spatial_buffer.document_rect_to_screen(range.visual_bounds).expand(1.0);
+            painter.rect_stroke(rect, 1.0, egui::Stroke::new(1.0, self.theme.active().selection));
+        }
+        self.render_resize_handles(ui);
+        
+        // Comment markers in the right margin
+        self.render_annotation_markers(ui);
+        
+        // Text layer vs OCR disagreements
+        self.render_discrepancies(&painter, &response);
+        
+        // Red squiggles under words missing from the dictionaries
+        let misspellings = if self.spellcheck_enabled && !self.dictionary.is_empty() {
+            // Rechecked only when the text changes
+            let revision = self.spatial_buffer.revision;
+            if !matches!(&self.misspelling_cache, Some((cached, _)) if *cached == revision) {
+                let found = spellcheck::find_misspellings(&self.spatial_buffer.rope, &self.dictionary, &self.project_words);
+                self.misspelling_cache = Some((revision, found));
+            }
+            self.misspelling_cache.as_ref().map_or_else(Vec::new, |(_, found)| found.clone())
+        } else {
+            Vec::new()
+        };
+        for misspelling in &misspellings {
+            self.render_squiggle(&painter, misspelling.start, misspelling.end);
+        }
+        
+        // Blue underlines under links; Ctrl+click opens them
+        if self.show_links {
+            self.ensure_pdf_links();
+            self.refresh_link_spans();
+            for (start, end, _) in self.link_cache.iter().flat_map(|(_, spans)| spans) {
+                if let Some(rect) = self.range_screen_rect(start, end) {
+                    painter.line_segment([rect.left_bottom(), rect.right_bottom()], egui::Stroke::new(1.0, egui::Color32::from_rgb(80, 150, 255)));
+                }
+            }
+            if response.hovered() && ui.input(|i| i.modifiers.command) {
+                let over_link = response.hover_pos()
+                    .and_then(|pos| self.spatial_buffer.screen_to_rope_position(pos))
+                    .and_then(|pos| self.link_at(pos))
+                    .is_some();
+                if over_link {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                }
+            }
+        }
+        
+        // Bates stamps outlined while their window is open
+        if self.show_bates {
+            let current = self.page_window.current;
+            for stamp in bates::detect(&self.document_elements()).into_iter().filter(|stamp| stamp.page == current) {
+                let rect = self.spatial_buffer.document_rect_to_screen(stamp.bounds).expand(2.0);
+                painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.5, egui::Color32::from_rgb(120, 170, 255)));
+            }
+        }
+        
+        // Form fields outlined while their window is open
+        if self.show_fields {
+            for kv in self.page_fields() {
+                let [x, y, w, h] = kv.bbox;
+                let rect = self.spatial_buffer.document_rect_to_screen(egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(w, h))).expand(2.0);
+                painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.5, egui::Color32::from_rgb(120, 200, 120)));
+            }
+        }
+        
+        // Orange dashes under elements that look like extraction failures
+        if self.garbled_check {
+            for (i, _) in self.garbled_findings() {
+                self.render_garble_underline(&painter, i);
+            }
+        }
+        
+        // Right-click a misspelling for suggestions, or any element to tag it
+        if response.secondary_clicked() {
+            self.context_element = response.interact_pointer_pos()
+                .and_then(|pos| self.spatial_buffer.element_at_screen_position(pos));
+            self.context_pos = response.interact_pointer_pos()
+                .map(|pos| self.spatial_buffer.transform.to_document(pos));
+            self.spell_menu = response.interact_pointer_pos()
+                .and_then(|pos| self.spatial_buffer.screen_to_rope_position(pos))
+                .and_then(|rope_pos| misspellings.iter().find(|m| rope_pos >= m.start && rope_pos <= m.end))
+                .map(|m| spellcheck::SpellMenu {
+                    misspelling: m.clone(),
+                    // Edit distance over the whole word list, so each word is only scored once
+                    suggestions: self.spell_suggestions.entry(m.word.clone())
+                        .or_insert_with(|| spellcheck::suggestions(&m.word, &self.dictionary, 5))
+                        .clone(),
+                });
+        }
+        response.context_menu(|ui| self.render_canvas_context_menu(ui));
+        
+        // Measure real glyph advances so clicks land on character boundaries
+        if self.spatial_buffer.proportional {
+            let font = self.editor_font();
+            ui.fonts(|f| self.spatial_buffer.measure_glyphs(|c| f.glyph_width(&font, c)));
+        }
+        
+        // Hovering a modified element shows what it was before editing
+        if let Some(hover_pos) = response.hover_pos() {
+            if let Some(idx) = self.spatial_buffer.element_at_screen_position(hover_pos) {
+                let range = &self.spatial_buffer.element_ranges[idx];
+                if range.modified {
+                    let was = &range.original_text;
+                    let now = self.spatial_buffer.element_text(idx).unwrap_or_default();
+                    response.clone().on_hover_text_at_pointer(format!("was: {}\nnow: {}", was, now));
+                }
+            }
+        }
+        
+        // Insert-text-box tool takes over dragging on the canvas
+        if self.text_box_tool {
+            if let Some(rect) = self.drag_document_rect(&response, &painter, egui::Color32::from_rgb(120, 255, 160)) {
+                self.pending_text_box = Some(rect);
+                self.text_box_input.clear();
+            }
+        } else if self.redact_tool {
+            if let Some(rect) = self.drag_document_rect(&response, &painter, egui::Color32::from_rgb(255, 90, 90)) {
+                self.redactions.regions.push(rect);
+                self.save_redactions();
+            }
+        } else if self.table_tool {
+            if let Some(rect) = self.drag_document_rect(&response, &painter, self.theme.active().table_text) {
+                self.pipeline.table_regions.push(table_regions::TableRegion { page: self.page_window.current, rect, columns: self.table_tool_columns });
+                self.save_table_regions();
+            }
+        } else if self.zone_tool {
+            if let Some(rect) = self.drag_document_rect(&response, &painter, egui::Color32::from_rgb(200, 140, 255)) {
+                let name = match self.zone_tool_name.trim() {
+                    "" => zones::next_name(&self.zones),
+                    name => name.to_string(),
+                };
+                let page = self.page_at(rect);
+                self.zones.push(zones::Zone::new(&name, page, rect));
+                self.zone_tool_name.clear();
+                self.show_zones = true;
+            }
+        }
+        self.render_table_regions(&painter);
+        self.render_zones(&painter);
+        
         // WYSIWYG cursor and editing
         if response.clicked() {
+            self.focused_pane = self.rendering_pane;
             if let Some(click_pos) = response.interact_pointer_pos() {
                 if let Some(rope_pos) = self.spatial_buffer.screen_to_rope_position(click_pos) {
                     self.spatial_cursor.rope_pos = rope_pos;
+                    if self.show_links && ui.input(|i| i.modifiers.command) {
+                        if let Some(target) = self.link_at(rope_pos) {
+                            match links::open(&target) {
+                                Ok(()) => println!("✅ Opened {}", target),
+                                Err(e) => eprintln!("Error opening link {}: {}", target, e),
+                            }
+                        }
+                    }
+                }
+                self.spatial_buffer.selection = None;
+                let clicked = self.spatial_buffer.element_at_screen_position(click_pos);
+                if ui.input(|i| i.modifiers.shift) {
+                    if let Some(idx) = clicked {
+                        match self.selection_group.iter().position(|&i| i == idx) {
+                            Some(pos) => { self.selection_group.remove(pos); }
+                            None => self.selection_group.push(idx),
+                        }
+                    }
+                } else {
+                    self.selection_group = clicked.into_iter().collect();
                 }
+                self.select_element(clicked);
             }
         }
         
         // Update and render cursor
         self.spatial_cursor.update_position(&self.spatial_buffer);
-        self.spatial_cursor.render(&painter);
+        self.spatial_cursor.render(&painter, self.theme.active().cursor);
+        
+        // Screen readers get the text, cursor and selection of the focused pane
+        if self.rendering_pane == self.focused_pane {
+            self.publish_accessibility(ui, &response);
+        }
+        
+        // Glide a jumped-to cursor to the middle of the viewport
+        if self.scroll_to_cursor {
+            if let Some(pos) = self.spatial_cursor.screen_pos {
+                let target = (pos - response.rect.min) - ui.clip_rect().size() / 2.0;
+                let now = ui.input(|i| i.time);
+                self.scroll_animation = Some(animation::ScrollAnimation::new(
+                    self.scroll_offset, target.max(egui::Vec2::ZERO), now));
+            }
+            self.scroll_to_cursor = false;
+        }
+        
+        // Dialog text fields own the keyboard while focused
+        if ui.ctx().memory(|m| m.focused().is_some()) {
+            return;
+        }
+        
+        // In split view only the focused pane takes the keyboard
+        if self.rendering_pane != self.focused_pane {
+            return;
+        }
+        
+        // Labeling mode turns digits into label hotkeys instead of typing
+        if self.labeling_mode {
+            self.handle_label_keys(ui);
+            return;
+        }
         
         // Handle text editing
         ui.input(|i| {
             for event in &i.events {
                 match event {
                     egui::Event::Text(text) => {
-                        self.spatial_buffer.insert_text(self.spatial_cursor.rope_pos, text);
-                        self.spatial_cursor.rope_pos += text.chars().count();
-                        self.modified = true;
+                        self.insert_at_cursor(text);
                     }
-                    egui::Event::Key { key, pressed: true, .. } => {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => {
                         match key {
                             egui::Key::Backspace => {
-                                if self.spatial_cursor.rope_pos > 0 {
-                                    self.spatial_buffer.delete_range(self.spatial_cursor.rope_pos - 1, self.spatial_cursor.rope_pos);
-                                    self.spatial_cursor.rope_pos -= 1;
-                                    self.modified = true;
-                                }
+                                self.delete_before_cursor();
                             }
                             egui::Key::ArrowLeft => {
-                                if self.spatial_cursor.rope_pos > 0 { self.spatial_cursor.rope_pos -= 1; }
+                                self.move_cursor_visually(false, modifiers.shift);
                             }
                             egui::Key::ArrowRight => {
-                                if self.spatial_cursor.rope_pos < self.spatial_buffer.rope.len_chars() { 
-                                    self.spatial_cursor.rope_pos += 1; 
-                                }
+                                self.move_cursor_visually(true, modifiers.shift);
                             }
                             _ => {}
                         }
@@ -766,52 +2092,93 @@ impl ChonkerApp {
         // Find the starting position (use first non-table element)
         let mut start_pos = egui::Pos2::new(100.0, 100.0); // Default position
         for element in &self.spatial_elements {
-            let content = element.content.trim();
-            let is_in_table_region = element.vpos >= 409.0 && element.vpos <= 517.0;
-            let is_table_content = content.contains('$') ||
-                                  content == "N/A" ||
-                                  content.contains('%') ||
-                                  (content.chars().all(|c| c.is_numeric()) && content.len() == 4);
-            
-            if !(is_in_table_region && is_table_content) {
+            if !table_regions::is_table_element(&self.pipeline.table_regions, element) {
                 start_pos = egui::Pos2::new(element.hpos * scale_x, element.vpos * scale_y);
                 break;
             }
         }
         
+        let transform = self.spatial_buffer.transform;
+        let mut font = self.editor_font();
+        font.size *= transform.zoom;
+        
+        if self.show_invisibles {
+            // Same 80-char lines in visual order, with whitespace swapped for marks
+            let chars: Vec<char> = live_text.chars().collect();
+            let mut slots: Vec<Option<usize>> = Vec::with_capacity(chars.len() + chars.len() / 80);
+            for (i, chunk) in chars.chunks(80).enumerate() {
+                if i > 0 {
+                    slots.push(None);
+                }
+                let order = bidi::visual_order(&chunk.iter().collect::<String>());
+                slots.extend((0..chunk.len()).map(|slot| Some(i * 80 + order.logical(slot))));
+            }
+            let palette = self.invisibles_palette();
+            let job = invisibles::layout_job_reordered(&chars, slots.into_iter(), &self.spatial_buffer.inferred_spaces(),
+                                                       self.rope_script_positions(), font, palette);
+            let galley = painter.layout_job(job);
+            painter.galley(transform.to_screen(start_pos), galley, palette.text);
+            return;
+        }
+        
+        // Superscripts and subscripts drawn smaller and raised/lowered, same 80-char lines in visual order
+        if let Some(positions) = self.rope_script_positions() {
+            let color = self.theme.active().text;
+            let format_for = |position: scripts::ScriptPosition| scripts::text_format(position, &font, color);
+            let chars: Vec<char> = live_text.chars().collect();
+            let mut job = egui::text::LayoutJob::default();
+            let mut run = String::new();
+            let mut run_position = scripts::ScriptPosition::Normal;
+            for (i, chunk) in chars.chunks(80).enumerate() {
+                let order = bidi::visual_order(&chunk.iter().collect::<String>());
+                let line_break = (i > 0).then_some(('\n', scripts::ScriptPosition::Normal));
+                let visual = (0..chunk.len()).map(|slot| {
+                    let logical = i * 80 + order.logical(slot);
+                    (chars[logical], positions.get(logical).copied().unwrap_or(scripts::ScriptPosition::Normal))
+                });
+                for (c, position) in line_break.into_iter().chain(visual) {
+                    if position != run_position && !run.is_empty() {
+                        job.append(&std::mem::take(&mut run), 0.0, format_for(run_position));
+                    }
+                    run_position = position;
+                    run.push(c);
+                }
+            }
+            if !run.is_empty() {
+                job.append(&run, 0.0, format_for(run_position));
+            }
+            let galley = painter.layout_job(job);
+            painter.galley(transform.to_screen(start_pos), galley, color);
+            return;
+        }
+        
         // Format live text with line breaks for readability
         let formatted_text = live_text
             .chars()
             .collect::<Vec<char>>()
             .chunks(80) // Break into 80-character lines
-            .map(|chunk| chunk.iter().collect::<String>())
+            .map(|chunk| bidi::display(&chunk.iter().collect::<String>()))
             .collect::<Vec<String>>()
             .join("\n");
         
         painter.text(
-            start_pos,
+            transform.to_screen(start_pos),
             egui::Align2::LEFT_TOP,
             &formatted_text,
-            egui::FontId::monospace(12.0),
-            egui::Color32::WHITE
+            font,
+            self.theme.active().text
         );
     }
     
     fn render_live_paragraph_text(&self, painter: &egui::Painter, scale_x: f32, scale_y: f32) {
         // Render the current rope content using spatial positioning
         // This shows the LIVE edited text, not the original ALTO text
+        let theme = self.theme.active();
         
-        for element_range in &self.spatial_buffer.element_ranges {
+        for (i, element_range) in self.spatial_buffer.element_ranges.iter().enumerate() {
             // Skip table elements (they're handled separately)
             if let Some(original_element) = self.spatial_elements.get(element_range.element_id) {
-                let content = original_element.content.trim();
-                let is_in_table_region = original_element.vpos >= 409.0 && original_element.vpos <= 517.0;
-                let is_table_content = content.contains('$') ||
-                                      content == "N/A" ||
-                                      content.contains('%') ||
-                                      (content.chars().all(|c| c.is_numeric()) && content.len() == 4);
-                
-                if is_in_table_region && is_table_content {
+                if table_regions::is_table_element(&self.pipeline.table_regions, original_element) {
                     continue; // Skip table elements
                 }
             }
@@ -829,34 +2196,37 @@ impl ChonkerApp {
                     element_range.visual_bounds.min.y * scale_y
                 );
                 
-                painter.text(
+                let rect = painter.text(
                     pos,
                     egui::Align2::LEFT_TOP,
-                    &current_text,
-                    egui::FontId::monospace(12.0),
-                    if element_range.modified {
-                        egui::Color32::from_rgb(255, 200, 100) // Orange for edited
-                    } else {
-                        egui::Color32::WHITE
-                    }
+                    &self.display_text(element_range, &current_text),
+                    scripts::font(self.element_script_position(i), self.editor_font()),
+                    if element_range.modified { theme.modified } else { theme.text }
                 );
+                if element_range.modified {
+                    theme.paint_cue(painter, rect, theme::StatusCue::Modified);
+                }
             }
         }
     }
     
     fn render_readable_display(&mut self, ui: &mut egui::Ui) {
         // Use the old readable text approach that worked well
-        let readable_text = self.generate_readable_text();
+        let ctx = self.pipeline.run(self.spatial_elements.clone());
+        let (readable_text, inferred) = pipeline::render_readable_spans(&ctx);
         
         ui.allocate_ui_with_layout(
             egui::Vec2::new(5000.0, 2000.0),  // Very wide area
             egui::Layout::top_down(egui::Align::LEFT),
             |ui| {
-                ui.add(egui::Label::new(
-                    egui::RichText::new(&readable_text)
-                        .monospace()
-                        .size(12.0)
-                ));
+                if self.show_invisibles {
+                    let font = self.editor_font();
+                    ui.add(egui::Label::new(invisibles::layout_job(&readable_text, &inferred, font, self.invisibles_palette())));
+                } else {
+                    ui.add(egui::Label::new(
+                        egui::RichText::new(&readable_text).font(self.editor_font())
+                    ));
+                }
             }
         );
         
@@ -873,152 +2243,5190 @@ impl ChonkerApp {
         }
     }
     
-    fn format_xml(&self) -> String {
-        // Simple XML formatting for better readability
-        let mut formatted = String::new();
-        let mut indent_level: usize = 0;
-        let lines: Vec<&str> = self.raw_xml.lines().collect();
-        
-        for line in lines {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-            
-            // Decrease indent for closing tags
-            if trimmed.starts_with("</") {
-                indent_level = indent_level.saturating_sub(1);
+    /// Insert typed text at the spatial cursor
+    fn insert_at_cursor(&mut self, text: &str) {
+        let pos = self.spatial_cursor.rope_pos;
+        let len = text.chars().count();
+        self.spatial_buffer.insert_text(pos, text);
+        self.bookmarks.shift_for_insert(pos, len);
+        self.spatial_cursor.rope_pos += len;
+        self.modified = true;
+    }
+    
+    /// Delete the character before the spatial cursor
+    fn delete_before_cursor(&mut self) {
+        let pos = self.spatial_cursor.rope_pos;
+        if pos > 0 {
+            self.spatial_buffer.delete_range(pos - 1, pos);
+            self.bookmarks.shift_for_delete(pos - 1, pos);
+            self.spatial_cursor.rope_pos -= 1;
+            self.modified = true;
+        }
+    }
+    
+    /// Drop a bookmark at the cursor and open the bookmarks panel
+    fn add_bookmark_at_cursor(&mut self) {
+        self.bookmarks.add(self.spatial_cursor.rope_pos);
+        self.show_bookmarks = true;
+        self.save_bookmarks();
+    }
+    
+    fn save_bookmarks(&self) {
+        if let Err(e) = self.bookmarks.save(&self.pdf_path) {
+            eprintln!("Error saving bookmarks: {}", e);
+        }
+    }
+    
+    fn render_bookmarks_panel(&mut self, ctx: &egui::Context) {
+        egui::SidePanel::right("bookmarks").show(ctx, |ui| {
+            ui.heading("🔖 Bookmarks");
+            if self.bookmarks.items.is_empty() {
+                ui.label("Ctrl+F2 drops a bookmark at the cursor");
             }
             
-            // Add indentation
-            formatted.push_str(&"  ".repeat(indent_level));
-            formatted.push_str(trimmed);
-            formatted.push('\n');
+            let mut jump_to = None;
+            let mut remove = None;
+            let mut renamed = false;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (i, bookmark) in self.bookmarks.items.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        renamed |= ui.text_edit_singleline(&mut bookmark.name).lost_focus();
+                        if ui.small_button("➡").clicked() {
+                            jump_to = Some(bookmark.rope_pos);
+                        }
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                    let snippet = bookmarks::Bookmarks::snippet(&self.spatial_buffer.rope, bookmark.rope_pos);
+                    ui.label(egui::RichText::new(snippet).monospace().weak());
+                    ui.separator();
+                }
+            });
             
-            // Increase indent for opening tags (but not self-closing)
-            if trimmed.starts_with('<') && !trimmed.starts_with("</") && !trimmed.ends_with("/>") && !trimmed.starts_with("<?") {
-                indent_level += 1;
+            if let Some(pos) = jump_to {
+                self.jump_to_rope_position(pos);
             }
-        }
-        
-        formatted
+            if let Some(i) = remove {
+                self.bookmarks.items.remove(i);
+            }
+            if renamed || remove.is_some() {
+                self.save_bookmarks();
+            }
+        });
     }
-}
-
-impl eframe::App for ChonkerApp {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        // Hot reload with Ctrl+U
-        ctx.input(|i| {
-            if i.key_pressed(egui::Key::U) && i.modifiers.ctrl {
-                // Bootleg hot reload: quit and restart in right quadrant
-                println!("🔄 Hot reloading...");
-                
-                // Use nohup to properly detach the process
-                let spawn_result = std::process::Command::new("nohup")
-                    .arg("/Users/jack/.local/bin/chonker9")
-                    .arg("--right-quadrant")
-                    .stdout(std::process::Stdio::null())
-                    .stderr(std::process::Stdio::null())
-                    .spawn();
-                    
-                match spawn_result {
-                    Ok(_) => {
-                        println!("✅ Hot reload spawned with nohup");
-                        thread::sleep(Duration::from_millis(100));
-                        std::process::exit(0);
+    
+    /// Editable document metadata; edits are kept beside the PDF and go into exports
+    fn render_metadata_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_metadata;
+        let mut committed = false;
+        egui::Window::new("ℹ Metadata")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("metadata_grid").num_columns(2).show(ui, |ui| {
+                    for (key, label) in metadata::DocumentMetadata::FIELDS {
+                        ui.label(label);
+                        if let Some(value) = self.metadata.field_mut(key) {
+                            committed |= ui.text_edit_singleline(value).lost_focus();
+                        }
+                        ui.end_row();
                     }
-                    Err(e) => {
-                        eprintln!("❌ nohup spawn failed: {}, trying direct spawn", e);
-                        // Try direct spawn with detached stdio
-                        if let Ok(_) = std::process::Command::new("/Users/jack/.local/bin/chonker9")
-                            .arg("--right-quadrant")
-                            .stdin(std::process::Stdio::null())
-                            .stdout(std::process::Stdio::null()) 
-                            .stderr(std::process::Stdio::null())
-                            .spawn() {
-                            println!("✅ Direct spawn succeeded");
-                            thread::sleep(Duration::from_millis(100));
-                            std::process::exit(0);
-                        } else {
-                            eprintln!("❌ All spawn methods failed");
+                });
+                if !self.metadata.alto_source.is_empty() {
+                    ui.separator();
+                    ui.label(egui::RichText::new("ALTO Description").strong());
+                    egui::Grid::new("alto_description_grid").num_columns(2).show(ui, |ui| {
+                        for (key, value) in &self.metadata.alto_source {
+                            ui.label(key);
+                            ui.label(egui::RichText::new(value).weak());
+                            ui.end_row();
                         }
+                    });
+                }
+                ui.separator();
+                if ui.button("↺ Reload from PDF").clicked() {
+                    if let Err(e) = metadata::DocumentMetadata::clear_edits(&self.pdf_path) {
+                        eprintln!("Error removing metadata edits: {}", e);
                     }
+                    self.metadata = metadata::DocumentMetadata::load(&self.pdf_path, self.metadata.alto_source.clone());
                 }
+            });
+        self.show_metadata = open;
+        
+        if committed {
+            if let Err(e) = self.metadata.save(&self.pdf_path) {
+                eprintln!("Error saving metadata: {}", e);
             }
-        });
-        // Top panel with controls
-        egui::TopBottomPanel::top("controls").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                if ui.button("📁 Load PDF").clicked() {
-                    if let Err(e) = self.load_pdf() {
-                        eprintln!("Error loading PDF: {}", e);
-                    }
+        }
+    }
+    
+    /// The PDF's own table of contents; entries open their page and jump to the first word at their destination
+    fn render_outline_panel(&mut self, ctx: &egui::Context) {
+        if self.outline.is_none() {
+            self.outline = Some(outline::extract(&self.pdf_path).unwrap_or_else(|e| {
+                eprintln!("Error reading PDF outline: {}", e);
+                Vec::new()
+            }));
+        }
+        let mut jump_to = None;
+        egui::SidePanel::right("outline").show(ctx, |ui| {
+            ui.heading("📑 Outline");
+            let entries = self.outline.as_deref().unwrap_or_default();
+            if entries.is_empty() {
+                ui.label("This PDF has no outline");
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for entry in entries {
+                    ui.horizontal(|ui| {
+                        ui.add_space(entry.level as f32 * 12.0);
+                        if ui.link(&entry.title).clicked() {
+                            jump_to = Some(entry.clone());
+                        }
+                        ui.label(egui::RichText::new(format!("p.{}", entry.page)).weak());
+                    });
                 }
-                
+            });
+        });
+        if let Some(entry) = jump_to {
+            self.show_page(entry.page);
+            if let Some(pos) = self.outline_target(&entry) {
+                self.jump_to_rope_position(pos);
+            }
+        }
+    }
+    
+    /// Rope position of the first word on the entry's page at or below its destination
+    fn outline_target(&self, entry: &outline::OutlineEntry) -> Option<usize> {
+        let top = entry.top.unwrap_or(0.0);
+        let on_page: Vec<&ElementRange> = self.spatial_buffer.element_ranges.iter()
+            .filter(|range| self.spatial_elements.get(range.element_id).is_some_and(|e| e.page == entry.page))
+            .collect();
+        let below = on_page.iter()
+            .filter(|range| range.visual_bounds.max.y >= top)
+            .min_by(|a, b| a.visual_bounds.min.y.total_cmp(&b.visual_bounds.min.y).then(a.visual_bounds.min.x.total_cmp(&b.visual_bounds.min.x)));
+        below.or(on_page.first()).map(|range| range.rope_start)
+    }
+    
+    fn render_pipeline_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_pipeline;
+        let mut changed = false;
+        egui::Window::new("⚙ Reconstruction Pipeline")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let profile_names: Vec<String> = self.pipeline.profiles.iter().map(|p| p.name.clone()).collect();
+                egui::ComboBox::from_label("Profile")
+                    .selected_text(profile_names[self.pipeline.active_profile].as_str())
+                    .show_ui(ui, |ui| {
+                        for (i, name) in profile_names.iter().enumerate() {
+                            changed |= ui.selectable_value(&mut self.pipeline.active_profile, i, name.as_str()).changed();
+                        }
+                    });
                 ui.separator();
                 
-                if ui.button("🔍 XML Debug").clicked() {
-                    self.show_xml_debug = !self.show_xml_debug;
-                }
-                
-                
-                if self.show_xml_debug {
-                    ui.label("📋 Debug Mode");
-                    if ui.button("💾 Save XML").clicked() {
-                        if let Err(e) = std::fs::write("chonker9_debug.xml", &self.raw_xml) {
-                            eprintln!("Error saving XML: {}", e);
+                let profile = self.pipeline.profile_mut();
+                let pass_count = profile.passes.len();
+                let mut move_up = None;
+                let mut move_down = None;
+                for (i, (name, enabled)) in profile.passes.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        changed |= ui.checkbox(enabled, name.as_str()).changed();
+                        if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).clicked() {
+                            move_up = Some(i);
                         }
-                    }
-                } else {
-                    if ui.button("💾 Save Text").clicked() {
-                        let content = self.spatial_buffer.rope.to_string();
-                        if let Err(e) = std::fs::write("chonker9_edited.txt", content) {
-                            eprintln!("Error saving text: {}", e);
+                        if ui.add_enabled(i + 1 < pass_count, egui::Button::new("⬇").small()).clicked() {
+                            move_down = Some(i);
                         }
-                    }
+                    });
+                }
+                if let Some(i) = move_up {
+                    profile.passes.swap(i, i - 1);
+                    changed = true;
+                }
+                if let Some(i) = move_down {
+                    profile.passes.swap(i, i + 1);
+                    changed = true;
                 }
             });
-        });
+        self.show_pipeline = open;
         
-        // Main content area
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if self.show_xml_debug {
-                // XML Debug View - Formatted and Readable
-                ui.heading("🔍 Raw ALTO XML Structure");
-                
-                // Format XML for better readability
-                let formatted_xml = self.format_xml();
-                
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    ui.add(egui::TextEdit::multiline(&mut formatted_xml.as_str())
-                        .font(egui::TextStyle::Monospace)
-                        .code_editor()
-                        .desired_width(f32::INFINITY)
-                        .desired_rows(40));
-                });
-            } else {
-                // PDF View with Absolute Coordinates
+        if changed {
+            self.show_pipeline_result();
+        }
+    }
+    
+    /// Paragraphs in reading order; selected ones move earlier or later
+    fn render_reading_order_window(&mut self, ctx: &egui::Context) {
+        // The pipeline only reruns after a setting or the page changes
+        let (mut keys, texts) = match self.order_cache.take() {
+            Some(cached) => cached,
+            None => {
+                let pass_ctx = self.pipeline.run(self.spatial_elements.clone());
+                (reading_order::paragraph_keys(&pass_ctx), pipeline::paragraph_texts(&pass_ctx))
+            }
+        };
+        let mut open = self.show_reading_order_list;
+        let mut move_by: Option<isize> = None;
+        let mut reset = false;
+        
+        egui::Window::new("↕ Reading Order")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                if keys.is_empty() {
+                    ui.label("Enable \"Detect paragraphs\" in the pipeline to reorder blocks");
+                    return;
+                }
                 ui.horizontal(|ui| {
-                    ui.heading("📄 PDF Content (Absolute Positioning)");
-                    ui.separator();
-                    if ui.button("📝 Readable Text").clicked() {
-                        // Toggle between absolute and readable view
+                    let selected = self.order_selected.filter(|&i| i < keys.len());
+                    if ui.add_enabled(selected.is_some_and(|i| i > 0), egui::Button::new("⬆ Earlier")).clicked() {
+                        move_by = Some(-1);
                     }
-                    if self.modified {
-                        ui.label("*MODIFIED*");
+                    if ui.add_enabled(selected.is_some_and(|i| i + 1 < keys.len()), egui::Button::new("⬇ Later")).clicked() {
+                        move_by = Some(1);
+                    }
+                    if ui.add_enabled(!self.pipeline.reading_order.is_empty(), egui::Button::new("↺ Reset")).clicked() {
+                        reset = true;
                     }
                 });
-                
-                egui::ScrollArea::both()
-                    .auto_shrink([false, false])  // Allow unlimited scrolling
-                    .show(ui, |ui| {
-                        if !self.spatial_elements.is_empty() {
-                            // Always use WYSIWYG spatial editing mode
-                            self.render_wysiwyg_readable(ui);
-                        } else {
-                            ui.label("Click '📁 Load PDF' to display content");
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (i, text) in texts.iter().enumerate() {
+                        let snippet: String = text.chars().take(48).collect();
+                        if ui.selectable_label(self.order_selected == Some(i), format!("{:>3}. {}", i + 1, snippet)).clicked() {
+                            self.order_selected = Some(i);
                         }
+                    }
+                });
+            });
+        self.show_reading_order_list = open;
+        
+        let new_order = match (move_by, self.order_selected) {
+            (Some(delta), Some(i)) => {
+                let target = (i as isize + delta) as usize;
+                keys.swap(i, target);
+                self.order_selected = Some(target);
+                Some(keys)
+            }
+            _ if reset => Some(Vec::new()),
+            _ => {
+                self.order_cache = Some((keys, texts));
+                None
+            }
+        };
+        if let Some(order) = new_order {
+            self.pipeline.reading_order = order;
+            if let Err(e) = reading_order::save(&self.page_sidecar_key(), &self.pipeline.reading_order) {
+                eprintln!("Error saving reading order: {}", e);
+            }
+            self.show_pipeline_result();
+        }
+    }
+    
+    fn render_search_bar(&mut self, ctx: &egui::Context) {
+        // Edits shift match positions, so refresh against the live rope each frame
+        self.search.update(&self.spatial_buffer.rope, self.page_window.current);
+        
+        egui::TopBottomPanel::top("search").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("🔎");
+                let response = ui.text_edit_singleline(&mut self.search.query);
+                if response.changed() {
+                    self.search.current = 0;
+                    self.search.page_counts = self.document_match_counts();
+                    self.search.update(&self.spatial_buffer.rope, self.page_window.current);
+                    if let Some(&(start, _)) = self.search.matches.first() {
+                        self.jump_to_rope_position(start);
+                    }
+                }
+                
+                // Enter / Shift+Enter cycle matches while keeping focus in the field
+                let (enter, shift) = ui.input(|i| (i.key_pressed(egui::Key::Enter), i.modifiers.shift));
+                let mut step = None;
+                if response.lost_focus() && enter {
+                    step = Some(shift);
+                    response.request_focus();
+                }
+                if ui.small_button("⬆").clicked() {
+                    step = Some(true);
+                }
+                if ui.small_button("⬇").clicked() {
+                    step = Some(false);
+                }
+                if let Some(pos) = step.and_then(|backwards| self.search_step(backwards)) {
+                    self.jump_to_rope_position(pos);
+                }
+                
+                ui.label(self.search.counter_label());
+                if ui.small_button("✖").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    self.search.open = false;
+                }
+            });
+        });
+    }
+    
+    /// Next match position, moving on to the next page with matches past this page's last one
+    fn search_step(&mut self, backwards: bool) -> Option<usize> {
+        if self.search.at_edge(backwards) {
+            if let Some(page) = self.search.next_page(backwards) {
+                self.show_page(page);
+                self.search.update(&self.spatial_buffer.rope, page);
+                self.search.current = if backwards { self.search.matches.len().saturating_sub(1) } else { 0 };
+                return self.search.matches.get(self.search.current).map(|&(start, _)| start);
+            }
+        }
+        self.search.cycle(backwards)
+    }
+    
+    /// Matches of the query on every page; pages that aren't resident are extracted and dropped again
+    fn document_match_counts(&self) -> std::collections::BTreeMap<usize, usize> {
+        let mut counts = std::collections::BTreeMap::new();
+        if self.search.query.is_empty() {
+            return counts;
+        }
+        let mut count = |page: usize, rope: &ropey::Rope| {
+            let found = search::find_all(&rope.to_string(), &self.search.query).len();
+            if found > 0 {
+                counts.insert(page, found);
+            }
+        };
+        count(self.page_window.current, &self.spatial_buffer.rope);
+        for (&number, resident) in &self.page_window.resident {
+            match &resident.buffer {
+                Some(buffer) => count(number, &buffer.rope),
+                None => count(number, &Self::page_buffer(&resident.elements, &resident.sidecars.pending_edits).rope),
+            }
+        }
+        self.for_each_unparsed_page(|number, page| {
+            count(number, &Self::page_buffer(&page.elements, &page.sidecars.pending_edits).rope);
+        });
+        counts
+    }
+    
+    /// Screen rectangle covering a rope range (clipped to its first line)
+    fn range_screen_rect(&self, start: usize, end: usize) -> Option<egui::Rect> {
+        let zoom = self.spatial_buffer.transform.zoom;
+        let start_pos = self.spatial_buffer.rope_to_screen_position(start)?;
+        let width = match self.spatial_buffer.rope_to_screen_position(end.saturating_sub(1)) {
+            Some(last) if (last.y - start_pos.y).abs() < 1.0 => last.x - start_pos.x + 7.0 * zoom,
+            _ => (end - start) as f32 * 7.0 * zoom,
+        };
+        Some(egui::Rect::from_min_size(start_pos, egui::vec2(width, 15.0 * zoom)))
+    }
+    
+    /// Replace a rope range with new text, keeping bookmarks attached
+    fn replace_range(&mut self, start: usize, end: usize, text: &str) {
+        self.spatial_buffer.delete_range(start, end);
+        self.bookmarks.shift_for_delete(start, end);
+        self.spatial_buffer.insert_text(start, text);
+        self.bookmarks.shift_for_insert(start, text.chars().count());
+        self.modified = true;
+    }
+    
+    /// Red zigzag under a misspelled range
+    fn render_squiggle(&self, painter: &egui::Painter, start: usize, end: usize) {
+        if let Some(rect) = self.range_screen_rect(start, end) {
+            let mut points = Vec::new();
+            let mut x = rect.left();
+            let mut up = true;
+            while x <= rect.right() {
+                points.push(egui::pos2(x, rect.bottom() + if up { 0.0 } else { 2.0 }));
+                x += 2.0;
+                up = !up;
+            }
+            painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, egui::Color32::RED)));
+        }
+    }
+    
+    /// Right-click menu on the canvas: spelling suggestions for the clicked word
+    fn render_canvas_context_menu(&mut self, ui: &mut egui::Ui) {
+        if self.selection_group.len() > 1 && ui.button("⊕ Merge selected elements").clicked() {
+            self.merge_selected();
+            ui.close_menu();
+        }
+        if self.selection_group.len() > 1 {
+            ui.menu_button("⫼ Align column", |ui| {
+                for align in align::ColumnAlign::ALL {
+                    if ui.button(align.label()).clicked() {
+                        self.align_selected(ui.ctx(), align);
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+        if ui.button("✂ Split element at cursor").clicked() {
+            self.split_at_cursor();
+            ui.close_menu();
+        }
+        if let Some(idx) = self.context_element {
+            let modified = self.spatial_buffer.element_ranges.get(idx).is_some_and(|r| r.modified);
+            if ui.add_enabled(modified, egui::Button::new("↺ Revert element")).clicked() {
+                self.revert_element(idx);
+                ui.close_menu();
+            }
+        }
+        let selection_bounds = self.selection_group.iter()
+            .filter_map(|&i| self.spatial_buffer.element_ranges.get(i))
+            .map(|r| r.visual_bounds)
+            .reduce(|a, b| a.union(b));
+        let anchor = selection_bounds.filter(|_| self.selection_group.len() > 1)
+            .or(self.context_element.and_then(|i| self.spatial_buffer.element_ranges.get(i)).map(|r| r.visual_bounds));
+        if let Some(rect) = anchor {
+            let label = if self.selection_group.len() > 1 { "💬 Comment on selection…" } else { "💬 Comment on element…" };
+            if ui.button(label).clicked() {
+                self.annotation_draft = Some((None, rect, String::new()));
+                ui.close_menu();
+            }
+        }
+        if let Some(idx) = self.context_element {
+            let redacted = self.element_labels.get(idx) == Some(labels::Label::Redacted);
+            let label = if redacted { "⬜ Unredact element" } else { "⬛ Redact element" };
+            if ui.button(label).clicked() {
+                self.element_labels.set(idx, (!redacted).then_some(labels::Label::Redacted));
+                self.save_element_tags();
+                ui.close_menu();
+            }
+        }
+        if let Some(pos) = self.context_pos.filter(|&pos| self.pipeline.table_regions.iter().any(|r| r.rect.contains(pos))) {
+            if ui.button("▦ Remove table region").clicked() {
+                table_regions::remove_at(&mut self.pipeline.table_regions, pos);
+                self.save_table_regions();
+                ui.close_menu();
+            }
+        }
+        if let Some(pos) = self.context_pos.filter(|&pos| self.zones.iter().any(|z| z.rect().contains(pos))) {
+            if ui.button("🗺 Remove zone").clicked() {
+                let page = self.page_at(egui::Rect::from_center_size(pos, egui::Vec2::ZERO));
+                zones::remove_at(&mut self.zones, page, pos);
+                ui.close_menu();
+            }
+        }
+        if let Some(pos) = self.context_pos.filter(|&pos| self.redactions.regions.iter().any(|r| r.contains(pos))) {
+            if ui.button("⬜ Remove redaction region").clicked() {
+                self.redactions.remove_at(pos);
+                self.save_redactions();
+                ui.close_menu();
+            }
+        }
+        if ui.button("↺ Revert all elements").clicked() {
+            self.revert_all_elements();
+            ui.close_menu();
+        }
+        if let Some(idx) = self.context_element {
+            let pending = self.spatial_buffer.element_ranges.get(idx).is_some_and(|r| r.pending_from.is_some());
+            if pending {
+                if ui.button("✔ Accept change").clicked() {
+                    self.spatial_buffer.accept_change(idx);
+                    ui.close_menu();
+                }
+                if ui.button("✖ Reject change").clicked() {
+                    self.reject_change(idx);
+                    ui.close_menu();
+                }
+            }
+        }
+        ui.separator();
+        if let Some(idx) = self.context_element {
+            ui.menu_button("🏷 Tag", |ui| {
+                for tag in tags::ColorTag::ALL {
+                    let label = egui::RichText::new(format!("⏺ {}", tag.label())).color(tag.color());
+                    if ui.selectable_label(self.element_tags.get(idx) == Some(tag), label).clicked() {
+                        self.set_element_tag(idx, Some(tag));
+                        ui.close_menu();
+                    }
+                }
+                if ui.button("✖ Clear tag").clicked() {
+                    self.set_element_tag(idx, None);
+                    ui.close_menu();
+                }
+            });
+            ui.separator();
+        }
+        
+        let menu = match self.spell_menu.clone() {
+            Some(menu) => menu,
+            None => {
+                ui.label("No spelling issues here");
+                return;
+            }
+        };
+        
+        ui.label(format!("\"{}\"", menu.misspelling.word));
+        ui.separator();
+        if menu.suggestions.is_empty() {
+            ui.label("No suggestions");
+        }
+        for suggestion in &menu.suggestions {
+            if ui.button(suggestion.as_str()).clicked() {
+                self.replace_range(menu.misspelling.start, menu.misspelling.end, suggestion);
+                self.spell_menu = None;
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui.button("➕ Add to dictionary").clicked() {
+            if let Err(e) = self.project_words.add(&menu.misspelling.word) {
+                eprintln!("Error saving project dictionary: {}", e);
+            }
+            self.misspelling_cache = None;
+            self.spell_menu = None;
+            ui.close_menu();
+        }
+    }
+    
+    fn render_ocr_fix_window(&mut self, ctx: &egui::Context) {
+        let suggestions = ocr_fix::find_suggestions(&self.spatial_buffer.rope, &self.ocr_rules, &self.dictionary);
+        let mut open = self.show_ocr_fixes;
+        let mut apply: Vec<ocr_fix::Suggestion> = Vec::new();
+        let mut jump_to = None;
+        let mut rules_changed = false;
+        
+        egui::Window::new("🩹 OCR Fixes")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} suspicious tokens", suggestions.len()));
+                    if ui.add_enabled(!suggestions.is_empty(), egui::Button::new("Apply all")).clicked() {
+                        apply = suggestions.clone();
+                    }
+                });
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for suggestion in &suggestions {
+                        ui.horizontal(|ui| {
+                            if ui.link(format!("{} → {}", suggestion.original, suggestion.replacement)).clicked() {
+                                jump_to = Some(suggestion.start);
+                            }
+                            ui.label(egui::RichText::new(&suggestion.rule).weak());
+                            if ui.small_button("Apply").clicked() {
+                                apply = vec![suggestion.clone()];
+                            }
+                        });
+                    }
+                });
+                
+                ui.collapsing("Rules", |ui| {
+                    let mut remove = None;
+                    for (i, rule) in self.ocr_rules.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.monospace(format!("{} → {} ({})", rule.pattern, rule.replacement, rule.context.label()));
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = remove {
+                        self.ocr_rules.remove(i);
+                        rules_changed = true;
+                    }
+                    
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.new_rule.pattern).desired_width(50.0));
+                        ui.label("→");
+                        ui.add(egui::TextEdit::singleline(&mut self.new_rule.replacement).desired_width(50.0));
+                        for context in [ocr_fix::RuleContext::Word, ocr_fix::RuleContext::Number, ocr_fix::RuleContext::Any] {
+                            ui.radio_value(&mut self.new_rule.context, context, context.label());
+                        }
+                        if ui.button("Add").clicked() && !self.new_rule.pattern.is_empty() {
+                            self.ocr_rules.push(self.new_rule.clone());
+                            self.new_rule.pattern.clear();
+                            self.new_rule.replacement.clear();
+                            rules_changed = true;
+                        }
+                    });
+                });
+            });
+        self.show_ocr_fixes = open;
+        
+        // Apply back to front so earlier offsets stay valid
+        apply.sort_by(|a, b| b.start.cmp(&a.start));
+        for suggestion in apply {
+            self.replace_range(suggestion.start, suggestion.end, &suggestion.replacement);
+        }
+        if let Some(pos) = jump_to {
+            self.jump_to_rope_position(pos);
+        }
+        if rules_changed {
+            if let Err(e) = ocr_fix::save_user_rules(&self.ocr_rules) {
+                eprintln!("Error saving OCR rules: {}", e);
+            }
+        }
+    }
+    
+    fn render_backend_compare_window(&mut self, ctx: &egui::Context) {
+        let sources = extractors::sources(&self.ocr_url_input);
+        for pick in &mut self.backend_compare_pick {
+            *pick = (*pick).min(sources.len() - 1);
+        }
+        let last_page = self.extractor_decisions.iter().map(|d| d.page).max().unwrap_or(1);
+        let plain = ctx.style().visuals.text_color();
+        let jobs = self.backend_comparison.as_ref().map(|c| self.diff_layout_jobs(plain, &c.ops));
+        
+        let mut open = self.show_backend_compare;
+        let mut run = false;
+        let mut use_source = None;
+        egui::Window::new("🆚 Compare Backends")
+            .open(&mut open)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.backend_compare_page).range(1..=last_page).prefix("page "));
+                    for (side, pick) in self.backend_compare_pick.iter_mut().enumerate() {
+                        egui::ComboBox::from_id_source(("backend_compare", side))
+                            .selected_text(sources[*pick].label())
+                            .show_ui(ui, |ui| {
+                                for (i, source) in sources.iter().enumerate() {
+                                    ui.selectable_value(pick, i, source.label());
+                                }
+                            });
+                    }
+                    let distinct = self.backend_compare_pick[0] != self.backend_compare_pick[1];
+                    let idle = self.backend_comparing.is_none();
+                    run = ui.add_enabled(distinct && idle, egui::Button::new("▶ Run")).clicked();
+                    if !idle {
+                        ui.spinner();
+                    }
+                });
+                let (Some(comparison), Some((left, right))) = (&self.backend_comparison, jobs) else { return };
+                ui.label(format!("Page {}: {} word change(s)", comparison.page, diff::change_count(&comparison.ops)));
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(400.0).auto_shrink([false, false]).show(ui, |ui| {
+                    ui.columns(2, |columns| {
+                        for (side, (ui, job)) in columns.iter_mut().zip([left, right]).enumerate() {
+                            let source = &comparison.sources[side];
+                            ui.horizontal(|ui| {
+                                ui.strong(source.label());
+                                ui.label(format!("score {:.1}", comparison.scores[side]));
+                                if ui.small_button("✅ Use").on_hover_text(format!("Extract page {} with this backend", comparison.page)).clicked() {
+                                    use_source = Some((comparison.page, source.clone()));
+                                }
+                            });
+                            let mut job = job;
+                            job.wrap.max_width = ui.available_width();
+                            ui.label(job);
+                        }
+                    });
+                });
+            });
+        self.show_backend_compare = open;
+        
+        if run {
+            let picked = self.backend_compare_pick.map(|i| sources[i].clone());
+            let (pdf_path, page, dictionary) = (self.pdf_path.clone(), self.backend_compare_page, self.dictionary.clone());
+            let (heuristics, passes) = (self.pipeline.heuristics, self.pipeline.profile_mut().passes.clone());
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let mut pipeline = pipeline::Pipeline::new(dictionary.clone());
+                pipeline.heuristics = heuristics;
+                pipeline.profile_mut().passes = passes;
+                let render = |elements: Vec<SpatialElement>| pipeline::render_readable_text(&pipeline.run(elements));
+                let comparison = extractors::compare_sources(&pdf_path, page, picked, &dictionary, render);
+                // The receiver is gone when another PDF was loaded meanwhile
+                let _ = sender.send(comparison.map_err(|e| e.to_string()));
+            });
+            self.backend_comparing = Some(receiver);
+        }
+        if let Some((page, source)) = use_source {
+            if source.extractor == Extractor::Ocr && source.engine != self.ocr_engine {
+                if let Err(e) = source.engine.save(&self.pdf_path) {
+                    eprintln!("Error saving OCR engine: {}", e);
+                }
+            }
+            match self.extractor_decisions.iter_mut().find(|d| d.page == page) {
+                Some(decision) => {
+                    decision.chosen = source.extractor;
+                    decision.overridden = true;
+                }
+                None => self.extractor_decisions.push(ExtractorDecision {
+                    page,
+                    chosen: source.extractor,
+                    text_layer_score: None,
+                    ocr_score: None,
+                    overridden: true,
+                }),
+            }
+            if let Err(e) = extractors::save_decisions(&self.pdf_path, &self.extractor_decisions) {
+                eprintln!("Error saving extractor decisions: {}", e);
+            }
+            if self.edited_element_count() > 0 {
+                self.confirm_reload = true;
+            } else {
+                match self.load_pdf() {
+                    Ok(()) => println!("✅ Page {} now uses {}", page, source.label()),
+                    Err(e) => eprintln!("Error loading PDF: {}", e),
+                }
+            }
+        }
+    }
+    
+    fn poll_backend_comparison(&mut self) {
+        let Some(receiver) = &self.backend_comparing else { return };
+        let compared = match receiver.try_recv() {
+            Ok(compared) => compared,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err("backend comparison worker stopped".to_string()),
+        };
+        self.backend_comparing = None;
+        match compared {
+            Ok(comparison) => self.backend_comparison = Some(comparison),
+            Err(e) => eprintln!("Error comparing backends: {}", e),
+        }
+    }
+    
+    /// OCR the viewed page and flag words where the embedded text layer disagrees
+    fn compare_text_layer_with_ocr(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.discrepancies.clear();
+        let ocr_backend = self.ocr_engine.backend();
+        let pdf_hash = self.page_window.pdf_hash;
+        let page = self.page_window.current;
+        let text_elements = self.extract_page_cached(pdf_hash, Extractor::TextLayer, page, ocr_backend.as_ref(), false)?.elements;
+        let ocr_elements = self.extract_page_cached(pdf_hash, Extractor::Ocr, page, ocr_backend.as_ref(), false)?.elements;
+        self.discrepancies.extend(discrepancy::find_discrepancies(&text_elements, &ocr_elements));
+        Ok(())
+    }
+    
+    /// Magenta boxes around text-layer words that OCR reads differently
+    fn render_discrepancies(&self, painter: &egui::Painter, response: &egui::Response) {
+        let stroke = egui::Stroke::new(1.5, egui::Color32::from_rgb(220, 0, 220));
+        let hover_pos = response.hover_pos();
+        for discrepancy in &self.discrepancies {
+            let rect = self.spatial_buffer.document_rect_to_screen(discrepancy.bounds);
+            painter.rect_stroke(rect, 0.0, stroke);
+            if hover_pos.map_or(false, |pos| rect.contains(pos)) {
+                response.clone().on_hover_text_at_pointer(format!(
+                    "text layer: {}\nOCR: {}", discrepancy.text_layer, discrepancy.ocr
+                ));
+            }
+        }
+    }
+    
+    /// Replace an element's current text (not its trailing separator)
+    fn replace_element_text(&mut self, element_idx: usize, text: &str) {
+        if let Some(current) = self.spatial_buffer.element_text(element_idx) {
+            let start = self.spatial_buffer.element_ranges[element_idx].rope_start;
+            self.replace_range(start, start + current.chars().count(), text);
+        }
+    }
+    
+    /// Force OCR for a page whose text layer is unusable, then re-extract
+    fn fall_back_to_ocr(&mut self, page: usize) {
+        if let Some(decision) = self.extractor_decisions.iter_mut().find(|d| d.page == page) {
+            decision.chosen = Extractor::Ocr;
+            decision.overridden = true;
+        }
+        if let Err(e) = extractors::save_decisions(&self.pdf_path, &self.extractor_decisions) {
+            eprintln!("Error saving extractor decisions: {}", e);
+        }
+        if let Err(e) = self.load_pdf() {
+            eprintln!("Error loading PDF: {}", e);
+        }
+    }
+    
+    fn render_encoding_window(&mut self, ctx: &egui::Context) {
+        // (element index, page, current text, issue)
+        let findings: Vec<(usize, usize, String, mojibake::EncodingIssue)> = (0..self.spatial_buffer.element_ranges.len())
+            .filter_map(|i| {
+                let text = self.spatial_buffer.element_text(i)?;
+                let issue = mojibake::detect(&text)?;
+                let page = self.spatial_elements.get(self.spatial_buffer.element_ranges[i].element_id).map_or(1, |e| e.page);
+                Some((i, page, text, issue))
+            })
+            .collect();
+        
+        let mut open = self.show_encoding;
+        let mut repairs: Vec<(usize, String)> = Vec::new();
+        let mut ocr_page = None;
+        let mut jump_to = None;
+        
+        egui::Window::new("🧪 Encoding Damage")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} damaged elements", findings.len()));
+                    if ui.button("Repair all").clicked() {
+                        repairs = findings.iter()
+                            .filter_map(|(i, _, _, issue)| match issue {
+                                mojibake::EncodingIssue::Mojibake { repaired } => Some((*i, repaired.clone())),
+                                mojibake::EncodingIssue::CidGarbage => None,
+                            })
+                            .collect();
+                    }
+                });
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (i, page, text, issue) in &findings {
+                        ui.horizontal(|ui| {
+                            if ui.link(text.as_str()).clicked() {
+                                jump_to = Some(self.spatial_buffer.element_ranges[*i].rope_start);
+                            }
+                            match issue {
+                                mojibake::EncodingIssue::Mojibake { repaired } => {
+                                    ui.label(format!("→ {}", repaired));
+                                    if ui.small_button("Repair").clicked() {
+                                        repairs.push((*i, repaired.clone()));
+                                    }
+                                }
+                                mojibake::EncodingIssue::CidGarbage => {
+                                    ui.label("unmapped glyphs");
+                                    if ui.small_button(format!("OCR page {}", page)).clicked() {
+                                        ocr_page = Some(*page);
+                                    }
+                                }
+                            }
+                        });
+                    }
+                });
+            });
+        self.show_encoding = open;
+        
+        // Back to front so earlier rope offsets stay valid
+        repairs.sort_by(|a, b| b.0.cmp(&a.0));
+        for (i, repaired) in repairs {
+            self.replace_element_text(i, &repaired);
+        }
+        if let Some(pos) = jump_to {
+            self.jump_to_rope_position(pos);
+        }
+        if let Some(page) = ocr_page {
+            self.fall_back_to_ocr(page);
+        }
+    }
+    
+    /// Fetch PDF link annotations for the viewed page on a worker, once per page view
+    fn ensure_pdf_links(&mut self) {
+        if self.pdf_links.is_some() || self.links_loading.is_some() {
+            return;
+        }
+        let (pdf_path, page) = (self.pdf_path.clone(), self.page_window.current);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            // The receiver is gone when another page was shown meanwhile
+            let _ = sender.send(links::extract_annotations(&pdf_path, page).map_err(|e| e.to_string()));
+        });
+        self.links_loading = Some((page, receiver));
+    }
+    
+    fn poll_pdf_links(&mut self) {
+        let Some((_, receiver)) = &self.links_loading else { return };
+        let loaded = match receiver.try_recv() {
+            Ok(loaded) => loaded,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err("link annotation worker stopped".to_string()),
+        };
+        let Some((page, _)) = self.links_loading.take() else { return };
+        self.pdf_links = Some(loaded.unwrap_or_else(|e| {
+            eprintln!("Error reading link annotations on page {}: {}", page, e);
+            Vec::new()
+        }));
+        // Annotated elements now link as a whole
+        self.link_cache = None;
+    }
+    
+    /// Recompute link spans once per buffer revision
+    fn refresh_link_spans(&mut self) {
+        let revision = self.spatial_buffer.revision;
+        if !matches!(&self.link_cache, Some((cached, _)) if *cached == revision) {
+            self.link_cache = Some((revision, self.link_spans()));
+        }
+    }
+    
+    /// (rope start, rope end, target) of every link: annotated elements whole, else URLs/emails in the text
+    fn link_spans(&self) -> Vec<(usize, usize, String)> {
+        let pdf_links = self.pdf_links.as_deref().unwrap_or(&[]);
+        let mut spans = Vec::new();
+        for (i, range) in self.spatial_buffer.element_ranges.iter().enumerate() {
+            let annotated = self.spatial_elements.get(range.element_id)
+                .and_then(|element| links::annotation_target(element, pdf_links));
+            if let Some(target) = annotated {
+                spans.push((range.rope_start, range.rope_end, target.to_string()));
+            } else if let Some(text) = self.spatial_buffer.element_text(i) {
+                for found in links::detect(&text) {
+                    spans.push((range.rope_start + found.start, range.rope_start + found.end, found.target));
+                }
+            }
+        }
+        spans
+    }
+    
+    /// Link under a rope position, from the spans cached while painting
+    fn link_at(&self, rope_pos: usize) -> Option<String> {
+        self.link_cache.iter().flat_map(|(_, spans)| spans)
+            .find(|(start, end, _)| rope_pos >= *start && rope_pos < *end)
+            .map(|(_, _, target)| target.clone())
+    }
+    
+    /// Elements whose text looks garbled, with the reasons, skipping dismissed ones
+    fn garbled_findings(&self) -> Vec<(usize, Vec<garbled::GarbleReason>)> {
+        self.spatial_buffer.element_ranges.iter().enumerate()
+            .filter(|(_, range)| !self.garbled_dismissed.contains(&range.element_id))
+            .filter_map(|(i, _)| {
+                let reasons = garbled::detect(&self.spatial_buffer.element_text(i)?);
+                (!reasons.is_empty()).then_some((i, reasons))
+            })
+            .collect()
+    }
+    
+    /// Dashed orange line under a garbled element
+    fn render_garble_underline(&self, painter: &egui::Painter, idx: usize) {
+        let range = &self.spatial_buffer.element_ranges[idx];
+        if let Some(rect) = self.range_screen_rect(range.rope_start, range.rope_end) {
+            let y = rect.bottom() + 1.0;
+            painter.extend(egui::Shape::dashed_line(
+                &[egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
+                egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 150, 0)),
+                3.0, 2.0,
+            ));
+        }
+    }
+    
+    fn render_garbled_window(&mut self, ctx: &egui::Context) {
+        // (element index, page, current text, reasons)
+        let findings: Vec<(usize, usize, String, String)> = self.garbled_findings().into_iter()
+            .map(|(i, reasons)| {
+                let range = &self.spatial_buffer.element_ranges[i];
+                let page = self.spatial_elements.get(range.element_id).map_or(1, |e| e.page);
+                let text = self.spatial_buffer.element_text(i).unwrap_or_default();
+                let reasons = reasons.iter().map(|r| r.label()).collect::<Vec<_>>().join(", ");
+                (i, page, text, reasons)
+            })
+            .collect();
+        
+        let mut open = self.show_garbled;
+        let mut jump_to = None;
+        let mut dismiss = None;
+        let mut ocr_page = None;
+        
+        egui::Window::new("⚠ Garbled Text")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} suspicious elements", findings.len()));
+                    ui.checkbox(&mut self.garbled_check, "Underline");
+                    if !self.garbled_dismissed.is_empty() && ui.button("Restore dismissed").clicked() {
+                        self.garbled_dismissed.clear();
+                    }
+                });
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (i, page, text, reasons) in &findings {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("p{}", page));
+                            if ui.link(text.as_str()).clicked() {
+                                jump_to = Some(self.spatial_buffer.element_ranges[*i].rope_start);
+                            }
+                            ui.weak(reasons.as_str());
+                            if ui.small_button("Looks fine").clicked() {
+                                dismiss = Some(self.spatial_buffer.element_ranges[*i].element_id);
+                            }
+                            if ui.small_button(format!("OCR page {}", page)).clicked() {
+                                ocr_page = Some(*page);
+                            }
+                        });
+                    }
+                });
+            });
+        self.show_garbled = open;
+        
+        if let Some(element_id) = dismiss {
+            self.garbled_dismissed.insert(element_id);
+        }
+        if let Some(pos) = jump_to {
+            self.jump_to_rope_position(pos);
+        }
+        if let Some(page) = ocr_page {
+            self.fall_back_to_ocr(page);
+        }
+    }
+    
+    /// Normalization and cleanup options with a before/after preview of affected elements
+    fn render_cleanup_window(&mut self, ctx: &egui::Context) {
+        let options_before = self.cleanup.clone();
+        let mut open = self.show_cleanup;
+        let mut changes: Vec<(usize, String)> = Vec::new();
+        let mut jump_to = None;
+        
+        egui::Window::new("🧹 Clean Text")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Normalize:");
+                    for form in cleanup::Normalization::ALL {
+                        ui.radio_value(&mut self.cleanup.normalization, form, form.label());
+                    }
+                });
+                ui.checkbox(&mut self.cleanup.unify_quotes, "Straighten curly quotes");
+                ui.checkbox(&mut self.cleanup.unify_dashes, "Unify dashes and minus signs to -");
+                ui.checkbox(&mut self.cleanup.remove_soft_hyphens, "Remove soft hyphens");
+                ui.checkbox(&mut self.cleanup.remove_zero_width, "Remove zero-width characters");
+                ui.checkbox(&mut self.cleanup.apply_on_load, "Apply when loading a PDF");
+                ui.separator();
+                
+                // (element index, current text, cleaned text)
+                let preview: Vec<(usize, String, String)> = (0..self.spatial_buffer.element_ranges.len())
+                    .filter_map(|i| {
+                        let text = self.spatial_buffer.element_text(i)?;
+                        let cleaned = cleanup::clean(&text, &self.cleanup);
+                        (cleaned != text).then_some((i, text, cleaned))
+                    })
+                    .collect();
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} elements would change", preview.len()));
+                    if ui.add_enabled(!preview.is_empty(), egui::Button::new("Apply all")).clicked() {
+                        changes = preview.iter().map(|(i, _, cleaned)| (*i, cleaned.clone())).collect();
+                    }
+                });
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (i, text, cleaned) in &preview {
+                        ui.horizontal(|ui| {
+                            // Debug formatting makes the invisible characters visible
+                            if ui.link(format!("{:?}", text)).clicked() {
+                                jump_to = Some(self.spatial_buffer.element_ranges[*i].rope_start);
+                            }
+                            ui.label(format!("→ {:?}", cleaned));
+                            if ui.small_button("Apply").clicked() {
+                                changes.push((*i, cleaned.clone()));
+                            }
+                        });
+                    }
+                });
+            });
+        self.show_cleanup = open;
+        
+        if self.cleanup != options_before {
+            if let Err(e) = self.cleanup.save() {
+                eprintln!("Error saving cleanup options: {}", e);
+            }
+        }
+        // Back to front so earlier rope offsets stay valid
+        changes.sort_by(|a, b| b.0.cmp(&a.0));
+        for (i, cleaned) in changes {
+            self.replace_element_text(i, &cleaned);
+        }
+        if let Some(pos) = jump_to {
+            self.jump_to_rope_position(pos);
+        }
+    }
+    
+    /// Shift+click group plus any element the rope selection touches, in document order
+    fn selected_element_indices(&self) -> Vec<usize> {
+        let mut indices = self.selection_group.clone();
+        if let Some((a, b)) = self.spatial_buffer.selection {
+            let (start, end) = (a.min(b), a.max(b));
+            indices.extend(self.spatial_buffer.element_ranges.iter().enumerate()
+                .filter(|(_, range)| range.rope_start < end && range.rope_end > start)
+                .map(|(i, _)| i));
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+    
+    /// Status bar with quick statistics when the selection holds numbers, e.g. a table column
+    fn render_selection_status(&self, ctx: &egui::Context) {
+        let texts: Vec<String> = self.selected_element_indices().into_iter()
+            .filter_map(|i| self.spatial_buffer.element_text(i))
+            .collect();
+        let summary = match numbers::Summary::of(texts.iter().map(String::as_str)) {
+            Some(summary) => summary,
+            None => return,
+        };
+        egui::TopBottomPanel::bottom("selection_status").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("Σ {}", numbers::format_value(summary.sum)));
+                ui.separator();
+                ui.label(format!("mean {}", numbers::format_value(summary.mean)));
+                ui.separator();
+                ui.label(format!("count {} of {}", summary.count, texts.len()));
+                if ui.small_button("📋 Copy").on_hover_text("Copy sum, mean and count").clicked() {
+                    ctx.copy_text(summary.to_clipboard());
+                }
+            });
+        });
+    }
+    
+    /// Number separator, currency and date rewrites for the selection (or every element), with a preview
+    fn render_normalize_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_normalize;
+        let mut changes: Vec<(usize, String)> = Vec::new();
+        let mut jump_to = None;
+        let selected = self.selected_element_indices();
+        
+        egui::Window::new("🔢 Normalize Numbers & Dates")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let options = &mut self.normalize_options;
+                ui.horizontal(|ui| {
+                    ui.label("Numbers:");
+                    for style in numbers::NumberStyle::ALL {
+                        ui.radio_value(&mut options.numbers, style, style.label());
+                    }
+                });
+                ui.checkbox(&mut options.strip_currency, "Strip currency symbols");
+                ui.horizontal(|ui| {
+                    ui.label("Dates:");
+                    for style in numbers::DateStyle::ALL {
+                        ui.radio_value(&mut options.dates, style, style.label());
+                    }
+                });
+                ui.checkbox(&mut options.day_first_input, "Read ambiguous dates as day first (03/04 = 3 April)");
+                ui.separator();
+                
+                let scope: Vec<usize> = if selected.is_empty() {
+                    (0..self.spatial_buffer.element_ranges.len()).collect()
+                } else {
+                    selected.clone()
+                };
+                // (element index, current text, normalized text)
+                let preview: Vec<(usize, String, String)> = scope.into_iter()
+                    .filter_map(|i| {
+                        let text = self.spatial_buffer.element_text(i)?;
+                        let normalized = numbers::normalize(&text, &self.normalize_options);
+                        (normalized != text).then_some((i, text, normalized))
+                    })
+                    .collect();
+                ui.horizontal(|ui| {
+                    let scope_label = if selected.is_empty() { "all elements".to_string() } else { format!("{} selected", selected.len()) };
+                    ui.label(format!("{} of {} would change", preview.len(), scope_label));
+                    if ui.add_enabled(!preview.is_empty(), egui::Button::new("Apply all")).clicked() {
+                        changes = preview.iter().map(|(i, _, normalized)| (*i, normalized.clone())).collect();
+                    }
+                });
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (i, text, normalized) in &preview {
+                        ui.horizontal(|ui| {
+                            if ui.link(text.as_str()).clicked() {
+                                jump_to = Some(self.spatial_buffer.element_ranges[*i].rope_start);
+                            }
+                            ui.label(format!("→ {}", normalized));
+                            if ui.small_button("Apply").clicked() {
+                                changes.push((*i, normalized.clone()));
+                            }
+                        });
+                    }
+                });
+            });
+        self.show_normalize = open;
+        
+        // Back to front so earlier rope offsets stay valid
+        changes.sort_by(|a, b| b.0.cmp(&a.0));
+        for (i, normalized) in changes {
+            self.replace_element_text(i, &normalized);
+        }
+        if let Some(pos) = jump_to {
+            self.jump_to_rope_position(pos);
+        }
+    }
+    
+    /// Layout thresholds with a live preview; saved as the global default or as this document's override
+    fn render_heuristics_window(&mut self, ctx: &egui::Context) {
+        let before = self.pipeline.heuristics;
+        let mut open = self.show_heuristics;
+        let has_override = heuristics::Heuristics::has_override(&self.pdf_path);
+        
+        egui::Window::new("📐 Layout Heuristics")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let settings = &mut self.pipeline.heuristics;
+                egui::Grid::new("heuristics_grid").num_columns(2).show(ui, |ui| {
+                    for (key, label, range) in heuristics::Heuristics::FIELDS {
+                        ui.label(label);
+                        if let Some(value) = settings.field_mut(key) {
+                            ui.add(egui::Slider::new(value, range).suffix(" px").step_by(0.5));
+                        }
+                        ui.end_row();
+                    }
+                    ui.label("Max inferred spaces");
+                    ui.add(egui::Slider::new(&mut settings.max_spaces, 2..=40));
+                    ui.end_row();
+                });
+                ui.checkbox(&mut settings.use_font_metrics, "Scale spacing by font size")
+                    .on_hover_text("Use each word's ALTO font size (or measured width per char) instead of the fixed space width");
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Save as default").clicked() {
+                        match settings.save_defaults() {
+                            Ok(()) => println!("✅ Saved heuristics to {}", heuristics::SETTINGS_FILE),
+                            Err(e) => eprintln!("Error saving heuristics: {}", e),
+                        }
+                    }
+                    if ui.button("📄 Save for this document").clicked() {
+                        match settings.save_override(&self.pdf_path) {
+                            Ok(()) => println!("✅ Saved heuristics override for {}", self.pdf_path),
+                            Err(e) => eprintln!("Error saving heuristics: {}", e),
+                        }
+                    }
+                    if ui.add_enabled(has_override, egui::Button::new("↺ Use defaults")).clicked() {
+                        match heuristics::Heuristics::clear_override(&self.pdf_path) {
+                            Ok(()) => *settings = heuristics::Heuristics::load_defaults(),
+                            Err(e) => eprintln!("Error removing heuristics override: {}", e),
+                        }
+                    }
+                });
+                if has_override {
+                    ui.label(egui::RichText::new("This document overrides the defaults").weak());
+                }
+                ui.separator();
+                
+                // Live preview of the first lines with the current values
+                let preview: String = self.generate_readable_text().lines().take(40).collect::<Vec<_>>().join("\n");
+                egui::ScrollArea::both().max_height(320.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(preview).monospace());
+                });
+            });
+        self.show_heuristics = open;
+        
+        if self.pipeline.heuristics != before {
+            self.show_pipeline_result();
+        }
+    }
+    
+    /// Substitution table editor; changes take effect by re-parsing the PDF
+    fn render_glyph_map_window(&mut self, ctx: &egui::Context) {
+        let map_before = self.glyph_map.clone();
+        let mut open = self.show_glyph_map;
+        let mut reload = false;
+        let mut finished = false; // A replacement field was left, or an entry added or removed
+        
+        // Suspicious characters still in the text, with how often they occur
+        let mut suspects: Vec<(char, usize)> = Vec::new();
+        for c in self.spatial_buffer.rope.chars().filter(|&c| glyph_map::is_suspicious(c)) {
+            match suspects.iter_mut().find(|(s, _)| *s == c) {
+                Some(entry) => entry.1 += 1,
+                None => suspects.push((c, 1)),
+            }
+        }
+        
+        egui::Window::new("🔡 Glyph Map")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label("Characters replaced when the PDF is parsed");
+                let mut remove = None;
+                egui::Grid::new("glyph_map_entries").striped(true).show(ui, |ui| {
+                    for (i, (source, replacement)) in self.glyph_map.entries.iter_mut().enumerate() {
+                        ui.label(format!("U+{:04X} {}", *source as u32, source));
+                        ui.label("→");
+                        finished |= ui.add(egui::TextEdit::singleline(replacement).desired_width(80.0)).lost_focus();
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(i) = remove {
+                    self.glyph_map.entries.remove(i);
+                    finished = true;
+                }
+                
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.glyph_map_input.0).hint_text("char or U+F001").desired_width(80.0));
+                    ui.label("→");
+                    ui.add(egui::TextEdit::singleline(&mut self.glyph_map_input.1).hint_text("replacement").desired_width(80.0));
+                    let source = glyph_map::parse_char(&self.glyph_map_input.0);
+                    if ui.add_enabled(source.is_some(), egui::Button::new("➕ Add")).clicked() {
+                        if let Some(source) = source {
+                            self.glyph_map.set(source, &self.glyph_map_input.1);
+                            self.glyph_map_input = (String::new(), String::new());
+                            finished = true;
+                        }
+                    }
+                });
+                
+                if !suspects.is_empty() {
+                    ui.separator();
+                    ui.label("Suspicious characters in this document:");
+                    ui.horizontal_wrapped(|ui| {
+                        for (c, count) in &suspects {
+                            if ui.small_button(format!("U+{:04X} {} ×{}", *c as u32, c, count)).clicked() {
+                                self.glyph_map_input = (format!("U+{:04X}", *c as u32), String::new());
+                            }
+                        }
+                    });
+                }
+                
+                ui.separator();
+                reload = ui.button("🔄 Re-parse with map").on_hover_text("Reloads the PDF; asks first when there are edits").clicked();
+            });
+        self.show_glyph_map = open;
+        
+        // Typing a replacement changes the map every keystroke; write it once the edit is done
+        self.glyph_map_dirty |= self.glyph_map != map_before;
+        if self.glyph_map_dirty && (finished || reload || !open) {
+            self.glyph_map_dirty = false;
+            if let Err(e) = self.glyph_map.save(&self.pdf_path) {
+                eprintln!("Error saving glyph map: {}", e);
+            }
+        }
+        if reload && self.edited_element_count() > 0 {
+            self.confirm_reload = true;
+        } else if reload {
+            match self.load_pdf() {
+                Ok(()) => println!("✅ Re-parsed with {} glyph substitutions", self.glyph_map.entries.len()),
+                Err(e) => eprintln!("Error loading PDF: {}", e),
+            }
+        }
+    }
+    
+    /// Text of the visual line under the cursor (elements sharing its baseline)
+    fn cursor_line_text(&self) -> Option<String> {
+        let pos = self.spatial_cursor.rope_pos;
+        let cursor_element = self.spatial_buffer.element_ranges.iter()
+            .find(|e| pos >= e.rope_start && pos < e.rope_end)?;
+        let line_vpos = cursor_element.visual_bounds.min.y;
+        
+        let mut line: Vec<(f32, String)> = self.spatial_buffer.element_ranges.iter()
+            .enumerate()
+            .filter(|(_, e)| (e.visual_bounds.min.y - line_vpos).abs() < 8.0)
+            .filter_map(|(i, e)| Some((e.visual_bounds.min.x, self.spatial_buffer.element_text(i)?)))
+            .collect();
+        line.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Some(line.into_iter().map(|(_, text)| text).collect::<Vec<String>>().join(" "))
+    }
+    
+    /// Read the selection (or the cursor's line) aloud, replacing any read-back in progress
+    fn speak_current_line(&mut self) {
+        let text = match self.spatial_buffer.selection {
+            Some((start, end)) if start < end => Some(self.spatial_buffer.rope.slice(start..end).to_string()),
+            _ => self.cursor_line_text(),
+        };
+        speech::stop(&mut self.speech);
+        if let Some(text) = text {
+            match speech::speak(&text) {
+                Ok(child) => self.speech = Some(child),
+                Err(e) => eprintln!("Error starting speech: {}", e),
+            }
+        }
+    }
+    
+    /// Detected paragraphs of the edited text, wrapped to the available width
+    fn render_reflow_view(&self, ui: &mut egui::Ui) {
+        let ctx = self.run_document_pipeline(self.parsed_export_elements());
+        for paragraph in pipeline::paragraph_texts(&ctx) {
+            ui.label(egui::RichText::new(paragraph).font(self.editor_font()));
+            ui.add_space(8.0);
+        }
+    }
+    
+    /// Edited elements on every parsed page, not just the viewed one
+    fn edited_element_count(&self) -> usize {
+        self.spatial_buffer.element_ranges.iter().filter(|range| range.modified).count() + self.page_window.edited_count()
+    }
+    
+    /// Reload straight away when nothing is edited; otherwise diff the edits against a fresh
+    /// extraction and ask before discarding them
+    fn pdf_changed_on_disk(&mut self) {
+        if self.edited_element_count() == 0 {
+            match self.load_pdf() {
+                Ok(()) => println!("✅ Re-extracted {} after it changed on disk", self.pdf_path),
+                Err(e) => eprintln!("Error loading PDF: {}", e),
+            }
+            return;
+        }
+        let pdf_path = self.pdf_path.clone();
+        let (page_count, current) = (self.page_window.page_count, self.page_window.current);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut fresh = ChonkerApp::default();
+            fresh.pdf_path = pdf_path.clone();
+            // Same page as the one on screen, so the diff compares like with like
+            fresh.page_window = page_window::PageWindow::new(&pdf_path, None, page_count, current);
+            let extracted = fresh.load_pdf().map(|()| fresh.spatial_elements).map_err(|e| e.to_string());
+            // The receiver is gone when another PDF was loaded meanwhile
+            let _ = sender.send(extracted);
+        });
+        self.reload_extraction = Some(receiver);
+    }
+    
+    /// Once the changed page is re-extracted, diff it against the edited page, both through the pipeline
+    fn poll_reload_extraction(&mut self) {
+        let Some(receiver) = &self.reload_extraction else { return };
+        let extracted = match receiver.try_recv() {
+            Ok(extracted) => extracted,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err("re-extraction worker stopped".to_string()),
+        };
+        self.reload_extraction = None;
+        match extracted {
+            Ok(elements) => {
+                let edited = pipeline::render_readable_text(&self.pipeline.run(self.current_elements()));
+                let extracted = pipeline::render_readable_text(&self.pipeline.run(elements));
+                self.reload_prompt = Some(diff::diff_words(&edited, &extracted));
+            }
+            Err(e) => eprintln!("Error re-extracting changed PDF: {}", e),
+        }
+    }
+    
+    fn render_reload_prompt(&mut self, ctx: &egui::Context) {
+        let Some(ops) = &self.reload_prompt else { return };
+        let edits = self.edited_element_count();
+        let mut job = egui::text::LayoutJob::default();
+        let plain = ctx.style().visuals.text_color();
+        for op in ops {
+            let (text, format) = match op {
+                diff::DiffOp::Equal(text) => (text, egui::TextFormat { color: plain, ..Default::default() }),
+                diff::DiffOp::Delete(text) => (text, egui::TextFormat {
+                    color: plain,
+                    background: egui::Color32::from_rgba_unmultiplied(220, 60, 60, 90),
+                    strikethrough: egui::Stroke::new(1.0, plain),
+                    ..Default::default()
+                }),
+                diff::DiffOp::Insert(text) => (text, egui::TextFormat {
+                    color: plain,
+                    background: egui::Color32::from_rgba_unmultiplied(60, 180, 80, 90),
+                    ..Default::default()
+                }),
+            };
+            job.append(&format!("{} ", text), 0.0, format);
+        }
+        let changes = diff::change_count(ops);
+        
+        let mut decision = None;
+        egui::Window::new("📄 PDF changed on disk")
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{} was modified. Re-extracting discards {} edited element(s).", self.pdf_path, edits));
+                ui.label(format!("{} word change(s) between your text (struck out) and the new extraction:", changes));
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    ui.label(job);
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("🔄 Re-extract, discard edits").clicked() {
+                        decision = Some(true);
+                    }
+                    if ui.button("✋ Keep my edits").clicked() {
+                        decision = Some(false);
+                    }
+                });
+            });
+        match decision {
+            Some(true) => {
+                self.reload_prompt = None;
+                match self.load_pdf() {
+                    Ok(()) => println!("✅ Re-extracted {}", self.pdf_path),
+                    Err(e) => eprintln!("Error loading PDF: {}", e),
+                }
+            }
+            Some(false) => self.reload_prompt = None,
+            None => {}
+        }
+    }
+    
+    /// Reloading re-extracts every page, so edits on pages other than the viewed one would go too
+    fn render_reload_confirm(&mut self, ctx: &egui::Context) {
+        let edits = self.edited_element_count();
+        let mut decision = None;
+        egui::Window::new("🔄 Reload PDF")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Reloading {} discards {} edited element(s).", self.pdf_path, edits));
+                ui.horizontal(|ui| {
+                    if ui.button("🔄 Reload, discard edits").clicked() {
+                        decision = Some(true);
+                    }
+                    if ui.button("✋ Keep my edits").clicked() {
+                        decision = Some(false);
+                    }
+                });
+            });
+        if let Some(reload) = decision {
+            self.confirm_reload = false;
+            match self.pending_rotation.take() {
+                Some(rotation) if reload => self.apply_rotation(rotation),
+                _ if reload => {
+                    if let Err(e) = self.load_pdf() {
+                        eprintln!("Error loading PDF: {}", e);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Original readable text and the edited rope side by side, changed words highlighted
+    fn render_diff_view(&mut self, ui: &mut egui::Ui) {
+        // Each element's extracted text against its current text, recomputed only after an edit
+        let revision = self.spatial_buffer.revision;
+        if !matches!(&self.diff_cache, Some((cached, _)) if *cached == revision) {
+            let buffer = &self.spatial_buffer;
+            let current: Vec<String> = (0..buffer.element_ranges.len())
+                .map(|i| buffer.element_text(i).unwrap_or_default())
+                .collect();
+            let ops = diff::diff_pairs(buffer.element_ranges.iter().zip(&current)
+                .map(|(range, text)| (range.original_text.as_str(), text.as_str())));
+            self.diff_cache = Some((revision, ops));
+        }
+        let Some((_, ops)) = &self.diff_cache else { return };
+        let (left, right) = self.diff_layout_jobs(ui.visuals().text_color(), ops);
+        
+        ui.label(format!("{} change(s)", diff::change_count(ops)));
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                ui.columns(2, |columns| {
+                    columns[0].strong("Original extraction");
+                    let mut left = left;
+                    left.wrap.max_width = columns[0].available_width();
+                    columns[0].label(left);
+                    columns[1].strong("Edited");
+                    let mut right = right;
+                    right.wrap.max_width = columns[1].available_width();
+                    columns[1].label(right);
+                });
+            });
+    }
+    
+    /// Both sides of a word diff: deletions highlighted on the left, insertions on the right
+    fn diff_layout_jobs(&self, plain: egui::Color32, ops: &[diff::DiffOp]) -> (egui::text::LayoutJob, egui::text::LayoutJob) {
+        let font = self.editor_font();
+        let mut left = egui::text::LayoutJob::default();
+        let mut right = egui::text::LayoutJob::default();
+        let mut append = |job: &mut egui::text::LayoutJob, text: &str, background: egui::Color32| {
+            let format = egui::TextFormat {
+                font_id: font.clone(),
+                color: plain,
+                background,
+                ..Default::default()
+            };
+            job.append(&format!("{} ", text), 0.0, format);
+        };
+        for op in ops {
+            match op {
+                diff::DiffOp::Equal(text) => {
+                    append(&mut left, text, egui::Color32::TRANSPARENT);
+                    append(&mut right, text, egui::Color32::TRANSPARENT);
+                }
+                diff::DiffOp::Delete(text) => append(&mut left, text, egui::Color32::from_rgba_unmultiplied(220, 60, 60, 90)),
+                diff::DiffOp::Insert(text) => append(&mut right, text, egui::Color32::from_rgba_unmultiplied(60, 180, 80, 90)),
+            }
+        }
+        (left, right)
+    }
+    
+    /// This document beside another PDF or export, scrolled together, changed words highlighted
+    fn render_compare_view(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let response = ui.add(egui::TextEdit::singleline(&mut self.compare_input)
+                .hint_text("other.pdf or previous export")
+                .desired_width(260.0));
+            let open = ui.button("📂 Compare").clicked()
+                || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)));
+            let path = self.compare_input.trim().to_string();
+            if open && !path.is_empty() {
+                let passes = self.pipeline.profile_mut().passes.clone();
+                let receiver = compare::load_in_background(&path, passes);
+                self.comparison_loading = Some((path, receiver));
+            }
+            if let Some((path, _)) = &self.comparison_loading {
+                ui.spinner();
+                ui.label(format!("Extracting {}…", path));
+            } else if let Some(comparison) = &self.comparison {
+                ui.label(format!("{} change(s)", diff::change_count(&comparison.ops)));
+            }
+        });
+        let Some(mut comparison) = self.comparison.take() else { return };
+        comparison.refresh(self.spatial_buffer.revision, || self.pipeline_text());
+        let jumps = comparison.take_sync();
+        let (left, right) = self.diff_layout_jobs(ui.visuals().text_color(), &comparison.ops);
+        let other_name = std::path::Path::new(&comparison.path).file_name()
+            .map_or(comparison.path.clone(), |n| n.to_string_lossy().to_string());
+        
+        ui.columns(2, |columns| {
+            let titles = ["This document".to_string(), other_name];
+            for (pane, (ui, job)) in columns.iter_mut().zip([left, right]).enumerate() {
+                ui.strong(&titles[pane]);
+                let mut area = egui::ScrollArea::vertical().id_source(("compare", pane)).auto_shrink([false, false]);
+                if let Some(offset) = jumps[pane] {
+                    area = area.vertical_scroll_offset(offset);
+                }
+                let mut job = job;
+                job.wrap.max_width = ui.available_width();
+                let output = area.show(ui, |ui| ui.label(job));
+                let max_scroll = (output.content_size.y - output.inner_rect.height()).max(0.0);
+                comparison.scrolled(pane, output.state.offset.y, max_scroll, jumps[pane].is_some());
+            }
+        });
+        if comparison.sync.iter().any(|&sync| sync) {
+            ui.ctx().request_repaint();
+        }
+        self.comparison = Some(comparison);
+    }
+    
+    /// Take the other document's text once its worker is done and diff it against this one
+    fn poll_comparison(&mut self) {
+        let Some((_, receiver)) = &self.comparison_loading else { return };
+        let loaded = match receiver.try_recv() {
+            Ok(loaded) => loaded,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err("comparison worker stopped".to_string()),
+        };
+        let Some((path, _)) = self.comparison_loading.take() else { return };
+        match loaded {
+            Ok(other) => {
+                let comparison = compare::Comparison::new(&path, self.spatial_buffer.revision, &self.pipeline_text(), other);
+                println!("✅ Comparing with {} ({} change(s))", path, diff::change_count(&comparison.ops));
+                self.comparison = Some(comparison);
+            }
+            Err(e) => eprintln!("Error opening {}: {}", path, e),
+        }
+    }
+    
+    /// Readable text of every parsed page through the document pipeline
+    fn pipeline_text(&self) -> String {
+        pipeline::render_readable_text(&self.run_document_pipeline(self.document_elements()))
+    }
+    
+    fn element_confidence(&self, idx: usize) -> Option<f32> {
+        self.spatial_buffer.element_ranges.get(idx)
+            .and_then(|range| self.spatial_elements.get(range.element_id))
+            .and_then(|element| element.confidence)
+    }
+    
+    /// Tint low-confidence words red (well below the threshold) or yellow
+    fn render_confidence_heatmap(&self, painter: &egui::Painter) {
+        for (idx, range) in self.spatial_buffer.element_ranges.iter().enumerate() {
+            if let Some(color) = confidence::tint(self.element_confidence(idx), self.low_confidence) {
+                painter.rect_filled(self.spatial_buffer.document_rect_to_screen(range.visual_bounds), 2.0, color);
+            }
+        }
+    }
+    
+    /// F8 / Shift+F8: outline the next (or previous) word below the confidence threshold
+    fn jump_to_low_confidence(&mut self, backwards: bool) {
+        let cursor = self.spatial_cursor.rope_pos;
+        let low: Vec<usize> = (0..self.spatial_buffer.element_ranges.len())
+            .filter(|&i| confidence::is_low(self.element_confidence(i), self.low_confidence))
+            .collect();
+        let start = |i: &usize| self.spatial_buffer.element_ranges[*i].rope_start;
+        let target = if backwards {
+            low.iter().rev().find(|i| start(i) < cursor).or(low.last())
+        } else {
+            low.iter().find(|i| start(i) > cursor).or(low.first())
+        };
+        match target.copied() {
+            Some(idx) => {
+                self.review_element = Some(idx);
+                let rope_start = self.spatial_buffer.element_ranges[idx].rope_start;
+                self.jump_to_rope_position(rope_start);
+            }
+            None => println!("✅ No words below {:.2} confidence", self.low_confidence),
+        }
+    }
+    
+    /// Step the review outline to the next (or previous) element in reading order
+    fn walk_elements(&mut self, backwards: bool) {
+        let count = self.spatial_buffer.element_ranges.len();
+        if count == 0 {
+            return;
+        }
+        let step = |i: usize| if backwards { (i + count - 1) % count } else { (i + 1) % count };
+        let mut next = match (self.review_element, backwards) {
+            (None, false) => 0,
+            (None, true) => count - 1,
+            (Some(i), _) => step(i),
+        };
+        // Skip auto-accepted words; stop if the whole queue is verified
+        let mut checked = 0;
+        while self.is_auto_accepted(next) {
+            checked += 1;
+            if checked == count {
+                self.review_element = None;
+                return;
+            }
+            next = step(next);
+        }
+        self.review_element = Some(next);
+        let rope_start = self.spatial_buffer.element_ranges[next].rope_start;
+        self.jump_to_rope_position(rope_start);
+    }
+    
+    fn is_auto_accepted(&self, idx: usize) -> bool {
+        review::is_auto_accepted(self.element_confidence(idx), self.auto_accept)
+    }
+    
+    /// Record the threshold and resulting queue size for audit
+    fn record_review_audit(&self) {
+        let result = match self.auto_accept {
+            Some(threshold) => {
+                let confidences: Vec<Option<f32>> = self.spatial_elements.iter().map(|e| e.confidence).collect();
+                review::ReviewAudit::new(threshold, &confidences).save(&self.pdf_path)
+            }
+            None => review::ReviewAudit::clear(&self.pdf_path),
+        };
+        if let Err(e) = result {
+            eprintln!("Error recording review audit: {}", e);
+        }
+    }
+    
+    fn select_element(&mut self, idx: Option<usize>) {
+        self.selected_element = idx;
+        self.inspector_text = idx.and_then(|i| self.spatial_buffer.element_text(i)).unwrap_or_default();
+    }
+    
+    /// Left strip with a miniature of every page; click one to open it, ✎ counts edited words.
+    /// Pages not parsed yet show as blank sheets
+    fn render_thumbnail_panel(&mut self, ctx: &egui::Context) {
+        let boxes_of = |buffer: &SpatialTextBuffer| -> Vec<(egui::Rect, bool)> {
+            buffer.element_ranges.iter().map(|range| (range.visual_bounds, range.modified)).collect()
+        };
+        let pages: Vec<thumbnails::PageThumbnail> = (1..=self.page_window.page_count.max(1))
+            .map(|page| {
+                let boxes = if page == self.page_window.current {
+                    boxes_of(&self.spatial_buffer)
+                } else {
+                    match self.page_window.resident.get(&page) {
+                        Some(resident) => match &resident.buffer {
+                            Some(buffer) => boxes_of(buffer),
+                            None => resident.elements.iter()
+                                .map(|e| (egui::Rect::from_min_size(egui::pos2(e.hpos, e.vpos), egui::vec2(e.width, e.height)), false))
+                                .collect(),
+                        },
+                        None => Vec::new(),
+                    }
+                };
+                let quality = self.page_quality.iter().find(|q| q.page == page).map(|q| q.score);
+                thumbnails::PageThumbnail { page, boxes, quality }
+            })
+            .collect();
+        
+        let current_page = self.page_window.current;
+        let page_size = self.page_size();
+        let theme = self.theme.active();
+        let mut open_page = None;
+        
+        egui::SidePanel::left("thumbnails").exact_width(130.0).show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for thumbnail in &pages {
+                    let width = ui.available_width() - 8.0;
+                    let height = width * page_size.y / page_size.x.max(1.0);
+                    let (rect, response) = ui.allocate_exact_size(egui::vec2(width, height.clamp(40.0, 400.0)), egui::Sense::click());
+                    thumbnails::paint(ui.painter(), rect, page_size, thumbnail, &theme, thumbnail.page == current_page);
+                    let details = self.page_quality.iter().find(|q| q.page == thumbnail.page)
+                        .map_or(String::new(), |q| format!("\nQuality {:.0}\n{}", q.score, q.details()));
+                    if response.on_hover_text(format!("Page {}{}", thumbnail.page, details)).clicked() {
+                        open_page = Some(thumbnail.page);
+                    }
+                    ui.label(egui::RichText::new(thumbnail.page.to_string()).small());
+                }
+            });
+        });
+        
+        if let Some(page) = open_page {
+            self.show_page(page);
+        }
+    }
+    
+    /// Side panel with the clicked element's ALTO attributes, all editable
+    fn render_inspector_panel(&mut self, ctx: &egui::Context) {
+        egui::SidePanel::left("inspector").default_width(240.0).show(ctx, |ui| {
+            ui.heading("🔬 Inspector");
+            ui.separator();
+            let idx = match self.selected_element.filter(|&i| i < self.spatial_buffer.element_ranges.len()) {
+                Some(idx) => idx,
+                None => {
+                    ui.label("Click an element to inspect it");
+                    return;
+                }
+            };
+            
+            let mut bounds = self.spatial_buffer.element_ranges[idx].visual_bounds;
+            let element_id = self.spatial_buffer.element_ranges[idx].element_id;
+            
+            ui.horizontal(|ui| {
+                for unit in units::DisplayUnit::ALL {
+                    ui.selectable_value(&mut self.display_unit, unit, unit.label());
+                }
+            }).response.on_hover_text(format!("Source unit: {} at {} dpi", self.source_units.unit.label(), self.source_units.dpi));
+            // Readout in the chosen unit; edits convert back to points
+            let per_point = self.display_unit.per_point(self.source_units.dpi);
+            let mut hpos = bounds.min.x * per_point;
+            let mut vpos = bounds.min.y * per_point;
+            let mut width = bounds.width() * per_point;
+            let mut height = bounds.height() * per_point;
+            let mut geometry_changed = false;
+            let mut content_committed = false;
+            
+            egui::Grid::new("inspector_fields").num_columns(2).show(ui, |ui| {
+                ui.label("CONTENT");
+                let response = ui.text_edit_singleline(&mut self.inspector_text);
+                content_committed = response.lost_focus();
+                ui.end_row();
+                
+                for (label, value) in [("HPOS", &mut hpos), ("VPOS", &mut vpos), ("WIDTH", &mut width), ("HEIGHT", &mut height)] {
+                    ui.label(label);
+                    geometry_changed |= ui.add(egui::DragValue::new(value).speed(0.5 * per_point).max_decimals(2)
+                        .suffix(format!(" {}", self.display_unit.label()))).changed();
+                    ui.end_row();
+                }
+                
+                if let Some(element) = self.spatial_elements.get_mut(element_id) {
+                    ui.label("STYLEREFS");
+                    let mut style_refs = element.style_refs.clone().unwrap_or_default();
+                    if ui.text_edit_singleline(&mut style_refs).changed() {
+                        element.style_refs = (!style_refs.is_empty()).then_some(style_refs);
+                    }
+                    ui.end_row();
+                    
+                    ui.label("PAGE");
+                    ui.label(element.page.to_string());
+                    ui.end_row();
+                    if let Some(confidence) = element.confidence {
+                        ui.label("WC");
+                        ui.label(format!("{:.2}", confidence));
+                        ui.end_row();
+                    }
+                }
+            });
+            
+            if content_committed && self.spatial_buffer.element_text(idx).as_deref() != Some(self.inspector_text.as_str()) {
+                let text = self.inspector_text.clone();
+                self.replace_element_text(idx, &text);
+            }
+            if geometry_changed {
+                let (hpos, vpos, width, height) = (hpos / per_point, vpos / per_point, width / per_point, height / per_point);
+                bounds = egui::Rect::from_min_size(egui::pos2(hpos, vpos), egui::vec2(width.max(1.0), height.max(1.0)));
+                self.set_element_bounds(ctx, idx, bounds);
+            }
+            if self.spatial_buffer.element_ranges[idx].overflow {
+                ui.colored_label(egui::Color32::from_rgb(255, 120, 80), "⚠ Text overflows its box");
+            }
+            
+            ui.separator();
+            if ui.button("↺ Reset geometry").clicked() {
+                let original = self.spatial_buffer.element_ranges[idx].original_bounds;
+                self.set_element_bounds(ctx, idx, original);
+            }
+            let merge = egui::Button::new(format!("⊕ Merge {} selected", self.selection_group.len()));
+            if ui.add_enabled(self.selection_group.len() > 1, merge)
+                .on_hover_text("Shift+click elements to select them (Ctrl+M)").clicked() {
+                self.merge_selected();
+            }
+        });
+    }
+    
+    /// Combine the multi-selected elements (which must be consecutive) into one
+    fn merge_selected(&mut self) {
+        let mut group = self.selection_group.clone();
+        group.sort();
+        group.dedup();
+        let (first, last) = match (group.first(), group.last()) {
+            (Some(&first), Some(&last)) if last > first => (first, last),
+            _ => {
+                eprintln!("Shift+click two or more elements to merge");
+                return;
+            }
+        };
+        if last - first + 1 != group.len() {
+            eprintln!("Only neighbouring elements can be merged");
+            return;
+        }
+        
+        if let Some(removed) = self.spatial_buffer.merge_elements(first, last) {
+            for pos in removed {
+                self.bookmarks.shift_for_delete(pos, pos + 1);
+            }
+            self.element_tags.shift_for_removal(first + 1, last - first);
+            self.element_labels.shift_for_removal(first + 1, last - first);
+            self.save_element_tags();
+            self.ner_suggestions.clear(); // Indices shifted
+            self.review_element = None;
+            self.selection_group = vec![first];
+            self.select_element(Some(first));
+            self.modified = true;
+        }
+    }
+    
+    /// Track a rectangle drawn by the text-box or redact tool; returns it when the drag ends
+    fn drag_document_rect(&mut self, response: &egui::Response, painter: &egui::Painter, color: egui::Color32) -> Option<egui::Rect> {
+        let transform = self.spatial_buffer.transform;
+        if response.drag_started() {
+            self.text_box_drag = response.interact_pointer_pos().map(|pos| transform.to_document(pos));
+        }
+        let (start, pointer) = match (self.text_box_drag, response.interact_pointer_pos()) {
+            (Some(start), Some(pointer)) => (start, transform.to_document(pointer)),
+            _ => return None,
+        };
+        let rect = egui::Rect::from_two_pos(start, pointer);
+        painter.rect_stroke(self.spatial_buffer.document_rect_to_screen(rect), 0.0, egui::Stroke::new(1.5, color));
+        
+        if response.drag_stopped() {
+            self.text_box_drag = None;
+            if rect.width() >= 4.0 && rect.height() >= 4.0 {
+                return Some(rect);
+            }
+        }
+        None
+    }
+    
+    /// Page of the elements under a document rectangle (page 1 when there are none)
+    fn page_at(&self, rect: egui::Rect) -> usize {
+        self.spatial_buffer.element_ranges.iter()
+            .filter(|range| range.visual_bounds.intersects(rect))
+            .filter_map(|range| self.spatial_elements.get(range.element_id))
+            .map(|element| element.page)
+            .min()
+            .unwrap_or(1)
+    }
+    
+    /// Outline each zone with its name
+    fn render_zones(&self, painter: &egui::Painter) {
+        let color = egui::Color32::from_rgb(200, 140, 255);
+        for zone in &self.zones {
+            let screen = self.spatial_buffer.document_rect_to_screen(zone.rect());
+            painter.rect_stroke(screen, 0.0, egui::Stroke::new(1.5, color));
+            painter.text(screen.left_top() + egui::vec2(2.0, -2.0), egui::Align2::LEFT_BOTTOM,
+                         format!("🗺 {}", zone.name), egui::FontId::proportional(11.0), color);
+        }
+    }
+    
+    fn render_zones_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_zones;
+        // Zone values are exports, so redactions apply to them too
+        let current = self.parsed_export_elements();
+        let templates = zones::ZoneTemplate::list();
+        let mut export = false;
+        let mut remove = None;
+        let mut load = None;
+        egui::Window::new("🗺 Zones")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.zones.is_empty() {
+                    ui.label("Drag with 🗺 Zone to mark a field, or load a template");
+                }
+                egui::Grid::new("zones_grid").num_columns(3).striped(true).show(ui, |ui| {
+                    for (i, zone) in self.zones.iter_mut().enumerate() {
+                        ui.add(egui::TextEdit::singleline(&mut zone.name).desired_width(120.0));
+                        let value = zone.text(&current);
+                        if value.is_empty() {
+                            ui.weak("—");
+                        } else {
+                            ui.label(value);
+                        }
+                        if ui.small_button("🗑").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+                export = ui.add_enabled(!self.zones.is_empty(), egui::Button::new("💾 Export JSON")).clicked();
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.zone_template_name).hint_text("Template name").desired_width(140.0));
+                    let can_save = !self.zones.is_empty() && !self.zone_template_name.trim().is_empty();
+                    if ui.add_enabled(can_save, egui::Button::new("💾 Save template")).clicked() {
+                        let template = zones::ZoneTemplate { name: self.zone_template_name.trim().to_string(), zones: self.zones.clone() };
+                        match template.save() {
+                            Ok(()) => println!("✅ Saved zone template '{}' to {}/", template.name, zones::ZONE_DIR),
+                            Err(e) => eprintln!("Error saving zone template: {}", e),
+                        }
+                    }
+                });
+                if !templates.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label("Apply:");
+                        for name in &templates {
+                            if ui.button(name).clicked() {
+                                load = Some(name.clone());
+                            }
+                        }
+                    });
+                }
+            });
+        self.show_zones = open;
+        if export {
+            // Every page, not just the parsed ones shown above
+            match zones::extract_json(&self.zones, &self.export_elements()) {
+                Ok(json) => match std::fs::write("chonker9_zone_fields.json", json) {
+                    Ok(()) => println!("✅ Saved {} zone(s) to chonker9_zone_fields.json", self.zones.len()),
+                    Err(e) => eprintln!("Error exporting zones: {}", e),
+                },
+                Err(e) => eprintln!("Error exporting zones: {}", e),
+            }
+        }
+        if let Some(i) = remove {
+            self.zones.remove(i);
+        }
+        if let Some(name) = load {
+            match zones::ZoneTemplate::load(&name) {
+                Ok(template) => {
+                    println!("✅ Applied zone template '{}' ({} zones)", template.name, template.zones.len());
+                    self.zone_template_name = template.name;
+                    self.zones = template.zones;
+                }
+                Err(e) => eprintln!("Error loading zone template: {}", e),
+            }
+        }
+    }
+    
+    fn save_table_regions(&self) {
+        if let Err(e) = table_regions::save(&self.page_sidecar_key(), &self.pipeline.table_regions) {
+            eprintln!("Error saving table regions: {}", e);
+        }
+    }
+    
+    /// Rows of each hand-drawn table from the current (edited) elements
+    fn table_region_rows(&self) -> Vec<Vec<Vec<String>>> {
+        let current = self.current_elements();
+        let elements: Vec<&SpatialElement> = current.iter().collect();
+        self.pipeline.table_regions.iter().map(|region| region.cells(&elements)).collect()
+    }
+    
+    /// Outline each hand-drawn table with its inferred column dividers and a totals badge
+    fn render_table_regions(&self, painter: &egui::Painter) {
+        let color = self.theme.active().table_text;
+        let current = self.current_elements();
+        let elements: Vec<&SpatialElement> = current.iter().collect();
+        for region in &self.pipeline.table_regions {
+            let screen = self.spatial_buffer.document_rect_to_screen(region.rect);
+            painter.rect_stroke(screen, 0.0, egui::Stroke::new(1.5, color));
+            for x in region.column_dividers(&elements) {
+                let top = self.spatial_buffer.transform.to_screen(egui::pos2(x, region.rect.min.y));
+                painter.extend(egui::Shape::dashed_line(
+                    &[top, egui::pos2(top.x, screen.max.y)],
+                    egui::Stroke::new(1.0, color), 4.0, 3.0,
+                ));
+            }
+            painter.text(screen.left_top() + egui::vec2(2.0, -2.0), egui::Align2::LEFT_BOTTOM,
+                         format!("▦ {} columns", region.columns), egui::FontId::proportional(11.0), color);
+            let checks = table_check::validate(&region.cells(&elements));
+            let totals: Vec<bool> = checks.iter().filter_map(|check| check.totals_pass()).collect();
+            if !totals.is_empty() {
+                let (badge, badge_color) = if totals.iter().all(|&pass| pass) {
+                    ("✔ totals", egui::Color32::from_rgb(80, 200, 120))
+                } else {
+                    ("✘ totals", egui::Color32::from_rgb(255, 90, 90))
+                };
+                painter.text(screen.right_top() + egui::vec2(-2.0, -2.0), egui::Align2::RIGHT_BOTTOM,
+                             badge, egui::FontId::proportional(11.0), badge_color);
+            }
+        }
+    }
+    
+    fn render_fields_window(&mut self, ctx: &egui::Context, pairs: &[key_values::KeyValue]) {
+        let mut open = self.show_fields;
+        let mut jump_to = None;
+        let mut saved = false;
+        let templates = &self.form_templates;
+        egui::Window::new("📋 Form Fields")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("{} label/value pair(s)", pairs.len()));
+                if ui.add_enabled(!pairs.is_empty(), egui::Button::new("💾 Export JSON")).clicked() {
+                    match serde_json::to_string_pretty(pairs) {
+                        Ok(json) => match std::fs::write("chonker9_fields.json", json) {
+                            Ok(()) => println!("✅ Saved {} field(s) to chonker9_fields.json", pairs.len()),
+                            Err(e) => eprintln!("Error exporting fields: {}", e),
+                        },
+                        Err(e) => eprintln!("Error exporting fields: {}", e),
+                    }
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("fields_grid").num_columns(2).striped(true).show(ui, |ui| {
+                        for kv in pairs {
+                            if ui.link(&kv.key).clicked() {
+                                jump_to = Some(egui::pos2(kv.bbox[0] + 1.0, kv.bbox[1] + kv.bbox[3] / 2.0));
+                            }
+                            ui.label(&kv.value);
+                            ui.end_row();
+                        }
+                    });
+                });
+                
+                // Templates: the labels of a recurring form, matched against each new document
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.form_template_name).hint_text("Form name").desired_width(140.0));
+                    let can_save = !pairs.is_empty() && !self.form_template_name.trim().is_empty();
+                    if ui.add_enabled(can_save, egui::Button::new("💾 Save as template")).clicked() {
+                        let template = key_values::FormTemplate {
+                            name: self.form_template_name.trim().to_string(),
+                            keys: pairs.iter().map(|kv| kv.key.clone()).collect(),
+                        };
+                        match template.save() {
+                            Ok(()) => {
+                                println!("✅ Saved form template '{}' to {}/", template.name, key_values::FORM_DIR);
+                                self.form_template = Some(template.name);
+                                saved = true;
+                            }
+                            Err(e) => eprintln!("Error saving form template: {}", e),
+                        }
+                    }
+                });
+                egui::ComboBox::from_id_source("form_template")
+                    .selected_text(self.form_template.as_deref().unwrap_or("No template"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.form_template, None, "No template");
+                        for template in templates {
+                            ui.selectable_value(&mut self.form_template, Some(template.name.clone()), &template.name);
+                        }
+                    });
+                if let Some(template) = templates.iter().find(|t| Some(&t.name) == self.form_template.as_ref()) {
+                    let fields = template.apply(pairs);
+                    let missing = fields.iter().filter(|(_, value)| value.is_none()).count();
+                    if missing > 0 {
+                        ui.colored_label(egui::Color32::from_rgb(255, 150, 0), format!("⚠ {} of {} field(s) not found", missing, fields.len()));
+                    }
+                    egui::Grid::new("form_template_grid").num_columns(2).striped(true).show(ui, |ui| {
+                        for (key, value) in &fields {
+                            ui.label(key);
+                            match value {
+                                Some(value) => { ui.label(value); }
+                                None => { ui.weak("—"); }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    if ui.button("💾 Export form JSON").clicked() {
+                        match key_values::template_json(&fields) {
+                            Ok(json) => match std::fs::write("chonker9_form.json", json) {
+                                Ok(()) => println!("✅ Saved '{}' fields to chonker9_form.json", template.name),
+                                Err(e) => eprintln!("Error exporting form: {}", e),
+                            },
+                            Err(e) => eprintln!("Error exporting form: {}", e),
+                        }
+                    }
+                }
+            });
+        self.show_fields = open;
+        if saved {
+            self.form_templates = key_values::FormTemplate::list();
+        }
+        if let Some(pos) = jump_to {
+            if let Some(idx) = self.spatial_buffer.element_ranges.iter().position(|r| r.visual_bounds.contains(pos)) {
+                let rope_start = self.spatial_buffer.element_ranges[idx].rope_start;
+                self.jump_to_rope_position(rope_start);
+            }
+        }
+    }
+    
+    /// Bates stamp per page, the export toggle and the page→Bates CSV; stamps are outlined while open
+    fn render_bates_window(&mut self, ctx: &egui::Context, stamps: &[bates::BatesStamp]) {
+        let mut open = self.show_bates;
+        let mut jump_to = None;
+        egui::Window::new("⚖ Bates Numbers")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                match (stamps.first(), stamps.last()) {
+                    (Some(first), Some(last)) => {
+                        ui.label(format!("{} – {} on {} page(s)", first.text, last.text, stamps.len()));
+                        // Productions number pages consecutively; a jump usually means a missing page
+                        let gaps = stamps.windows(2).filter(|pair| pair[1].number != pair[0].number + 1).count();
+                        if gaps > 0 {
+                            ui.colored_label(egui::Color32::from_rgb(255, 150, 0), format!("⚠ {} break(s) in the sequence", gaps));
+                        }
+                    }
+                    _ if self.page_window.resident.is_empty() => {
+                        ui.label("Stamps are recognised by repeating across pages; only one page is parsed");
+                    }
+                    _ => { ui.label("No Bates stamps found"); }
+                }
+                ui.checkbox(&mut self.exclude_bates, "Exclude from text exports");
+                if ui.add_enabled(!stamps.is_empty(), egui::Button::new("💾 Export page → Bates CSV")).clicked() {
+                    match std::fs::write("chonker9_bates.csv", bates::to_csv(stamps)) {
+                        Ok(()) => println!("✅ Saved {} Bates number(s) to chonker9_bates.csv", stamps.len()),
+                        Err(e) => eprintln!("Error exporting Bates CSV: {}", e),
+                    }
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("bates_grid").num_columns(2).striped(true).show(ui, |ui| {
+                        for stamp in stamps {
+                            ui.label(format!("Page {}", stamp.page));
+                            if ui.link(&stamp.text).clicked() {
+                                jump_to = Some((stamp.page, stamp.bounds.center()));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.show_bates = open;
+        if let Some((page, pos)) = jump_to {
+            self.show_page(page);
+            if let Some(idx) = self.spatial_buffer.element_ranges.iter().position(|r| r.visual_bounds.contains(pos)) {
+                let rope_start = self.spatial_buffer.element_ranges[idx].rope_start;
+                self.jump_to_rope_position(rope_start);
+            }
+        }
+    }
+    
+    /// Numeric column issues and Total-row checks for every hand-drawn table
+    fn render_table_check_window(&mut self, ctx: &egui::Context) {
+        let tables = self.table_region_rows();
+        let mut open = self.show_table_check;
+        egui::Window::new("🧮 Table Validation")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if tables.is_empty() {
+                    ui.label("Draw a table region with ▦ Table first");
+                }
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (t, rows) in tables.iter().enumerate() {
+                        ui.strong(format!("Table {} ({} rows)", t + 1, rows.len()));
+                        let checks = table_check::validate(rows);
+                        if checks.is_empty() {
+                            ui.weak("No numeric columns");
+                        }
+                        for check in &checks {
+                            let header = rows.first().and_then(|row| row.get(check.column)).filter(|h| !h.is_empty())
+                                .map_or(format!("Column {}", check.column + 1), |h| format!("Column {} ({})", check.column + 1, h));
+                            ui.horizontal(|ui| {
+                                ui.label(header);
+                                match check.totals_pass() {
+                                    Some(true) => { ui.colored_label(egui::Color32::from_rgb(80, 200, 120), "✔ totals match"); }
+                                    Some(false) => { ui.colored_label(egui::Color32::from_rgb(255, 90, 90), "✘ totals differ"); }
+                                    None => { ui.weak("no Total row"); }
+                                }
+                            });
+                            for total in check.totals.iter().filter(|total| !total.passes()) {
+                                ui.label(format!("    row {}: printed {} vs computed {}", total.row + 1,
+                                    numbers::format_value(total.printed), numbers::format_value(total.computed)));
+                            }
+                            for (row, issue) in &check.issues {
+                                let cell = rows[*row].get(check.column).map_or("", String::as_str);
+                                ui.label(format!("    row {}: \"{}\" — {}", row + 1, cell, issue.label()));
+                            }
+                        }
+                        ui.separator();
+                    }
+                });
+            });
+        self.show_table_check = open;
+    }
+    
+    fn save_redactions(&self) {
+        if let Err(e) = self.redactions.save(&self.page_sidecar_key()) {
+            eprintln!("Error saving redactions: {}", e);
+        }
+    }
+    
+    /// Drawn regions plus the bounds of every element labeled redacted, on the viewed page
+    fn redacted_rects(&self) -> Vec<egui::Rect> {
+        Self::page_redacted_rects(&self.redactions, &self.element_labels, &self.spatial_buffer)
+    }
+    
+    fn page_redacted_rects(redactions: &redaction::Redactions, element_labels: &tags::ElementTags<labels::Label>, buffer: &SpatialTextBuffer) -> Vec<egui::Rect> {
+        let labeled = element_labels.iter()
+            .filter(|&(_, label)| label == labels::Label::Redacted)
+            .filter_map(|(idx, _)| buffer.element_ranges.get(idx))
+            .map(|range| range.visual_bounds);
+        redactions.regions.iter().copied().chain(labeled).collect()
+    }
+    
+    /// The rope with every redacted element replaced by a placeholder
+    fn redacted_text(&self) -> String {
+        Self::buffer_redacted_text(&self.spatial_buffer, &self.redacted_rects())
+    }
+    
+    /// Redacted rectangles of every page in the PDF, parsed or not, for burning the whole document
+    fn every_page_redaction(&self) -> Result<Vec<(usize, Vec<egui::Rect>)>, Box<dyn std::error::Error>> {
+        let mut pages = Vec::new();
+        for page in 1..=self.page_window.page_count {
+            let rects = if page == self.page_window.current {
+                self.redacted_rects()
+            } else if let Some(resident) = self.page_window.resident.get(&page) {
+                let fresh;
+                let buffer = match &resident.buffer {
+                    Some(buffer) => buffer,
+                    None => {
+                        fresh = Self::spatial_buffer_for(&resident.elements);
+                        &fresh
+                    }
+                };
+                Self::page_redacted_rects(&resident.sidecars.redactions, &resident.sidecars.labels, buffer)
+            } else {
+                // Labels point at elements, so an unparsed page is only extracted when it has redacted labels;
+                // failing that extraction fails the export rather than leaving labeled words visible
+                let sidecars = page_window::PageSidecars::load(&self.pdf_path, page);
+                if sidecars.labels.count(labels::Label::Redacted) > 0 {
+                    let buffer = Self::spatial_buffer_for(&self.extract_view_page(page, false)?.elements);
+                    Self::page_redacted_rects(&sidecars.redactions, &sidecars.labels, &buffer)
+                } else {
+                    sidecars.redactions.regions
+                }
+            };
+            pages.push((page, rects));
+        }
+        Ok(pages)
+    }
+    
+    fn buffer_redacted_text(buffer: &SpatialTextBuffer, redacted: &[egui::Rect]) -> String {
+        let mut rope = buffer.rope.clone();
+        // Back to front so earlier ranges stay valid
+        for (idx, range) in buffer.element_ranges.iter().enumerate().rev() {
+            if !redaction::covers(redacted, range.visual_bounds) {
+                continue;
+            }
+            let len = buffer.element_text(idx).map_or(0, |t| t.chars().count());
+            rope.remove(range.rope_start..range.rope_start + len);
+            rope.insert(range.rope_start, redaction::PLACEHOLDER);
+        }
+        rope.to_string()
+    }
+    
+    /// Prompt for the content of a freshly drawn text box
+    fn render_text_box_window(&mut self, ctx: &egui::Context) {
+        let rect = match self.pending_text_box {
+            Some(rect) => rect,
+            None => return,
+        };
+        let mut insert = false;
+        let mut cancel = false;
+        egui::Window::new("➕ New Text Box")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("at {:.0}, {:.0} ({:.0} × {:.0})", rect.min.x, rect.min.y, rect.width(), rect.height()));
+                let response = ui.text_edit_singleline(&mut self.text_box_input);
+                response.request_focus();
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    insert = true;
+                }
+                ui.horizontal(|ui| {
+                    insert |= ui.button("Insert").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        
+        let content = self.text_box_input.trim().to_string();
+        if insert && !content.is_empty() {
+            self.insert_text_box(rect, &content);
+        }
+        if insert || cancel {
+            self.pending_text_box = None;
+        }
+    }
+    
+    /// 💬 per comment beside its anchor; hover shows the text, click edits it
+    fn render_annotation_markers(&mut self, ui: &mut egui::Ui) {
+        let margin_x = self.page_size().x + 6.0;
+        let mut edit = None;
+        for (i, annotation) in self.annotations.iter().enumerate() {
+            let anchor = self.spatial_buffer.document_rect_to_screen(annotation.rect());
+            let marker_pos = self.spatial_buffer.transform.to_screen(egui::pos2(margin_x, annotation.rect[1]));
+            let marker = egui::Rect::from_min_size(marker_pos, egui::vec2(18.0, 18.0));
+            let response = ui.interact(marker, ui.id().with(("annotation", i)), egui::Sense::click());
+            let color = if response.hovered() {
+                egui::Color32::from_rgb(255, 220, 80)
+            } else {
+                egui::Color32::from_rgba_unmultiplied(255, 220, 80, 90)
+            };
+            ui.painter().rect_stroke(anchor.expand(2.0), 2.0, egui::Stroke::new(1.0, color));
+            ui.painter().line_segment([anchor.right_center(), marker.left_center()], egui::Stroke::new(1.0, color));
+            ui.painter().text(marker.center(), egui::Align2::CENTER_CENTER, "💬", egui::FontId::proportional(14.0), color);
+            if response.on_hover_text(&annotation.text).clicked() {
+                edit = Some(i);
+            }
+        }
+        if let Some(i) = edit {
+            let annotation = &self.annotations[i];
+            self.annotation_draft = Some((Some(i), annotation.rect(), annotation.text.clone()));
+        }
+    }
+    
+    fn render_annotation_window(&mut self, ctx: &egui::Context) {
+        let Some((existing, rect, mut text)) = self.annotation_draft.take() else { return };
+        let mut save = false;
+        let mut delete = false;
+        let mut cancel = false;
+        egui::Window::new("💬 Comment")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("at {:.0}, {:.0} ({:.0} × {:.0})", rect.min.x, rect.min.y, rect.width(), rect.height()));
+                ui.text_edit_multiline(&mut text);
+                ui.horizontal(|ui| {
+                    save = ui.button("Save").clicked();
+                    if existing.is_some() {
+                        delete = ui.button("🗑 Delete").clicked();
+                    }
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        
+        let trimmed = text.trim().to_string();
+        match existing {
+            _ if cancel => return,
+            Some(i) if delete || (save && trimmed.is_empty()) => {
+                self.annotations.remove(i);
+            }
+            Some(i) if save => self.annotations[i].text = trimmed,
+            None if save && !trimmed.is_empty() => self.annotations.push(annotations::Annotation::new(self.page_window.current, rect, trimmed)),
+            _ => {
+                self.annotation_draft = Some((existing, rect, text));
+                return;
+            }
+        }
+        if let Err(e) = annotations::save(&self.page_sidecar_key(), &self.annotations) {
+            eprintln!("Error saving annotations: {}", e);
+        }
+    }
+    
+    /// New element at document rectangle `rect`, included in exports like any other
+    fn insert_text_box(&mut self, rect: egui::Rect, content: &str) {
+        self.spatial_elements.push(SpatialElement {
+            content: content.to_string(),
+            hpos: rect.min.x,
+            vpos: rect.min.y,
+            width: rect.width(),
+            height: rect.height(),
+            page: 1,
+            confidence: None,
+            modified: true,
+            tag: None,
+            style_refs: None,
+            font_size: None,
+            extracted: None,
+        });
+        let element_id = self.spatial_elements.len() - 1;
+        let (idx, rope_pos, inserted) = self.spatial_buffer.insert_element(element_id, content, rect);
+        self.bookmarks.shift_for_insert(rope_pos, inserted);
+        self.element_tags.shift_for_insert(idx, 1);
+        self.element_labels.shift_for_insert(idx, 1);
+        self.save_element_tags();
+        self.ner_suggestions.clear(); // Indices shifted
+        self.review_element = None;
+        self.selection_group = vec![idx];
+        self.select_element(Some(idx));
+        self.modified = true;
+    }
+    
+    /// Every edited or moved element on every page with its original and corrected text.
+    /// Redacted elements are reported with the placeholder for both texts
+    fn change_records(&self) -> Vec<change_report::ChangeRecord> {
+        let mut records = Self::page_change_records(self.page_window.current, &self.spatial_buffer, &self.redacted_rects());
+        for (&number, resident) in &self.page_window.resident {
+            let sidecars = &resident.sidecars;
+            let fresh;
+            let buffer = match &resident.buffer {
+                Some(buffer) => buffer,
+                None if sidecars.pending_edits.is_empty() => continue,
+                None => {
+                    fresh = Self::page_buffer(&resident.elements, &sidecars.pending_edits);
+                    &fresh
+                }
+            };
+            let redacted = Self::page_redacted_rects(&sidecars.redactions, &sidecars.labels, buffer);
+            records.extend(Self::page_change_records(number, buffer, &redacted));
+        }
+        // Other pages only carry edits that were saved as pending tracked changes
+        self.for_each_unparsed_page(|number, page| {
+            if page.sidecars.pending_edits.is_empty() {
+                return;
+            }
+            let buffer = Self::page_buffer(&page.elements, &page.sidecars.pending_edits);
+            let redacted = Self::page_redacted_rects(&page.sidecars.redactions, &page.sidecars.labels, &buffer);
+            records.extend(Self::page_change_records(number, &buffer, &redacted));
+        });
+        records.sort_by_key(|record| record.page);
+        records
+    }
+    
+    fn page_change_records(page: usize, buffer: &SpatialTextBuffer, redacted: &[egui::Rect]) -> Vec<change_report::ChangeRecord> {
+        buffer.element_ranges.iter().enumerate()
+            .filter_map(|(idx, range)| {
+                let modified_at = range.modified_at?;
+                let bounds = range.visual_bounds;
+                let (original, corrected) = if redaction::covers(redacted, bounds) {
+                    (redaction::PLACEHOLDER.to_string(), redaction::PLACEHOLDER.to_string())
+                } else {
+                    (range.original_text.clone(), buffer.element_text(idx).unwrap_or_default())
+                };
+                Some(change_report::ChangeRecord {
+                    element_id: range.element_id,
+                    page,
+                    x: bounds.min.x,
+                    y: bounds.min.y,
+                    width: bounds.width(),
+                    height: bounds.height(),
+                    original,
+                    corrected,
+                    modified_at,
+                })
+            })
+            .collect()
+    }
+    
+    /// Review queue of every modified element: original vs current, jump and revert per row
+    fn render_changes_panel(&mut self, ctx: &egui::Context) {
+        let mut jump_to = None;
+        let mut revert = None;
+        let mut decide = None; // (element, accept), element None for accept all
+        egui::TopBottomPanel::bottom("changes").resizable(true).default_height(180.0).show(ctx, |ui| {
+            let modified: Vec<usize> = self.spatial_buffer.element_ranges.iter().enumerate()
+                .filter(|(_, r)| r.modified)
+                .map(|(i, _)| i)
+                .collect();
+            ui.horizontal(|ui| {
+                ui.heading(format!("📝 Modified Elements ({})", modified.len()));
+                if !modified.is_empty() && ui.button("↺ Revert all").clicked() {
+                    revert = Some(None);
+                }
+                let pending = self.spatial_buffer.element_ranges.iter().filter(|r| r.pending_from.is_some()).count();
+                if pending > 0 && ui.button(format!("✔ Accept all ({})", pending)).clicked() {
+                    decide = Some((None, true));
+                }
+                if ui.button("🧾 Export Report").on_hover_text("chonker9_changes.csv / .json").clicked() {
+                    match change_report::write("chonker9_changes", &self.change_records()) {
+                        Ok(count) => println!("✅ Exported {} change(s) to chonker9_changes.csv/.json", count),
+                        Err(e) => eprintln!("Error exporting change report: {}", e),
+                    }
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                egui::Grid::new("changes_grid").striped(true).num_columns(5).show(ui, |ui| {
+                    for idx in modified {
+                        let range = &self.spatial_buffer.element_ranges[idx];
+                        let original = range.original_text.clone();
+                        let moved = range.visual_bounds != range.original_bounds;
+                        let current = self.spatial_buffer.element_text(idx).unwrap_or_default();
+                        if ui.link(format!("#{}", idx + 1)).clicked() {
+                            jump_to = Some(idx);
+                        }
+                        ui.label(egui::RichText::new(&original).strikethrough().color(egui::Color32::from_rgb(220, 120, 120)));
+                        let mut now = egui::RichText::new(if current.is_empty() { "∅".to_string() } else { current });
+                        now = now.color(egui::Color32::from_rgb(140, 220, 140));
+                        ui.label(now).on_hover_text(if moved { "Text and/or bounds changed" } else { "Text changed" });
+                        if ui.small_button("↺").on_hover_text("Revert this element").clicked() {
+                            revert = Some(Some(idx));
+                        }
+                        ui.horizontal(|ui| {
+                            if let Some(before) = &self.spatial_buffer.element_ranges[idx].pending_from {
+                                if ui.small_button("✔").on_hover_text(format!("Accept (replaces \"{}\")", before)).clicked() {
+                                    decide = Some((Some(idx), true));
+                                }
+                                if ui.small_button("✖").on_hover_text(format!("Reject, back to \"{}\"", before)).clicked() {
+                                    decide = Some((Some(idx), false));
+                                }
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        
+        if let Some(idx) = jump_to {
+            self.select_element(Some(idx));
+            self.selection_group = vec![idx];
+            let rope_start = self.spatial_buffer.element_ranges[idx].rope_start;
+            self.jump_to_rope_position(rope_start);
+        }
+        match revert {
+            Some(Some(idx)) => self.revert_element(idx),
+            Some(None) => self.revert_all_elements(),
+            None => {}
+        }
+        match decide {
+            Some((Some(idx), true)) => self.spatial_buffer.accept_change(idx),
+            Some((Some(idx), false)) => self.reject_change(idx),
+            Some((None, _)) => {
+                for idx in 0..self.spatial_buffer.element_ranges.len() {
+                    self.spatial_buffer.accept_change(idx);
+                }
+            }
+            None => {}
+        }
+    }
+    
+    /// Persist the viewed page's pending tracked edits
+    fn save_pending_edits(&self) {
+        let edits = track_changes::collect(&self.spatial_buffer, |idx| self.extracted_id(idx));
+        if let Err(e) = track_changes::save(&self.page_sidecar_key(), &edits) {
+            eprintln!("Error saving tracked changes: {}", e);
+        }
+    }
+    
+    /// Put a tracked element back to the text its pending edit replaced
+    fn reject_change(&mut self, idx: usize) {
+        if let Some((start, old_len, new_len)) = self.spatial_buffer.reject_change(idx) {
+            self.bookmarks.shift_for_delete(start, start + old_len);
+            self.bookmarks.shift_for_insert(start, new_len);
+        }
+        self.modified = self.spatial_buffer.element_ranges.iter().any(|r| r.modified);
+    }
+    
+    /// Pending edits: the replaced text struck through in red above, the new text underlined in green
+    fn render_pending_changes(&self, painter: &egui::Painter) {
+        let zoom = self.spatial_buffer.transform.zoom;
+        let removed = egui::Color32::from_rgb(220, 90, 90);
+        let inserted = egui::Color32::from_rgb(90, 200, 110);
+        for range in &self.spatial_buffer.element_ranges {
+            let Some(before) = &range.pending_from else { continue };
+            let rect = self.spatial_buffer.document_rect_to_screen(range.visual_bounds);
+            painter.line_segment([rect.left_bottom(), rect.right_bottom()], egui::Stroke::new(2.0, inserted));
+            if before.is_empty() {
+                continue;
+            }
+            let galley = painter.layout_no_wrap(before.clone(), egui::FontId::proportional(9.0 * zoom), removed);
+            let origin = rect.left_top() - egui::vec2(0.0, galley.size().y);
+            let strike_y = origin.y + galley.size().y / 2.0;
+            painter.line_segment(
+                [egui::pos2(origin.x, strike_y), egui::pos2(origin.x + galley.size().x, strike_y)],
+                egui::Stroke::new(1.0, removed),
+            );
+            painter.galley(origin, galley, removed);
+        }
+    }
+    
+    /// Undo every correction to one element: extracted text and bounds come back
+    fn revert_element(&mut self, idx: usize) {
+        if let Some((start, old_len, new_len)) = self.spatial_buffer.revert_element(idx) {
+            self.bookmarks.shift_for_delete(start, start + old_len);
+            self.bookmarks.shift_for_insert(start, new_len);
+        }
+        self.modified = self.spatial_buffer.element_ranges.iter().any(|r| r.modified);
+    }
+    
+    fn revert_all_elements(&mut self) {
+        // Back to front so earlier rope positions stay put
+        for idx in (0..self.spatial_buffer.element_ranges.len()).rev() {
+            if self.spatial_buffer.element_ranges[idx].modified {
+                self.revert_element(idx);
+            }
+        }
+    }
+    
+    /// Split the element under the cursor into two Strings
+    fn split_at_cursor(&mut self) {
+        let cursor = self.spatial_cursor.rope_pos;
+        match self.spatial_buffer.split_element(cursor) {
+            Some((second, inserted)) => {
+                if inserted {
+                    self.bookmarks.shift_for_insert(cursor, 1);
+                }
+                self.element_tags.shift_for_insert(second, 1);
+                if let Some(tag) = self.element_tags.get(second - 1) {
+                    self.element_tags.set(second, Some(tag));
+                }
+                self.element_labels.shift_for_insert(second, 1);
+                if let Some(label) = self.element_labels.get(second - 1) {
+                    self.element_labels.set(second, Some(label));
+                }
+                self.save_element_tags();
+                self.ner_suggestions.clear(); // Indices shifted
+                self.review_element = None;
+                self.selection_group = vec![second - 1, second];
+                self.select_element(Some(second));
+                self.modified = true;
+            }
+            None => eprintln!("Place the cursor inside an element to split it"),
+        }
+    }
+    
+    /// Snap the Shift+click group onto one column x: left edges, right edges or decimal points
+    fn align_selected(&mut self, ctx: &egui::Context, align: align::ColumnAlign) {
+        let font = self.editor_font();
+        let measure = |text: String| ctx.fonts(|f| f.layout_no_wrap(text, font.clone(), egui::Color32::WHITE).size().x);
+        // (element, bounds, anchor x in document points)
+        let anchored: Vec<(usize, egui::Rect, f32)> = self.selection_group.iter()
+            .filter_map(|&i| {
+                let bounds = self.spatial_buffer.element_ranges.get(i)?.visual_bounds;
+                let anchor = match align {
+                    align::ColumnAlign::Left => bounds.min.x,
+                    align::ColumnAlign::Right => bounds.max.x,
+                    align::ColumnAlign::Decimal => {
+                        // Where the anchor falls in the shaped text, scaled to the element's width
+                        let text = self.spatial_buffer.element_text(i)?;
+                        let prefix: String = text.chars().take(align::decimal_index(&text)).collect();
+                        let full = measure(text);
+                        let fraction = if full > 0.0 { measure(prefix) / full } else { 1.0 };
+                        bounds.min.x + bounds.width() * fraction
+                    }
+                };
+                Some((i, bounds, anchor))
+            })
+            .collect();
+        let anchors: Vec<f32> = anchored.iter().map(|(_, _, anchor)| *anchor).collect();
+        let target = match align::column_x(&anchors) {
+            Some(target) => target,
+            None => return,
+        };
+        for (i, bounds, anchor) in anchored {
+            if (anchor - target).abs() > 0.01 {
+                self.set_element_bounds(ctx, i, align::shifted(bounds, anchor, target));
+            }
+        }
+        println!("✅ Aligned {} element(s) at x = {:.1}", anchors.len(), target);
+    }
+    
+    /// Move or resize an element, re-measuring whether its shaped text still fits
+    fn set_element_bounds(&mut self, ctx: &egui::Context, idx: usize, bounds: egui::Rect) {
+        self.spatial_buffer.set_element_bounds(idx, bounds);
+        let text = self.spatial_buffer.element_text(idx).unwrap_or_default();
+        // Bounds are in document units, so measure at the element's own font size (its extracted
+        // height when the ALTO gave none), not the screen font
+        let Some(range) = self.spatial_buffer.element_ranges.get(idx) else { return };
+        let size = self.spatial_elements.get(range.element_id)
+            .and_then(|element| element.font_size)
+            .filter(|&size| size > 0.0)
+            .unwrap_or(range.original_bounds.height());
+        let font = egui::FontId::new(size, self.editor_font().family);
+        let text_width = ctx.fonts(|f| f.layout_no_wrap(text, font, egui::Color32::WHITE).size().x);
+        if let Some(range) = self.spatial_buffer.element_ranges.get_mut(idx) {
+            range.overflow = text_width > bounds.width() + 0.5;
+        }
+        self.modified = true;
+    }
+    
+    /// Corner and edge handles that resize the selected element, and its body to move it,
+    /// with smart guides snapping to neighbouring elements and the grid
+    fn render_resize_handles(&mut self, ui: &mut egui::Ui) {
+        let idx = match self.selected_element.filter(|&i| i < self.spatial_buffer.element_ranges.len()) {
+            Some(idx) => idx,
+            None => return,
+        };
+        let bounds = self.spatial_buffer.element_ranges[idx].visual_bounds;
+        let screen = self.spatial_buffer.document_rect_to_screen(bounds);
+        let overflow = self.spatial_buffer.element_ranges[idx].overflow;
+        let outline = if overflow { egui::Color32::from_rgb(255, 120, 80) } else { egui::Color32::from_rgb(255, 220, 80) };
+        ui.painter().rect_stroke(screen, 0.0, egui::Stroke::new(1.0, outline));
+        
+        // Body first so the handles on top win the hit test
+        let mut drag: Option<(egui::Response, snap::Edges)> = None;
+        let body = ui.interact(screen, ui.id().with("move_element"), egui::Sense::drag())
+            .on_hover_cursor(egui::CursorIcon::Move);
+        if body.dragged() || body.drag_started() || body.drag_stopped() {
+            drag = Some((body, snap::Edges::ALL));
+        }
+        
+        // (x, y) anchor of each handle: 0 = min edge, 0.5 = middle, 1 = max edge
+        const HANDLES: [(f32, f32); 8] = [
+            (0.0, 0.0), (0.5, 0.0), (1.0, 0.0), (1.0, 0.5),
+            (1.0, 1.0), (0.5, 1.0), (0.0, 1.0), (0.0, 0.5),
+        ];
+        for (i, &(hx, hy)) in HANDLES.iter().enumerate() {
+            let center = screen.min + screen.size() * egui::vec2(hx, hy);
+            let handle = egui::Rect::from_center_size(center, egui::vec2(7.0, 7.0));
+            let response = ui.interact(handle, ui.id().with(("resize_handle", i)), egui::Sense::drag());
+            let cursor = if hx == 0.5 {
+                egui::CursorIcon::ResizeVertical
+            } else if hy == 0.5 {
+                egui::CursorIcon::ResizeHorizontal
+            } else if hx == hy {
+                egui::CursorIcon::ResizeNwSe
+            } else {
+                egui::CursorIcon::ResizeNeSw
+            };
+            let response = response.on_hover_cursor(cursor);
+            ui.painter().rect_filled(handle, 1.0, outline);
+            
+            if response.dragged() || response.drag_started() || response.drag_stopped() {
+                let edges = snap::Edges { left: hx == 0.0, right: hx == 1.0, top: hy == 0.0, bottom: hy == 1.0 };
+                drag = Some((response, edges));
+            }
+        }
+        
+        let (response, edges) = match drag {
+            Some(drag) => drag,
+            None => return,
+        };
+        let zoom = self.spatial_buffer.transform.zoom;
+        if response.drag_started() {
+            self.drag_origin = Some((bounds, egui::Vec2::ZERO));
+        }
+        let (origin, mut offset) = match self.drag_origin {
+            Some(origin) => origin,
+            None => return,
+        };
+        offset += response.drag_delta() / zoom;
+        self.drag_origin = Some((origin, offset));
+        
+        // Work from the drag origin so a snap never swallows small movements
+        let mut moved = origin;
+        if edges.left { moved.min.x += offset.x; }
+        if edges.right { moved.max.x += offset.x; }
+        if edges.top { moved.min.y += offset.y; }
+        if edges.bottom { moved.max.y += offset.y; }
+        let others: Vec<egui::Rect> = self.spatial_buffer.element_ranges.iter().enumerate()
+            .filter(|(i, _)| *i != idx)
+            .map(|(_, range)| range.visual_bounds)
+            .collect();
+        let (snapped, guides) = snap::snap_rect(moved, edges, &others, &self.snap, zoom);
+        self.render_guides(ui, &guides);
+        
+        if response.drag_stopped() {
+            self.drag_origin = None;
+        }
+        // Keep at least a point of size so handles never cross over
+        if snapped != bounds && snapped.width() >= 1.0 && snapped.height() >= 1.0 {
+            self.set_element_bounds(&ui.ctx().clone(), idx, snapped);
+        }
+    }
+    
+    /// Guide lines across the visible canvas
+    fn render_guides(&self, ui: &egui::Ui, guides: &[snap::Guide]) {
+        let clip = ui.clip_rect();
+        let transform = self.spatial_buffer.transform;
+        let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 80, 200));
+        for guide in guides {
+            match *guide {
+                snap::Guide::Vertical(x) => {
+                    let x = transform.to_screen(egui::pos2(x, 0.0)).x;
+                    ui.painter().line_segment([egui::pos2(x, clip.top()), egui::pos2(x, clip.bottom())], stroke);
+                }
+                snap::Guide::Horizontal(y) => {
+                    let y = transform.to_screen(egui::pos2(0.0, y)).y;
+                    ui.painter().line_segment([egui::pos2(clip.left(), y), egui::pos2(clip.right(), y)], stroke);
+                }
+            }
+        }
+    }
+    
+    /// Draw the cached raster of page 1 at the current zoom
+    fn render_page_image(&mut self, ctx: &egui::Context, painter: &egui::Painter) {
+        let transform = self.spatial_buffer.transform;
+        if let Some(texture) = &self.iiif_texture {
+            // IIIF canvas image stretched over the page
+            let rect = egui::Rect::from_min_max(transform.to_screen(egui::Pos2::ZERO), transform.to_screen(self.page_size().to_pos2()));
+            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+            painter.image(texture.id(), rect, uv, egui::Color32::from_white_alpha(90));
+            return;
+        }
+        let key = tile_cache::TileKey::new(1, transform.zoom);
+        match self.tile_cache.get_or_render(ctx, &self.pdf_path, key) {
+            Ok((texture, page_size)) => {
+                let rect = egui::Rect::from_min_max(transform.to_screen(egui::Pos2::ZERO), transform.to_screen(page_size.to_pos2()));
+                let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                painter.image(texture.id(), rect, uv, egui::Color32::from_white_alpha(90));
+            }
+            Err(e) => {
+                // Don't retry every frame
+                eprintln!("Error rendering page image: {}", e);
+                self.show_page_image = false;
+            }
+        }
+    }
+    
+    /// Pictures and large graphics at their page positions, so the page reads like the original
+    fn render_figures(&mut self, ctx: &egui::Context, painter: &egui::Painter) {
+        // Crops are cut again when redactions change, and not shown until they are
+        let redacted = self.redacted_rects();
+        let current = matches!(&self.figures, Some((applied, _)) if *applied == redacted);
+        if !current && !matches!(&self.figures_loading, Some((_, applied, _)) if *applied == redacted) {
+            let (pdf_path, page, view_rotation) = (self.pdf_path.clone(), self.page_window.current, self.view_rotation);
+            let rects = redacted.clone();
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                // The receiver is gone when another page was shown meanwhile
+                let _ = sender.send(figures::load_page(&pdf_path, page, view_rotation, &rects).map_err(|e| e.to_string()));
+            });
+            self.figures_loading = Some((page, redacted, receiver));
+        }
+        if !current {
+            return;
+        }
+        
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        for (figure, texture) in self.figures.iter().flat_map(|(_, placed)| placed) {
+            painter.image(texture.id(), self.spatial_buffer.document_rect_to_screen(figure.rect), uv, egui::Color32::WHITE);
+        }
+    }
+    
+    fn poll_figures(&mut self, ctx: &egui::Context) {
+        let Some((_, _, receiver)) = &self.figures_loading else { return };
+        let loaded = match receiver.try_recv() {
+            Ok(loaded) => loaded,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err("figure worker stopped".to_string()),
+        };
+        let Some((page, redacted, _)) = self.figures_loading.take() else { return };
+        let crops = loaded.unwrap_or_else(|e| {
+            eprintln!("Error finding figures on page {}: {}", page, e);
+            Vec::new()
+        });
+        let placed: Vec<_> = crops.into_iter().enumerate()
+            .map(|(i, (figure, image))| {
+                let texture = ctx.load_texture(format!("figure_p{}_{}", figure.page, i), image, egui::TextureOptions::LINEAR);
+                (figure, texture)
+            })
+            .collect();
+        println!("✅ {} figure(s) placed", placed.len());
+        self.figures = Some((redacted, placed));
+    }
+    
+    /// Stroke extracted bounds in blue and current bounds in orange where they differ
+    fn render_bounds_overlay(&self, painter: &egui::Painter) {
+        let original_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(90, 150, 255));
+        let current_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 160, 40));
+        for range in &self.spatial_buffer.element_ranges {
+            painter.rect_stroke(self.spatial_buffer.document_rect_to_screen(range.original_bounds), 0.0, original_stroke);
+            if range.visual_bounds != range.original_bounds {
+                painter.rect_stroke(self.spatial_buffer.document_rect_to_screen(range.visual_bounds), 0.0, current_stroke);
+            }
+        }
+    }
+    
+    /// Number elements in extraction order and link them with arrows; backward jumps stand out
+    fn render_reading_order(&self, painter: &egui::Painter) {
+        let zoom = self.spatial_buffer.transform.zoom;
+        let rects: Vec<egui::Rect> = self.spatial_buffer.element_ranges.iter()
+            .map(|range| self.spatial_buffer.document_rect_to_screen(range.visual_bounds))
+            .collect();
+        
+        for pair in rects.windows(2) {
+            let (from, to) = (pair[0].center(), pair[1].center());
+            // Moving up the page, or left on the same line, is usually a reading-order mistake
+            let backwards = to.y < pair[0].top() || (to.y < pair[0].bottom() && to.x < from.x);
+            let color = if backwards {
+                egui::Color32::from_rgb(255, 120, 60)
+            } else {
+                egui::Color32::from_rgba_unmultiplied(120, 180, 255, 140)
+            };
+            painter.arrow(from, to - from, egui::Stroke::new(if backwards { 2.0 } else { 1.0 }, color));
+        }
+        
+        for (i, rect) in rects.iter().enumerate() {
+            let center = rect.left_top();
+            painter.circle_filled(center, 7.0 * zoom, egui::Color32::from_rgb(40, 90, 170));
+            painter.text(center, egui::Align2::CENTER_CENTER, (i + 1).to_string(),
+                egui::FontId::proportional(8.0 * zoom), egui::Color32::WHITE);
+        }
+    }
+    
+    fn set_element_tag(&mut self, idx: usize, tag: Option<tags::ColorTag>) {
+        self.element_tags.set(idx, tag);
+        self.save_element_tags();
+    }
+    
+    /// Extraction index of a viewed element; inserted text boxes are not re-extracted,
+    /// so what is saved for them has nothing to return to
+    fn extracted_id(&self, idx: usize) -> Option<usize> {
+        let id = self.spatial_buffer.element_ranges.get(idx)?.element_id;
+        self.spatial_elements.get(id).filter(|e| !e.modified).map(|_| id)
+    }
+    
+    /// Persist color tags and labels after either changes or elements are renumbered
+    fn save_element_tags(&self) {
+        let extracted_id = |idx: usize| self.extracted_id(idx);
+        if let Err(e) = self.element_tags.save(&self.page_sidecar_key(), extracted_id) {
+            eprintln!("Error saving tags: {}", e);
+        }
+        if let Err(e) = self.element_labels.save(&self.page_sidecar_key(), extracted_id) {
+            eprintln!("Error saving labels: {}", e);
+        }
+    }
+    
+    /// Digits 1-5 label the selection, 0 clears it
+    fn handle_label_keys(&mut self, ui: &egui::Ui) {
+        const DIGITS: [egui::Key; 6] = [
+            egui::Key::Num0, egui::Key::Num1, egui::Key::Num2,
+            egui::Key::Num3, egui::Key::Num4, egui::Key::Num5,
+        ];
+        let Some(digit) = DIGITS.iter().position(|&key| ui.input(|i| i.key_pressed(key))) else { return };
+        let label = labels::Label::for_hotkey(digit);
+        for &idx in &self.selection_group {
+            self.element_labels.set(idx, label);
+        }
+        self.save_element_tags();
+    }
+    
+    /// Labeled elements filled in their category color with the label name above
+    fn render_label_overlay(&self, painter: &egui::Painter) {
+        let zoom = self.spatial_buffer.transform.zoom;
+        for (idx, label) in self.element_labels.iter() {
+            let Some(range) = self.spatial_buffer.element_ranges.get(idx) else { continue };
+            let rect = self.spatial_buffer.document_rect_to_screen(range.visual_bounds);
+            painter.rect_filled(rect, 2.0, label.color().gamma_multiply(0.3));
+            painter.text(rect.left_top(), egui::Align2::LEFT_BOTTOM, label.label(),
+                egui::FontId::proportional(7.0 * zoom), label.color());
+        }
+    }
+    
+    /// Run the recognizer over the rope and queue its entities as label suggestions
+    fn run_ner(&mut self) {
+        let text = self.spatial_buffer.rope.to_string();
+        match ner::run(&self.ner_command, &text) {
+            Ok(spans) => {
+                self.ner_suggestions = ner::map_to_elements(&spans, &self.spatial_buffer.element_ranges);
+                println!("🧠 {} entity suggestion(s)", self.ner_suggestions.len());
+            }
+            Err(e) => eprintln!("Error running NER: {}", e),
+        }
+    }
+    
+    fn render_ner_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_ner;
+        let mut decide = None; // (suggestion, accept), suggestion None for all
+        let mut run = false;
+        egui::Window::new("🧠 Entities")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Command:");
+                    ui.text_edit_singleline(&mut self.ner_command)
+                        .on_hover_text("Reads text on stdin, prints [{\"start\", \"end\", \"label\"}] in character offsets");
+                    run = ui.button("▶ Run").clicked();
+                });
+                ui.separator();
+                if self.ner_suggestions.is_empty() {
+                    ui.label("No suggestions.");
+                    return;
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("✔ Accept all").clicked() {
+                        decide = Some((None, true));
+                    }
+                    if ui.button("✖ Dismiss all").clicked() {
+                        decide = Some((None, false));
+                    }
+                });
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("ner_grid").striped(true).num_columns(3).show(ui, |ui| {
+                        for (i, suggestion) in self.ner_suggestions.iter().enumerate() {
+                            let text = self.spatial_buffer.element_text(suggestion.element_idx).unwrap_or_default();
+                            ui.label(text);
+                            ui.colored_label(suggestion.label.color(), suggestion.label.label())
+                                .on_hover_text(&suggestion.entity);
+                            ui.horizontal(|ui| {
+                                if ui.small_button("✔").clicked() {
+                                    decide = Some((Some(i), true));
+                                }
+                                if ui.small_button("✖").clicked() {
+                                    decide = Some((Some(i), false));
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.show_ner = open;
+        
+        if run {
+            self.run_ner();
+        }
+        let Some((which, accept)) = decide else { return };
+        let decided: Vec<ner::Suggestion> = match which {
+            Some(i) => vec![self.ner_suggestions.remove(i)],
+            None => std::mem::take(&mut self.ner_suggestions),
+        };
+        if accept {
+            for suggestion in decided {
+                self.element_labels.set(suggestion.element_idx, Some(suggestion.label));
+            }
+            self.save_element_tags();
+        }
+    }
+    
+    /// label + text + bbox for every labeled element, in reading order
+    fn export_labels(&self, path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut entries: Vec<(usize, labels::Label)> = self.element_labels.iter().collect();
+        entries.sort_by_key(|&(idx, _)| idx);
+        // Redacted elements (by label or by box) keep their place but not their words
+        let redacted = self.redacted_rects();
+        let labeled: Vec<labels::LabeledElement> = entries.into_iter()
+            .filter_map(|(idx, label)| {
+                let range = self.spatial_buffer.element_ranges.get(idx)?;
+                let bounds = range.visual_bounds;
+                let text = if redaction::covers(&redacted, bounds) {
+                    redaction::PLACEHOLDER.to_string()
+                } else {
+                    self.spatial_buffer.element_text(idx)?
+                };
+                Some(labels::LabeledElement {
+                    label,
+                    text,
+                    page: self.spatial_elements.get(range.element_id).map_or(1, |e| e.page),
+                    bbox: [bounds.min.x, bounds.min.y, bounds.width(), bounds.height()],
+                })
+            })
+            .collect();
+        labels::write_json(path, &labeled)
+    }
+    
+    /// Outline tagged elements in their tag color; an active filter dims the rest
+    fn render_tag_overlay(&self, painter: &egui::Painter) {
+        for (i, range) in self.spatial_buffer.element_ranges.iter().enumerate() {
+            let rect = self.spatial_buffer.document_rect_to_screen(range.visual_bounds);
+            let tag = self.element_tags.get(i);
+            if !self.tag_filter.is_empty() && !tag.is_some_and(|t| self.tag_filter.contains(&t)) {
+                painter.rect_filled(rect.expand(1.0), 0.0, egui::Color32::from_black_alpha(190));
+            } else if let Some(tag) = tag {
+                painter.rect_filled(rect, 2.0, tag.color().gamma_multiply(0.2));
+                painter.rect_stroke(rect.expand(1.0), 2.0, egui::Stroke::new(1.5, tag.color()));
+            }
+        }
+    }
+    
+    /// Legend: count per tag, with checkboxes to show only chosen tags
+    fn render_tags_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_tags;
+        egui::Window::new("🏷 Tags")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Right-click an element to tag it. Checked tags filter the view.");
+                ui.separator();
+                egui::Grid::new("tag_legend").num_columns(3).show(ui, |ui| {
+                    for tag in tags::ColorTag::ALL {
+                        let mut shown = self.tag_filter.contains(&tag);
+                        if ui.checkbox(&mut shown, "").changed() {
+                            if shown {
+                                self.tag_filter.insert(tag);
+                            } else {
+                                self.tag_filter.remove(&tag);
+                            }
+                        }
+                        ui.colored_label(tag.color(), format!("⏺ {}", tag.label()));
+                        ui.label(self.element_tags.count(tag).to_string());
+                        ui.end_row();
+                    }
+                });
+                if !self.tag_filter.is_empty() && ui.button("Show all").clicked() {
+                    self.tag_filter.clear();
+                }
+            });
+        self.show_tags = open;
+    }
+    
+    /// Tint each element by the text column it belongs to
+    fn render_column_overlay(&self, painter: &egui::Painter) {
+        const COLUMN_COLORS: [egui::Color32; 4] = [
+            egui::Color32::from_rgba_premultiplied(0, 60, 120, 60),
+            egui::Color32::from_rgba_premultiplied(120, 40, 0, 60),
+            egui::Color32::from_rgba_premultiplied(0, 100, 40, 60),
+            egui::Color32::from_rgba_premultiplied(90, 0, 100, 60),
+        ];
+        let elements = self.current_elements();
+        let element_refs: Vec<&SpatialElement> = elements.iter().collect();
+        let columns = layout::detect_columns(&element_refs);
+        for element in &elements {
+            let column = layout::column_of(element, &columns);
+            let bounds = egui::Rect::from_min_size(
+                egui::pos2(element.hpos, element.vpos),
+                egui::vec2(element.width, element.height),
+            );
+            painter.rect_filled(
+                self.spatial_buffer.document_rect_to_screen(bounds),
+                0.0,
+                COLUMN_COLORS[column % COLUMN_COLORS.len()],
+            );
+        }
+    }
+    
+    /// Character grid over the page, with the cells each word is exported into filled in
+    fn render_terminal_grid(&self, painter: &egui::Painter) {
+        let metrics = &self.terminal_metrics;
+        let page = self.page_size();
+        let (cols, rows) = metrics.pdf_to_terminal(page.x, page.y);
+        let stroke = egui::Stroke::new(0.5, self.theme.active().text.gamma_multiply(0.15));
+        for col in 0..=cols + 1 {
+            let x = col as f32 * metrics.cell_width_pts;
+            let top = self.spatial_buffer.transform.to_screen(egui::pos2(x, 0.0));
+            let bottom = self.spatial_buffer.transform.to_screen(egui::pos2(x, page.y));
+            painter.line_segment([top, bottom], stroke);
+        }
+        for row in 0..=rows + 1 {
+            let y = row as f32 * metrics.cell_height_pts;
+            let left = self.spatial_buffer.transform.to_screen(egui::pos2(0.0, y));
+            let right = self.spatial_buffer.transform.to_screen(egui::pos2(page.x, y));
+            painter.line_segment([left, right], stroke);
+        }
+        
+        // Same cell placement as layout_text, before collision shifting
+        let fill = self.theme.active().cursor.gamma_multiply(0.25);
+        for element in self.current_elements() {
+            let (col, row) = metrics.pdf_to_terminal(element.hpos, element.vpos + element.height / 2.0);
+            let width = element.content.trim().chars().map(cjk::cell_width).sum::<usize>() as u16;
+            let cells = metrics.cell_rect(col, row).union(metrics.cell_rect(col + width.max(1) - 1, row));
+            painter.rect_filled(self.spatial_buffer.document_rect_to_screen(cells), 0.0, fill);
+        }
+    }
+    
+    /// Cell size controls and the text the grid produces
+    fn render_terminal_grid_window(&mut self, ctx: &egui::Context) {
+        let before = self.terminal_metrics.clone();
+        let mut open = self.show_terminal_grid;
+        
+        egui::Window::new("▦ Terminal Grid")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Cell");
+                    ui.add(egui::DragValue::new(&mut self.terminal_metrics.cell_width_pts).range(2.0..=40.0).speed(0.1).suffix(" pt wide"));
+                    ui.add(egui::DragValue::new(&mut self.terminal_metrics.cell_height_pts).range(2.0..=60.0).speed(0.1).suffix(" pt tall"));
+                    if ui.button("Reset").clicked() {
+                        self.terminal_metrics = TerminalMetrics::new();
+                    }
+                });
+                let page = self.page_size();
+                let (cols, rows) = self.terminal_metrics.pdf_to_terminal(page.x, page.y);
+                ui.label(format!("Page ≈ {} columns × {} rows", cols + 1, rows + 1));
+                ui.separator();
+                let text = self.terminal_metrics.layout_text(&self.current_elements());
+                egui::ScrollArea::both().max_height(360.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(text).monospace());
+                });
+            });
+        self.show_terminal_grid = open;
+        
+        if self.terminal_metrics != before {
+            if let Err(e) = self.terminal_metrics.save() {
+                eprintln!("Error saving terminal metrics: {}", e);
+            }
+        }
+    }
+    
+    /// Highlight every search match, with the focused match drawn stronger
+    fn render_search_highlights(&self, painter: &egui::Painter) {
+        for (i, &(start, end)) in self.search.matches.iter().enumerate() {
+            let rect = match self.range_screen_rect(start, end) {
+                Some(rect) => rect,
+                None => continue,
+            };
+            let color = if i == self.search.current {
+                egui::Color32::from_rgba_unmultiplied(255, 200, 0, 140)
+            } else {
+                egui::Color32::from_rgba_unmultiplied(255, 255, 0, 60)
+            };
+            painter.rect_filled(rect, 2.0, color);
+        }
+    }
+    
+    /// Page extent in document points, assuming symmetric margins
+    fn page_size(&self) -> egui::Vec2 {
+        Self::elements_page_size(&self.spatial_elements)
+    }
+    
+    /// page_size for any page's elements
+    fn elements_page_size(elements: &[SpatialElement]) -> egui::Vec2 {
+        let right = elements.iter().map(|e| e.hpos + e.width).fold(0.0, f32::max);
+        let bottom = elements.iter().map(|e| e.vpos + e.height).fold(0.0, f32::max);
+        let left = elements.iter().map(|e| e.hpos).fold(right, f32::min);
+        let top = elements.iter().map(|e| e.vpos).fold(bottom, f32::min);
+        egui::vec2(right + left, bottom + top)
+    }
+    
+    /// Fallback for pages whose rotation wasn't detected: turn the view a quarter and re-parse;
+    /// with edits the turn waits for the reload confirmation
+    fn rotate_view(&mut self) {
+        let rotation = self.view_rotation.then(rotation::Rotation::Cw90);
+        if self.edited_element_count() > 0 {
+            self.pending_rotation = Some(rotation);
+            self.confirm_reload = true;
+            return;
+        }
+        self.apply_rotation(rotation);
+    }
+    
+    fn apply_rotation(&mut self, rotation: rotation::Rotation) {
+        if let Err(e) = rotation::save_manual(&self.pdf_path, rotation) {
+            eprintln!("Error saving rotation: {}", e);
+            return;
+        }
+        if let Err(e) = self.load_pdf() {
+            eprintln!("Error loading PDF: {}", e);
+        }
+    }
+    
+    /// Switch zoom preset of the focused pane; the main pane's is remembered for this document
+    fn set_zoom_mode(&mut self, mode: zoom::ZoomMode) {
+        if self.focused_pane == 1 {
+            self.second_pane.zoom_mode = mode;
+            return;
+        }
+        self.zoom_mode = mode;
+        if let Err(e) = mode.save(&self.pdf_path) {
+            eprintln!("Failed to save zoom setting: {}", e);
+        }
+    }
+    
+    fn focused_zoom_mode(&self) -> zoom::ZoomMode {
+        if self.focused_pane == 1 { self.second_pane.zoom_mode } else { self.zoom_mode }
+    }
+    
+    /// Exchange the main view with the second pane's
+    fn swap_pane_view(&mut self) {
+        std::mem::swap(&mut self.zoom_mode, &mut self.second_pane.zoom_mode);
+        std::mem::swap(&mut self.scroll_offset, &mut self.second_pane.scroll_offset);
+        std::mem::swap(&mut self.scroll_animation, &mut self.second_pane.scroll_animation);
+    }
+    
+    /// One scrollable canvas; in split view called per pane with that pane's view swapped in
+    fn render_canvas_pane(&mut self, ui: &mut egui::Ui, pane: usize) {
+        self.rendering_pane = pane;
+        let mut scroll_area = egui::ScrollArea::both()
+            .id_source(("canvas_pane", pane))
+            .auto_shrink([false, false]);  // Allow unlimited scrolling
+        if let Some(animation) = self.scroll_animation {
+            let (offset, landed) = animation.offset_at(ui.input(|i| i.time));
+            scroll_area = scroll_area.scroll_offset(offset);
+            if landed {
+                self.scroll_animation = None;
+            } else {
+                ui.ctx().request_repaint();
+            }
+        }
+        let output = scroll_area.show(ui, |ui| {
+            if !self.spatial_elements.is_empty() {
+                // Always use WYSIWYG spatial editing mode
+                self.render_wysiwyg_readable(ui);
+            } else {
+                ui.label("Click '📁 Load PDF' to display content");
+            }
+        });
+        self.scroll_offset = output.state.offset;
+        self.pane_zoom[pane] = self.spatial_buffer.transform.zoom;
+    }
+    
+    /// Real whitespace dimmed, inferred spacing in the cursor color so it stands out
+    fn invisibles_palette(&self) -> invisibles::Palette {
+        let theme = self.theme.active();
+        invisibles::Palette { text: theme.text, mark: theme.text.gamma_multiply(0.45), inferred: theme.cursor }
+    }
+    
+    /// Element text as painted: vertical CJK stacked one char per line, RTL in visual order,
+    /// whitespace marked in invisibles mode
+    fn display_text(&self, range: &ElementRange, text: &str) -> String {
+        let shown = if self.spatial_buffer.is_vertical(range, text) {
+            cjk::stack_vertically(text)
+        } else {
+            bidi::display(text)
+        };
+        if self.show_invisibles {
+            let separated = range.rope_end < self.spatial_buffer.rope.len_chars();
+            invisibles::show(&shown, separated && text.ends_with(' '))
+        } else {
+            shown
+        }
+    }
+    
+    /// Arrow keys follow screen order, so they move with RTL text instead of against it;
+    /// Shift extends the (logical) selection from its anchor
+    fn move_cursor_visually(&mut self, right: bool, extend: bool) {
+        let from = self.spatial_cursor.rope_pos;
+        let to = self.spatial_buffer.visual_step(from, right);
+        self.spatial_buffer.selection = if extend {
+            let anchor = match self.spatial_buffer.selection {
+                Some((start, end)) if end == from => start,
+                Some((start, end)) if start == from => end,
+                _ => from,
+            };
+            Some((anchor.min(to), anchor.max(to)))
+        } else {
+            None
+        };
+        self.spatial_cursor.rope_pos = to;
+    }
+    
+    /// Element texts with their on-screen rects, for the AccessKit tree
+    fn publish_accessibility(&self, ui: &egui::Ui, response: &egui::Response) {
+        let redacted = self.redacted_rects();
+        // Placeholders change lengths, so each run tracks where it lands in the redacted document
+        let mut shift: isize = 0;
+        let runs: Vec<accessibility::TextRun> = self.spatial_buffer.element_ranges.iter().enumerate()
+            .filter_map(|(idx, range)| {
+                let element_text = self.spatial_buffer.element_text(idx)?;
+                let rope_len = element_text.chars().count();
+                let text = if redaction::covers(&redacted, range.visual_bounds) {
+                    redaction::PLACEHOLDER.to_string()
+                } else {
+                    element_text
+                };
+                let document_start = (range.rope_start as isize + shift) as usize;
+                shift += text.chars().count() as isize - rope_len as isize;
+                Some(accessibility::TextRun {
+                    rect: self.spatial_buffer.document_rect_to_screen(range.visual_bounds),
+                    text,
+                    rope_start: range.rope_start,
+                    rope_len,
+                    document_start,
+                })
+            })
+            .collect();
+        let document = self.redacted_text();
+        accessibility::publish(ui, response, &document, &runs, self.spatial_cursor.rope_pos, self.spatial_buffer.selection);
+    }
+    
+    /// Font used for editable document text
+    fn editor_font(&self) -> egui::FontId {
+        let size = self.font_settings.size;
+        if self.font_settings.family.is_some() && self.editor_family_ready {
+            egui::FontId::new(size, egui::FontFamily::Name(fonts::EDITOR_FAMILY.into()))
+        } else if self.spatial_buffer.proportional {
+            egui::FontId::proportional(size)
+        } else {
+            egui::FontId::monospace(size)
+        }
+    }
+    
+    fn save_font_settings(&mut self) {
+        self.spatial_buffer.glyph_advances.clear();
+        if let Err(e) = self.font_settings.save() {
+            eprintln!("Error saving font settings: {}", e);
+        }
+    }
+    
+    /// Move the spatial cursor and scroll it into view on the next frame
+    fn jump_to_rope_position(&mut self, pos: usize) {
+        self.spatial_cursor.move_to_rope_position(pos, &self.spatial_buffer);
+        self.scroll_to_cursor = true;
+    }
+    
+    /// Jump to the 1-based line number or character offset typed in the Go To dialog;
+    /// lines are the page's visual rows, the rope itself has no line breaks
+    fn goto_target(&mut self) {
+        let value = match self.goto_input.trim().parse::<usize>() {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+        
+        let target = if self.goto_by_line {
+            let rows = self.spatial_buffer.row_starts();
+            let line = value.saturating_sub(1).min(rows.len().saturating_sub(1));
+            rows.get(line).copied().unwrap_or(0)
+        } else {
+            value.min(self.spatial_buffer.rope.len_chars())
+        };
+        
+        self.jump_to_rope_position(target);
+        self.goto_open = false;
+    }
+    
+    fn render_goto_dialog(&mut self, ctx: &egui::Context) {
+        let mut open = self.goto_open;
+        egui::Window::new("Go To")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.goto_by_line, true, "Line");
+                    ui.radio_value(&mut self.goto_by_line, false, "Offset");
+                });
+                
+                let response = ui.text_edit_singleline(&mut self.goto_input);
+                response.request_focus();
+                
+                let submitted = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button("Go").clicked() || submitted {
+                    self.goto_target();
+                }
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    self.goto_open = false;
+                }
+            });
+        self.goto_open = self.goto_open && open;
+    }
+    
+    /// Elements of the viewed page with their current (edited) text and bounds from the spatial buffer
+    fn current_elements(&self) -> Vec<SpatialElement> {
+        Self::edited_elements(&self.spatial_elements, &self.spatial_buffer, &self.element_tags, false)
+    }
+    
+    /// Label/value pairs on the current page, detected on the export elements so redacted values stay out
+    fn page_fields(&self) -> Vec<key_values::KeyValue> {
+        let current = self.page_window.current;
+        let elements: Vec<SpatialElement> = self.parsed_export_elements().into_iter().filter(|e| e.page == current).collect();
+        key_values::detect(&elements)
+    }
+    
+    /// `accepted` leaves out tracked edits still pending, keeping the text they would replace
+    fn edited_elements(elements: &[SpatialElement], buffer: &SpatialTextBuffer, tags: &tags::ElementTags, accepted: bool) -> Vec<SpatialElement> {
+        buffer.element_ranges.iter().enumerate()
+            .filter_map(|(i, range)| {
+                let mut element = elements.get(range.element_id).cloned()?;
+                let pending = range.pending_from.clone().filter(|_| accepted);
+                let (content, modified) = match pending {
+                    Some(before) => {
+                        let modified = before != range.original_text || range.visual_bounds != range.original_bounds;
+                        (before, modified)
+                    }
+                    None => (buffer.element_text(i)?, range.modified),
+                };
+                element.content = content;
+                element.hpos = range.visual_bounds.min.x;
+                element.vpos = range.visual_bounds.min.y;
+                element.width = range.visual_bounds.width();
+                element.height = range.visual_bounds.height();
+                element.modified = modified;
+                element.tag = tags.get(i);
+                Some(element)
+            })
+            .filter(|e| !e.content.is_empty())
+            .collect()
+    }
+    
+    /// Every parsed page in page order: its current elements and redacted rectangles,
+    /// with its reading order and table regions
+    fn document_pages(&self) -> Vec<page_window::DocumentPage<'_>> {
+        self.pages_with(false, false)
+    }
+    
+    /// Every page of the document as exported: pending tracked edits are left out until accepted,
+    /// and pages that aren't parsed are extracted for the export without being kept
+    fn export_pages(&self) -> Vec<page_window::DocumentPage<'_>> {
+        self.pages_with(true, true)
+    }
+    
+    fn pages_with(&self, accepted: bool, every_page: bool) -> Vec<page_window::DocumentPage<'_>> {
+        let mut pages = vec![page_window::DocumentPage {
+            number: self.page_window.current,
+            elements: Self::edited_elements(&self.spatial_elements, &self.spatial_buffer, &self.element_tags, accepted),
+            redacted: self.redacted_rects(),
+            reading_order: Cow::Borrowed(&self.pipeline.reading_order),
+            table_regions: Cow::Borrowed(&self.pipeline.table_regions),
+        }];
+        for (&number, resident) in &self.page_window.resident {
+            let sidecars = &resident.sidecars;
+            let fresh;
+            let buffer = match &resident.buffer {
+                Some(buffer) => buffer,
+                None => {
+                    fresh = Self::page_buffer(&resident.elements, &sidecars.pending_edits);
+                    &fresh
+                }
+            };
+            let elements = Self::edited_elements(&resident.elements, buffer, &sidecars.tags, accepted);
+            let redacted = Self::page_redacted_rects(&sidecars.redactions, &sidecars.labels, buffer);
+            pages.push(page_window::DocumentPage {
+                number,
+                elements,
+                redacted,
+                reading_order: Cow::Borrowed(&sidecars.reading_order),
+                table_regions: Cow::Borrowed(&sidecars.table_regions),
+            });
+        }
+        if every_page {
+            self.for_each_unparsed_page(|number, page| {
+                let buffer = Self::page_buffer(&page.elements, &page.sidecars.pending_edits);
+                let elements = Self::edited_elements(&page.elements, &buffer, &page.sidecars.tags, accepted);
+                let redacted = Self::page_redacted_rects(&page.sidecars.redactions, &page.sidecars.labels, &buffer);
+                pages.push(page_window::DocumentPage {
+                    number,
+                    elements,
+                    redacted,
+                    reading_order: Cow::Owned(page.sidecars.reading_order),
+                    table_regions: Cow::Owned(page.sidecars.table_regions),
+                });
+            });
+        }
+        pages.sort_by_key(|page| page.number);
+        pages
+    }
+    
+    /// Extract each page that is neither viewed nor resident, one at a time, and hand it to `visit`;
+    /// the page is dropped afterwards, so covering the whole document doesn't keep it parsed
+    fn for_each_unparsed_page(&self, mut visit: impl FnMut(usize, page_window::ResidentPage)) {
+        let source = self.page_source();
+        for number in 1..=self.page_window.page_count {
+            if number == self.page_window.current || self.page_window.resident.contains_key(&number) {
+                continue;
+            }
+            match source.extract(number, false) {
+                Ok(parsed) => visit(number, page_window::ResidentPage {
+                    elements: parsed.elements,
+                    buffer: None,
+                    sidecars: page_window::PageSidecars::load(&self.pdf_path, number),
+                }),
+                Err(e) => eprintln!("Error extracting page {}: {}", number, e),
+            }
+        }
+    }
+    
+    /// A page's buffer as first built, with its saved tracked edits put back
+    fn page_buffer(elements: &[SpatialElement], pending_edits: &[track_changes::PendingEdit]) -> SpatialTextBuffer {
+        let mut buffer = Self::spatial_buffer_for(elements);
+        track_changes::restore(&mut buffer, pending_edits);
+        buffer
+    }
+    
+    /// Current elements of every parsed page, in page order
+    fn document_elements(&self) -> Vec<SpatialElement> {
+        self.document_pages().into_iter().flat_map(|page| page.elements).collect()
+    }
+    
+    /// Pipeline over elements from any pages, with each page's table regions and reading order
+    /// (read from the sidecars for pages that aren't parsed)
+    fn run_document_pipeline(&self, elements: Vec<SpatialElement>) -> pipeline::PassContext {
+        let mut regions = self.pipeline.table_regions.clone();
+        let mut order = self.pipeline.reading_order.clone();
+        for resident in self.page_window.resident.values() {
+            regions.extend_from_slice(&resident.sidecars.table_regions);
+            order.extend_from_slice(&resident.sidecars.reading_order);
+        }
+        let unparsed: std::collections::BTreeSet<usize> = elements.iter()
+            .map(|e| e.page)
+            .filter(|page| *page != self.page_window.current && !self.page_window.resident.contains_key(page))
+            .collect();
+        for page in unparsed {
+            let key = page_window::sidecar_key(&self.pdf_path, page);
+            regions.extend(table_regions::load(&key, page));
+            order.extend(reading_order::load(&key));
+        }
+        self.pipeline.run_with(elements, &regions, &order)
+    }
+    
+    /// Every page as exported: accepted text only, copies with redactions removed, headers/footers
+    /// stripped and words dehyphenated when those passes are on, leaving the spatial elements untouched
+    fn export_elements(&self) -> Vec<SpatialElement> {
+        self.export_elements_with(false)
+    }
+    
+    /// Export elements, with redacted ones kept as a placeholder for plain-text output
+    fn export_elements_with(&self, redacted_placeholder: bool) -> Vec<SpatialElement> {
+        self.prepare_export(&self.export_pages(), redacted_placeholder)
+    }
+    
+    /// Export elements of the parsed pages only, for views that are redrawn every frame
+    fn parsed_export_elements(&self) -> Vec<SpatialElement> {
+        self.prepare_export(&self.pages_with(true, false), false)
+    }
+    
+    fn prepare_export(&self, pages: &[page_window::DocumentPage<'_>], redacted_placeholder: bool) -> Vec<SpatialElement> {
+        let mut elements = Vec::new();
+        for page in pages {
+            let mut page_elements = page.elements.clone();
+            page_elements.retain_mut(|e| {
+                let bounds = egui::Rect::from_min_size(egui::pos2(e.hpos, e.vpos), egui::vec2(e.width, e.height));
+                if !redaction::covers(&page.redacted, bounds) {
+                    return true;
+                }
+                e.content = redaction::PLACEHOLDER.to_string();
+                redacted_placeholder
+            });
+            // Raised and lowered text is judged against neighbours on the same page
+            if self.script_encoding != scripts::ScriptEncoding::Plain {
+                let bounds: Vec<egui::Rect> = page_elements.iter()
+                    .map(|e| egui::Rect::from_min_size(egui::pos2(e.hpos, e.vpos), egui::vec2(e.width, e.height)))
+                    .collect();
+                let texts: Vec<&str> = page_elements.iter().map(|e| e.content.as_str()).collect();
+                let positions = scripts::classify(&bounds, &texts);
+                for (element, position) in page_elements.iter_mut().zip(positions) {
+                    element.content = scripts::encode(&element.content, position, self.script_encoding);
+                }
+            }
+            elements.extend(page_elements);
+        }
+        if self.pipeline.is_enabled("Strip headers/footers") {
+            for idx in headers::detect(&elements) {
+                elements[idx].content.clear();
+            }
+        }
+        if self.pipeline.is_enabled("Dehyphenate") {
+            pipeline::dehyphenate(&mut elements, &self.dictionary);
+        }
+        elements.retain(|e| !e.content.is_empty());
+        elements
+    }
+    
+    /// Export elements for the text exporters, without Bates stamps when those are excluded;
+    /// structural exports (ALTO, METS, records) keep them
+    fn without_bates(&self, mut elements: Vec<SpatialElement>) -> Vec<SpatialElement> {
+        if self.exclude_bates {
+            let stamped: std::collections::HashSet<usize> = bates::detect(&elements).into_iter().flat_map(|stamp| stamp.indices).collect();
+            let mut idx = 0;
+            elements.retain(|_| {
+                idx += 1;
+                !stamped.contains(&(idx - 1))
+            });
+        }
+        elements
+    }
+    
+    /// Classify the viewed page's words once per buffer revision
+    fn refresh_script_positions(&mut self) {
+        let revision = self.spatial_buffer.revision;
+        if matches!(&self.script_cache, Some((cached, ..)) if *cached == revision) {
+            return;
+        }
+        let ranges = &self.spatial_buffer.element_ranges;
+        let bounds: Vec<egui::Rect> = ranges.iter().map(|r| r.visual_bounds).collect();
+        let texts: Vec<String> = (0..ranges.len()).map(|i| self.spatial_buffer.element_text(i).unwrap_or_default()).collect();
+        let text_refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let positions = scripts::classify(&bounds, &text_refs);
+        let per_char = positions.iter().any(|&p| p != scripts::ScriptPosition::Normal).then(|| {
+            let mut per_char = vec![scripts::ScriptPosition::Normal; self.spatial_buffer.rope.len_chars()];
+            for (range, &position) in ranges.iter().zip(&positions) {
+                let end = range.rope_end.min(per_char.len());
+                if range.rope_start < end {
+                    per_char[range.rope_start..end].fill(position);
+                }
+            }
+            per_char
+        });
+        self.script_cache = Some((revision, positions, per_char));
+    }
+    
+    fn element_script_position(&self, idx: usize) -> scripts::ScriptPosition {
+        self.script_cache.as_ref()
+            .and_then(|(_, positions, _)| positions.get(idx).copied())
+            .unwrap_or(scripts::ScriptPosition::Normal)
+    }
+    
+    /// Raised/lowered position of every rope char, or None when the page has no scripts
+    fn rope_script_positions(&self) -> Option<&[scripts::ScriptPosition]> {
+        self.script_cache.as_ref().and_then(|(_, _, per_char)| per_char.as_deref())
+    }
+    
+    /// Snapshot the canvas as SVG, over a rendered page image when one sits next to the PDF
+    /// With several pages parsed each gets its own file (`name_p3.svg`); only the viewed
+    /// page carries the on-screen overlays, the others just their redactions
+    fn export_svg(&self, path: &str) -> std::io::Result<Vec<String>> {
+        let page_size = self.page_size();
+        let pages = self.export_pages();
+        let stem = path.strip_suffix(".svg").unwrap_or(path);
+        let mut written = Vec::new();
+        for page in &pages {
+            let background = format!("{}.page{}.png", self.pdf_path, page.number);
+            let background = std::path::Path::new(&background).exists().then_some(background.as_str());
+            let elements: Vec<SpatialElement> = page.elements.iter()
+                .filter(|e| {
+                    let bounds = egui::Rect::from_min_size(egui::pos2(e.hpos, e.vpos), egui::vec2(e.width, e.height));
+                    !redaction::covers(&page.redacted, bounds)
+                })
+                .cloned()
+                .collect();
+            let overlays = if page.number == self.page_window.current {
+                self.view_overlays()
+            } else {
+                page.redacted.iter().copied().map(svg::Overlay::Redaction).collect()
+            };
+            let page_path = if pages.len() == 1 { path.to_string() } else { format!("{}_p{}.svg", stem, page.number) };
+            std::fs::write(&page_path, svg::render(&elements, page_size.x, page_size.y, background, &overlays))?;
+            written.push(page_path);
+        }
+        Ok(written)
+    }
+    
+    /// Tags, labels, search matches and redactions in document coordinates, as on the canvas
+    fn view_overlays(&self) -> Vec<svg::Overlay> {
+        let mut overlays = Vec::new();
+        for (idx, tag) in self.element_tags.iter() {
+            if let Some(range) = self.spatial_buffer.element_ranges.get(idx) {
+                overlays.push(svg::Overlay::Highlight(range.visual_bounds, tag.color().gamma_multiply(0.2)));
+            }
+        }
+        if self.labeling_mode {
+            for (idx, label) in self.element_labels.iter() {
+                if let Some(range) = self.spatial_buffer.element_ranges.get(idx) {
+                    overlays.push(svg::Overlay::Highlight(range.visual_bounds, label.color().gamma_multiply(0.3)));
+                }
+            }
+        }
+        if self.search.open {
+            let transform = self.spatial_buffer.transform;
+            for &(start, end) in &self.search.matches {
+                if let Some(rect) = self.range_screen_rect(start, end) {
+                    let rect = egui::Rect::from_min_max(transform.to_document(rect.min), transform.to_document(rect.max));
+                    overlays.push(svg::Overlay::Highlight(rect, egui::Color32::from_rgba_unmultiplied(255, 255, 0, 60)));
+                }
+            }
+        }
+        overlays.extend(self.redacted_rects().into_iter().map(svg::Overlay::Redaction));
+        overlays
+    }
+    
+    /// Crop a delivered viewport screenshot to the canvas and save it as PNG
+    fn save_canvas_screenshot(&mut self, ctx: &egui::Context) {
+        let screenshot = ctx.input(|i| i.events.iter().find_map(|event| match event {
+            egui::Event::Screenshot { image, .. } => Some(image.clone()),
+            _ => None,
+        }));
+        let Some(image) = screenshot else { return };
+        self.screenshot_requested = false;
+        let canvas = image.region(&self.canvas_rect, Some(ctx.pixels_per_point()));
+        match std::fs::write("chonker9_view.png", png::encode(&canvas)) {
+            Ok(()) => println!("✅ Saved PNG to chonker9_view.png"),
+            Err(e) => eprintln!("Error exporting PNG: {}", e),
+        }
+    }
+    
+    /// Corrected document as PDF, each source page starting a new sheet
+    fn export_print_pdf(&self, path: &str) -> std::io::Result<()> {
+        let elements = self.without_bates(self.export_elements());
+        let mut by_page: std::collections::BTreeMap<usize, Vec<&SpatialElement>> = std::collections::BTreeMap::new();
+        for element in &elements {
+            by_page.entry(element.page).or_default().push(element);
+        }
+        let size = self.page_size();
+        let pages: Vec<print::SourcePage> = by_page.into_values()
+            .map(|page_elements| {
+                let ctx = self.run_document_pipeline(page_elements.iter().map(|&e| e.clone()).collect());
+                print::SourcePage {
+                    size,
+                    paragraphs: pipeline::paragraph_texts(&ctx),
+                    elements: page_elements,
+                }
+            })
+            .collect();
+        std::fs::write(path, print::render_pdf(&pages, self.print_layout))
+    }
+    
+    /// EPUB with a chapter per bookmark, or per top-level heading when there are none
+    fn export_epub(&self, path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let elements = self.without_bates(self.export_elements());
+        let paragraphs = |chapter_elements: Vec<SpatialElement>| {
+            pipeline::paragraph_texts(&self.run_document_pipeline(chapter_elements))
+        };
+        
+        let mut chapters = Vec::new();
+        if self.bookmarks.items.is_empty() {
+            let element_refs: Vec<&SpatialElement> = elements.iter().collect();
+            for section in layout::split_sections(&element_refs) {
+                chapters.push(epub::Chapter {
+                    title: section.heading.clone().unwrap_or_else(|| "Front Matter".to_string()),
+                    paragraphs: paragraphs(section.lines.iter().flatten().map(|&e| e.clone()).collect()),
+                });
+            }
+        } else {
+            // Each bookmark starts a chapter at the (page, vpos) of the element it sits in
+            let key = |e: &SpatialElement| (e.page, e.vpos);
+            let mut starts: Vec<(String, (usize, f32))> = self.bookmarks.items.iter()
+                .filter_map(|b| {
+                    let range = self.spatial_buffer.element_ranges.iter()
+                        .find(|r| b.rope_pos >= r.rope_start && b.rope_pos < r.rope_end)?;
+                    let page = self.spatial_elements.get(range.element_id).map_or(1, |e| e.page);
+                    Some((b.name.clone(), (page, range.visual_bounds.min.y)))
+                })
+                .collect();
+            let last = (usize::MAX, f32::MAX);
+            let first_start = starts.first().map_or(last, |(_, start)| *start);
+            if elements.iter().any(|e| key(e) < first_start) {
+                starts.insert(0, ("Front Matter".to_string(), (0, f32::MIN)));
+            }
+            for (i, (title, start)) in starts.iter().enumerate() {
+                let end = starts.get(i + 1).map_or(last, |(_, end)| *end);
+                let chapter_elements: Vec<SpatialElement> = elements.iter()
+                    .filter(|e| key(e) >= *start && key(e) < end)
+                    .cloned()
+                    .collect();
+                chapters.push(epub::Chapter { title: title.clone(), paragraphs: paragraphs(chapter_elements) });
+            }
+        }
+        
+        epub::write(path, &self.metadata, &self.pdf_path, &chapters)?;
+        Ok(chapters.len())
+    }
+    
+    /// Detected tables to a workbook, one sheet per table
+    fn export_xlsx(&self, path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let model = self.document_model();
+        let mut sheets = Vec::new();
+        for page in &model.pages {
+            for (i, table) in page.tables.iter().enumerate() {
+                let name = if page.tables.len() == 1 {
+                    format!("Page {}", page.number)
+                } else {
+                    format!("Page {} Table {}", page.number, i + 1)
+                };
+                sheets.push(xlsx::Sheet { name, rows: table.rows.clone() });
+            }
+        }
+        xlsx::write(path, &sheets)?;
+        Ok(sheets.len())
+    }
+    
+    /// (name, Markdown pipe table) for every detected table, named like the XLSX sheets
+    fn markdown_tables(&self) -> Vec<(String, String)> {
+        let model = self.document_model();
+        let mut tables = Vec::new();
+        for page in &model.pages {
+            for (i, table) in page.tables.iter().enumerate() {
+                let name = if page.tables.len() == 1 {
+                    format!("Page {}", page.number)
+                } else {
+                    format!("Page {} Table {}", page.number, i + 1)
+                };
+                tables.push((name, markdown::table(&table.rows)));
+            }
+        }
+        tables
+    }
+    
+    /// METS package: mets.xml plus ALTO and a page image per page
+    fn export_mets(&self, dir: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let root = std::path::Path::new(dir);
+        std::fs::create_dir_all(root.join("alto"))?;
+        std::fs::create_dir_all(root.join("images"))?;
+        
+        let elements = self.export_elements();
+        let mut by_page: std::collections::BTreeMap<usize, Vec<SpatialElement>> = std::collections::BTreeMap::new();
+        for element in elements {
+            by_page.entry(element.page).or_default().push(element);
+        }
+        // Images are redacted like the ALTO beside them; a failed lookup must not ship unredacted pages
+        let redacted: std::collections::HashMap<usize, Vec<egui::Rect>> = self.every_page_redaction()?.into_iter().collect();
+        let mut pages = Vec::new();
+        for (number, page_elements) in by_page {
+            let alto = format!("alto/page_{:04}.xml", number);
+            std::fs::write(root.join(&alto), alto::write_with_metadata(&page_elements, Some(&self.metadata)))?;
+            let image = format!("images/page_{:04}.png", number);
+            let rects = redacted.get(&number).map_or(&[][..], Vec::as_slice);
+            let image = match redaction::render_redacted_page(&self.pdf_path, number, rects, 150.0) {
+                Ok(rendered) => {
+                    std::fs::write(root.join(&image), png::encode(&rendered))?;
+                    Some(image)
+                }
+                Err(e) => {
+                    eprintln!("No image for page {}: {}", number, e);
+                    None
+                }
+            };
+            pages.push(mets::PageFiles { number, alto, image });
+        }
+        
+        let title = self.metadata.title_or_file(&self.pdf_path);
+        std::fs::write(root.join("mets.xml"), mets::write(&title, &self.metadata, &pages))?;
+        Ok(pages.len())
+    }
+    
+    /// Open a IIIF manifest on a worker; the current page's canvas becomes the underlay
+    fn load_iiif(&mut self) {
+        let source = self.iiif_source.trim().to_string();
+        let page = self.page_window.current;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let loaded = iiif::load_manifest(&source)
+                .and_then(|canvases| {
+                    let canvas = canvases.get(page.saturating_sub(1)).unwrap_or(&canvases[0]);
+                    let image = iiif::fetch_image(canvas)?;
+                    Ok((canvases, image))
+                })
+                .map_err(|e| e.to_string());
+            // The receiver is gone when the manifest was cleared or reloaded meanwhile
+            let _ = sender.send(loaded);
+        });
+        self.iiif_loading = Some(receiver);
+    }
+    
+    /// Take the worker's manifest and underlay image once they are in
+    fn poll_iiif(&mut self, ctx: &egui::Context) {
+        let Some(receiver) = &self.iiif_loading else { return };
+        let loaded = match receiver.try_recv() {
+            Ok(loaded) => loaded,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err("IIIF worker stopped".to_string()),
+        };
+        self.iiif_loading = None;
+        match loaded {
+            Ok((canvases, image)) => {
+                self.iiif_texture = Some(ctx.load_texture("iiif_canvas", image, egui::TextureOptions::LINEAR));
+                self.iiif_canvases = canvases;
+                self.show_page_image = true;
+            }
+            Err(e) => eprintln!("Error loading IIIF manifest: {}", e),
+        }
+    }
+    
+    /// Corrected elements and comments as Web Annotations, one AnnotationPage per page's canvas
+    fn export_iiif_annotations(&self, path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        if self.iiif_canvases.is_empty() {
+            return Err("no IIIF manifest loaded".into());
+        }
+        // Sizes come from every element of a page, so redacted or stripped ones still count
+        let pages = self.export_pages();
+        let sizes: std::collections::HashMap<usize, egui::Vec2> = pages.iter()
+            .map(|page| (page.number, Self::elements_page_size(&page.elements)))
+            .collect();
+        let mut notes: std::collections::BTreeMap<usize, Vec<iiif::CanvasNote>> = std::collections::BTreeMap::new();
+        for element in self.prepare_export(&pages, false).into_iter().filter(|e| e.modified) {
+            notes.entry(element.page).or_default().push(iiif::CanvasNote {
+                rect: egui::Rect::from_min_size(egui::pos2(element.hpos, element.vpos), egui::vec2(element.width, element.height)),
+                text: element.content,
+                commenting: false,
+            });
+        }
+        for annotation in &self.annotations {
+            notes.entry(annotation.page).or_default()
+                .push(iiif::CanvasNote { rect: annotation.rect(), text: annotation.text.clone(), commenting: true });
+        }
+        
+        let mut pages = Vec::new();
+        let mut count = 0;
+        for (page, page_notes) in &notes {
+            let Some(canvas) = page.checked_sub(1).and_then(|i| self.iiif_canvases.get(i)) else {
+                eprintln!("Skipping {} annotation(s) on page {}: the manifest has {} canvas(es)", page_notes.len(), page, self.iiif_canvases.len());
+                continue;
+            };
+            let size = sizes.get(page).copied().unwrap_or_else(|| self.page_size());
+            pages.push(iiif::annotation_page(canvas, size, page_notes));
+            count += page_notes.len();
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&pages)?)?;
+        Ok(count)
+    }
+    
+    fn render_iiif_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_iiif;
+        let mut load = false;
+        egui::Window::new("🌐 IIIF")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Manifest:");
+                    ui.text_edit_singleline(&mut self.iiif_source).on_hover_text("URL or local path of a IIIF manifest");
+                    load = ui.add_enabled(self.iiif_loading.is_none(), egui::Button::new("Load")).clicked();
+                });
+                if self.iiif_loading.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Fetching manifest…");
+                    });
+                }
+                if let Some(canvas) = self.iiif_canvases.first() {
+                    ui.label(format!("{} canvas(es); underlay: {} ({:.0} × {:.0})",
+                        self.iiif_canvases.len(), canvas.id, canvas.width, canvas.height));
+                    if ui.button("📤 Export Web Annotations").clicked() {
+                        match self.export_iiif_annotations("chonker9_iiif_annotations.json") {
+                            Ok(count) => println!("✅ Exported {} annotation(s) to chonker9_iiif_annotations.json", count),
+                            Err(e) => eprintln!("Error exporting IIIF annotations: {}", e),
+                        }
+                    }
+                    if ui.button("Clear underlay").clicked() {
+                        self.iiif_loading = None;
+                        self.iiif_texture = None;
+                        self.iiif_canvases.clear();
+                    }
+                }
+            });
+        self.show_iiif = open;
+        if load {
+            self.load_iiif();
+        }
+    }
+    
+    /// Write each top-level section to its own file named after its heading
+    fn export_sections(&self, dir: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let elements = self.without_bates(self.export_elements());
+        let element_refs: Vec<&SpatialElement> = elements.iter().collect();
+        let sections = layout::split_sections(&element_refs);
+        
+        std::fs::create_dir_all(dir)?;
+        for (i, section) in sections.iter().enumerate() {
+            let name = match &section.heading {
+                Some(heading) => layout::slugify(heading),
+                None => "front_matter".to_string(),
+            };
+            let section_elements: Vec<&SpatialElement> = section.lines.iter().flatten().copied().collect();
+            let text = self.generate_readable_text_from_elements(&section_elements);
+            let path = std::path::Path::new(dir).join(format!("{:02}_{}.txt", i, name));
+            std::fs::write(path, text)?;
+        }
+        
+        Ok(sections.len())
+    }
+    
+    /// Document model handed to export templates
+    fn document_model(&self) -> export::DocumentModel {
+        let pages = self.export_pages();
+        let elements = self.without_bates(self.prepare_export(&pages, false));
+        let mut model = export::DocumentModel { source: self.pdf_path.clone(), pages: Vec::new() };
+        for page in &pages {
+            let page_elements: Vec<SpatialElement> = elements.iter().filter(|e| e.page == page.number).cloned().collect();
+            // Link annotations are only read for the viewed page
+            let pdf_links = if page.number == self.page_window.current { self.pdf_links.as_deref().unwrap_or(&[]) } else { &[] };
+            model.pages.extend(export::DocumentModel::from_elements(&self.pdf_path, page.number, &page_elements, &page.reading_order,
+                                                                    pdf_links, &page.table_regions).pages);
+        }
+        model
+    }
+    
+    /// XML Debug View - Formatted and Readable
+    fn render_xml_panel(&self, ui: &mut egui::Ui) {
+        ui.heading("🔍 Raw ALTO XML Structure");
+        
+        // Format XML for better readability
+        let formatted_xml = self.format_xml();
+        
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add(egui::TextEdit::multiline(&mut formatted_xml.as_str())
+                .font(egui::TextStyle::Monospace)
+                .text_color(self.theme.active().xml_text)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .desired_rows(40));
+        });
+    }
+    
+    /// Custom palette editor; every change is applied live and saved
+    fn render_theme_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut changed = false;
+        egui::Window::new("🎨 Custom Palette").open(&mut open).show(ctx, |ui| {
+            changed |= ui.checkbox(&mut self.theme.custom.dark, "Dark widgets").changed();
+            changed |= ui.checkbox(&mut self.theme.custom.shape_cues, "Shape cues")
+                .on_hover_text("Underline edits and tables, dash overflow outlines").changed();
+            egui::Grid::new("theme_slots").show(ui, |ui| {
+                for (name, color) in self.theme.custom.slots_mut() {
+                    ui.label(name);
+                    changed |= ui.color_edit_button_srgba(color).changed();
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("⬛ From dark").clicked() {
+                    self.theme.custom = theme::Theme::dark();
+                    changed = true;
+                }
+                if ui.button("⬜ From light").clicked() {
+                    self.theme.custom = theme::Theme::light();
+                    changed = true;
+                }
+                if ui.button("◐ From high contrast").clicked() {
+                    self.theme.custom = theme::Theme::high_contrast();
+                    changed = true;
+                }
+                if ui.button("👁 From color-blind").clicked() {
+                    self.theme.custom = theme::Theme::color_blind();
+                    changed = true;
+                }
+            });
+        });
+        if changed {
+            self.save_theme();
+        }
+        self.show_theme_editor = open;
+    }
+    
+    fn save_theme(&self) {
+        if let Err(e) = self.theme.save() {
+            eprintln!("Error saving theme: {}", e);
+        }
+    }
+    
+    fn save_panel_layout(&self) {
+        if let Err(e) = self.panel_layout.save() {
+            eprintln!("Error saving panel layout: {}", e);
+        }
+    }
+    
+    /// XML panel in a separate viewport; closing the window docks it again
+    fn show_detached_xml(&mut self, ctx: &egui::Context) {
+        let mut builder = egui::ViewportBuilder::default()
+            .with_title("Chonker9 - ALTO XML")
+            .with_inner_size([800.0, 900.0]);
+        if let Some(rect) = self.panel_layout.xml_window {
+            // Reopen where it was last left, which may be another monitor
+            builder = builder.with_position(rect.min).with_inner_size(rect.size());
+        }
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of(("chonker9_xml_panel", &self.pdf_path)),
+            builder,
+            |ctx, class| {
+                if class == egui::ViewportClass::Embedded {
+                    // Backend without multi-window support: fall back to a floating window
+                    egui::Window::new("🔍 ALTO XML").show(ctx, |ui| self.render_xml_panel(ui));
+                } else {
+                    egui::CentralPanel::default().show(ctx, |ui| self.render_xml_panel(ui));
+                }
+                if class != egui::ViewportClass::Embedded {
+                    // Restored with with_position (outer) and with_inner_size, so keep the same pair:
+                    // the frame's corner and the content size, or the window grows by its decorations each time
+                    let (outer, inner) = ctx.input(|i| (i.viewport().outer_rect, i.viewport().inner_rect));
+                    let placed = outer.zip(inner).map(|(outer, inner)| egui::Rect::from_min_size(outer.min, inner.size()));
+                    if let Some(rect) = placed.filter(|rect| Some(*rect) != self.panel_layout.xml_window) {
+                        if !ctx.input(|i| i.pointer.any_down()) {
+                            self.panel_layout.xml_window = Some(rect);
+                            self.save_panel_layout();
+                        }
+                    }
+                }
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    self.xml_detached = false;
+                    self.panel_layout.xml_detached = false;
+                    self.save_panel_layout();
+                }
+            },
+        );
+    }
+    
+    fn format_xml(&self) -> String {
+        // Simple XML formatting for better readability
+        let mut formatted = String::new();
+        let mut indent_level: usize = 0;
+        let lines: Vec<&str> = self.raw_xml.as_deref().unwrap_or_default().lines().collect();
+        
+        for line in lines {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            
+            // Decrease indent for closing tags
+            if trimmed.starts_with("</") {
+                indent_level = indent_level.saturating_sub(1);
+            }
+            
+            // Add indentation
+            formatted.push_str(&"  ".repeat(indent_level));
+            formatted.push_str(trimmed);
+            formatted.push('\n');
+            
+            // Increase indent for opening tags (but not self-closing)
+            if trimmed.starts_with('<') && !trimmed.starts_with("</") && !trimmed.ends_with("/>") && !trimmed.starts_with("<?") {
+                indent_level += 1;
+            }
+        }
+        
+        formatted
+    }
+}
+
+impl eframe::App for ChonkerApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if self.extractor_sampling.is_some() {
+            self.poll_extractor_sampling(false);
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        if self.iiif_loading.is_some() {
+            self.poll_iiif(ctx);
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        // Pending tracked edits are saved as they change, so a review can be finished later
+        if !self.pdf_path.is_empty() && self.saved_edits_revision != self.spatial_buffer.revision {
+            self.saved_edits_revision = self.spatial_buffer.revision;
+            self.save_pending_edits();
+        }
+        if self.comparison_loading.is_some() {
+            self.poll_comparison();
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        if self.backend_comparing.is_some() {
+            self.poll_backend_comparison();
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        if self.links_loading.is_some() {
+            self.poll_pdf_links();
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        if self.figures_loading.is_some() {
+            self.poll_figures(ctx);
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        
+        // Glyph coverage, checked the frame after fonts change (set_fonts applies next frame)
+        // and whenever the text length changes
+        if self.glyph_check_len != Some(self.spatial_buffer.rope.len_chars()) && !self.fonts_dirty {
+            self.glyph_check_len = Some(self.spatial_buffer.rope.len_chars());
+            self.missing_glyphs = fonts::missing_glyphs(ctx, &self.editor_font(), self.spatial_buffer.rope.chars());
+        }
+        
+        // Editor font and fallbacks: installed on startup and whenever the picker changes them
+        if self.fonts_dirty {
+            self.fonts_dirty = false;
+            if self.font_catalog.is_none() && (self.font_settings.family.is_some() || !self.font_settings.fallbacks.is_empty()) {
+                self.font_catalog = Some(fonts::FontCatalog::load_system());
+            }
+            let catalog = self.font_catalog.as_ref();
+            let installed = fonts::install(ctx, catalog, self.font_settings.family.as_deref(), &self.font_settings.fallbacks)
+                .or_else(|e| {
+                    eprintln!("Error loading font: {}", e);
+                    self.font_settings.family = None;
+                    fonts::install(ctx, catalog, None, &self.font_settings.fallbacks)
+                });
+            if let Ok(installed) = installed {
+                println!("✅ Font fallbacks: {}", if installed.is_empty() { "none installed".to_string() } else { installed.join(", ") });
+            }
+            self.spatial_buffer.glyph_advances.clear();
+            self.glyph_check_len = None;
+        }
+        // Watch mode: re-extract when the PDF is rewritten, asking first if edits would be lost
+        if self.watch_pdf {
+            if self.pdf_watch.as_ref().map_or(true, |w| w.path != std::path::Path::new(&self.pdf_path)) {
+                self.pdf_watch = Some(watch::FileWatch::new(&self.pdf_path));
+            }
+            if self.pdf_watch.as_mut().is_some_and(|w| w.poll()) {
+                self.pdf_changed_on_disk();
+            }
+            self.poll_reload_extraction();
+            ctx.request_repaint_after(watch::POLL_INTERVAL);
+        }
+        if self.reload_prompt.is_some() {
+            self.render_reload_prompt(ctx);
+        }
+        if self.confirm_reload {
+            self.render_reload_confirm(ctx);
+        }
+        // Neighbouring pages are parsed on a worker while this one is viewed
+        if !self.pdf_path.is_empty() && self.prefetch_page() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        self.editor_family_ready = ctx.fonts(|f| f.families().contains(&egui::FontFamily::Name(fonts::EDITOR_FAMILY.into())));
+        // Ctrl+=/- sizes the editor text, not the whole UI
+        ctx.options_mut(|o| o.zoom_with_keyboard = false);
+        
+        // Live theme switching: only touch the style when the palette changed
+        let visuals = self.theme.active().visuals();
+        if ctx.style().visuals != visuals {
+            ctx.set_visuals(visuals);
+        }
+        
+        if self.screenshot_requested {
+            self.save_canvas_screenshot(ctx);
+        }
+        
+        // Hot reload with Ctrl+U
+        ctx.input(|i| {
+            if i.key_pressed(egui::Key::U) && i.modifiers.ctrl {
+                // Bootleg hot reload: quit and restart in right quadrant
+                println!("🔄 Hot reloading...");
+                
+                // Use nohup to properly detach the process
+                let spawn_result = std::process::Command::new("nohup")
+                    .arg("/Users/jack/.local/bin/chonker9")
+                    .arg("--right-quadrant")
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .spawn();
+                    
+                match spawn_result {
+                    Ok(_) => {
+                        println!("✅ Hot reload spawned with nohup");
+                        thread::sleep(Duration::from_millis(100));
+                        std::process::exit(0);
+                    }
+                    Err(e) => {
+                        eprintln!("❌ nohup spawn failed: {}, trying direct spawn", e);
+                        // Try direct spawn with detached stdio
+                        if let Ok(_) = std::process::Command::new("/Users/jack/.local/bin/chonker9")
+                            .arg("--right-quadrant")
+                            .stdin(std::process::Stdio::null())
+                            .stdout(std::process::Stdio::null()) 
+                            .stderr(std::process::Stdio::null())
+                            .spawn() {
+                            println!("✅ Direct spawn succeeded");
+                            thread::sleep(Duration::from_millis(100));
+                            std::process::exit(0);
+                        } else {
+                            eprintln!("❌ All spawn methods failed");
+                        }
+                    }
+                }
+            }
+        });
+        // Zoom: Ctrl+Shift+W fit width, Ctrl+Shift+P fit page, Ctrl+Shift+A 100%, Ctrl+Shift+=/- step
+        let zoom_request = ctx.input(|i| {
+            if !(i.modifiers.ctrl && i.modifiers.shift) {
+                None
+            } else if i.key_pressed(egui::Key::W) {
+                Some(zoom::ZoomMode::FitWidth)
+            } else if i.key_pressed(egui::Key::P) {
+                Some(zoom::ZoomMode::FitPage)
+            } else if i.key_pressed(egui::Key::A) {
+                Some(zoom::ZoomMode::Actual)
+            } else if i.key_pressed(egui::Key::Equals) || i.key_pressed(egui::Key::Plus) {
+                Some(zoom::ZoomMode::step(self.pane_zoom[self.focused_pane], true))
+            } else if i.key_pressed(egui::Key::Minus) {
+                Some(zoom::ZoomMode::step(self.pane_zoom[self.focused_pane], false))
+            } else {
+                None
+            }
+        });
+        if let Some(mode) = zoom_request {
+            self.set_zoom_mode(mode);
+        }
+        
+        // Editor text size: Ctrl+= / Ctrl+-, independent of canvas zoom
+        let size_step = ctx.input(|i| {
+            if !i.modifiers.command || i.modifiers.shift {
+                None
+            } else if i.key_pressed(egui::Key::Equals) || i.key_pressed(egui::Key::Plus) {
+                Some(true)
+            } else if i.key_pressed(egui::Key::Minus) {
+                Some(false)
+            } else {
+                None
+            }
+        });
+        if let Some(larger) = size_step {
+            if self.font_settings.step(larger) {
+                self.save_font_settings();
+            }
+        }
+        
+        // Nudge the selected element: Alt+arrows by 1pt, Alt+Shift+arrows by 10pt
+        if let Some(idx) = self.selected_element.filter(|&i| i < self.spatial_buffer.element_ranges.len()) {
+            let nudge = ctx.input_mut(|i| {
+                let mut delta = egui::Vec2::ZERO;
+                // Shifted first: consume_key ignores an extra Shift when matching
+                for (modifiers, step) in [(egui::Modifiers::ALT | egui::Modifiers::SHIFT, 10.0), (egui::Modifiers::ALT, 1.0)] {
+                    if i.consume_key(modifiers, egui::Key::ArrowLeft) { delta.x -= step; }
+                    if i.consume_key(modifiers, egui::Key::ArrowRight) { delta.x += step; }
+                    if i.consume_key(modifiers, egui::Key::ArrowUp) { delta.y -= step; }
+                    if i.consume_key(modifiers, egui::Key::ArrowDown) { delta.y += step; }
+                }
+                delta
+            });
+            if nudge != egui::Vec2::ZERO {
+                let bounds = self.spatial_buffer.element_ranges[idx].visual_bounds.translate(nudge);
+                self.set_element_bounds(ctx, idx, bounds);
+            }
+        }
+        
+        // Merge the Shift+clicked elements with Ctrl+M; Ctrl+Shift+M splits at the cursor
+        let (merge, split) = ctx.input(|i| {
+            let pressed = i.key_pressed(egui::Key::M) && i.modifiers.ctrl;
+            (pressed && !i.modifiers.shift, pressed && i.modifiers.shift)
+        });
+        if merge {
+            self.merge_selected();
+        }
+        if split {
+            self.split_at_cursor();
+        }
+        
+        // Go to line / offset with Ctrl+G
+        if ctx.input(|i| i.key_pressed(egui::Key::G) && i.modifiers.ctrl) {
+            self.goto_open = true;
+            self.goto_input.clear();
+        }
+        if self.goto_open {
+            self.render_goto_dialog(ctx);
+        }
+        
+        // Read-back: F9 speaks the current line, Shift+F9 stops
+        let (speak, stop_speaking) = ctx.input(|i| {
+            let f9 = i.key_pressed(egui::Key::F9);
+            (f9 && !i.modifiers.shift, f9 && i.modifiers.shift)
+        });
+        if speak {
+            self.speak_current_line();
+        }
+        if stop_speaking {
+            speech::stop(&mut self.speech);
+        }
+        
+        // Proofreading: F8 / Shift+F8 jump between low-confidence words
+        let (next_low, previous_low) = ctx.input(|i| {
+            let f8 = i.key_pressed(egui::Key::F8);
+            (f8 && !i.modifiers.shift, f8 && i.modifiers.shift)
+        });
+        if next_low || previous_low {
+            self.jump_to_low_confidence(previous_low);
+        }
+        
+        // Review mode: Tab / Shift+Tab walk elements instead of moving widget focus
+        if self.review_mode {
+            let (forward, backward) = ctx.input_mut(|i| (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+                i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab),
+            ));
+            if forward || backward {
+                self.walk_elements(backward);
+            }
+        }
+        
+        // Incremental search with Ctrl+F
+        if ctx.input(|i| i.key_pressed(egui::Key::F) && i.modifiers.ctrl) {
+            self.search.open = true;
+        }
+        
+        // Bookmarks: Ctrl+F2 drops one, F2 / Shift+F2 cycle through them
+        let (add_bookmark, next_bookmark, previous_bookmark) = ctx.input(|i| {
+            let f2 = i.key_pressed(egui::Key::F2);
+            (f2 && i.modifiers.ctrl, f2 && !i.modifiers.ctrl && !i.modifiers.shift, f2 && i.modifiers.shift)
+        });
+        if add_bookmark {
+            self.add_bookmark_at_cursor();
+        }
+        let cursor = self.spatial_cursor.rope_pos;
+        let bookmark_target = if next_bookmark {
+            self.bookmarks.next_after(cursor).map(|b| b.rope_pos)
+        } else if previous_bookmark {
+            self.bookmarks.previous_before(cursor).map(|b| b.rope_pos)
+        } else {
+            None
+        };
+        if let Some(pos) = bookmark_target {
+            self.jump_to_rope_position(pos);
+        }
+        
+        // Top panel with controls
+        egui::TopBottomPanel::top("controls").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("📁 Load PDF").clicked() {
+                    if self.edited_element_count() > 0 {
+                        self.confirm_reload = true;
+                    } else if let Err(e) = self.load_pdf() {
+                        eprintln!("Error loading PDF: {}", e);
+                    }
+                }
+                ui.checkbox(&mut self.watch_pdf, "👁 Watch")
+                    .on_hover_text("Re-extract when the PDF changes on disk (e.g. re-scanned); asks first if you have edits");
+                
+                if self.page_window.page_count > 1 {
+                    let current = self.page_window.current;
+                    let mut target = current;
+                    if ui.add_enabled(current > 1, egui::Button::new("◀")).clicked() {
+                        target = current - 1;
+                    }
+                    ui.add(egui::DragValue::new(&mut target).range(1..=self.page_window.page_count)
+                        .suffix(format!(" / {}", self.page_window.page_count)));
+                    if ui.add_enabled(current < self.page_window.page_count, egui::Button::new("▶")).clicked() {
+                        target = current + 1;
+                    }
+                    if target != current {
+                        self.show_page(target);
+                    }
+                    if ui.add(egui::DragValue::new(&mut self.page_window.prefetch).range(0..=20).prefix("± "))
+                        .on_hover_text(format!("Pages parsed ahead either side; {} held in memory besides this one", self.page_window.resident.len()))
+                        .changed() {
+                        self.page_window.evict();
+                    }
+                }
+                
+                ui.separator();
+                
+                if ui.checkbox(&mut self.spatial_buffer.proportional, "🔤 Proportional").changed() {
+                    self.spatial_buffer.glyph_advances.clear();
+                }
+                ui.checkbox(&mut self.spatial_buffer.vertical_cjk, "縦 Vertical CJK")
+                    .on_hover_text("Lay out tall CJK text boxes top to bottom");
+                let mut picked = None;
+                egui::ComboBox::from_id_source("editor_font_family")
+                    .selected_text(format!("🅰 {}", self.font_settings.family.as_deref().unwrap_or("Default")))
+                    .height(400.0)
+                    .show_ui(ui, |ui| {
+                        let catalog = self.font_catalog.get_or_insert_with(fonts::FontCatalog::load_system);
+                        if ui.selectable_label(self.font_settings.family.is_none(), "Default").clicked() {
+                            picked = Some(None);
+                        }
+                        for (family, monospaced) in &catalog.families {
+                            let label = if *monospaced { format!("{} (mono)", family) } else { family.clone() };
+                            if ui.selectable_label(self.font_settings.family.as_ref() == Some(family), label).clicked() {
+                                picked = Some(Some(family.clone()));
+                            }
+                        }
+                    });
+                if let Some(family) = picked {
+                    self.font_settings.family = family;
+                    self.fonts_dirty = true;
+                    self.save_font_settings();
+                }
+                let size = ui.add(egui::DragValue::new(&mut self.font_settings.size)
+                    .range(fonts::MIN_SIZE..=fonts::MAX_SIZE).speed(0.25).suffix(" pt"))
+                    .on_hover_text("Editor text size (Ctrl+= / Ctrl+-)");
+                if size.changed() {
+                    self.spatial_buffer.glyph_advances.clear();
+                }
+                // A drag changes the size every frame; write the settings once it ends
+                if size.drag_stopped() || (size.changed() && !size.dragged()) {
+                    self.save_font_settings();
+                }
+                
+                if ui.button("🔖 Bookmarks").clicked() {
+                    self.show_bookmarks = !self.show_bookmarks;
+                }
+                if ui.button("📑 Outline").clicked() {
+                    self.show_outline = !self.show_outline;
+                }
+                if ui.button("ℹ Metadata").clicked() {
+                    self.show_metadata = !self.show_metadata;
+                }
+                if ui.button("🏷 Tags").clicked() {
+                    self.show_tags = !self.show_tags;
+                }
+                if ui.button("🧠 Entities").clicked() {
+                    self.show_ner = !self.show_ner;
+                }
+                if ui.button("🌐 IIIF").clicked() {
+                    self.show_iiif = !self.show_iiif;
+                }
+                if ui.button("📝 Changes").clicked() {
+                    self.show_changes = !self.show_changes;
+                }
+                if ui.button("↕ Order").clicked() {
+                    self.show_reading_order_list = !self.show_reading_order_list;
+                }
+                
+                if ui.button("⚙ Pipeline").clicked() {
+                    self.show_pipeline = !self.show_pipeline;
+                }
+                
+                if ui.button("🤖 Extractors").clicked() {
+                    self.show_extractors = !self.show_extractors;
+                }
+                
+                let mut dehyphenate = self.pipeline.is_enabled("Dehyphenate");
+                if ui.checkbox(&mut dehyphenate, "Dehyphenate").changed() {
+                    self.pipeline.set_active_enabled("Dehyphenate", dehyphenate);
+                    self.show_pipeline_result();
+                }
+                
+                let mut strip_headers = self.pipeline.is_enabled("Strip headers/footers");
+                if ui.checkbox(&mut strip_headers, "Strip headers").changed() {
+                    self.pipeline.set_active_enabled("Strip headers/footers", strip_headers);
+                    self.show_pipeline_result();
+                }
+                egui::ComboBox::from_id_source("script_encoding")
+                    .selected_text(format!("x² {}", self.script_encoding.label()))
+                    .show_ui(ui, |ui| {
+                        for encoding in scripts::ScriptEncoding::ALL {
+                            ui.selectable_value(&mut self.script_encoding, encoding, encoding.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text("How superscripts and subscripts are written in exports");
+                let mut join_pages = self.pipeline.is_enabled("Join across pages");
+                if ui.checkbox(&mut join_pages, "Join pages")
+                    .on_hover_text("Continue paragraphs that run over a page break instead of breaking them").changed() {
+                    self.pipeline.set_active_enabled("Join across pages", join_pages);
+                    // Joining needs the neighbouring pages, so parse them all and show the joined text
+                    if join_pages {
+                        if let Err(e) = self.parse_all_pages() {
+                            eprintln!("Error parsing pages to join: {}", e);
+                        }
+                        self.reflow_mode = true;
+                    }
+                    self.show_pipeline_result();
+                }
+                
+                ui.checkbox(&mut self.spellcheck_enabled, "✔ Spelling");
+                
+                if ui.button("🩹 OCR Fixes").clicked() {
+                    self.show_ocr_fixes = !self.show_ocr_fixes;
+                }
+                
+                if ui.button("🧪 Encoding").clicked() {
+                    self.show_encoding = !self.show_encoding;
+                }
+                if ui.button("📋 Fields").on_hover_text("Label/value pairs on forms; export them as JSON or save the form as a template").clicked() {
+                    self.show_fields = !self.show_fields;
+                }
+                if ui.button("⚖ Bates").on_hover_text("Bates stamps per page; exclude them from exports or export a page map").clicked() {
+                    self.show_bates = !self.show_bates;
+                }
+                if ui.button("⚠ Garbled").on_hover_text("Elements with no vowels, improbable letters, mixed scripts or mostly symbols").clicked() {
+                    self.show_garbled = !self.show_garbled;
+                }
+                if ui.button("🧹 Clean Text").clicked() {
+                    self.show_cleanup = !self.show_cleanup;
+                }
+                if ui.button("🔢 Normalize").on_hover_text("Thousands separators, currency symbols and date formats").clicked() {
+                    self.show_normalize = !self.show_normalize;
+                }
+                if ui.button("🔡 Glyph Map").clicked() {
+                    self.show_glyph_map = !self.show_glyph_map;
+                }
+                if ui.button("📐 Heuristics").clicked() {
+                    self.show_heuristics = !self.show_heuristics;
+                }
+                
+                if ui.button("🔊 Read Line").on_hover_text("F9 / Shift+F9 to stop").clicked() {
+                    self.speak_current_line();
+                }
+                
+                if ui.add_enabled(self.ocr_available, egui::Button::new("🔀 Compare OCR")).clicked() {
+                    match self.compare_text_layer_with_ocr() {
+                        Ok(()) => println!("✅ {} text layer / OCR discrepancies", self.discrepancies.len()),
+                        Err(e) => eprintln!("Error comparing with OCR: {}", e),
+                    }
+                }
+                if !self.discrepancies.is_empty() && ui.small_button("✖ Clear").clicked() {
+                    self.discrepancies.clear();
+                }
+                
+                let previous = self.theme.preset;
+                egui::ComboBox::from_id_source("theme_preset")
+                    .selected_text(format!("🎨 {}", self.theme.preset.label()))
+                    .show_ui(ui, |ui| {
+                        for preset in theme::ThemePreset::ALL {
+                            ui.selectable_value(&mut self.theme.preset, preset, preset.label());
+                        }
+                    });
+                if self.theme.preset != previous {
+                    self.save_theme();
+                }
+                if self.theme.preset == theme::ThemePreset::Custom {
+                    ui.toggle_value(&mut self.show_theme_editor, "✏ Palette");
+                }
+                
+                if ui.button("🔍 XML Debug").clicked() {
+                    self.show_xml_debug = !self.show_xml_debug;
+                    // The raw XML is only kept while it's being looked at
+                    if self.show_xml_debug {
+                        self.capture_raw_xml();
+                    } else {
+                        self.raw_xml = None;
+                    }
+                }
+                
+                
+                if self.show_xml_debug {
+                    ui.label("📋 Debug Mode");
+                    ui.label(self.tile_cache.stats());
+                    let detach_label = if self.xml_detached { "📥 Dock XML" } else { "🗗 Pop Out XML" };
+                    if ui.button(detach_label).clicked() {
+                        self.xml_detached = !self.xml_detached;
+                        self.panel_layout.xml_detached = self.xml_detached;
+                        self.save_panel_layout();
+                    }
+                    if !self.xml_detached {
+                        let hide_label = if self.panel_layout.xml_hidden { "◧ Show XML" } else { "⬜ Hide XML" };
+                        if ui.button(hide_label).on_hover_text("Hide the docked XML panel for a full-width editor").clicked() {
+                            self.panel_layout.xml_hidden = !self.panel_layout.xml_hidden;
+                            self.save_panel_layout();
+                        }
+                    }
+                    if ui.button("💾 Save XML").clicked() {
+                        if let Err(e) = std::fs::write("chonker9_debug.xml", self.raw_xml.as_deref().unwrap_or_default()) {
+                            eprintln!("Error saving XML: {}", e);
+                        }
+                    }
+                } else {
+                    // Unreviewed garbled elements would otherwise go out silently with every export
+                    let garbled = if self.garbled_check { self.garbled_findings().len() } else { 0 };
+                    if garbled > 0 && ui.button(egui::RichText::new(format!("⚠ {} garbled", garbled)).color(egui::Color32::from_rgb(255, 150, 0)))
+                        .on_hover_text("Review suspicious elements before exporting").clicked() {
+                        self.show_garbled = true;
+                    }
+                    if ui.button("💾 Save Text").clicked() {
+                        let ctx = self.run_document_pipeline(self.without_bates(self.export_elements_with(true)));
+                        if let Err(e) = std::fs::write("chonker9_edited.txt", pipeline::render_readable_text(&ctx)) {
+                            eprintln!("Error saving text: {}", e);
+                        }
+                    }
+                    ui.menu_button("🧩 Templates", |ui| {
+                        let templates = export::list_templates();
+                        if templates.is_empty() {
+                            ui.label(format!("No templates in {}/", export::TEMPLATE_DIR));
+                        }
+                        for template in templates {
+                            let name = template.file_name().unwrap_or_default().to_string_lossy().to_string();
+                            if ui.button(&name).clicked() {
+                                match export::render_template(&template, &self.document_model()) {
+                                    Ok(path) => println!("✅ Rendered {} to {}", name, path.display()),
+                                    Err(e) => eprintln!("Error rendering template {}: {}", name, e),
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    if ui.button("📊 Quality Manifest").clicked() {
+                        if let Err(e) = quality::write_manifest("chonker9_manifest.json", &self.pdf_path, &self.page_quality) {
+                            eprintln!("Error writing manifest: {}", e);
+                        }
+                    }
+                    ui.menu_button("🔣 Export Elements", |ui| {
+                        for (label, path, lines) in [("JSON", "chonker9_elements.json", false), ("JSONL", "chonker9_elements.jsonl", true)] {
+                            if ui.button(label).clicked() {
+                                match export::write_element_records(path, &self.export_elements(), lines) {
+                                    Ok(count) => println!("✅ Exported {} elements to {}", count, path),
+                                    Err(e) => eprintln!("Error exporting elements: {}", e),
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    if ui.button("📏 Export Layout Text").clicked() {
+                        let text = self.terminal_metrics.layout_text(&self.without_bates(self.export_elements()));
+                        match std::fs::write("chonker9_layout.txt", text) {
+                            Ok(()) => println!("✅ Saved columnar text to chonker9_layout.txt"),
+                            Err(e) => eprintln!("Error exporting layout text: {}", e),
+                        }
+                    }
+                    if ui.button("📊 Export XLSX").clicked() {
+                        match self.export_xlsx("chonker9_tables.xlsx") {
+                            Ok(count) => println!("✅ Saved {} table(s) to chonker9_tables.xlsx", count),
+                            Err(e) => eprintln!("Error exporting XLSX: {}", e),
+                        }
+                    }
+                    ui.menu_button("📝 Markdown Tables", |ui| {
+                        let tables = self.markdown_tables();
+                        if tables.is_empty() {
+                            ui.label("No tables detected");
+                        }
+                        for (name, table) in &tables {
+                            ui.horizontal(|ui| {
+                                ui.label(name);
+                                if ui.small_button("📋 Copy").clicked() {
+                                    ui.ctx().copy_text(table.clone());
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                        if !tables.is_empty() && ui.button("💾 Save all").clicked() {
+                            let content: Vec<String> = tables.iter().map(|(name, table)| format!("## {}\n\n{}", name, table)).collect();
+                            match std::fs::write("chonker9_tables.md", content.join("\n")) {
+                                Ok(()) => println!("✅ Saved {} table(s) to chonker9_tables.md", tables.len()),
+                                Err(e) => eprintln!("Error exporting Markdown tables: {}", e),
+                            }
+                            ui.close_menu();
+                        }
+                    });
+                    if ui.button("🏛 Export METS").clicked() {
+                        match self.export_mets("chonker9_mets") {
+                            Ok(count) => println!("✅ Saved METS package with {} page(s) to chonker9_mets/", count),
+                            Err(e) => eprintln!("Error exporting METS: {}", e),
+                        }
+                    }
+                    if ui.button("📚 Export EPUB").clicked() {
+                        match self.export_epub("chonker9.epub") {
+                            Ok(count) => println!("✅ Saved chonker9.epub ({} chapters)", count),
+                            Err(e) => eprintln!("Error exporting EPUB: {}", e),
+                        }
+                    }
+                    if ui.button("📝 Export DOCX").clicked() {
+                        let paragraphs = pipeline::paragraph_texts(&self.run_document_pipeline(self.without_bates(self.export_elements())));
+                        match docx::write("chonker9.docx", &self.metadata, &self.pdf_path, &paragraphs) {
+                            Ok(()) => println!("✅ Saved chonker9.docx ({} paragraphs)", paragraphs.len()),
+                            Err(e) => eprintln!("Error exporting DOCX: {}", e),
+                        }
+                    }
+                    if ui.button("📑 Export Sections").clicked() {
+                        match self.export_sections("chonker9_sections") {
+                            Ok(count) => println!("✅ Exported {} sections", count),
+                            Err(e) => eprintln!("Error exporting sections: {}", e),
+                        }
+                    }
+                    if ui.button("📤 Export ALTO").clicked() {
+                        match std::fs::write("chonker9_edited_alto.xml", alto::write_with_metadata(&self.export_elements(), Some(&self.metadata))) {
+                            Ok(()) => println!("✅ Saved ALTO to chonker9_edited_alto.xml"),
+                            Err(e) => eprintln!("Error exporting ALTO: {}", e),
+                        }
+                    }
+                    if ui.button("🔖 Export Labels").clicked() {
+                        match self.export_labels("chonker9_labels.json") {
+                            Ok(count) => println!("✅ Exported {} labeled elements to chonker9_labels.json", count),
+                            Err(e) => eprintln!("Error exporting labels: {}", e),
+                        }
+                    }
+                    if ui.button("⬛ Export Redacted PDF").clicked() {
+                        let burned = self.every_page_redaction()
+                            .and_then(|pages| redaction::burn_pdf(&self.pdf_path, &pages, "chonker9_redacted.pdf"));
+                        match burned {
+                            Ok(()) => println!("✅ Saved {} redacted page(s) to chonker9_redacted.pdf", self.page_window.page_count),
+                            Err(e) => eprintln!("Error exporting redacted PDF: {}", e),
+                        }
+                    }
+                    ui.menu_button("🖨 Print", |ui| {
+                        ui.radio_value(&mut self.print_layout, print::PrintLayout::Readable, "Readable text");
+                        ui.radio_value(&mut self.print_layout, print::PrintLayout::Spatial, "Spatial layout");
+                        ui.separator();
+                        let save = ui.button("💾 Save as PDF").clicked();
+                        let send = ui.button("🖨 Print").clicked();
+                        if save || send {
+                            let result = self.export_print_pdf("chonker9_print.pdf")
+                                .map_err(|e| e.into())
+                                .and_then(|()| if send { print::send_to_printer("chonker9_print.pdf") } else { Ok(()) });
+                            match result {
+                                Ok(()) => println!("✅ {} chonker9_print.pdf", if send { "Printed" } else { "Saved" }),
+                                Err(e) => eprintln!("Error printing: {}", e),
+                            }
+                            ui.close_menu();
+                        }
+                    });
+                    if ui.button("📸 Export PNG").clicked() {
+                        self.screenshot_requested = true;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot);
+                    }
+                    if ui.button("🖼 Export SVG").clicked() {
+                        match self.export_svg("chonker9_view.svg") {
+                            Ok(written) => println!("✅ Saved SVG to {}", written.join(", ")),
+                            Err(e) => eprintln!("Error exporting SVG: {}", e),
+                        }
+                    }
+                }
+            });
+        });
+        
+        if self.search.open {
+            self.render_search_bar(ctx);
+        }
+        if self.show_bookmarks {
+            self.render_bookmarks_panel(ctx);
+        }
+        if self.show_outline {
+            self.render_outline_panel(ctx);
+        }
+        if self.show_metadata {
+            self.render_metadata_window(ctx);
+        }
+        if self.show_thumbnails {
+            self.render_thumbnail_panel(ctx);
+        }
+        if self.show_inspector {
+            self.render_inspector_panel(ctx);
+        }
+        if self.show_changes {
+            self.render_changes_panel(ctx);
+        }
+        self.render_text_box_window(ctx);
+        self.render_annotation_window(ctx);
+        if self.show_tags {
+            self.render_tags_window(ctx);
+        }
+        if self.show_ner {
+            self.render_ner_window(ctx);
+        }
+        if self.show_iiif {
+            self.render_iiif_window(ctx);
+        }
+        if self.show_reading_order_list {
+            self.render_reading_order_window(ctx);
+        }
+        if self.show_pipeline {
+            self.render_pipeline_window(ctx);
+        }
+        if self.show_extractors {
+            self.render_extractor_window(ctx);
+        }
+        if self.show_backend_compare {
+            self.render_backend_compare_window(ctx);
+        }
+        if self.show_ocr_fixes {
+            self.render_ocr_fix_window(ctx);
+        }
+        if self.show_encoding {
+            self.render_encoding_window(ctx);
+        }
+        if self.show_garbled {
+            self.render_garbled_window(ctx);
+        }
+        if self.show_cleanup {
+            self.render_cleanup_window(ctx);
+        }
+        if self.show_normalize {
+            self.render_normalize_window(ctx);
+        }
+        if self.show_table_check {
+            self.render_table_check_window(ctx);
+        }
+        if self.show_zones {
+            self.render_zones_window(ctx);
+        }
+        if self.show_fields {
+            let pairs = self.page_fields();
+            self.render_fields_window(ctx, &pairs);
+        }
+        if self.show_bates {
+            let stamps = bates::detect(&self.document_elements());
+            self.render_bates_window(ctx, &stamps);
+        }
+        if self.show_glyph_map {
+            self.render_glyph_map_window(ctx);
+        }
+        if self.show_heuristics {
+            self.render_heuristics_window(ctx);
+        }
+        if self.show_terminal_grid {
+            self.render_terminal_grid_window(ctx);
+        }
+        if self.show_theme_editor && self.theme.preset == theme::ThemePreset::Custom {
+            self.render_theme_window(ctx);
+        }
+        
+        // Sum/mean/count of numbers in the selection
+        self.render_selection_status(ctx);
+        
+        // Popped-out XML panel gets its own native window
+        if self.show_xml_debug && self.xml_detached {
+            self.show_detached_xml(ctx);
+        }
+        
+        // Docked XML beside the editor; drag the divider to resize
+        if self.show_xml_debug && !self.xml_detached && !self.panel_layout.xml_hidden {
+            let max_width = (ctx.screen_rect().width() - panels::MIN_XML_WIDTH).max(panels::MIN_XML_WIDTH);
+            let response = egui::SidePanel::right("xml_panel")
+                .resizable(true)
+                .default_width(self.panel_layout.xml_width.min(max_width))
+                .width_range(panels::MIN_XML_WIDTH..=max_width)
+                .show(ctx, |ui| self.render_xml_panel(ui))
+                .response;
+            let width = response.rect.width();
+            if (width - self.panel_layout.xml_width).abs() > 0.5 && !ctx.input(|i| i.pointer.any_down()) {
+                self.panel_layout.xml_width = width;
+                self.save_panel_layout();
+            }
+        }
+        
+        // Main content area
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // PDF View with Absolute Coordinates
+            ui.horizontal(|ui| {
+                ui.heading("📄 PDF Content (Absolute Positioning)");
+                ui.separator();
+                if ui.button("📝 Readable Text").clicked() {
+                    // Toggle between absolute and readable view
+                }
+                if !self.missing_glyphs.is_empty() {
+                    let listing: Vec<String> = self.missing_glyphs.iter()
+                        .map(|c| format!("U+{:04X} {}", *c as u32, c))
+                        .collect();
+                    ui.colored_label(self.theme.active().overflow, format!("⚠ {} glyphs missing", self.missing_glyphs.len()))
+                        .on_hover_text(format!("No installed font covers:\n{}\n\nAdd a family to the fallback= line in {}",
+                                               listing.join("\n"), fonts::SETTINGS_FILE));
+                }
+                ui.checkbox(&mut self.reflow_mode, "↩ Reflow");
+                ui.checkbox(&mut self.diff_mode, "⇆ Diff");
+                ui.checkbox(&mut self.compare_mode, "⇔ Compare")
+                    .on_hover_text("Another PDF or a previous export side by side, scrolled together");
+                ui.checkbox(&mut self.labeling_mode, "🔖 Label")
+                    .on_hover_text("1 Name · 2 Amount · 3 Date · 4 Address · 5 Redacted · 0 clear");
+                ui.checkbox(&mut self.spatial_buffer.track_changes, "✍ Track")
+                    .on_hover_text("Record edits as pending changes to accept or reject");
+                ui.checkbox(&mut self.show_columns, "▥ Columns");
+                ui.checkbox(&mut self.show_terminal_grid, "▦ Grid")
+                    .on_hover_text("Preview the character grid used by 📏 Export Layout Text");
+                ui.checkbox(&mut self.show_invisibles, "¶ Invisibles")
+                    .on_hover_text("· space  → tab  ¶ line break  ␣ spacing inferred from a gap");
+                ui.checkbox(&mut self.show_reading_order, "🔢 Order");
+                ui.checkbox(&mut self.show_inspector, "🔬 Inspector");
+                ui.checkbox(&mut self.show_thumbnails, "🗂 Pages");
+                if ui.toggle_value(&mut self.text_box_tool, "➕ Text box")
+                    .on_hover_text("Drag a rectangle to insert a missing word").changed() {
+                    self.redact_tool = false;
+                    self.table_tool = false;
+                    self.zone_tool = false;
+                }
+                if ui.toggle_value(&mut self.redact_tool, "⬛ Redact")
+                    .on_hover_text("Drag a rectangle to redact a region; right-click to remove").changed() {
+                    self.text_box_tool = false;
+                    self.table_tool = false;
+                    self.zone_tool = false;
+                }
+                if ui.toggle_value(&mut self.table_tool, "▦ Table")
+                    .on_hover_text("Drag a rectangle to declare a table; right-click to remove. Drawn tables replace detection").changed() {
+                    self.text_box_tool = false;
+                    self.redact_tool = false;
+                    self.zone_tool = false;
+                }
+                if self.table_tool {
+                    ui.add(egui::DragValue::new(&mut self.table_tool_columns).range(1..=30).suffix(" cols"));
+                }
+                if ui.toggle_value(&mut self.zone_tool, "🗺 Zone")
+                    .on_hover_text("Drag a rectangle to mark a named field; save the zones as a template for the same form").changed() {
+                    self.text_box_tool = false;
+                    self.redact_tool = false;
+                    self.table_tool = false;
+                }
+                if self.zone_tool {
+                    ui.add(egui::TextEdit::singleline(&mut self.zone_tool_name).hint_text("zone name").desired_width(100.0));
+                }
+                if !self.zones.is_empty() {
+                    ui.toggle_value(&mut self.show_zones, "🗺 Fields");
+                }
+                if !self.pipeline.table_regions.is_empty() {
+                    ui.toggle_value(&mut self.show_table_check, "🧮 Check")
+                        .on_hover_text("Validate numeric columns and Total rows of the drawn tables");
+                }
+                ui.checkbox(&mut self.snap.enabled, "🧲 Snap");
+                if self.snap.enabled {
+                    ui.add(egui::DragValue::new(&mut self.snap.grid).range(0.0..=100.0).prefix("grid ").suffix(" pt"))
+                        .on_hover_text("Grid spacing; 0 snaps to neighbouring elements only");
+                }
+                ui.checkbox(&mut self.show_page_image, "🖼 Page");
+                ui.checkbox(&mut self.show_confidence, "🌡 Confidence")
+                    .on_hover_text("Tint OCR words below the threshold; F8 / Shift+F8 jump between them");
+                if self.show_confidence {
+                    ui.add(egui::DragValue::new(&mut self.low_confidence).range(0.0..=1.0).speed(0.01).fixed_decimals(2).prefix("< "));
+                }
+                ui.checkbox(&mut self.show_figures, "🏞 Figures")
+                    .on_hover_text("Draw the PDF's images and graphics at their positions");
+                ui.checkbox(&mut self.show_links, "🔗 Links")
+                    .on_hover_text("Underline URLs, emails and PDF links; Ctrl+click to open");
+                if self.show_page_image {
+                    let budget = ui.add(egui::DragValue::new(&mut self.tile_cache.budget_mb)
+                        .range(16..=4096).suffix(" MB"))
+                        .on_hover_text("Page image cache budget");
+                    if budget.changed() {
+                        self.tile_cache.evict(None);
+                    }
+                }
+                ui.checkbox(&mut self.show_bounds, "▭ Boxes")
+                    .on_hover_text("Blue: extracted bounds, orange: current bounds");
+                ui.separator();
+                if ui.small_button("➖").on_hover_text("Zoom out (Ctrl+Shift+-)").clicked() {
+                    self.set_zoom_mode(zoom::ZoomMode::step(self.pane_zoom[self.focused_pane], false));
+                }
+                egui::ComboBox::from_id_source("zoom_mode")
+                    .selected_text(self.focused_zoom_mode().label())
+                    .show_ui(ui, |ui| {
+                        for mode in [zoom::ZoomMode::FitWidth, zoom::ZoomMode::FitPage, zoom::ZoomMode::Actual] {
+                            if ui.selectable_label(self.focused_zoom_mode() == mode, mode.label()).clicked() {
+                                self.set_zoom_mode(mode);
+                            }
+                        }
+                    });
+                if ui.small_button("➕").on_hover_text("Zoom in (Ctrl+Shift+=)").clicked() {
+                    self.set_zoom_mode(zoom::ZoomMode::step(self.pane_zoom[self.focused_pane], true));
+                }
+                let rotate_hint = format!("Rotate the page 90° clockwise and re-parse (now {}°)", self.view_rotation.degrees());
+                if ui.small_button("↻").on_hover_text(rotate_hint).clicked() {
+                    self.rotate_view();
+                }
+                let split_label = match self.split_view {
+                    None => "Single",
+                    Some(SplitView::SideBySide) => "◫ Side by side",
+                    Some(SplitView::Stacked) => "⬒ Stacked",
+                };
+                egui::ComboBox::from_id_source("split_view")
+                    .selected_text(split_label)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.split_view, None, "Single");
+                        ui.selectable_value(&mut self.split_view, Some(SplitView::SideBySide), "◫ Side by side");
+                        ui.selectable_value(&mut self.split_view, Some(SplitView::Stacked), "⬒ Stacked");
                     });
+                if self.split_view.is_none() {
+                    self.focused_pane = 0;
+                }
+                if ui.checkbox(&mut self.review_mode, "👁 Review").changed() && !self.review_mode {
+                    self.review_element = None;
+                }
+                if self.review_mode {
+                    let mut enabled = self.auto_accept.is_some();
+                    let mut threshold = self.auto_accept.unwrap_or(0.9);
+                    let mut changed = ui.checkbox(&mut enabled, "Auto-accept ≥").changed();
+                    changed |= ui.add_enabled(enabled, egui::DragValue::new(&mut threshold)
+                        .range(0.0..=1.0).speed(0.01).fixed_decimals(2)).changed();
+                    if changed {
+                        self.auto_accept = enabled.then_some(threshold);
+                        self.record_review_audit();
+                    }
+                    let queued = (0..self.spatial_buffer.element_ranges.len())
+                        .filter(|&i| !self.is_auto_accepted(i))
+                        .count();
+                    ui.label(format!("{} queued", queued));
+                }
+                if self.modified {
+                    ui.label("*MODIFIED*");
+                }
+            });
+            
+            if self.diff_mode {
+                self.render_diff_view(ui);
+                return;
+            }
+            
+            if self.compare_mode {
+                self.render_compare_view(ui);
+                return;
+            }
+            
+            if self.reflow_mode {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| self.render_reflow_view(ui));
+                return;
+            }
+            
+            match self.split_view {
+                None => self.render_canvas_pane(ui, 0),
+                Some(split) => {
+                    let rect = ui.available_rect_before_wrap();
+                    let center = rect.center();
+                    let (first, second) = match split {
+                        SplitView::SideBySide => (rect.with_max_x(center.x - 2.0), rect.with_min_x(center.x + 2.0)),
+                        SplitView::Stacked => (rect.with_max_y(center.y - 2.0), rect.with_min_y(center.y + 2.0)),
+                    };
+                    ui.allocate_ui_at_rect(first, |ui| self.render_canvas_pane(ui, 0));
+                    self.swap_pane_view();
+                    ui.allocate_ui_at_rect(second, |ui| self.render_canvas_pane(ui, 1));
+                    self.swap_pane_view();
+                    let focused = if self.focused_pane == 0 { first } else { second };
+                    ui.painter().rect_stroke(focused, 0.0, egui::Stroke::new(1.0, ui.visuals().selection.stroke.color));
+                }
             }
         });
         
@@ -1026,11 +7434,148 @@ impl eframe::App for ChonkerApp {
     }
 }
 
+/// Write one PDF's outputs, returning how many files were written
+fn convert_pdf(input: &batch::BatchInput, options: &batch::BatchOptions, zone_template: Option<&zones::ZoneTemplate>) -> Result<usize, Box<dyn std::error::Error>> {
+    let pdf = input.pdf.as_path();
+    std::fs::create_dir_all(options.output_dir(input))?;
+    let mut app = ChonkerApp::default();
+    app.pdf_path = pdf.to_string_lossy().to_string();
+    app.load_pdf()?;
+    // No UI to poll from, so wait for the OCR sampling before extracting every page
+    app.poll_extractor_sampling(true);
+    app.parse_all_pages()?;
+    let mut written = 0;
+    let mut write = |path: std::path::PathBuf, content: String| -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(&path, content)?;
+        println!("✅ {}", path.display());
+        written += 1;
+        Ok(())
+    };
+    if let Some(template) = zone_template {
+        let path = options.output_dir(input).join(format!("{}_zones.json", pdf.file_stem().map_or("document".into(), |s| s.to_string_lossy())));
+        let elements = app.export_elements();
+        write(path, zones::extract_json(&template.zones, &elements)?)?;
+    }
+    let elements = app.export_elements();
+    let mut pages: std::collections::BTreeMap<usize, Vec<SpatialElement>> = std::collections::BTreeMap::new();
+    for element in &elements {
+        pages.entry(element.page).or_default().push(element.clone());
+    }
+    
+    for &format in &options.formats {
+        match format {
+            batch::Format::Alto if options.per_page => {
+                for (page, page_elements) in &pages {
+                    write(options.output_path(input, Some(*page), format), alto::write_with_metadata(page_elements, Some(&app.metadata)))?;
+                }
+            }
+            batch::Format::Alto => write(options.output_path(input, None, format), alto::write_with_metadata(&elements, Some(&app.metadata)))?,
+            batch::Format::Txt if options.join_pages => {
+                app.pipeline.set_active_enabled("Strip headers/footers", true);
+                app.pipeline.set_active_enabled("Join across pages", true);
+                // One pass over every page so paragraphs can continue across breaks
+                let text = pipeline::render_readable_text(&app.run_document_pipeline(elements.clone()));
+                write(options.output_path(input, None, format), text)?;
+            }
+            batch::Format::Txt => {
+                let page_texts: Vec<(usize, String)> = pages.iter()
+                    .map(|(page, page_elements)| (*page, pipeline::render_readable_text(&app.run_document_pipeline(page_elements.clone()))))
+                    .collect();
+                if options.per_page {
+                    for (page, text) in page_texts {
+                        write(options.output_path(input, Some(page), format), text)?;
+                    }
+                } else {
+                    let text: Vec<&str> = page_texts.iter().map(|(_, text)| text.as_str()).collect();
+                    write(options.output_path(input, None, format), text.join("\n\x0c"))?;
+                }
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Convert PDFs without opening a window, on a pool of worker threads
+fn run_batch(options: &batch::BatchOptions) -> Result<Vec<batch::Outcome>, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(&options.out_dir)?;
+    let zone_template = match &options.zones {
+        Some(name) => Some(zones::ZoneTemplate::load(name).map_err(|e| format!("zone template '{}': {}", name, e))?),
+        None => None,
+    };
+    let pdfs = batch::collect_pdfs(&options.inputs);
+    if pdfs.is_empty() {
+        return Err("no PDFs matched the inputs".into());
+    }
+    let clashes = batch::output_clashes(options, &pdfs);
+    if !clashes.is_empty() {
+        let names: Vec<String> = clashes.iter().map(|path| path.display().to_string()).collect();
+        return Err(format!("several inputs would write {}; convert them separately or pass a glob so folders are kept", names.join(", ")).into());
+    }
+    let mut pool = rayon::ThreadPoolBuilder::new();
+    if let Some(jobs) = options.jobs {
+        pool = pool.num_threads(jobs);
+    }
+    let pool = pool.build()?;
+    println!("📦 Converting {} PDF(s) on {} thread(s)", pdfs.len(), pool.current_num_threads());
+    
+    let outcomes = pool.install(|| {
+        use rayon::prelude::*;
+        pdfs.par_iter()
+            .map(|input| {
+                let started = std::time::Instant::now();
+                let result = convert_pdf(input, options, zone_template.as_ref()).map_err(|e| e.to_string());
+                if let Err(e) = &result {
+                    eprintln!("❌ {}: {}", input.pdf.display(), e);
+                }
+                batch::Outcome { pdf: input.pdf.clone(), result, elapsed: started.elapsed() }
+            })
+            .collect()
+    });
+    Ok(outcomes)
+}
+
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().collect();
+    
+    if args.get(1).is_some_and(|a| a == "cache-clear" || a == "--clear-cache") {
+        match extraction_cache::clear() {
+            Ok(count) => println!("✅ Cleared {} cached page(s) from {}/", count, extraction_cache::CACHE_DIR),
+            Err(e) => {
+                eprintln!("❌ Clearing cache failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    
+    // Headless batch conversion
+    match batch::BatchOptions::from_args(&args) {
+        Some(Ok(options)) => {
+            let started = std::time::Instant::now();
+            match run_batch(&options) {
+                Ok(outcomes) => {
+                    print!("\n{}", batch::summary_table(&outcomes, started.elapsed()));
+                    if outcomes.iter().any(|o| o.result.is_err()) {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ Batch failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Some(Err(e)) => {
+            eprintln!("❌ {}\n{}", e, batch::USAGE);
+            std::process::exit(2);
+        }
+        None => {}
+    }
+    
     println!("🚀 Starting Chonker9...");
     
     // Check for right quadrant positioning argument
-    let args: Vec<String> = std::env::args().collect();
     let right_quadrant = args.contains(&"--right-quadrant".to_string());
     
     let mut app = ChonkerApp::default();
@@ -1083,7 +7628,7 @@ fn main() -> Result<(), eframe::Error> {
         options,
         Box::new(|_cc| {
             println!("✅ Window created");
-            Ok(Box::new(app))
+            Ok(Box::new(tabs::DocumentTabs::new(app)))
         }),
     )
 }
\ No newline at end of file
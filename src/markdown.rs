@@ -0,0 +1,79 @@
+// markdown.rs - GitHub-flavored Markdown pipe tables
+use crate::xlsx;
+
+/// Column alignment inferred from the body cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    fn marker(&self, width: usize) -> String {
+        let dashes = "-".repeat(width.max(3) - 1);
+        match self {
+            Alignment::Left => format!(":{}", dashes),
+            Alignment::Center => format!(":{}:", &dashes[1..]),
+            Alignment::Right => format!("{}:", dashes),
+        }
+    }
+}
+
+/// Numbers right, short flags and codes (✓, N/A, Y) centred, everything else left
+fn infer_alignment(cells: &[&str]) -> Alignment {
+    let filled: Vec<&str> = cells.iter().map(|c| c.trim()).filter(|c| !c.is_empty()).collect();
+    if filled.is_empty() {
+        return Alignment::Left;
+    }
+    let numeric = filled.iter().filter(|c| xlsx::parse_number(c).is_some() || **c == "N/A" || **c == "-").count();
+    if numeric * 2 > filled.len() {
+        Alignment::Right
+    } else if filled.iter().all(|c| c.chars().count() <= 3) {
+        Alignment::Center
+    } else {
+        Alignment::Left
+    }
+}
+
+fn escape(cell: &str) -> String {
+    cell.trim().replace('|', "\\|").replace('\n', " ")
+}
+
+fn pad(cell: &str, width: usize, alignment: Alignment) -> String {
+    let fill = width.saturating_sub(cell.chars().count());
+    match alignment {
+        Alignment::Right => format!("{}{}", " ".repeat(fill), cell),
+        Alignment::Center => format!("{}{}{}", " ".repeat(fill / 2), cell, " ".repeat(fill - fill / 2)),
+        Alignment::Left => format!("{}{}", cell, " ".repeat(fill)),
+    }
+}
+
+/// A pipe table with the first row as header; short rows are padded with empty cells
+pub fn table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+    let cells: Vec<Vec<String>> = rows.iter()
+        .map(|row| (0..columns).map(|c| row.get(c).map_or(String::new(), |cell| escape(cell))).collect())
+        .collect();
+    let alignments: Vec<Alignment> = (0..columns)
+        .map(|c| infer_alignment(&cells[1..].iter().map(|row| row[c].as_str()).collect::<Vec<_>>()))
+        .collect();
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| cells.iter().map(|row| row[c].chars().count()).max().unwrap_or(0).max(3))
+        .collect();
+
+    let line = |row: &[String]| {
+        let padded: Vec<String> = row.iter().enumerate().map(|(c, cell)| pad(cell, widths[c], alignments[c])).collect();
+        format!("| {} |\n", padded.join(" | "))
+    };
+    let mut output = line(&cells[0]);
+    let markers: Vec<String> = alignments.iter().zip(&widths).map(|(a, &w)| a.marker(w)).collect();
+    output.push_str(&format!("| {} |\n", markers.join(" | ")));
+    for row in &cells[1..] {
+        output.push_str(&line(row));
+    }
+    output
+}
@@ -0,0 +1,117 @@
+// metadata.rs - Document Title/Author/Subject/CreationDate from the PDF info dictionary, with user edits
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Descriptive metadata carried into ALTO, METS, EPUB and DOCX exports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentMetadata {
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+    pub creation_date: String,
+    pub alto_source: Vec<(String, String)>,  // Read-only ALTO Description entries (file name, software)
+}
+
+impl DocumentMetadata {
+    /// (pdfinfo / sidecar key, label) of each editable field
+    pub const FIELDS: [(&'static str, &'static str); 4] = [
+        ("Title", "Title"),
+        ("Author", "Author"),
+        ("Subject", "Subject"),
+        ("CreationDate", "Created"),
+    ];
+
+    pub fn field_mut(&mut self, key: &str) -> Option<&mut String> {
+        match key {
+            "Title" => Some(&mut self.title),
+            "Author" => Some(&mut self.author),
+            "Subject" => Some(&mut self.subject),
+            "CreationDate" => Some(&mut self.creation_date),
+            _ => None,
+        }
+    }
+
+    /// PDF info, then the ALTO Description, then the user's edits on top
+    pub fn load(pdf_path: &str, alto_source: Vec<(String, String)>) -> Self {
+        let mut metadata = Self::default();
+        if let Ok(output) = Command::new("pdfinfo").arg(pdf_path).output() {
+            metadata.apply(&String::from_utf8_lossy(&output.stdout), ':');
+        }
+        metadata.alto_source = alto_source;
+        if metadata.title.is_empty() {
+            if let Some((_, file)) = metadata.alto_source.iter().find(|(key, _)| key == "fileName") {
+                metadata.title = file.clone();
+            }
+        }
+        metadata.apply(&std::fs::read_to_string(Self::sidecar_path(pdf_path)).unwrap_or_default(), '=');
+        metadata
+    }
+
+    pub fn save(&self, pdf_path: &str) -> std::io::Result<()> {
+        let content = format!("Title={}\nAuthor={}\nSubject={}\nCreationDate={}\n",
+                              self.title, self.author, self.subject, self.creation_date);
+        std::fs::write(Self::sidecar_path(pdf_path), content)
+    }
+
+    /// Drop the user's edits so the PDF's values show again
+    pub fn clear_edits(pdf_path: &str) -> std::io::Result<()> {
+        match std::fs::remove_file(Self::sidecar_path(pdf_path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Title for exports, falling back to the PDF file name
+    pub fn title_or_file(&self, pdf_path: &str) -> String {
+        if !self.title.trim().is_empty() {
+            return self.title.trim().to_string();
+        }
+        std::path::Path::new(pdf_path)
+            .file_stem()
+            .map_or("Chonker document".to_string(), |stem| stem.to_string_lossy().to_string())
+    }
+
+    /// Non-empty fields as (label, value)
+    pub fn entries(&self) -> Vec<(&'static str, &str)> {
+        [("Title", &self.title), ("Author", &self.author), ("Subject", &self.subject), ("CreationDate", &self.creation_date)]
+            .into_iter()
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(label, value)| (label, value.trim()))
+            .collect()
+    }
+
+    /// Creation date as YYYY-MM-DD for formats that require it; reads ISO dates and pdfinfo's
+    /// `Thu Mar  7 10:12:01 2024 UTC`, None for anything else
+    pub fn iso_creation_date(&self) -> Option<String> {
+        const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+        let date = self.creation_date.trim();
+        let iso = date.get(..10).filter(|d| {
+            d.char_indices().all(|(i, c)| if i == 4 || i == 7 { c == '-' } else { c.is_ascii_digit() })
+        });
+        if let Some(iso) = iso {
+            return Some(iso.to_string());
+        }
+        let parts: Vec<&str> = date.split_whitespace().collect();
+        let month = MONTHS.iter().position(|m| Some(m) == parts.get(1))? + 1;
+        let day: u32 = parts.get(2)?.parse().ok()?;
+        let year: u32 = parts.get(4)?.parse().ok()?;
+        Some(format!("{:04}-{:02}-{:02}", year, month, day))
+    }
+
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-metadata", pdf_path))
+    }
+
+    fn apply(&mut self, content: &str, separator: char) {
+        for line in content.lines() {
+            if let Some((key, value)) = line.split_once(separator) {
+                if let Some(field) = self.field_mut(key.trim()) {
+                    *field = value.trim().to_string();
+                }
+            }
+        }
+    }
+}
+
+/// ALTO Description elements shown as the document's source (fileName, processing software)
+pub const ALTO_SOURCE_FIELDS: [&str; 4] = ["fileName", "softwareName", "softwareVersion", "processingDateTime"];
@@ -0,0 +1,80 @@
+// mets.rs - METS wrapper around per-page ALTO and page images for digital preservation
+use crate::metadata::DocumentMetadata;
+use std::fmt::Write;
+
+/// Files produced for one page, relative to the METS file
+pub struct PageFiles {
+    pub number: usize,
+    pub alto: String,
+    pub image: Option<String>, // PNG, when the page could be rasterized
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// METS document with MODS descriptive metadata, MASTER (image) and FULLTEXT (ALTO) file groups
+/// and a physical structMap
+pub fn write(title: &str, metadata: &DocumentMetadata, pages: &[PageFiles]) -> String {
+    let created = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut mods = format!("<mods:titleInfo><mods:title>{}</mods:title></mods:titleInfo>", escape(title));
+    if !metadata.author.trim().is_empty() {
+        let _ = write!(mods, r#"<mods:name><mods:namePart>{}</mods:namePart><mods:role><mods:roleTerm type="text">author</mods:roleTerm></mods:role></mods:name>"#,
+                       escape(metadata.author.trim()));
+    }
+    if !metadata.subject.trim().is_empty() {
+        let _ = write!(mods, "<mods:subject><mods:topic>{}</mods:topic></mods:subject>", escape(metadata.subject.trim()));
+    }
+    if !metadata.creation_date.trim().is_empty() {
+        let _ = write!(mods, "<mods:originInfo><mods:dateCreated>{}</mods:dateCreated></mods:originInfo>", escape(metadata.creation_date.trim()));
+    }
+    let mut images = String::new();
+    let mut altos = String::new();
+    let mut struct_map = String::new();
+    for page in pages {
+        let n = page.number;
+        if let Some(image) = &page.image {
+            let _ = writeln!(images, r#"      <mets:file ID="IMG_{n:04}" MIMETYPE="image/png"><mets:FLocat LOCTYPE="URL" xlink:href="{}"/></mets:file>"#, escape(image));
+        }
+        let _ = writeln!(altos, r#"      <mets:file ID="ALTO_{n:04}" MIMETYPE="text/xml"><mets:FLocat LOCTYPE="URL" xlink:href="{}"/></mets:file>"#, escape(&page.alto));
+        let _ = writeln!(struct_map, r#"      <mets:div TYPE="page" ID="PHYS_{n:04}" ORDER="{n}">"#);
+        if page.image.is_some() {
+            let _ = writeln!(struct_map, r#"        <mets:fptr FILEID="IMG_{n:04}"/>"#);
+        }
+        let _ = writeln!(struct_map, r#"        <mets:fptr FILEID="ALTO_{n:04}"/>"#);
+        let _ = writeln!(struct_map, "      </mets:div>");
+    }
+    
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<mets:mets xmlns:mets="http://www.loc.gov/METS/" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:mods="http://www.loc.gov/mods/v3" LABEL="{title}">
+  <mets:metsHdr CREATEDATE="{created}">
+    <mets:agent ROLE="CREATOR" TYPE="OTHER" OTHERTYPE="SOFTWARE"><mets:name>Chonker9</mets:name></mets:agent>
+  </mets:metsHdr>
+  <mets:dmdSec ID="DMD_1">
+    <mets:mdWrap MDTYPE="MODS"><mets:xmlData>
+      <mods:mods>{mods}</mods:mods>
+    </mets:xmlData></mets:mdWrap>
+  </mets:dmdSec>
+  <mets:fileSec>
+    <mets:fileGrp USE="MASTER">
+{images}    </mets:fileGrp>
+    <mets:fileGrp USE="FULLTEXT">
+{altos}    </mets:fileGrp>
+  </mets:fileSec>
+  <mets:structMap TYPE="PHYSICAL">
+    <mets:div TYPE="document" DMDID="DMD_1">
+{struct_map}    </mets:div>
+  </mets:structMap>
+</mets:mets>
+"#,
+        title = escape(title),
+        created = crate::epub::iso8601(created),
+    )
+}
@@ -0,0 +1,62 @@
+// mojibake.rs - Encoding damage detection and repair
+/// Windows-1252 characters for bytes 0x80-0x9F (None where the byte is undefined)
+const CP1252_HIGH: [Option<char>; 32] = [
+    Some('€'), None, Some('‚'), Some('ƒ'), Some('„'), Some('…'), Some('†'), Some('‡'),
+    Some('ˆ'), Some('‰'), Some('Š'), Some('‹'), Some('Œ'), None, Some('Ž'), None,
+    None, Some('‘'), Some('’'), Some('“'), Some('”'), Some('•'), Some('–'), Some('—'),
+    Some('˜'), Some('™'), Some('š'), Some('›'), Some('œ'), None, Some('ž'), Some('Ÿ'),
+];
+
+/// Lead characters of UTF-8 sequences misread as Windows-1252 / Latin-1
+const MOJIBAKE_MARKERS: &[char] = &['Ã', 'Â', 'â', 'Ð', 'Ñ', 'Å', 'Ä', 'Î', 'Ï'];
+
+/// What is wrong with an element's text
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodingIssue {
+    Mojibake { repaired: String },  // Re-decoding recovers the intended text
+    CidGarbage,                     // Unmapped glyph IDs, only OCR can recover these
+}
+
+/// Byte a Windows-1252 / Latin-1 character came from
+pub fn cp1252_byte(c: char) -> Option<u8> {
+    let code = c as u32;
+    if code < 0x80 || (0xA0..=0xFF).contains(&code) {
+        return Some(code as u8);
+    }
+    CP1252_HIGH.iter()
+        .position(|&mapped| mapped == Some(c))
+        .map(|i| 0x80 + i as u8)
+}
+
+/// Undo UTF-8 text that was decoded as Windows-1252 ("â€™" → "’")
+pub fn repair_mojibake(text: &str) -> Option<String> {
+    if !text.chars().any(|c| MOJIBAKE_MARKERS.contains(&c)) {
+        return None;
+    }
+    let bytes: Option<Vec<u8>> = text.chars().map(cp1252_byte).collect();
+    let repaired = String::from_utf8(bytes?).ok()?;
+    if repaired != text { Some(repaired) } else { None }
+}
+
+/// "(cid:72)" sequences emitted for glyphs without a ToUnicode mapping
+pub fn has_cid_garbage(text: &str) -> bool {
+    let mut rest = text;
+    while let Some(index) = rest.find("(cid:") {
+        let after = &rest[index + 5..];
+        let digits = after.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 && after[digits..].starts_with(')') {
+            return true;
+        }
+        rest = after;
+    }
+    false
+}
+
+/// Classify an element's text, if it shows encoding damage
+pub fn detect(text: &str) -> Option<EncodingIssue> {
+    if has_cid_garbage(text) {
+        Some(EncodingIssue::CidGarbage)
+    } else {
+        repair_mojibake(text).map(|repaired| EncodingIssue::Mojibake { repaired })
+    }
+}
@@ -0,0 +1,73 @@
+// ner.rs - Named-entity pre-tagging through an external recognizer
+use crate::labels::Label;
+use crate::spatial_text::ElementRange;
+use serde::Deserialize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Recognizer run when none is configured; override with CHONKER_NER_CMD
+pub const DEFAULT_COMMAND: &str = "chonker-ner";
+
+/// Entity span reported by the recognizer, in character offsets into the text it was given
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntitySpan {
+    pub start: usize,
+    pub end: usize,
+    pub label: String, // PERSON, ORG, GPE, DATE, MONEY, ...
+}
+
+/// An element the recognizer thinks should carry a label, awaiting confirmation
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub element_idx: usize,
+    pub label: Label,
+    pub entity: String,
+}
+
+/// Command from the environment, or the default
+pub fn configured_command() -> String {
+    std::env::var("CHONKER_NER_CMD").unwrap_or_else(|_| DEFAULT_COMMAND.to_string())
+}
+
+/// Pipe `text` to the shell command on stdin; it must print a JSON array of `EntitySpan`s
+pub fn run(command: &str, text: &str) -> Result<Vec<EntitySpan>, Box<dyn std::error::Error>> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().ok_or("recognizer has no stdin")?.write_all(text.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!("NER command failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// Our label for a recognizer entity type (spaCy / CoNLL names)
+pub fn to_label(entity: &str) -> Option<Label> {
+    match entity.to_uppercase().as_str() {
+        "PERSON" | "PER" | "ORG" | "NORP" => Some(Label::Name),
+        "LOC" | "GPE" | "FAC" | "ADDRESS" => Some(Label::Address),
+        "DATE" | "TIME" => Some(Label::Date),
+        "MONEY" | "PERCENT" | "QUANTITY" => Some(Label::Amount),
+        _ => None,
+    }
+}
+
+/// Every element overlapped by a span with a known label, first span wins
+pub fn map_to_elements(spans: &[EntitySpan], ranges: &[ElementRange]) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for span in spans {
+        let Some(label) = to_label(&span.label) else { continue };
+        for (idx, range) in ranges.iter().enumerate() {
+            let overlaps = range.rope_start < span.end && range.rope_end > span.start;
+            if overlaps && !suggestions.iter().any(|s| s.element_idx == idx) {
+                suggestions.push(Suggestion { element_idx: idx, label, entity: span.label.clone() });
+            }
+        }
+    }
+    suggestions.sort_by_key(|s| s.element_idx);
+    suggestions
+}
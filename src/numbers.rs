@@ -0,0 +1,291 @@
+// numbers.rs - Number and date parsing/normalization for extracted financial text
+
+/// Currency symbols recognised before or after an amount
+const CURRENCY: &[char] = &['$', '€', '£', '¥', '₹', '₩', '₽', '¢'];
+
+const MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July",
+    "August", "September", "October", "November", "December"];
+
+/// How grouped numbers are rewritten
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberStyle {
+    Keep,
+    Comma,   // 1,234.56
+    Period,  // 1.234,56
+    Plain,   // 1234.56
+}
+
+impl NumberStyle {
+    pub const ALL: [NumberStyle; 4] = [NumberStyle::Keep, NumberStyle::Comma, NumberStyle::Period, NumberStyle::Plain];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NumberStyle::Keep => "keep",
+            NumberStyle::Comma => "1,234.56",
+            NumberStyle::Period => "1.234,56",
+            NumberStyle::Plain => "1234.56",
+        }
+    }
+}
+
+/// How numeric dates are rewritten
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    Keep,
+    Iso,       // 2024-01-31
+    MonthFirst, // 01/31/2024
+    DayFirst,  // 31/01/2024
+    Long,      // 31 January 2024
+}
+
+impl DateStyle {
+    pub const ALL: [DateStyle; 5] = [DateStyle::Keep, DateStyle::Iso, DateStyle::MonthFirst, DateStyle::DayFirst, DateStyle::Long];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateStyle::Keep => "keep",
+            DateStyle::Iso => "YYYY-MM-DD",
+            DateStyle::MonthFirst => "MM/DD/YYYY",
+            DateStyle::DayFirst => "DD/MM/YYYY",
+            DateStyle::Long => "D Month YYYY",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalizeOptions {
+    pub numbers: NumberStyle,
+    pub strip_currency: bool,
+    pub dates: DateStyle,
+    pub day_first_input: bool,  // Read ambiguous 03/04/2024 as 3 April rather than March 4
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self { numbers: NumberStyle::Comma, strip_currency: false, dates: DateStyle::Keep, day_first_input: false }
+    }
+}
+
+/// An amount split into its parts: sign, digits before and after the decimal separator
+#[derive(Debug, Clone, PartialEq)]
+struct Amount {
+    negative: bool,
+    integer: String,
+    fraction: Option<String>,
+    grouped: bool,  // Thousands separators present in the source
+}
+
+/// Digits with `,`/`.` separators; the later of two different separators is the decimal point,
+/// and a lone comma followed by exactly three digits is a thousands separator
+fn parse_amount(core: &str) -> Option<Amount> {
+    let (negative, digits) = match core.strip_prefix('-').or_else(|| core.strip_prefix('−')) {
+        Some(rest) => (true, rest),
+        None => (false, core),
+    };
+    if digits.is_empty() || !digits.chars().next()?.is_ascii_digit()
+        || !digits.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.') {
+        return None;
+    }
+    let last_comma = digits.rfind(',');
+    let last_period = digits.rfind('.');
+    let decimal = match (last_comma, last_period) {
+        (Some(c), Some(p)) => Some(c.max(p)),
+        (Some(c), None) => (digits.matches(',').count() == 1 && digits.len() - c - 1 != 3).then_some(c),
+        (None, Some(p)) => (digits.matches('.').count() == 1).then_some(p),
+        (None, None) => None,
+    };
+    let (integer_part, fraction) = match decimal {
+        Some(at) => (&digits[..at], Some(digits[at + 1..].to_string())),
+        None => (digits, None),
+    };
+    if fraction.as_ref().map_or(false, |f| f.is_empty() || !f.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    // Thousands groups: 1-3 leading digits, then groups of exactly three, one separator kind
+    let separators: Vec<char> = integer_part.chars().filter(|c| !c.is_ascii_digit()).collect();
+    if separators.windows(2).any(|pair| pair[0] != pair[1]) {
+        return None;
+    }
+    let groups: Vec<&str> = integer_part.split(|c| c == ',' || c == '.').collect();
+    if groups[0].is_empty() || (groups.len() > 1 && (groups[0].len() > 3 || groups[1..].iter().any(|g| g.len() != 3))) {
+        return None;
+    }
+    Some(Amount { negative, integer: groups.concat(), fraction, grouped: groups.len() > 1 })
+}
+
+/// Value of a text that reads as an amount in either separator convention:
+/// `1,234.56`, `1.234,56`, `$12`, `12 €`, `(300)`, `-45%`
+pub fn value(text: &str) -> Option<f64> {
+    let trimmed = text.trim();
+    let (bracketed, inner) = match trimmed.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        Some(inner) => (true, inner),
+        None => (false, trimmed),
+    };
+    let (percent, inner) = match inner.strip_suffix('%') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let inner = inner.trim_matches(|c: char| CURRENCY.contains(&c) || c.is_whitespace());
+    let (minus, inner) = match inner.strip_prefix('-').or_else(|| inner.strip_prefix('−')) {
+        Some(rest) => (true, rest.trim_start_matches(|c: char| CURRENCY.contains(&c) || c.is_whitespace())),
+        None => (false, inner),
+    };
+    let amount = parse_amount(inner)?;
+    let number = match &amount.fraction {
+        Some(fraction) => format!("{}.{}", amount.integer, fraction),
+        None => amount.integer.clone(),
+    };
+    let mut value: f64 = number.parse().ok()?;
+    if percent {
+        value /= 100.0;
+    }
+    Some(if bracketed || minus || amount.negative { -value } else { value })
+}
+
+fn format_amount(amount: &Amount, style: NumberStyle) -> String {
+    let (thousands, decimal) = match style {
+        NumberStyle::Period => (".", ","),
+        NumberStyle::Plain => ("", "."),
+        _ => (",", "."),
+    };
+    let mut integer = String::new();
+    for (i, digit) in amount.integer.chars().enumerate() {
+        if i > 0 && (amount.integer.len() - i) % 3 == 0 {
+            integer.push_str(thousands);
+        }
+        integer.push(digit);
+    }
+    let sign = if amount.negative { "-" } else { "" };
+    match &amount.fraction {
+        Some(fraction) => format!("{}{}{}{}", sign, integer, decimal, fraction),
+        None => format!("{}{}", sign, integer),
+    }
+}
+
+/// Count, sum and mean of the numeric values among some texts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub sum: f64,
+    pub mean: f64,
+}
+
+impl Summary {
+    pub fn of<'a>(texts: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let values: Vec<f64> = texts.into_iter().filter_map(value).collect();
+        if values.is_empty() {
+            return None;
+        }
+        let sum: f64 = values.iter().sum();
+        Some(Self { count: values.len(), sum, mean: sum / values.len() as f64 })
+    }
+
+    /// Tab-separated, spreadsheet-friendly
+    pub fn to_clipboard(&self) -> String {
+        format!("sum\t{:.2}\nmean\t{:.2}\ncount\t{}\n", self.sum, self.mean, self.count)
+    }
+}
+
+/// `1,234.56` (two decimals, comma thousands) for display
+pub fn format_value(value: f64) -> String {
+    let text = format!("{:.2}", value.abs());
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, "00"));
+    let amount = Amount { negative: value < 0.0, integer: integer.to_string(), fraction: Some(fraction.to_string()), grouped: false };
+    format_amount(&amount, NumberStyle::Comma)
+}
+
+/// (year, month, day) from 2024-01-31, or d/m/y and m/d/y with `/`, `.` or `-`
+fn parse_date(core: &str, day_first: bool) -> Option<(u32, u32, u32)> {
+    let parts: Vec<&str> = core.split(|c| c == '/' || c == '.' || c == '-').collect();
+    if parts.len() != 3 || parts.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    let numbers: Vec<u32> = parts.iter().filter_map(|p| p.parse().ok()).collect();
+    let (year, month, day) = if parts[0].len() == 4 {
+        (numbers[0], numbers[1], numbers[2])
+    } else if parts[2].len() == 4 || parts[2].len() == 2 {
+        let year = match parts[2].len() {
+            2 if numbers[2] < 70 => 2000 + numbers[2],
+            2 => 1900 + numbers[2],
+            _ => numbers[2],
+        };
+        // A part over 12 can only be the day
+        let day_first = if numbers[0] > 12 { true } else if numbers[1] > 12 { false } else { day_first };
+        if day_first { (year, numbers[1], numbers[0]) } else { (year, numbers[0], numbers[1]) }
+    } else {
+        return None;
+    };
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+fn format_date((year, month, day): (u32, u32, u32), style: DateStyle) -> String {
+    match style {
+        DateStyle::MonthFirst => format!("{:02}/{:02}/{}", month, day, year),
+        DateStyle::DayFirst => format!("{:02}/{:02}/{}", day, month, year),
+        DateStyle::Long => format!("{} {} {}", day, MONTHS[month as usize - 1], year),
+        _ => format!("{}-{:02}-{:02}", year, month, day),
+    }
+}
+
+fn normalize_token(token: &str, options: &NormalizeOptions) -> String {
+    // Keep brackets and trailing punctuation (including a full stop) around the value
+    let start = token.find(|c: char| c != '(' && c != '[').unwrap_or(token.len());
+    let end = token.rfind(|c: char| !",;:.)]".contains(c)).map_or(start, |i| i + token[i..].chars().next().map_or(1, char::len_utf8));
+    if start >= end {
+        return token.to_string();
+    }
+    let (prefix, core, suffix) = (&token[..start], &token[start..end], &token[end..]);
+
+    if options.dates != DateStyle::Keep {
+        if let Some(date) = parse_date(core, options.day_first_input) {
+            return format!("{}{}{}", prefix, format_date(date, options.dates), suffix);
+        }
+    }
+
+    // Currency may sit on either side of the amount, and a minus on either side of the symbol
+    let leading: String = core.chars().take_while(|c| CURRENCY.contains(c) || *c == '-').collect();
+    let trailing: String = core.chars().rev().take_while(|c| CURRENCY.contains(c) || *c == '%').collect::<Vec<_>>().into_iter().rev().collect();
+    if leading.len() + trailing.len() >= core.len() {
+        return token.to_string();
+    }
+    let amount_text = &core[leading.len()..core.len() - trailing.len()];
+    let negative = leading.contains('-');
+    let mut amount = match parse_amount(amount_text) {
+        Some(amount) => amount,
+        None => return token.to_string(),
+    };
+    let has_currency = leading.chars().chain(trailing.chars()).any(|c| CURRENCY.contains(&c));
+    // Plain digit runs (years, IDs) are only touched when they carry a currency symbol
+    if !amount.grouped && amount.fraction.is_none() && !has_currency {
+        return token.to_string();
+    }
+    amount.negative |= negative;
+    let number = match options.numbers {
+        NumberStyle::Keep => amount_text.trim_start_matches(['-', '−']).to_string(),
+        style => format_amount(&Amount { negative: false, ..amount.clone() }, style),
+    };
+    let sign = if amount.negative { "-" } else { "" };
+    let (symbol_before, symbol_after): (String, String) = if options.strip_currency {
+        (String::new(), trailing.chars().filter(|&c| c == '%').collect())
+    } else {
+        (leading.chars().filter(|&c| c != '-').collect(), trailing)
+    };
+    format!("{}{}{}{}{}{}", prefix, sign, symbol_before, number, symbol_after, suffix)
+}
+
+/// Rewrite every number and date token in `text`; whitespace is kept as-is
+pub fn normalize(text: &str, options: &NormalizeOptions) -> String {
+    let mut output = String::new();
+    let mut token = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() {
+            output.push_str(&normalize_token(&token, options));
+            token.clear();
+            output.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    output.push_str(&normalize_token(&token, options));
+    output
+}
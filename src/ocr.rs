@@ -0,0 +1,123 @@
+// ocr.rs - Pluggable OCR engines: page image in, word boxes and confidence out
+use crate::parse_alto;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// One recognized word in image pixels
+#[derive(Debug, Clone, Deserialize)]
+pub struct OcrWord {
+    pub text: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub confidence: Option<f32>, // 0.0-1.0
+}
+
+/// A recognizer; implement this to plug in another engine
+pub trait OcrBackend {
+    fn name(&self) -> String;
+    /// Whether the engine can be reached right now
+    fn is_available(&self) -> bool;
+    fn recognize(&self, image_path: &Path) -> Result<Vec<OcrWord>, Box<dyn std::error::Error>>;
+}
+
+/// Local tesseract producing ALTO
+pub struct TesseractBackend;
+
+impl OcrBackend for TesseractBackend {
+    fn name(&self) -> String {
+        "Tesseract".to_string()
+    }
+    
+    fn is_available(&self) -> bool {
+        Command::new("tesseract").arg("--version").output().is_ok()
+    }
+    
+    fn recognize(&self, image_path: &Path) -> Result<Vec<OcrWord>, Box<dyn std::error::Error>> {
+        let output = Command::new("tesseract")
+            .arg(image_path)
+            .args(["stdout", "alto"])
+            .output()?;
+        if !output.status.success() {
+            return Err("tesseract failed".into());
+        }
+        let elements = parse_alto(&String::from_utf8_lossy(&output.stdout))?;
+        Ok(elements.into_iter()
+            .map(|e| OcrWord {
+                text: e.content,
+                x: e.hpos,
+                y: e.vpos,
+                width: e.width,
+                height: e.height,
+                confidence: e.confidence,
+            })
+            .collect())
+    }
+}
+
+/// Self-hosted service (Kraken, PaddleOCR wrappers, ...): the page PNG is POSTed as
+/// multipart field `image`; the reply is a JSON array of `OcrWord`s in pixels
+pub struct RemoteBackend {
+    pub url: String,
+}
+
+impl OcrBackend for RemoteBackend {
+    fn name(&self) -> String {
+        format!("Remote ({})", self.url)
+    }
+    
+    fn is_available(&self) -> bool {
+        !self.url.is_empty() && Command::new("curl").arg("--version").output().is_ok()
+    }
+    
+    fn recognize(&self, image_path: &Path) -> Result<Vec<OcrWord>, Box<dyn std::error::Error>> {
+        let output = Command::new("curl")
+            .args(["-sS", "--fail", "--max-time", "120", "-F"])
+            .arg(format!("image=@{}", image_path.display()))
+            .arg(&self.url)
+            .output()?;
+        if !output.status.success() {
+            return Err(format!("OCR service failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+        }
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+}
+
+/// Engine chosen for a document
+#[derive(Debug, Clone, PartialEq)]
+pub enum OcrEngine {
+    Tesseract,
+    Remote(String), // Service URL
+}
+
+impl OcrEngine {
+    pub fn backend(&self) -> Box<dyn OcrBackend> {
+        match self {
+            OcrEngine::Tesseract => Box::new(TesseractBackend),
+            OcrEngine::Remote(url) => Box::new(RemoteBackend { url: url.clone() }),
+        }
+    }
+    
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-ocr", pdf_path))
+    }
+    
+    /// Engine saved for a PDF (`tesseract` or `remote<TAB>url`), tesseract by default
+    pub fn load(pdf_path: &str) -> Self {
+        let content = std::fs::read_to_string(Self::sidecar_path(pdf_path)).unwrap_or_default();
+        match content.trim_end().split_once('\t') {
+            Some(("remote", url)) => OcrEngine::Remote(url.to_string()),
+            _ => OcrEngine::Tesseract,
+        }
+    }
+    
+    pub fn save(&self, pdf_path: &str) -> std::io::Result<()> {
+        let content = match self {
+            OcrEngine::Tesseract => "tesseract\n".to_string(),
+            OcrEngine::Remote(url) => format!("remote\t{}\n", url),
+        };
+        std::fs::write(Self::sidecar_path(pdf_path), content)
+    }
+}
@@ -0,0 +1,218 @@
+// ocr_fix.rs - Common OCR confusion patterns and correction suggestions
+use crate::dictionary::Dictionary;
+use ropey::Rope;
+
+/// User rules live here, one `from<TAB>to<TAB>word|number|any` per line
+pub const RULES_FILE: &str = "chonker9_ocr_rules.txt";
+
+/// Which tokens a rule may touch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleContext {
+    Word,    // Mostly letters; fix must produce a known word
+    Number,  // Mostly digits; fix must produce a clean number
+    Any,     // Always applies (e.g. ligature glyphs)
+}
+
+impl RuleContext {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RuleContext::Word => "word",
+            RuleContext::Number => "number",
+            RuleContext::Any => "any",
+        }
+    }
+    
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "word" => Some(RuleContext::Word),
+            "number" => Some(RuleContext::Number),
+            "any" => Some(RuleContext::Any),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CorrectionRule {
+    pub pattern: String,
+    pub replacement: String,
+    pub context: RuleContext,
+}
+
+/// A flagged token with its proposed fix, as a rope char range
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub start: usize,
+    pub end: usize,
+    pub original: String,
+    pub replacement: String,
+    pub rule: String,
+}
+
+fn rule(pattern: &str, replacement: &str, context: RuleContext) -> CorrectionRule {
+    CorrectionRule { pattern: pattern.to_string(), replacement: replacement.to_string(), context }
+}
+
+/// Built-in confusions seen in most OCR engines
+pub fn default_rules() -> Vec<CorrectionRule> {
+    use RuleContext::*;
+    vec![
+        rule("rn", "m", Word),
+        rule("vv", "w", Word),
+        rule("cl", "d", Word),
+        rule("0", "o", Word),
+        rule("1", "l", Word),
+        rule("5", "s", Word),
+        rule("O", "0", Number),
+        rule("o", "0", Number),
+        rule("l", "1", Number),
+        rule("I", "1", Number),
+        rule("S", "5", Number),
+        rule("B", "8", Number),
+        rule("\u{FB01}", "fi", Any),
+        rule("\u{FB02}", "fl", Any),
+        rule("\u{FB00}", "ff", Any),
+    ]
+}
+
+/// Built-in rules followed by user rules from RULES_FILE
+pub fn load_rules() -> Vec<CorrectionRule> {
+    let mut rules = default_rules();
+    if let Ok(content) = std::fs::read_to_string(RULES_FILE) {
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() == 3 && !fields[0].is_empty() {
+                if let Some(context) = RuleContext::from_label(fields[2]) {
+                    rules.push(rule(fields[0], fields[1], context));
+                }
+            }
+        }
+    }
+    rules
+}
+
+/// Persist the rules that are not built in
+pub fn save_user_rules(rules: &[CorrectionRule]) -> std::io::Result<()> {
+    let defaults = default_rules();
+    let content: String = rules.iter()
+        .filter(|r| !defaults.iter().any(|d| d.pattern == r.pattern && d.replacement == r.replacement && d.context == r.context))
+        .map(|r| format!("{}\t{}\t{}\n", r.pattern, r.replacement, r.context.label()))
+        .collect();
+    std::fs::write(RULES_FILE, content)
+}
+
+/// Whitespace-separated tokens with their rope char offsets, punctuation trimmed
+fn tokens(text: &str) -> Vec<(usize, String)> {
+    let mut result = Vec::new();
+    let mut char_pos = 0;
+    let mut current = String::new();
+    let mut current_start = 0;
+    
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !current.is_empty() {
+                result.push((current_start, std::mem::take(&mut current)));
+            }
+        } else {
+            if current.is_empty() {
+                current_start = char_pos;
+            }
+            current.push(c);
+        }
+        char_pos += 1;
+    }
+    if !current.is_empty() {
+        result.push((current_start, current));
+    }
+    
+    result.into_iter()
+        .filter_map(|(start, token)| {
+            let leading = token.chars().take_while(|c| !c.is_alphanumeric()).count();
+            let core: String = token.chars().skip(leading).collect::<String>()
+                .trim_end_matches(|c: char| !c.is_alphanumeric())
+                .to_string();
+            if core.is_empty() { None } else { Some((start + leading, core)) }
+        })
+        .collect()
+}
+
+fn is_known_word(word: &str, dictionary: &Dictionary) -> bool {
+    if dictionary.is_empty() {
+        word.chars().all(|c| c.is_alphabetic())
+    } else {
+        dictionary.contains(word)
+    }
+}
+
+fn is_clean_number(token: &str) -> bool {
+    token.chars().any(|c| c.is_ascii_digit())
+        && token.chars().all(|c| c.is_ascii_digit() || ",.$%-".contains(c))
+}
+
+/// Suggest a fix for one token, if any rule turns it into something valid
+fn suggest_for_token(core: &str, rules: &[CorrectionRule], dictionary: &Dictionary) -> Option<(String, String)> {
+    let digits = core.chars().filter(|c| c.is_ascii_digit()).count();
+    let letters = core.chars().filter(|c| c.is_alphabetic()).count();
+    let number_like = digits > 0 && digits >= letters;
+    
+    for rule in rules {
+        if !core.contains(rule.pattern.as_str()) {
+            continue;
+        }
+        let candidate = core.replace(rule.pattern.as_str(), &rule.replacement);
+        let accepted = match rule.context {
+            RuleContext::Any => true,
+            RuleContext::Word => !number_like && !is_known_word(core, dictionary) && is_known_word(&candidate, dictionary),
+            RuleContext::Number => number_like && !is_clean_number(core) && is_clean_number(&candidate),
+        };
+        if accepted {
+            return Some((candidate, format!("{} → {}", rule.pattern, rule.replacement)));
+        }
+    }
+    None
+}
+
+/// Scan the rope for suspicious tokens and broken ligature splits ("ef ficient")
+pub fn find_suggestions(rope: &Rope, rules: &[CorrectionRule], dictionary: &Dictionary) -> Vec<Suggestion> {
+    let tokens = tokens(&rope.to_string());
+    let mut suggestions = Vec::new();
+    let mut skip_next = false;
+    
+    for (i, (start, core)) in tokens.iter().enumerate() {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        let end = start + core.chars().count();
+        
+        // Ligature breakage: "f" + "i..." split into two tokens
+        if let Some((next_start, next_core)) = tokens.get(i + 1) {
+            let joined = format!("{}{}", core, next_core);
+            if core.ends_with('f') && !dictionary.is_empty()
+                && !dictionary.contains(core) && !dictionary.contains(next_core) && dictionary.contains(&joined)
+            {
+                suggestions.push(Suggestion {
+                    start: *start,
+                    end: next_start + next_core.chars().count(),
+                    original: format!("{} {}", core, next_core),
+                    replacement: joined,
+                    rule: "ligature break".to_string(),
+                });
+                skip_next = true;
+                continue;
+            }
+        }
+        
+        if let Some((replacement, rule)) = suggest_for_token(core, rules, dictionary) {
+            suggestions.push(Suggestion {
+                start: *start,
+                end,
+                original: core.clone(),
+                replacement,
+                rule,
+            });
+        }
+    }
+    
+    suggestions
+}
@@ -0,0 +1,98 @@
+// outline.rs - PDF outline (bookmark tree) via pdfalto's -outline output
+use quick_xml::{escape::unescape, events::Event, Reader};
+use std::process::Command;
+
+/// One outline entry, flattened with its depth
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry {
+    pub title: String,
+    pub level: usize,        // 0 = top level
+    pub page: usize,         // 1-based
+    pub top: Option<f32>,    // Destination y on the page, in points from the top
+}
+
+/// Run pdfalto with -outline on the first page (the outline covers the whole document)
+pub fn extract(pdf_path: &str) -> Result<Vec<OutlineEntry>, Box<dyn std::error::Error>> {
+    let dir = crate::temp_path("chonker9_outline");
+    std::fs::create_dir_all(&dir)?;
+    let output_path = dir.join("outline.xml");
+    let status = Command::new("pdfalto")
+        .args(["-outline", "-noImage", "-f", "1", "-l", "1", pdf_path])
+        .arg(&output_path)
+        .status()?;
+    if !status.success() {
+        return Err("pdfalto failed".into());
+    }
+    let xml = std::fs::read_to_string(dir.join("outline_outline.xml")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&dir);
+    Ok(parse(&xml))
+}
+
+/// `<ITEM level=".."><STRING>title</STRING><LINK page=".." top=".."/></ITEM>` entries
+pub fn parse(xml: &str) -> Vec<OutlineEntry> {
+    let mut reader = Reader::from_str(xml);
+    let mut entries = Vec::new();
+    let mut current: Option<OutlineEntry> = None;
+    let mut in_title = false;
+    let mut depth = 0;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"ITEM" => {
+                    // A nested ITEM means the parent's title and link are complete
+                    if let Some(parent) = current.take() {
+                        entries.push(parent);
+                    }
+                    let level = e.attributes().flatten()
+                        .find(|attr| attr.key.as_ref() == b"level")
+                        .and_then(|attr| String::from_utf8_lossy(&attr.value).parse().ok())
+                        .unwrap_or(depth);
+                    depth += 1;
+                    current = Some(OutlineEntry { title: String::new(), level, page: 1, top: None });
+                }
+                b"STRING" => in_title = true,
+                b"LINK" => {
+                    if let Some(entry) = current.as_mut() {
+                        for attr in e.attributes().flatten() {
+                            let value = String::from_utf8_lossy(&attr.value);
+                            match attr.key.as_ref() {
+                                b"page" => entry.page = value.parse().unwrap_or(1),
+                                b"top" => entry.top = value.parse().ok(),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Text(text)) if in_title => {
+                if let Some(entry) = current.as_mut() {
+                    entry.title.push_str(&String::from_utf8_lossy(&text));
+                }
+            }
+            // Entity and character references (`&amp;`, `&#233;`) arrive as their own events
+            Ok(Event::GeneralRef(reference)) if in_title => {
+                if let Some(entry) = current.as_mut() {
+                    let raw = format!("&{};", String::from_utf8_lossy(&reference));
+                    match unescape(&raw) {
+                        Ok(resolved) => entry.title.push_str(&resolved),
+                        Err(_) => entry.title.push_str(&raw),
+                    }
+                }
+            }
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"STRING" => in_title = false,
+                b"ITEM" => {
+                    depth = depth.saturating_sub(1);
+                    if let Some(entry) = current.take() {
+                        entries.push(entry);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    entries
+}
@@ -0,0 +1,174 @@
+// page_window.rs - Pages parsed on demand around the one being viewed; distant pages are evicted
+use crate::annotations::{self, Annotation};
+use crate::labels::Label;
+use crate::redaction::Redactions;
+use crate::spatial_text::SpatialTextBuffer;
+use crate::table_regions::{self, TableRegion};
+use crate::tags::ElementTags;
+use crate::track_changes::{self, PendingEdit};
+use crate::{reading_order, SpatialElement};
+use eframe::egui;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::Command;
+
+/// Pages either side of the viewed one that are parsed ahead of time
+pub const DEFAULT_PREFETCH: usize = 2;
+
+/// Page count from pdfinfo's "Pages:" line
+pub fn page_count(pdf_path: &str) -> Option<usize> {
+    let output = Command::new("pdfinfo").arg(pdf_path).output().ok()?;
+    String::from_utf8_lossy(&output.stdout).lines()
+        .find_map(|line| line.strip_prefix("Pages:")?.trim().parse().ok())
+        .filter(|&count| count > 0)
+}
+
+/// Sidecar name for a page: page 1 keeps the document's own name so files saved before
+/// pages were loaded separately still apply
+pub fn sidecar_key(pdf_path: &str, page: usize) -> String {
+    match page {
+        1 => pdf_path.to_string(),
+        page => format!("{}#p{}", pdf_path, page),
+    }
+}
+
+/// What is drawn on or attached to one page, each saved in its own sidecar
+#[derive(Default)]
+pub struct PageSidecars {
+    pub tags: ElementTags,
+    pub labels: ElementTags<Label>,
+    pub redactions: Redactions,
+    pub annotations: Vec<Annotation>,
+    pub reading_order: Vec<String>,
+    pub table_regions: Vec<TableRegion>,
+    pub pending_edits: Vec<PendingEdit>, // Restored into the page's buffer when it is built
+}
+
+impl PageSidecars {
+    pub fn load(pdf_path: &str, page: usize) -> Self {
+        let key = sidecar_key(pdf_path, page);
+        PageSidecars {
+            tags: ElementTags::load(&key),
+            labels: ElementTags::load(&key),
+            redactions: Redactions::load(&key),
+            annotations: annotations::load(&key),
+            reading_order: reading_order::load(&key),
+            table_regions: table_regions::load(&key, page),
+            pending_edits: track_changes::load(&key),
+        }
+    }
+}
+
+/// One page as exported: current elements, redacted rectangles and what orders them
+/// (borrowed from a parsed page, owned for one extracted just for the export)
+pub struct DocumentPage<'a> {
+    pub number: usize,
+    pub elements: Vec<SpatialElement>,
+    pub redacted: Vec<egui::Rect>,
+    pub reading_order: Cow<'a, [String]>,
+    pub table_regions: Cow<'a, [TableRegion]>,
+}
+
+/// A parsed page that isn't on screen; the buffer exists once the page has been viewed
+pub struct ResidentPage {
+    pub elements: Vec<SpatialElement>,
+    pub buffer: Option<SpatialTextBuffer>,
+    pub sidecars: PageSidecars,
+}
+
+impl ResidentPage {
+    /// Elements whose text or box was changed; edits only live in the buffer
+    pub fn edited_count(&self) -> usize {
+        self.buffer.as_ref().map_or(0, |buffer| buffer.element_ranges.iter().filter(|range| range.modified).count())
+    }
+}
+
+pub struct PageWindow {
+    pub pdf_path: String,
+    pub pdf_hash: Option<u64>,  // Extraction cache key, hashed once per load rather than per page
+    pub page_count: usize,
+    pub current: usize,
+    pub prefetch: usize,
+    pub resident: BTreeMap<usize, ResidentPage>,  // Parsed pages other than the current one
+    pub failed: BTreeSet<usize>,                  // Prefetch failed; only retried when viewed
+}
+
+impl PageWindow {
+    pub fn new(pdf_path: &str, pdf_hash: Option<u64>, page_count: usize, current: usize) -> Self {
+        let page_count = page_count.max(1);
+        PageWindow {
+            pdf_path: pdf_path.to_string(),
+            pdf_hash,
+            page_count,
+            current: current.clamp(1, page_count),
+            prefetch: DEFAULT_PREFETCH,
+            resident: BTreeMap::new(),
+            failed: BTreeSet::new(),
+        }
+    }
+
+    /// Nearest page in the window that hasn't been parsed yet
+    pub fn next_to_prefetch(&self) -> Option<usize> {
+        (1..=self.prefetch)
+            .flat_map(|distance| [self.current + distance, self.current.saturating_sub(distance)])
+            .find(|&page| page >= 1 && page <= self.page_count && page != self.current
+                && !self.resident.contains_key(&page) && !self.failed.contains(&page))
+    }
+
+    /// Edited elements on parsed pages other than the viewed one
+    pub fn edited_count(&self) -> usize {
+        self.resident.values().map(ResidentPage::edited_count).sum()
+    }
+
+    /// Drop parsed pages outside the window (edited ones stay), returning how many went
+    pub fn evict(&mut self) -> usize {
+        let before = self.resident.len();
+        let (current, prefetch) = (self.current, self.prefetch);
+        self.resident.retain(|&page, resident| page.abs_diff(current) <= prefetch || resident.edited_count() > 0);
+        before - self.resident.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resident(edited: bool) -> ResidentPage {
+        let mut buffer = SpatialTextBuffer::from_alto_elements(&[("word".to_string(), 0.0, 0.0, 40.0, 12.0)]);
+        if edited {
+            buffer.edit_element(0, "ward");
+        }
+        ResidentPage { elements: Vec::new(), buffer: Some(buffer), sidecars: PageSidecars::default() }
+    }
+
+    #[test]
+    fn prefetches_nearest_pages_first() {
+        let mut window = PageWindow::new("doc.pdf", None, 10, 5);
+        let mut order = Vec::new();
+        while let Some(page) = window.next_to_prefetch() {
+            order.push(page);
+            window.resident.insert(page, resident(false));
+        }
+        assert_eq!(order, vec![6, 4, 7, 3]);
+    }
+
+    #[test]
+    fn prefetch_stays_in_the_document_and_skips_failures() {
+        let mut window = PageWindow::new("doc.pdf", None, 3, 1);
+        window.failed.insert(2);
+        assert_eq!(window.next_to_prefetch(), Some(3));
+        window.resident.insert(3, resident(false));
+        assert_eq!(window.next_to_prefetch(), None);
+    }
+
+    #[test]
+    fn evicts_distant_pages_unless_edited() {
+        let mut window = PageWindow::new("doc.pdf", None, 10, 5);
+        for page in [1, 3, 7, 9] {
+            window.resident.insert(page, resident(page == 9));
+        }
+        assert_eq!(window.evict(), 1);
+        assert_eq!(window.resident.keys().copied().collect::<Vec<_>>(), vec![3, 7, 9]);
+        assert_eq!(window.edited_count(), 1);
+    }
+}
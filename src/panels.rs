@@ -0,0 +1,60 @@
+// panels.rs - XML panel width, visibility and pop-out window, remembered across sessions
+use eframe::egui;
+
+/// Layout for every document, one `key=value` per line
+pub const SETTINGS_FILE: &str = "chonker9_panels.txt";
+
+pub const MIN_XML_WIDTH: f32 = 200.0;
+const DEFAULT_XML_WIDTH: f32 = 480.0;
+
+/// Layout of the docked side panels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelLayout {
+    pub xml_width: f32,
+    pub xml_hidden: bool,   // Full-width editing surface even in debug mode
+    pub xml_detached: bool,
+    pub xml_window: Option<egui::Rect>,   // Popped-out window position and content size (e.g. on a second monitor)
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self { xml_width: DEFAULT_XML_WIDTH, xml_hidden: false, xml_detached: false, xml_window: None }
+    }
+}
+
+impl PanelLayout {
+    /// The remembered layout; it belongs to the screen, not to a document
+    pub fn load() -> PanelLayout {
+        let mut layout = PanelLayout::default();
+        let content = std::fs::read_to_string(SETTINGS_FILE).unwrap_or_default();
+        for line in content.lines() {
+            match line.split_once('=') {
+                Some(("xml_width", value)) => {
+                    if let Ok(width) = value.trim().parse::<f32>() {
+                        layout.xml_width = width.max(MIN_XML_WIDTH);
+                    }
+                }
+                Some(("xml_hidden", value)) => layout.xml_hidden = value.trim() == "true",
+                Some(("xml_detached", value)) => layout.xml_detached = value.trim() == "true",
+                Some(("xml_window", value)) => {
+                    let numbers: Vec<f32> = value.split(',').filter_map(|v| v.trim().parse().ok()).collect();
+                    if let [x, y, w, h] = numbers[..] {
+                        layout.xml_window = Some(egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(w, h)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        layout
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut content = format!("xml_width={:.0}\nxml_hidden={}\nxml_detached={}\n",
+                                  self.xml_width, self.xml_hidden, self.xml_detached);
+        if let Some(rect) = self.xml_window {
+            content.push_str(&format!("xml_window={:.0},{:.0},{:.0},{:.0}\n",
+                                      rect.min.x, rect.min.y, rect.width(), rect.height()));
+        }
+        std::fs::write(SETTINGS_FILE, content)
+    }
+}
@@ -0,0 +1,485 @@
+// pipeline.rs - Composable text reconstruction passes
+use crate::dictionary::Dictionary;
+use crate::heuristics::Heuristics;
+use crate::table_regions::{self, TableRegion};
+use crate::{headers, layout, reading_order, SpatialElement};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Working state handed from pass to pass
+#[derive(Debug, Clone, Default)]
+pub struct PassContext {
+    pub elements: Vec<SpatialElement>,
+    pub lines: Vec<Vec<usize>>,       // Element indices per line, left to right
+    pub paragraphs: Vec<Vec<usize>>,  // Line indices per paragraph
+    pub table_elements: Vec<usize>,   // Element indices detected as table cells
+    pub columns: Vec<(f32, f32)>,     // Column x-ranges, left to right
+    pub heuristics: Heuristics,
+    pub table_regions: Vec<TableRegion>,
+}
+
+impl PassContext {
+    pub fn new(elements: Vec<SpatialElement>) -> Self {
+        Self { elements, ..Default::default() }
+    }
+}
+
+/// A single reconstruction step; implement this to insert custom passes
+pub trait ReconstructionPass {
+    fn name(&self) -> &str;
+    fn run(&self, ctx: &mut PassContext);
+}
+
+/// Join words split with a trailing hyphen at line end ("finan-" + "cial"),
+/// keeping the hyphen when the joined word isn't in the dictionary ("self-" + "aware")
+pub fn dehyphenate(elements: &mut [SpatialElement], dictionary: &Dictionary) {
+    for i in 0..elements.len().saturating_sub(1) {
+        let (head, tail) = elements.split_at_mut(i + 1);
+        let current = &mut head[i];
+        let next = &mut tail[0];
+
+        let breaks_line = next.page != current.page || next.vpos > current.vpos + 4.0;
+        let continues_lowercase = next.content.chars().next().map_or(false, |c| c.is_lowercase());
+        if current.content.len() > 1 && current.content.ends_with('-') && breaks_line && continues_lowercase {
+            let stem = &current.content[..current.content.len() - 1];
+            let joined = format!("{}{}", stem, next.content);
+            let is_split_word = dictionary.is_empty() || dictionary.contains(&joined);
+            current.content = if is_split_word { joined } else { format!("{}-{}", stem, next.content) };
+            next.content.clear();
+        }
+    }
+}
+
+pub struct DehyphenatePass {
+    pub dictionary: Arc<Dictionary>,
+}
+
+impl ReconstructionPass for DehyphenatePass {
+    fn name(&self) -> &str { "Dehyphenate" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        dehyphenate(&mut ctx.elements, &self.dictionary);
+    }
+}
+
+/// Drop running headers, footers and page numbers from the text
+pub struct StripHeadersPass;
+
+impl ReconstructionPass for StripHeadersPass {
+    fn name(&self) -> &str { "Strip headers/footers" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        for idx in headers::detect(&ctx.elements) {
+            ctx.elements[idx].content.clear();
+        }
+    }
+}
+
+/// Trim whitespace, collapse internal runs and expand typographic ligatures
+pub struct NormalizePass;
+
+impl ReconstructionPass for NormalizePass {
+    fn name(&self) -> &str { "Normalize" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        for element in &mut ctx.elements {
+            let expanded = element.content
+                .replace('\u{FB00}', "ff")
+                .replace('\u{FB01}', "fi")
+                .replace('\u{FB02}', "fl")
+                .replace('\u{FB03}', "ffi")
+                .replace('\u{FB04}', "ffl");
+            element.content = expanded.split_whitespace().collect::<Vec<&str>>().join(" ");
+        }
+    }
+}
+
+/// Group elements into lines (within 8 pixels vertically), page by page
+pub struct ClusterLinesPass;
+
+impl ReconstructionPass for ClusterLinesPass {
+    fn name(&self) -> &str { "Cluster lines" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        let elements = &ctx.elements;
+        let tolerance = ctx.heuristics.line_tolerance;
+        let mut order: Vec<usize> = (0..elements.len()).collect();
+        order.sort_by(|&a, &b| {
+            elements[a].page.cmp(&elements[b].page)
+                .then(elements[a].vpos.partial_cmp(&elements[b].vpos).unwrap())
+        });
+
+        let mut lines: Vec<Vec<usize>> = Vec::new();
+        for idx in order {
+            let found_line = lines.iter_mut()
+                .find(|line| elements[line[0]].page == elements[idx].page
+                    && (elements[idx].vpos - elements[line[0]].vpos).abs() < tolerance);
+            if let Some(line) = found_line {
+                line.push(idx);
+            } else {
+                lines.push(vec![idx]);
+            }
+        }
+        for line in &mut lines {
+            line.sort_by(|&a, &b| elements[a].hpos.partial_cmp(&elements[b].hpos).unwrap());
+        }
+
+        ctx.lines = lines;
+    }
+}
+
+/// Detect text columns and re-order lines column by column, so two-column
+/// pages read down the left column before the right one
+pub struct LinearizeColumnsPass;
+
+impl ReconstructionPass for LinearizeColumnsPass {
+    fn name(&self) -> &str { "Linearize columns" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        let element_refs: Vec<&SpatialElement> = ctx.elements.iter().filter(|e| !e.content.is_empty()).collect();
+        ctx.columns = layout::detect_columns(&element_refs);
+        if ctx.columns.len() < 2 {
+            return;
+        }
+
+        // Split every line into per-column segments, then emit columns in order
+        let mut per_column: Vec<Vec<Vec<usize>>> = vec![Vec::new(); ctx.columns.len()];
+        for line in &ctx.lines {
+            let mut segments: Vec<Vec<usize>> = vec![Vec::new(); ctx.columns.len()];
+            for &idx in line {
+                segments[layout::column_of(&ctx.elements[idx], &ctx.columns)].push(idx);
+            }
+            for (column, segment) in segments.into_iter().enumerate() {
+                if !segment.is_empty() {
+                    per_column[column].push(segment);
+                }
+            }
+        }
+        ctx.lines = per_column.into_iter().flatten().collect();
+    }
+}
+
+/// Split lines into paragraphs using vertical gaps, first-line indentation and
+/// short sentence-ending lines
+pub struct DetectParagraphsPass;
+
+/// Median of a list of values (0.0 when empty)
+fn median(values: &mut Vec<f32>) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values[values.len() / 2]
+}
+
+impl ReconstructionPass for DetectParagraphsPass {
+    fn name(&self) -> &str { "Detect paragraphs" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        let elements = &ctx.elements;
+        let line_start = |line: &Vec<usize>| elements[line[0]].hpos;
+        let line_end = |line: &Vec<usize>| {
+            let last = &elements[*line.last().unwrap()];
+            last.hpos + last.width
+        };
+        let line_vpos = |line: &Vec<usize>| elements[line[0]].vpos;
+
+        // Typical line spacing, left margin and right edge for this page
+        let mut spacings: Vec<f32> = ctx.lines.windows(2)
+            .map(|pair| line_vpos(&pair[1]) - line_vpos(&pair[0]))
+            .filter(|&gap| gap > 0.0)
+            .collect();
+        let typical_spacing = median(&mut spacings);
+        let mut starts: Vec<f32> = ctx.lines.iter().map(line_start).collect();
+        let left_margin = median(&mut starts);
+        let mut ends: Vec<f32> = ctx.lines.iter().map(line_end).collect();
+        ends.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let right_edge = ends.get(ends.len() * 9 / 10).copied().unwrap_or(0.0);
+
+        let mut paragraphs: Vec<Vec<usize>> = Vec::new();
+        let mut previous: Option<&Vec<usize>> = None;
+
+        for (line_idx, line) in ctx.lines.iter().enumerate() {
+            let starts_paragraph = match previous {
+                None => true,
+                Some(prev) => {
+                    let gap = line_vpos(line) - line_vpos(prev);
+                    let large_gap = gap < 0.0 || gap > ctx.heuristics.section_gap || (typical_spacing > 0.0 && gap > typical_spacing * 1.5);
+                    let indented = line_start(line) > left_margin + 10.0 && line_start(prev) <= left_margin + 2.0;
+                    let prev_text = &elements[*prev.last().unwrap()].content;
+                    let ends_sentence = prev_text.ends_with(|c: char| ".:?!".contains(c));
+                    let short_previous = ends_sentence && line_end(prev) < right_edge * 0.75;
+                    large_gap || indented || short_previous
+                }
+            };
+            if starts_paragraph {
+                paragraphs.push(Vec::new());
+            }
+            paragraphs.last_mut().unwrap().push(line_idx);
+            previous = Some(line);
+        }
+
+        ctx.paragraphs = paragraphs;
+    }
+}
+
+/// Join a paragraph broken by a page boundary: when the last line before the break
+/// doesn't end a sentence and the next page doesn't start with a capital (or the line
+/// ended on a comma, semicolon or hyphen). Runs after header stripping, so emptied
+/// running headers and footers don't count as the first or last line
+pub struct JoinPagesPass;
+
+impl ReconstructionPass for JoinPagesPass {
+    fn name(&self) -> &str { "Join across pages" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        let elements = &ctx.elements;
+        let lines = &ctx.lines;
+        let line_text = |line_idx: usize| {
+            lines[line_idx].iter()
+                .map(|&i| elements[i].content.as_str())
+                .filter(|c| !c.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" ")
+        };
+        let page = |line_idx: usize| elements[lines[line_idx][0]].page;
+
+        let mut merged: Vec<Vec<usize>> = Vec::new();
+        for paragraph in std::mem::take(&mut ctx.paragraphs) {
+            let first = match paragraph.iter().copied().find(|&l| !line_text(l).is_empty()) {
+                Some(first) => first,
+                None => {
+                    // Nothing but stripped headers/footers: keep it from separating its neighbours
+                    match merged.last_mut() {
+                        Some(previous) => previous.extend(paragraph),
+                        None => merged.push(paragraph),
+                    }
+                    continue;
+                }
+            };
+            let last = merged.last().and_then(|previous| previous.iter().rev().copied().find(|&l| !line_text(l).is_empty()));
+            let continues = last.is_some_and(|last| {
+                let before = line_text(last);
+                let after = line_text(first);
+                let before = before.trim_end_matches(|c: char| "\"')]”’".contains(c));
+                let ends_sentence = before.ends_with(|c: char| ".?!:".contains(c));
+                let open_clause = before.ends_with(|c: char| ",;-".contains(c));
+                let starts_capital = after.chars().next().is_some_and(char::is_uppercase);
+                page(first) > page(last) && !ends_sentence && (open_clause || !starts_capital)
+            });
+            match merged.last_mut() {
+                Some(previous) if continues => previous.extend(paragraph),
+                _ => merged.push(paragraph),
+            }
+        }
+        ctx.paragraphs = merged;
+    }
+}
+
+/// Mark currency, percentage and year cells in the table region, or the hand-drawn regions when there are any
+pub struct DetectTablesPass;
+
+impl ReconstructionPass for DetectTablesPass {
+    fn name(&self) -> &str { "Detect tables" }
+
+    fn run(&self, ctx: &mut PassContext) {
+        ctx.table_elements = ctx.elements.iter()
+            .enumerate()
+            .filter(|(_, e)| table_regions::is_table_element(&ctx.table_regions, e))
+            .map(|(i, _)| i)
+            .collect();
+    }
+}
+
+/// Named ordering of passes with per-pass enable flags
+#[derive(Debug, Clone)]
+pub struct PipelineProfile {
+    pub name: String,
+    pub passes: Vec<(String, bool)>,
+}
+
+/// Registered passes plus the profiles that order and toggle them
+pub struct Pipeline {
+    passes: Vec<Box<dyn ReconstructionPass>>,
+    pub profiles: Vec<PipelineProfile>,
+    pub active_profile: usize,
+    pub reading_order: Vec<String>, // Manual paragraph order (block keys); empty = as detected
+    pub heuristics: Heuristics,
+    pub table_regions: Vec<TableRegion>, // Hand-drawn tables; non-empty overrides detection
+}
+
+impl Pipeline {
+    pub fn new(dictionary: Arc<Dictionary>) -> Self {
+        let mut pipeline = Self {
+            passes: Vec::new(),
+            profiles: vec![
+                PipelineProfile { name: "Default".to_string(), passes: Vec::new() },
+                PipelineProfile { name: "Raw".to_string(), passes: Vec::new() },
+            ],
+            active_profile: 0,
+            reading_order: Vec::new(),
+            heuristics: Heuristics::default(),
+            table_regions: Vec::new(),
+        };
+        pipeline.register(Box::new(StripHeadersPass));
+        pipeline.register(Box::new(DehyphenatePass { dictionary }));
+        pipeline.register(Box::new(NormalizePass));
+        pipeline.register(Box::new(ClusterLinesPass));
+        pipeline.register(Box::new(LinearizeColumnsPass));
+        pipeline.register(Box::new(DetectParagraphsPass));
+        pipeline.register(Box::new(JoinPagesPass));
+        pipeline.register(Box::new(DetectTablesPass));
+
+        // Content-rewriting passes are opt-in; Raw only clusters lines
+        pipeline.set_enabled(0, "Strip headers/footers", false);
+        pipeline.set_enabled(0, "Dehyphenate", false);
+        pipeline.set_enabled(0, "Join across pages", false);
+        for name in ["Strip headers/footers", "Dehyphenate", "Normalize", "Linearize columns", "Detect paragraphs", "Join across pages", "Detect tables"] {
+            pipeline.set_enabled(1, name, false);
+        }
+        pipeline
+    }
+
+    /// Add a pass at the end of every profile, enabled
+    pub fn register(&mut self, pass: Box<dyn ReconstructionPass>) {
+        for profile in &mut self.profiles {
+            profile.passes.push((pass.name().to_string(), true));
+        }
+        self.passes.push(pass);
+    }
+
+    fn set_enabled(&mut self, profile: usize, name: &str, enabled: bool) {
+        if let Some(entry) = self.profiles[profile].passes.iter_mut().find(|(n, _)| n == name) {
+            entry.1 = enabled;
+        }
+    }
+
+    /// Whether a pass is enabled in the active profile
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.profiles[self.active_profile].passes.iter().any(|(n, enabled)| n == name && *enabled)
+    }
+
+    pub fn set_active_enabled(&mut self, name: &str, enabled: bool) {
+        self.set_enabled(self.active_profile, name, enabled);
+    }
+
+    pub fn profile_mut(&mut self) -> &mut PipelineProfile {
+        &mut self.profiles[self.active_profile]
+    }
+
+    /// Run the enabled passes of the active profile in order
+    pub fn run(&self, elements: Vec<SpatialElement>) -> PassContext {
+        self.run_with(elements, &self.table_regions, &self.reading_order)
+    }
+
+    /// Run with other pages' table regions and reading order, e.g. every parsed page for an export
+    pub fn run_with(&self, elements: Vec<SpatialElement>, table_regions: &[TableRegion], reading_order: &[String]) -> PassContext {
+        let mut ctx = PassContext::new(elements);
+        ctx.heuristics = self.heuristics;
+        ctx.table_regions = table_regions.to_vec();
+        for (name, enabled) in &self.profiles[self.active_profile].passes {
+            if !enabled {
+                continue;
+            }
+            if let Some(pass) = self.passes.iter().find(|p| p.name() == name) {
+                pass.run(&mut ctx);
+            }
+        }
+        reading_order::apply(&mut ctx, reading_order);
+        ctx
+    }
+}
+
+/// Readable text from pipeline output: lines with gap-based spacing, blank lines between paragraphs
+pub fn render_readable_text(ctx: &PassContext) -> String {
+    render_readable_spans(ctx).0
+}
+
+/// Readable text plus the char ranges the layout inferred from gaps: multi-space runs
+/// and the extra blank lines at section breaks
+pub fn render_readable_spans(ctx: &PassContext) -> (String, Vec<Range<usize>>) {
+    // Without line clustering every element stands on its own line
+    let fallback_lines: Vec<Vec<usize>>;
+    let lines = if ctx.lines.is_empty() {
+        fallback_lines = (0..ctx.elements.len()).map(|i| vec![i]).collect();
+        &fallback_lines
+    } else {
+        &ctx.lines
+    };
+    let paragraph_starts: Vec<usize> = ctx.paragraphs.iter().filter_map(|p| p.first().copied()).collect();
+
+    let mut output = String::new();
+    let mut output_chars = 0;
+    let mut inferred: Vec<Range<usize>> = Vec::new();
+    let mut last_vpos = 0.0;
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        let line_elements: Vec<&SpatialElement> = line.iter()
+            .map(|&i| &ctx.elements[i])
+            .filter(|e| !e.content.is_empty())
+            .collect();
+        if line_elements.is_empty() {
+            continue;
+        }
+        let current_vpos = line_elements[0].vpos;
+
+        // Add extra spacing for large vertical gaps (section breaks)
+        if last_vpos > 0.0 && paragraph_starts.contains(&line_idx) {
+            let vertical_gap = current_vpos - last_vpos;
+            let extra_lines = ctx.heuristics.blank_lines_for_gap(vertical_gap);
+            output.push_str(&"\n".repeat(extra_lines));
+            inferred.push(output_chars..output_chars + extra_lines);
+            output_chars += extra_lines;
+        }
+
+        let mut line_text = String::new();
+        let mut line_chars = 0;
+        let mut previous: Option<&SpatialElement> = None;
+
+        for element in line_elements {
+            if let Some(previous) = previous {
+                let spaces = ctx.heuristics.spaces_between(previous, element);
+                line_text.push_str(&" ".repeat(spaces));
+                if spaces > 1 {  // Large gap - inferred run
+                    inferred.push(output_chars + line_chars..output_chars + line_chars + spaces);
+                }
+                line_chars += spaces;
+            }
+
+            line_text.push_str(&element.content);
+            line_chars += element.content.chars().count();
+            previous = Some(element);
+        }
+
+        output.push_str(&line_text);
+        output.push('\n');
+        output_chars += line_chars + 1;
+        last_vpos = current_vpos;
+    }
+
+    (output, inferred)
+}
+
+/// One string per paragraph with its lines joined, for reflowed display
+pub fn paragraph_texts(ctx: &PassContext) -> Vec<String> {
+    let line_text = |line: &Vec<usize>| {
+        line.iter()
+            .map(|&i| ctx.elements[i].content.as_str())
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    };
+
+    if ctx.paragraphs.is_empty() {
+        return ctx.lines.iter().map(line_text).collect();
+    }
+    ctx.paragraphs.iter()
+        .map(|paragraph| {
+            paragraph.iter()
+                .map(|&line_idx| line_text(&ctx.lines[line_idx]))
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect()
+}
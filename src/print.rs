@@ -0,0 +1,162 @@
+// print.rs - Corrected document as PDF (one page per source page) and printing via lp
+use crate::{mojibake, SpatialElement};
+use eframe::egui;
+use std::process::Command;
+
+const MARGIN: f32 = 54.0;
+const FONT_SIZE: f32 = 11.0;
+const LEADING: f32 = 14.0;
+const AVG_CHAR_WIDTH: f32 = 0.5; // Helvetica, as a fraction of font size
+
+/// What goes on the printed page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrintLayout {
+    Readable, // Reconstructed paragraphs, wrapped to the page
+    Spatial,  // Each word at its source coordinates
+}
+
+/// One source page: its size in points and the content to lay out
+pub struct SourcePage<'a> {
+    pub size: egui::Vec2,
+    pub elements: Vec<&'a SpatialElement>,
+    pub paragraphs: Vec<String>,
+}
+
+/// Lay out every source page; readable text that overflows continues on extra pages
+/// so each source page still starts on a fresh sheet
+pub fn render_pdf(pages: &[SourcePage], layout: PrintLayout) -> Vec<u8> {
+    let mut sheets: Vec<(egui::Vec2, String)> = Vec::new();
+    for page in pages {
+        match layout {
+            PrintLayout::Spatial => sheets.push((page.size, spatial_content(page))),
+            PrintLayout::Readable => {
+                for content in readable_content(page) {
+                    sheets.push((page.size, content));
+                }
+            }
+        }
+    }
+    write_pdf(&sheets)
+}
+
+fn spatial_content(page: &SourcePage) -> String {
+    let mut content = String::new();
+    for element in &page.elements {
+        let size = (element.height * 0.8).max(4.0);
+        // PDF y runs up from the bottom
+        let baseline = page.size.y - element.vpos - element.height * 0.8;
+        content.push_str(&format!(
+            "BT /F1 {:.1} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+            size, element.hpos, baseline, escape(element.content.trim())
+        ));
+    }
+    content
+}
+
+/// Content streams for one source page's paragraphs, split where the sheet fills up
+fn readable_content(page: &SourcePage) -> Vec<String> {
+    let max_chars = ((page.size.x - 2.0 * MARGIN) / (FONT_SIZE * AVG_CHAR_WIDTH)).max(10.0) as usize;
+    let lines_per_sheet = ((page.size.y - 2.0 * MARGIN) / LEADING).max(1.0) as usize;
+    
+    let mut lines = Vec::new();
+    for paragraph in &page.paragraphs {
+        lines.extend(wrap(paragraph, max_chars));
+        lines.push(String::new());
+    }
+    
+    let mut sheets: Vec<String> = lines.chunks(lines_per_sheet)
+        .map(|chunk| {
+            let mut content = format!("BT /F1 {} Tf {} TL {} {} Td\n", FONT_SIZE, LEADING, MARGIN, page.size.y - MARGIN - FONT_SIZE);
+            for line in chunk {
+                content.push_str(&format!("({}) Tj T*\n", escape(line)));
+            }
+            content.push_str("ET\n");
+            content
+        })
+        .collect();
+    if sheets.is_empty() {
+        sheets.push(String::new());
+    }
+    sheets
+}
+
+/// Greedy word wrap at `max_chars`
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// PDF string literal in WinAnsi, one char per byte (smart quotes, dashes and € included);
+/// characters outside Windows-1252 print as '?'
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            c if (c as u32) < 32 => " ".to_string(),
+            c => char::from(mojibake::cp1252_byte(c).unwrap_or(b'?')).to_string(),
+        })
+        .collect()
+}
+
+/// Serialize sheets of content streams with a shared Helvetica font
+fn write_pdf(sheets: &[(egui::Vec2, String)]) -> Vec<u8> {
+    // Objects: 1 catalog, 2 page tree, 3 font, then a page and its content per sheet
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    let kids: Vec<String> = (0..sheets.len()).map(|i| format!("{} 0 R", 4 + i * 2)).collect();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), sheets.len()).into_bytes());
+    objects.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec());
+    for (i, (size, content)) in sheets.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            size.x, size.y, 5 + i * 2
+        ).into_bytes());
+        // WinAnsi bytes, matching the escaping above
+        let bytes: Vec<u8> = content.chars().map(|c| c as u32 as u8).collect();
+        let mut stream = format!("<< /Length {} >>\nstream\n", bytes.len()).into_bytes();
+        stream.extend_from_slice(&bytes);
+        stream.extend_from_slice(b"\nendstream");
+        objects.push(stream);
+    }
+    
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1, xref
+    ).as_bytes());
+    pdf
+}
+
+/// Hand a PDF to the system print queue
+pub fn send_to_printer(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let status = Command::new("lp").arg(path).status()?;
+    if !status.success() {
+        return Err("lp failed".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// quality.rs - Per-page extraction quality scoring
+use crate::dictionary::{self, Dictionary};
+use crate::{layout, SpatialElement};
+use serde::Serialize;
+
+/// Quality components for one page, each in 0.0..=1.0, plus a 0-100 score
+#[derive(Debug, Clone, Serialize)]
+pub struct PageQuality {
+    pub page: usize,
+    pub element_count: usize,
+    pub density: f32,                  // Elements per content area, saturating
+    pub mean_confidence: Option<f32>,  // From ALTO WC when present
+    pub low_confidence_ratio: Option<f32>,
+    pub dictionary_hit_rate: f32,
+    pub layout_regularity: f32,        // Consistency of line spacing
+    pub score: f32,
+}
+
+/// Score every page present in `elements`
+pub fn score_pages(elements: &[SpatialElement], dictionary: &Dictionary) -> Vec<PageQuality> {
+    let mut pages: Vec<usize> = elements.iter().map(|e| e.page).collect();
+    pages.sort();
+    pages.dedup();
+    pages.into_iter()
+        .map(|page| {
+            let page_elements: Vec<&SpatialElement> = elements.iter().filter(|e| e.page == page).collect();
+            score_page(page, &page_elements, dictionary)
+        })
+        .collect()
+}
+
+impl PageQuality {
+    /// The score's ingredients, one per line
+    pub fn details(&self) -> String {
+        format!(
+            "Elements: {}\nDensity: {:.2}\nConfidence: {}\nDictionary hits: {:.0}%\nLayout regularity: {:.2}",
+            self.element_count,
+            self.density,
+            self.mean_confidence.map_or("n/a".to_string(), |c| format!("{:.2}", c)),
+            self.dictionary_hit_rate * 100.0,
+            self.layout_regularity,
+        )
+    }
+}
+
+pub fn score_page(page: usize, elements: &[&SpatialElement], dictionary: &Dictionary) -> PageQuality {
+    let element_count = elements.len();
+    
+    // Density: elements per 10,000 pt² of the occupied area; sparse pages usually failed
+    let min_x = elements.iter().map(|e| e.hpos).fold(f32::MAX, f32::min);
+    let min_y = elements.iter().map(|e| e.vpos).fold(f32::MAX, f32::min);
+    let max_x = elements.iter().map(|e| e.hpos + e.width).fold(0.0, f32::max);
+    let max_y = elements.iter().map(|e| e.vpos + e.height).fold(0.0, f32::max);
+    let area = ((max_x - min_x) * (max_y - min_y)).max(1.0);
+    let density = if element_count == 0 { 0.0 } else { (element_count as f32 / area * 10_000.0 / 4.0).min(1.0) };
+    
+    // Confidence distribution
+    let confidences: Vec<f32> = elements.iter().filter_map(|e| e.confidence).collect();
+    let (mean_confidence, low_confidence_ratio) = if confidences.is_empty() {
+        (None, None)
+    } else {
+        let mean = confidences.iter().sum::<f32>() / confidences.len() as f32;
+        let low = confidences.iter().filter(|&&c| c < 0.8).count() as f32 / confidences.len() as f32;
+        (Some(mean), Some(low))
+    };
+    
+    // Dictionary hit rate (letters-only heuristic when no word list is installed)
+    let words: Vec<&str> = elements.iter().filter_map(|e| dictionary::checkable_word(&e.content)).collect();
+    let dictionary_hit_rate = if words.is_empty() {
+        0.0
+    } else if dictionary.is_empty() {
+        words.iter().filter(|w| w.chars().all(|c| c.is_alphabetic())).count() as f32 / words.len() as f32
+    } else {
+        words.iter().filter(|w| dictionary.contains(w)).count() as f32 / words.len() as f32
+    };
+    
+    // Layout regularity: 1 / (1 + coefficient of variation) of in-paragraph line gaps
+    let lines = layout::group_into_lines(elements);
+    let gaps: Vec<f32> = lines.windows(2)
+        .map(|pair| pair[1][0].vpos - pair[0][0].vpos)
+        .filter(|&gap| gap > 0.0 && gap <= 15.0)
+        .collect();
+    let layout_regularity = if gaps.len() < 2 {
+        0.5
+    } else {
+        let mean = gaps.iter().sum::<f32>() / gaps.len() as f32;
+        let variance = gaps.iter().map(|g| (g - mean).powi(2)).sum::<f32>() / gaps.len() as f32;
+        1.0 / (1.0 + variance.sqrt() / mean)
+    };
+    
+    let confidence_component = mean_confidence.unwrap_or(dictionary_hit_rate);
+    let score = 100.0 * (0.4 * dictionary_hit_rate
+        + 0.3 * confidence_component
+        + 0.2 * layout_regularity
+        + 0.1 * density);
+    
+    PageQuality {
+        page,
+        element_count,
+        density,
+        mean_confidence,
+        low_confidence_ratio,
+        dictionary_hit_rate,
+        layout_regularity,
+        score,
+    }
+}
+
+/// Write the page-quality manifest consumed by triage tooling
+pub fn write_manifest(path: &str, source: &str, pages: &[PageQuality]) -> Result<(), Box<dyn std::error::Error>> {
+    #[derive(Serialize)]
+    struct Manifest<'a> {
+        source: &'a str,
+        pages: &'a [PageQuality],
+    }
+    let json = serde_json::to_string_pretty(&Manifest { source, pages })?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
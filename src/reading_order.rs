@@ -0,0 +1,83 @@
+// reading_order.rs - Manual paragraph order overriding the detected reading order
+use crate::pipeline::PassContext;
+use crate::SpatialElement;
+use std::path::PathBuf;
+
+/// Stable key for a block: page and position of its first element
+pub fn block_key(first: &SpatialElement) -> String {
+    format!("{}:{:.0}:{:.0}", first.page, first.hpos, first.vpos)
+}
+
+/// Rank of a block in the manual order; unlisted blocks (e.g. new after edits) go last
+pub fn rank(order: &[String], key: &str) -> usize {
+    order.iter().position(|k| k == key).unwrap_or(usize::MAX)
+}
+
+/// Key of each paragraph in pipeline output
+pub fn paragraph_keys(ctx: &PassContext) -> Vec<String> {
+    ctx.paragraphs.iter()
+        .filter_map(|paragraph| {
+            let line = ctx.lines.get(*paragraph.first()?)?;
+            Some(block_key(&ctx.elements[*line.first()?]))
+        })
+        .collect()
+}
+
+/// Reorder paragraphs, and the lines under them, to follow a manual order; each page
+/// is ordered on its own, so pages without one keep their detected order and place
+pub fn apply(ctx: &mut PassContext, order: &[String]) {
+    if order.is_empty() || ctx.paragraphs.is_empty() {
+        return;
+    }
+    let keys = paragraph_keys(ctx);
+    let page = |paragraph: &Vec<usize>| paragraph.first()
+        .and_then(|&line| ctx.lines.get(line)?.first())
+        .map_or(0, |&element| ctx.elements[element].page);
+    let paragraphs = std::mem::take(&mut ctx.paragraphs);
+    let mut ranked: Vec<((usize, usize), Vec<usize>)> = paragraphs.into_iter()
+        .zip(keys)
+        .map(|(paragraph, key)| ((page(&paragraph), rank(order, &key)), paragraph))
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank); // Stable: ties keep detected order
+
+    // Lines are rendered in sequence, so rebuild them in paragraph order
+    let old_lines = std::mem::take(&mut ctx.lines);
+    let mut claimed = vec![false; old_lines.len()];
+    for (_, paragraph) in ranked {
+        let mut renumbered = Vec::new();
+        for line_idx in paragraph {
+            claimed[line_idx] = true;
+            renumbered.push(ctx.lines.len());
+            ctx.lines.push(old_lines[line_idx].clone());
+        }
+        ctx.paragraphs.push(renumbered);
+    }
+    // Lines outside every paragraph still belong in the text; they follow the ordered ones
+    for (line, claimed) in old_lines.into_iter().zip(claimed) {
+        if !claimed {
+            ctx.lines.push(line);
+        }
+    }
+}
+
+fn sidecar_path(pdf_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.chonker-order", pdf_path))
+}
+
+/// Saved manual order for a PDF (one block key per line); empty when never reordered
+pub fn load(pdf_path: &str) -> Vec<String> {
+    std::fs::read_to_string(sidecar_path(pdf_path))
+        .map(|content| content.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+pub fn save(pdf_path: &str, order: &[String]) -> std::io::Result<()> {
+    if order.is_empty() {
+        // Reset: fall back to detected order
+        return match std::fs::remove_file(sidecar_path(pdf_path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    std::fs::write(sidecar_path(pdf_path), order.join("\n") + "\n")
+}
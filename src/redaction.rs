@@ -0,0 +1,111 @@
+// redaction.rs - Redacted regions: blacked out on screen, stripped or burned into exports
+use eframe::egui;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Stands in for redacted words in text exports
+pub const PLACEHOLDER: &str = "[REDACTED]";
+
+/// Rasterization resolution for burned-in PDFs
+const BURN_DPI: f32 = 150.0;
+
+/// Drawn redaction rectangles in PDF points
+#[derive(Debug, Default)]
+pub struct Redactions {
+    pub regions: Vec<egui::Rect>,
+}
+
+/// Whether any redacted rectangle touches a word's box; a partly covered word is blacked
+/// out on screen, so it must not survive in exports either
+pub fn covers(redacted: &[egui::Rect], bounds: egui::Rect) -> bool {
+    redacted.iter().any(|r| r.intersects(bounds))
+}
+
+impl Redactions {
+    /// Drop the region under a document point, returning whether one was removed
+    pub fn remove_at(&mut self, pos: egui::Pos2) -> bool {
+        let before = self.regions.len();
+        self.regions.retain(|r| !r.contains(pos));
+        self.regions.len() != before
+    }
+    
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-redactions", pdf_path))
+    }
+    
+    /// Regions saved for a PDF (JSON array of [x0, y0, x1, y1])
+    pub fn load(pdf_path: &str) -> Self {
+        let regions: Vec<[f32; 4]> = std::fs::read_to_string(Self::sidecar_path(pdf_path))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            regions: regions.iter()
+                .map(|r| egui::Rect::from_min_max(egui::pos2(r[0], r[1]), egui::pos2(r[2], r[3])))
+                .collect(),
+        }
+    }
+    
+    pub fn save(&self, pdf_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let regions: Vec<[f32; 4]> = self.regions.iter()
+            .map(|r| [r.min.x, r.min.y, r.max.x, r.max.y])
+            .collect();
+        std::fs::write(Self::sidecar_path(pdf_path), serde_json::to_string(&regions)?)?;
+        Ok(())
+    }
+}
+
+/// Rasterize every page, paint each page's rectangles solid black and write them as one
+/// image-only PDF, so no text survives underneath (needs pdftoppm and ImageMagick)
+pub fn burn_pdf(pdf_path: &str, pages: &[(usize, Vec<egui::Rect>)], output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut ppm_paths = Vec::new();
+    let result = burn_pages(pdf_path, pages, output, &mut ppm_paths);
+    for path in &ppm_paths {
+        let _ = std::fs::remove_file(path);
+    }
+    result
+}
+
+/// Rasterize one page with its rectangles painted solid black, for any export that ships page images
+pub fn render_redacted_page(pdf_path: &str, page: usize, rects: &[egui::Rect], dpi: f32) -> Result<egui::ColorImage, Box<dyn std::error::Error>> {
+    let mut image = crate::tile_cache::render_page(pdf_path, page, dpi)?;
+    let scale = dpi / 72.0;
+    let [width, height] = image.size;
+    for rect in rects {
+        let x0 = ((rect.min.x * scale).floor().max(0.0) as usize).min(width);
+        let x1 = ((rect.max.x * scale).ceil().max(0.0) as usize).min(width);
+        let y0 = ((rect.min.y * scale).floor().max(0.0) as usize).min(height);
+        let y1 = ((rect.max.y * scale).ceil().max(0.0) as usize).min(height);
+        for y in y0..y1 {
+            image.pixels[y * width + x0..y * width + x1].fill(egui::Color32::BLACK);
+        }
+    }
+    Ok(image)
+}
+
+fn burn_pages(pdf_path: &str, pages: &[(usize, Vec<egui::Rect>)], output: &str, ppm_paths: &mut Vec<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    for (page, rects) in pages {
+        let image = render_redacted_page(pdf_path, *page, rects, BURN_DPI)?;
+        let [width, height] = image.size;
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for pixel in &image.pixels {
+            ppm.extend_from_slice(&[pixel.r(), pixel.g(), pixel.b()]);
+        }
+        let ppm_path = crate::temp_path(&format!("chonker9_redacted_p{}", page)).with_extension("ppm");
+        std::fs::write(&ppm_path, ppm)?;
+        ppm_paths.push(ppm_path);
+    }
+    
+    // One input image per output page, in order
+    let density = format!("{:.0}", BURN_DPI);
+    let convert = |program: &str| Command::new(program)
+        .args(ppm_paths.iter())
+        .args(["-units", "PixelsPerInch", "-density", &density])
+        .arg(output)
+        .status();
+    let status = convert("magick").or_else(|_| convert("convert"));
+    if !status?.success() {
+        return Err("ImageMagick failed to write the PDF".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// review.rs - Confidence auto-accept for the review queue, with an audit record
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// What auto-accept did to the queue, saved next to the PDF for audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewAudit {
+    pub threshold: f32,
+    pub total: usize,
+    pub auto_accepted: usize,
+    pub queued: usize,
+    pub recorded_at: u64, // Unix seconds
+}
+
+/// Words at or above the threshold count as verified; words without WC always need review
+pub fn is_auto_accepted(confidence: Option<f32>, threshold: Option<f32>) -> bool {
+    match (confidence, threshold) {
+        (Some(confidence), Some(threshold)) => confidence >= threshold,
+        _ => false,
+    }
+}
+
+impl ReviewAudit {
+    pub fn new(threshold: f32, confidences: &[Option<f32>]) -> Self {
+        let auto_accepted = confidences.iter()
+            .filter(|&&c| is_auto_accepted(c, Some(threshold)))
+            .count();
+        let recorded_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            threshold,
+            total: confidences.len(),
+            auto_accepted,
+            queued: confidences.len() - auto_accepted,
+            recorded_at,
+        }
+    }
+    
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-review.json", pdf_path))
+    }
+    
+    pub fn load(pdf_path: &str) -> Option<Self> {
+        let json = std::fs::read_to_string(Self::sidecar_path(pdf_path)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+    
+    pub fn save(&self, pdf_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(Self::sidecar_path(pdf_path), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+    
+    /// Forget the record when auto-accept is switched off
+    pub fn clear(pdf_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        match std::fs::remove_file(Self::sidecar_path(pdf_path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// rotation.rs - Page /Rotate detection and rotating element coordinates into display orientation
+use crate::SpatialElement;
+use eframe::egui;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Clockwise page rotation, as in the PDF /Rotate entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+impl Rotation {
+    pub fn from_degrees(degrees: i32) -> Self {
+        match degrees.rem_euclid(360) {
+            90 => Rotation::Cw90,
+            180 => Rotation::Cw180,
+            270 => Rotation::Cw270,
+            _ => Rotation::None,
+        }
+    }
+
+    pub fn degrees(&self) -> i32 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Cw90 => 90,
+            Rotation::Cw180 => 180,
+            Rotation::Cw270 => 270,
+        }
+    }
+
+    /// Both rotations applied one after the other
+    pub fn then(&self, other: Rotation) -> Rotation {
+        Rotation::from_degrees(self.degrees() + other.degrees())
+    }
+}
+
+/// /Rotate and unrotated size (points) of a page, from pdfinfo
+pub fn page_geometry(pdf_path: &str, page: usize) -> Option<(Rotation, (f32, f32))> {
+    let page_arg = page.to_string();
+    let output = Command::new("pdfinfo").args(["-f", &page_arg, "-l", &page_arg, pdf_path]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let prefix = format!("Page {:>4}", page);
+    let mut rotation = Rotation::None;
+    let mut size = None;
+    for line in text.lines().filter_map(|line| line.strip_prefix(&prefix)) {
+        // "Page    1 size: 612 x 792 pts (letter)" / "Page    1 rot:  90"
+        if let Some(value) = line.trim().strip_prefix("rot:") {
+            rotation = Rotation::from_degrees(value.trim().parse().unwrap_or(0));
+        } else if let Some(value) = line.trim().strip_prefix("size:") {
+            let mut parts = value.split_whitespace();
+            let width = parts.next().and_then(|w| w.parse().ok());
+            let height = parts.nth(1).and_then(|h| h.parse().ok());
+            size = width.zip(height);
+        }
+    }
+    Some((rotation, size?))
+}
+
+/// Rotate element boxes clockwise within a page of unrotated size `page`
+pub fn rotate(elements: &mut [SpatialElement], rotation: Rotation, page: (f32, f32)) {
+    if rotation == Rotation::None {
+        return;
+    }
+    for element in elements {
+        let (hpos, vpos, width, height) = rotate_box((element.hpos, element.vpos, element.width, element.height), rotation, page);
+        element.hpos = hpos;
+        element.vpos = vpos;
+        element.width = width;
+        element.height = height;
+    }
+}
+
+/// A rectangle turned the same way as `rotate` turns elements
+pub fn rotate_rect(rect: egui::Rect, rotation: Rotation, page: (f32, f32)) -> egui::Rect {
+    let (x, y, width, height) = rotate_box((rect.min.x, rect.min.y, rect.width(), rect.height()), rotation, page);
+    egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(width, height))
+}
+
+/// Page size once turned by `rotation`
+pub fn rotated_size(page: (f32, f32), rotation: Rotation) -> (f32, f32) {
+    match rotation {
+        Rotation::Cw90 | Rotation::Cw270 => (page.1, page.0),
+        _ => page,
+    }
+}
+
+fn rotate_box((x, y, w, h): (f32, f32, f32, f32), rotation: Rotation, (page_width, page_height): (f32, f32)) -> (f32, f32, f32, f32) {
+    match rotation {
+        Rotation::None => (x, y, w, h),
+        Rotation::Cw90 => (page_height - (y + h), x, h, w),
+        Rotation::Cw180 => (page_width - (x + w), page_height - (y + h), w, h),
+        Rotation::Cw270 => (y, page_width - (x + w), h, w),
+    }
+}
+
+/// Extent of the elements, for pages pdfinfo can't size
+pub fn element_extent(elements: &[SpatialElement]) -> (f32, f32) {
+    elements.iter().fold((0.0, 0.0), |(w, h), e| (f32::max(w, e.hpos + e.width), f32::max(h, e.vpos + e.height)))
+}
+
+fn sidecar_path(pdf_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.chonker-rotation", pdf_path))
+}
+
+/// Extra clockwise view rotation the user applied to this document
+pub fn load_manual(pdf_path: &str) -> Rotation {
+    let content = std::fs::read_to_string(sidecar_path(pdf_path)).unwrap_or_default();
+    Rotation::from_degrees(content.trim().parse().unwrap_or(0))
+}
+
+pub fn save_manual(pdf_path: &str, rotation: Rotation) -> std::io::Result<()> {
+    if rotation == Rotation::None {
+        return match std::fs::remove_file(sidecar_path(pdf_path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    std::fs::write(sidecar_path(pdf_path), format!("{}\n", rotation.degrees()))
+}
@@ -0,0 +1,128 @@
+// scripts.rs - Superscript/subscript recognition from word geometry within a line
+use eframe::egui;
+
+/// Where a word sits relative to its line's baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptPosition {
+    Normal,
+    Super,
+    Sub,
+}
+
+/// How raised and lowered words are written in text exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptEncoding {
+    Plain,    // Leave the text as extracted
+    Unicode,  // ² ₂ where every character has a form, markers otherwise
+    Markers,  // ^2^ ~2~ (Pandoc)
+}
+
+impl ScriptEncoding {
+    pub const ALL: [ScriptEncoding; 3] = [ScriptEncoding::Plain, ScriptEncoding::Unicode, ScriptEncoding::Markers];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScriptEncoding::Plain => "plain",
+            ScriptEncoding::Unicode => "x² H₂O",
+            ScriptEncoding::Markers => "x^2^ H~2~",
+        }
+    }
+}
+
+/// Raised and lowered text is drawn at this fraction of the font size
+const DISPLAY_SCALE: f32 = 0.65;
+
+/// Scripts are shorter than this fraction of the line's typical word height
+const MAX_HEIGHT_RATIO: f32 = 0.8;
+/// ...and shifted by at least this fraction of it
+const MIN_SHIFT_RATIO: f32 = 0.2;
+/// Longer words are never treated as scripts (footnote marks, exponents, ™, chemical indices)
+const MAX_SCRIPT_CHARS: usize = 6;
+
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'), ('1', '¹'), ('2', '²'), ('3', '³'), ('4', '⁴'), ('5', '⁵'), ('6', '⁶'), ('7', '⁷'),
+    ('8', '⁸'), ('9', '⁹'), ('+', '⁺'), ('-', '⁻'), ('=', '⁼'), ('(', '⁽'), (')', '⁾'), ('n', 'ⁿ'), ('i', 'ⁱ'),
+];
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'), ('1', '₁'), ('2', '₂'), ('3', '₃'), ('4', '₄'), ('5', '₅'), ('6', '₆'), ('7', '₇'),
+    ('8', '₈'), ('9', '₉'), ('+', '₊'), ('-', '₋'), ('=', '₌'), ('(', '₍'), (')', '₎'),
+];
+
+fn median(mut values: Vec<f32>) -> f32 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    values.get(values.len() / 2).copied().unwrap_or(0.0)
+}
+
+/// Font for drawing text at `position`: scripts smaller, set against the top or bottom of the line
+pub fn text_format(position: ScriptPosition, font: &egui::FontId, color: egui::Color32) -> egui::TextFormat {
+    let small = egui::FontId::new(font.size * DISPLAY_SCALE, font.family.clone());
+    match position {
+        ScriptPosition::Normal => egui::TextFormat::simple(font.clone(), color),
+        ScriptPosition::Super => egui::TextFormat { font_id: small, color, valign: egui::Align::TOP, ..Default::default() },
+        ScriptPosition::Sub => egui::TextFormat { font_id: small, color, valign: egui::Align::BOTTOM, ..Default::default() },
+    }
+}
+
+/// Font for a word drawn at its own box
+pub fn font(position: ScriptPosition, font: egui::FontId) -> egui::FontId {
+    match position {
+        ScriptPosition::Normal => font,
+        _ => egui::FontId::new(font.size * DISPLAY_SCALE, font.family),
+    }
+}
+
+/// Classify each word box. Lines are built around the tallest words, so a raised or
+/// lowered word joins the line whose box its centre falls in
+pub fn classify(bounds: &[egui::Rect], texts: &[&str]) -> Vec<ScriptPosition> {
+    let mut by_height: Vec<usize> = (0..bounds.len()).collect();
+    by_height.sort_by(|&a, &b| bounds[b].height().partial_cmp(&bounds[a].height()).unwrap_or(std::cmp::Ordering::Equal));
+    let mut lines: Vec<(egui::Rect, Vec<usize>)> = Vec::new();
+    for i in by_height {
+        let centre = bounds[i].center().y;
+        match lines.iter_mut().find(|(reference, _)| reference.y_range().contains(centre)) {
+            Some((_, members)) => members.push(i),
+            None => lines.push((bounds[i], vec![i])),
+        }
+    }
+
+    let mut positions = vec![ScriptPosition::Normal; bounds.len()];
+    for (_, members) in lines {
+        if members.len() < 2 {
+            continue;
+        }
+        let height = median(members.iter().map(|&i| bounds[i].height()).collect());
+        let top = median(members.iter().map(|&i| bounds[i].min.y).collect());
+        let bottom = median(members.iter().map(|&i| bounds[i].max.y).collect());
+        for &i in &members {
+            let rect = bounds[i];
+            if rect.height() > height * MAX_HEIGHT_RATIO || texts[i].trim().chars().count() > MAX_SCRIPT_CHARS {
+                continue;
+            }
+            if rect.max.y < bottom - height * MIN_SHIFT_RATIO {
+                positions[i] = ScriptPosition::Super;
+            } else if rect.min.y > top + height * MIN_SHIFT_RATIO {
+                positions[i] = ScriptPosition::Sub;
+            }
+        }
+    }
+    positions
+}
+
+/// `text` written for its position in the chosen encoding
+pub fn encode(text: &str, position: ScriptPosition, encoding: ScriptEncoding) -> String {
+    let (table, marker) = match position {
+        ScriptPosition::Normal => return text.to_string(),
+        ScriptPosition::Super => (SUPERSCRIPTS, '^'),
+        ScriptPosition::Sub => (SUBSCRIPTS, '~'),
+    };
+    match encoding {
+        ScriptEncoding::Plain => text.to_string(),
+        ScriptEncoding::Unicode => {
+            let mapped: Option<String> = text.chars()
+                .map(|c| table.iter().find(|(plain, _)| *plain == c).map(|(_, script)| *script))
+                .collect();
+            mapped.unwrap_or_else(|| format!("{}{}{}", marker, text, marker))
+        }
+        ScriptEncoding::Markers => format!("{}{}{}", marker, text, marker),
+    }
+}
@@ -0,0 +1,112 @@
+// search.rs - Incremental in-editor search over the spatial buffer
+use ropey::Rope;
+use std::collections::BTreeMap;
+
+/// Find bar state with all current matches as rope char ranges
+#[derive(Debug)]
+pub struct SearchState {
+    pub open: bool,
+    pub query: String,
+    pub matches: Vec<(usize, usize)>, // (start, end) char ranges in the rope
+    pub current: usize,               // Index of the focused match
+    pub page: usize,                  // Page the matches are on
+    pub page_counts: BTreeMap<usize, usize>, // Matches per page across the document, set when the query changes
+}
+
+impl SearchState {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            matches: Vec::new(),
+            current: 0,
+            page: 1,
+            page_counts: BTreeMap::new(),
+        }
+    }
+    
+    /// Recompute matches against the current rope contents of `page`
+    pub fn update(&mut self, rope: &Rope, page: usize) {
+        self.matches = find_all(&rope.to_string(), &self.query);
+        if self.current >= self.matches.len() {
+            self.current = 0;
+        }
+        self.page = page;
+        // The viewed page's count follows its edits
+        match self.matches.len() {
+            0 => self.page_counts.remove(&page),
+            count => self.page_counts.insert(page, count),
+        };
+    }
+    
+    /// Whether stepping would wrap around this page's matches
+    pub fn at_edge(&self, backwards: bool) -> bool {
+        self.matches.is_empty()
+            || (backwards && self.current == 0)
+            || (!backwards && self.current + 1 == self.matches.len())
+    }
+    
+    /// Next other page with matches after (or before) this one, wrapping around the document
+    pub fn next_page(&self, backwards: bool) -> Option<usize> {
+        let others = self.page_counts.keys().copied().filter(|&p| p != self.page);
+        if backwards {
+            let pages: Vec<usize> = others.collect();
+            pages.iter().rev().find(|&&p| p < self.page).or(pages.last()).copied()
+        } else {
+            let pages: Vec<usize> = others.collect();
+            pages.iter().find(|&&p| p > self.page).or(pages.first()).copied()
+        }
+    }
+    
+    /// Advance to the next (or previous) match, returning its start position
+    pub fn cycle(&mut self, backwards: bool) -> Option<usize> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        self.current = if backwards {
+            (self.current + self.matches.len() - 1) % self.matches.len()
+        } else {
+            (self.current + 1) % self.matches.len()
+        };
+        Some(self.matches[self.current].0)
+    }
+    
+    /// "3 of 12" style counter for the find bar, with matches on other pages after it
+    pub fn counter_label(&self) -> String {
+        let elsewhere: usize = self.page_counts.iter().filter(|(&p, _)| p != self.page).map(|(_, count)| count).sum();
+        let pages = self.page_counts.keys().filter(|&&p| p != self.page).count();
+        let here = if self.matches.is_empty() {
+            if self.query.is_empty() || elsewhere > 0 { String::new() } else { "No matches".to_string() }
+        } else {
+            format!("{} of {}", self.current + 1, self.matches.len())
+        };
+        match elsewhere {
+            0 => here,
+            _ if here.is_empty() => format!("{} on {} other page(s)", elsewhere, pages),
+            _ => format!("{} (+{} on {} other page(s))", here, elsewhere, pages),
+        }
+    }
+}
+
+/// Case-insensitive matches of `query` in `text` as char ranges
+pub fn find_all(text: &str, query: &str) -> Vec<(usize, usize)> {
+    // Lowercase char-by-char so char offsets stay aligned with the rope
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let haystack: Vec<char> = text.chars().map(fold).collect();
+    let needle: Vec<char> = query.chars().map(fold).collect();
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return Vec::new();
+    }
+    
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i + needle.len() <= haystack.len() {
+        if haystack[i..i + needle.len()] == needle[..] {
+            matches.push((i, i + needle.len()));
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
@@ -0,0 +1,107 @@
+// snap.rs - Smart guides and grid snapping for element moves and resizes
+use eframe::egui;
+
+/// Snapping options shown in the view toolbar
+#[derive(Debug, Clone, Copy)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    pub grid: f32,       // Grid spacing in points; 0 disables the grid
+    pub threshold: f32,  // Snap distance in screen pixels
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self { enabled: true, grid: 0.0, threshold: 6.0 }
+    }
+}
+
+/// A guide line in document coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Guide {
+    Vertical(f32),    // x
+    Horizontal(f32),  // y
+}
+
+/// Which edges of a rectangle are being dragged
+#[derive(Debug, Clone, Copy)]
+pub struct Edges {
+    pub left: bool,
+    pub right: bool,
+    pub top: bool,
+    pub bottom: bool,
+}
+
+impl Edges {
+    pub const ALL: Edges = Edges { left: true, right: true, top: true, bottom: true };
+    
+    fn is_move(&self) -> bool {
+        self.left && self.right && self.top && self.bottom
+    }
+}
+
+/// Closest (delta, target) pulling one of `values` onto one of `targets` within `threshold`
+fn nearest(values: &[f32], targets: &[f32], threshold: f32) -> Option<(f32, f32)> {
+    values.iter()
+        .flat_map(|&v| targets.iter().map(move |&t| (t - v, t)))
+        .filter(|(delta, _)| delta.abs() <= threshold)
+        .min_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))
+}
+
+/// Grid line nearest to one of `values`, within `threshold`
+fn nearest_grid(values: &[f32], grid: f32, threshold: f32) -> Option<f32> {
+    if grid <= 0.0 {
+        return None;
+    }
+    values.iter()
+        .map(|&v| (v / grid).round() * grid - v)
+        .filter(|delta| delta.abs() <= threshold)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+}
+
+/// Snap the dragged edges of `rect` to edges/baselines of `others` (preferred) or the grid.
+/// `zoom` converts the pixel threshold to points. Returns the snapped rect and guides to draw.
+pub fn snap_rect(rect: egui::Rect, edges: Edges, others: &[egui::Rect], settings: &SnapSettings, zoom: f32) -> (egui::Rect, Vec<Guide>) {
+    if !settings.enabled {
+        return (rect, Vec::new());
+    }
+    let threshold = settings.threshold / zoom;
+    let x_targets: Vec<f32> = others.iter().flat_map(|r| [r.min.x, r.max.x]).collect();
+    let y_targets: Vec<f32> = others.iter().flat_map(|r| [r.min.y, r.max.y]).collect(); // Tops and baselines
+    
+    let mut snapped = rect;
+    let mut guides = Vec::new();
+    
+    let mut x_values = Vec::new();
+    if edges.left { x_values.push(rect.min.x); }
+    if edges.right { x_values.push(rect.max.x); }
+    let mut y_values = Vec::new();
+    if edges.top { y_values.push(rect.min.y); }
+    if edges.bottom { y_values.push(rect.max.y); }
+    
+    let x_delta = match nearest(&x_values, &x_targets, threshold) {
+        Some((delta, target)) => {
+            guides.push(Guide::Vertical(target));
+            Some(delta)
+        }
+        None => nearest_grid(&x_values, settings.grid, threshold),
+    };
+    let y_delta = match nearest(&y_values, &y_targets, threshold) {
+        Some((delta, target)) => {
+            guides.push(Guide::Horizontal(target));
+            Some(delta)
+        }
+        None => nearest_grid(&y_values, settings.grid, threshold),
+    };
+    
+    if edges.is_move() {
+        snapped = snapped.translate(egui::vec2(x_delta.unwrap_or(0.0), y_delta.unwrap_or(0.0)));
+    } else {
+        if let Some(dx) = x_delta {
+            if edges.left { snapped.min.x += dx; } else { snapped.max.x += dx; }
+        }
+        if let Some(dy) = y_delta {
+            if edges.top { snapped.min.y += dy; } else { snapped.max.y += dy; }
+        }
+    }
+    (snapped, guides)
+}
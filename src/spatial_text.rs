@@ -1,7 +1,16 @@
 // spatial_text.rs - Core WYSIWYG spatial text editing system
+use crate::{bidi, cjk};
 use eframe::egui;
 use ropey::Rope;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of buffer revisions; shared by all buffers so a revision never repeats across pages
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
 
 /// Maps a range in the unified text buffer to spatial positioning
 #[derive(Debug, Clone)]
@@ -11,8 +20,26 @@ pub struct ElementRange {
     pub element_id: usize,        // Original ALTO element index
     pub visual_bounds: egui::Rect, // Current display bounds
     pub original_bounds: egui::Rect, // Original ALTO bounds
+    pub original_text: String,    // Extracted CONTENT, for reverting
     pub overflow: bool,           // Text exceeds original bounds
     pub modified: bool,           // Has been edited from original
+    pub modified_at: Option<u64>, // Unix seconds of the last edit, for the change report
+    pub pending_from: Option<String>, // Text before tracked edits, until accepted or rejected
+}
+
+impl ElementRange {
+    /// Flag as edited and stamp the time
+    fn mark_modified(&mut self) {
+        self.modified = true;
+        self.modified_at = Some(unix_now());
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 /// Fast spatial lookup index for coordinate queries
@@ -62,6 +89,29 @@ impl SpatialIndex {
     }
 }
 
+/// Document <-> screen mapping shared by painting and hit-testing
+#[derive(Debug, Clone, Copy)]
+pub struct CoordinateTransform {
+    pub zoom: f32,       // Screen pixels per document point
+    pub pan: egui::Vec2, // Screen position of the document origin
+}
+
+impl Default for CoordinateTransform {
+    fn default() -> Self {
+        Self { zoom: 1.0, pan: egui::Vec2::ZERO }
+    }
+}
+
+impl CoordinateTransform {
+    pub fn to_document(&self, screen_pos: egui::Pos2) -> egui::Pos2 {
+        (screen_pos - self.pan) / self.zoom
+    }
+
+    pub fn to_screen(&self, doc_pos: egui::Pos2) -> egui::Pos2 {
+        doc_pos * self.zoom + self.pan
+    }
+}
+
 /// Main spatial text buffer that bridges linear editing and 2D layout
 #[derive(Debug)]
 pub struct SpatialTextBuffer {
@@ -70,8 +120,12 @@ pub struct SpatialTextBuffer {
     pub spatial_index: SpatialIndex,         // Fast spatial queries
     pub cursor_pos: usize,                   // Current cursor position in rope
     pub selection: Option<(usize, usize)>,   // Selection range in rope
-    pub zoom: f32,                           // Current zoom level
-    pub pan: egui::Vec2,                     // Current pan offset
+    pub transform: CoordinateTransform,      // Current zoom and pan
+    pub proportional: bool,                  // Proportional font rendering
+    pub vertical_cjk: bool,                  // Lay out tall CJK boxes top to bottom
+    pub glyph_advances: HashMap<char, f32>,  // Measured advances for the current font
+    pub track_changes: bool,                 // Keep pre-edit text so edits can be rejected
+    pub revision: u64,                       // Changes with every edit, for caches derived from the text
 }
 
 impl SpatialTextBuffer {
@@ -82,8 +136,12 @@ impl SpatialTextBuffer {
             spatial_index: SpatialIndex::new(),
             cursor_pos: 0,
             selection: None,
-            zoom: 1.0,
-            pan: egui::Vec2::ZERO,
+            transform: CoordinateTransform::default(),
+            proportional: false,
+            vertical_cjk: true,
+            glyph_advances: HashMap::new(),
+            track_changes: false,
+            revision: next_revision(),
         }
     }
     
@@ -121,8 +179,11 @@ impl SpatialTextBuffer {
                     egui::pos2(*hpos, *vpos), 
                     egui::vec2(*width, *height)
                 ),
+                original_text: content.clone(),
                 overflow: false,
                 modified: false,
+                modified_at: None,
+                pending_from: None,
             };
             
             buffer.element_ranges.push(element_range);
@@ -147,38 +208,62 @@ impl SpatialTextBuffer {
             // Calculate position within element
             let local_pos = doc_pos - element.visual_bounds.min;
             
-            // Better character positioning that accounts for accumulation error
-            let element_text_len = (element.rope_end - element.rope_start) as f32;
+            let text = self.rope.slice(element.rope_start..element.rope_end).to_string();
             
-            let char_offset = if element_text_len > 0.0 {
-                // Use proportional positioning instead of fixed char width
-                let relative_x = local_pos.x / element.visual_bounds.width();
-                ((relative_x * element_text_len) as usize).min(element_text_len as usize)
-            } else {
-                0
-            };
+            // Vertical blocks: one glyph cell per char, top to bottom
+            if self.is_vertical(element, &text) {
+                let chars = text.trim_end().chars().count();
+                let relative_y = local_pos.y / element.visual_bounds.height();
+                return Some(element.rope_start + ((relative_y * chars as f32).round() as usize).min(chars));
+            }
             
-            Some(element.rope_start + char_offset)
+            // Offsets below are visual boundaries; RTL runs map back to logical order at the end
+            let order = bidi::visual_order(&text);
+            let offsets = self.char_offsets(element, &order.display(&text));
+            // Snap to the nearest character boundary
+            let char_offset = offsets.iter()
+                .enumerate()
+                .min_by(|a, b| (a.1 - local_pos.x).abs().partial_cmp(&(b.1 - local_pos.x).abs()).unwrap())
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            
+            Some(element.rope_start + order.caret_to_logical(char_offset))
         } else {
             None
         }
     }
     
+    /// Index of the element under a screen position, if any
+    pub fn element_at_screen_position(&self, screen_pos: egui::Pos2) -> Option<usize> {
+        self.spatial_index.find_element_at_position(self.screen_to_document_pos(screen_pos))
+    }
+    
     /// Convert rope position to screen coordinates
     pub fn rope_to_screen_position(&self, rope_pos: usize) -> Option<egui::Pos2> {
         // Find which element contains this rope position
         for element in &self.element_ranges {
             if rope_pos >= element.rope_start && rope_pos < element.rope_end {
-                let char_offset = rope_pos - element.rope_start;
-                let element_text_len = element.rope_end - element.rope_start;
+                let text = self.rope.slice(element.rope_start..element.rope_end).to_string();
                 
-                // Calculate position within element with matching offset compensation
-                let char_width = if element_text_len > 0 {
-                    element.visual_bounds.width() / element_text_len as f32
-                } else {
-                    8.0
-                };
-                let local_x = (char_offset as f32 * char_width) + 5.0; // Apply same offset compensation
+                if self.is_vertical(element, &text) {
+                    let chars = text.trim_end().chars().count().max(1);
+                    let local_y = (rope_pos - element.rope_start) as f32 * element.visual_bounds.height() / chars as f32;
+                    let doc_pos = element.visual_bounds.min + egui::vec2(0.0, local_y);
+                    return Some(self.document_to_screen_pos(doc_pos));
+                }
+                
+                let order = bidi::visual_order(&text);
+                // Caret sits at a visual boundary, which differs from the logical one inside RTL runs
+                let char_offset = order.caret_to_visual(rope_pos - element.rope_start);
+                let local_x = self.char_offsets(element, &order.display(&text))[char_offset];
+                
+                if self.proportional && !self.glyph_advances.is_empty() {
+                    let doc_pos = element.visual_bounds.min + egui::vec2(local_x, 0.0);
+                    return Some(self.document_to_screen_pos(doc_pos));
+                }
+                
+                // Monospace keeps its offset compensation
+                let local_x = local_x + 5.0;
                 
                 // Transform to screen coordinates
                 let doc_pos = element.visual_bounds.min + egui::vec2(local_x, 0.0);
@@ -188,18 +273,169 @@ impl SpatialTextBuffer {
         None
     }
     
+    /// Caret one step left or right on screen: visual order inside RTL runs, logical elsewhere
+    pub fn visual_step(&self, rope_pos: usize, right: bool) -> usize {
+        let len = self.rope.len_chars();
+        let element = self.element_ranges.iter().find(|e| rope_pos >= e.rope_start && rope_pos < e.rope_end);
+        let mut forward = right;
+        if let Some(element) = element {
+            let text = self.rope.slice(element.rope_start..element.rope_end.min(len)).to_string();
+            if bidi::has_rtl(&text) {
+                if let Some(local) = bidi::visual_order(&text).step(rope_pos - element.rope_start, right) {
+                    return element.rope_start + local;
+                }
+                // Leaving an RTL element: right goes back in reading order
+                forward = right != bidi::is_rtl_base(&text);
+            }
+        }
+        if forward { (rope_pos + 1).min(len) } else { rope_pos.saturating_sub(1) }
+    }
+    
+    /// Document-space highlight rects for a logical selection; RTL runs may split into several
+    pub fn selection_rects(&self, start: usize, end: usize) -> Vec<egui::Rect> {
+        let mut rects = Vec::new();
+        let len = self.rope.len_chars();
+        for element in &self.element_ranges {
+            // Ranges can run past the rope while an edit is being applied
+            let element_end = element.rope_end.min(len);
+            let from = start.max(element.rope_start);
+            let to = end.min(element_end);
+            if from >= to {
+                continue;
+            }
+            let text = self.rope.slice(element.rope_start..element_end).to_string();
+            let order = bidi::visual_order(&text);
+            let offsets = self.char_offsets(element, &order.display(&text));
+            let mut slots: Vec<usize> = (from - element.rope_start..to - element.rope_start).map(|i| order.slot(i)).collect();
+            slots.sort_unstable();
+            // Merge adjacent visual slots into one rect
+            let mut run_start = slots[0];
+            for (i, &slot) in slots.iter().enumerate() {
+                let run_ends = slots.get(i + 1) != Some(&(slot + 1));
+                if run_ends {
+                    let x0 = offsets.get(run_start).copied().unwrap_or(0.0);
+                    let x1 = offsets.get(slot + 1).copied().unwrap_or(x0);
+                    rects.push(egui::Rect::from_x_y_ranges(
+                        element.visual_bounds.min.x + x0..=element.visual_bounds.min.x + x1,
+                        element.visual_bounds.y_range(),
+                    ));
+                    if let Some(&next) = slots.get(i + 1) {
+                        run_start = next;
+                    }
+                }
+            }
+        }
+        rects
+    }
+    
+    /// Rope position of the leftmost element on each visual row, top to bottom; an element joins
+    /// the row above when its vertical centre falls within that row's first box
+    pub fn row_starts(&self) -> Vec<usize> {
+        let mut by_top: Vec<&ElementRange> = self.element_ranges.iter().collect();
+        by_top.sort_by(|a, b| a.visual_bounds.min.y.total_cmp(&b.visual_bounds.min.y));
+        let mut rows: Vec<(f32, &ElementRange)> = Vec::new(); // (row bottom, leftmost element)
+        for range in by_top {
+            match rows.last_mut() {
+                Some((bottom, leftmost)) if range.visual_bounds.center().y <= *bottom => {
+                    if range.visual_bounds.min.x < leftmost.visual_bounds.min.x {
+                        *leftmost = range;
+                    }
+                }
+                _ => rows.push((range.visual_bounds.max.y, range)),
+            }
+        }
+        rows.into_iter().map(|(_, leftmost)| leftmost.rope_start).collect()
+    }
+    
+    /// Rope positions of the separator spaces inserted between elements (not in the PDF text)
+    pub fn inferred_spaces(&self) -> HashSet<usize> {
+        let len = self.rope.len_chars();
+        self.element_ranges.iter()
+            .take(self.element_ranges.len().saturating_sub(1))
+            .filter(|element| element.rope_end > element.rope_start && element.rope_end <= len)
+            .map(|element| element.rope_end - 1)
+            .filter(|&pos| self.rope.char(pos) == ' ')
+            .collect()
+    }
+    
+    /// Cache glyph advances for every character in the rope (proportional mode)
+    pub fn measure_glyphs(&mut self, mut measure: impl FnMut(char) -> f32) {
+        if !self.glyph_advances.contains_key(&' ') {
+            self.glyph_advances.insert(' ', measure(' '));
+        }
+        for c in self.rope.chars() {
+            if !self.glyph_advances.contains_key(&c) {
+                self.glyph_advances.insert(c, measure(c));
+            }
+        }
+    }
+    
+    /// Whether an element is laid out as a vertical CJK block
+    pub fn is_vertical(&self, element: &ElementRange, text: &str) -> bool {
+        self.vertical_cjk && cjk::is_vertical(element.visual_bounds, text)
+    }
+    
+    /// X offset of every character boundary of an element's (visual-order) text: measured
+    /// advances in proportional mode, otherwise monospace cells spread over the box width,
+    /// with full-width CJK glyphs taking two cells
+    fn char_offsets(&self, element: &ElementRange, text: &str) -> Vec<f32> {
+        if self.proportional && !self.glyph_advances.is_empty() {
+            return self.boundary_offsets(text);
+        }
+        let cells = cjk::cell_offsets(text);
+        let total = cells.last().copied().unwrap_or(0);
+        let cell_width = if total > 0 { element.visual_bounds.width() / total as f32 } else { 8.0 };
+        cells.into_iter().map(|c| c as f32 * cell_width).collect()
+    }
+    
+    /// X offset of every character boundary in `text`, with tabs snapping to 4-space stops
+    fn boundary_offsets(&self, text: &str) -> Vec<f32> {
+        let space = self.glyph_advances.get(&' ').copied().unwrap_or(8.0);
+        let tab_width = space * 4.0;
+        let mut offsets = vec![0.0];
+        let mut x = 0.0;
+        for c in text.chars() {
+            x = if c == '\t' {
+                ((x / tab_width).floor() + 1.0) * tab_width
+            } else {
+                x + self.glyph_advances.get(&c).copied().unwrap_or(space * cjk::cell_width(c) as f32)
+            };
+            offsets.push(x);
+        }
+        offsets
+    }
+    
+    /// Move or resize an element, flagging it as edited, and refresh the spatial index
+    pub fn set_element_bounds(&mut self, element_idx: usize, bounds: egui::Rect) {
+        if let Some(range) = self.element_ranges.get_mut(element_idx) {
+            self.spatial_index.mark_dirty_region(range.visual_bounds);
+            self.spatial_index.mark_dirty_region(bounds);
+            range.visual_bounds = bounds;
+            range.mark_modified();
+            self.spatial_index.rebuild(&self.element_ranges);
+            self.revision = next_revision();
+        }
+    }
+    
+    /// Document-space rectangle in screen coordinates
+    pub fn document_rect_to_screen(&self, rect: egui::Rect) -> egui::Rect {
+        egui::Rect::from_min_max(self.document_to_screen_pos(rect.min), self.document_to_screen_pos(rect.max))
+    }
+    
     /// Screen coordinate transformations
     fn screen_to_document_pos(&self, screen_pos: egui::Pos2) -> egui::Pos2 {
-        (screen_pos - self.pan) / self.zoom
+        self.transform.to_document(screen_pos)
     }
     
     fn document_to_screen_pos(&self, doc_pos: egui::Pos2) -> egui::Pos2 {
-        doc_pos * self.zoom + self.pan
+        self.transform.to_screen(doc_pos)
     }
     
     /// Insert text at rope position and update spatial mappings
     pub fn insert_text(&mut self, pos: usize, text: &str) {
         let insert_len = text.chars().count();
+        self.record_pending(pos, pos);
+        self.revision = next_revision();
         
         // Insert into rope
         self.rope.insert(pos, text);
@@ -211,7 +447,7 @@ impl SpatialTextBuffer {
                 element.rope_end += insert_len;
             } else if element.rope_end > pos {
                 element.rope_end += insert_len;
-                element.modified = true;
+                element.mark_modified();
                 
                 // Check for overflow (defer text_exceeds_bounds call to avoid borrow issues)
                 element.overflow = true; // Mark for later overflow check
@@ -241,6 +477,8 @@ impl SpatialTextBuffer {
     /// Delete text range and update spatial mappings
     pub fn delete_range(&mut self, start: usize, end: usize) {
         let delete_len = end - start;
+        self.record_pending(start, end);
+        self.revision = next_revision();
         
         // Delete from rope
         self.rope.remove(start..end);
@@ -261,7 +499,7 @@ impl SpatialTextBuffer {
                 } else {
                     element.rope_end = start;
                 }
-                element.modified = true;
+                element.mark_modified();
             }
         }
         
@@ -269,13 +507,273 @@ impl SpatialTextBuffer {
         self.spatial_index.rebuild(&self.element_ranges);
     }
     
+    /// Merge consecutive elements `first..=last` into one with the union of their bounds.
+    /// Touching boxes (a word broken in two) are joined without the separator space;
+    /// returns the rope positions of removed separators, back to front.
+    pub fn merge_elements(&mut self, first: usize, last: usize) -> Option<Vec<usize>> {
+        if first >= last || last >= self.element_ranges.len() {
+            return None;
+        }
+        let mut removed = Vec::new();
+        
+        // Drop separators back to front so earlier positions stay valid
+        for k in (first..last).rev() {
+            let current = self.element_ranges[k].visual_bounds;
+            let next = self.element_ranges[k + 1].visual_bounds;
+            let same_line = (next.center().y - current.center().y).abs() < current.height() / 2.0;
+            let touching = same_line && next.min.x - current.max.x < current.height() * 0.3;
+            let separator = self.element_ranges[k].rope_end.saturating_sub(1);
+            if touching && self.rope.get_char(separator) == Some(' ') {
+                self.delete_range(separator, separator + 1);
+                removed.push(separator);
+            }
+        }
+        
+        let merged_text = self.element_ranges[first..=last].iter()
+            .map(|r| r.original_text.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let merged_bounds = self.element_ranges[first..=last].iter()
+            .fold(self.element_ranges[first].visual_bounds, |acc, r| acc.union(r.visual_bounds));
+        let rope_end = self.element_ranges[last].rope_end;
+        let range = &mut self.element_ranges[first];
+        range.rope_end = rope_end;
+        range.visual_bounds = merged_bounds;
+        range.original_text = merged_text;
+        range.mark_modified();
+        range.overflow = false;
+        self.element_ranges.drain(first + 1..=last);
+        self.spatial_index.rebuild(&self.element_ranges);
+        self.revision = next_revision();
+        Some(removed)
+    }
+    
+    /// Add a new element in reading position (before the first element that starts
+    /// below it, or right of it on the same line); returns (range index, rope position, chars inserted)
+    pub fn insert_element(&mut self, element_id: usize, text: &str, bounds: egui::Rect) -> (usize, usize, usize) {
+        let idx = self.element_ranges.iter()
+            .position(|e| {
+                let same_line = (e.visual_bounds.center().y - bounds.center().y).abs() < bounds.height() / 2.0;
+                if same_line { e.visual_bounds.min.x > bounds.min.x } else { e.visual_bounds.min.y > bounds.min.y }
+            })
+            .unwrap_or(self.element_ranges.len());
+        
+        let text_len = text.chars().count();
+        let (rope_pos, rope_start, rope_end) = if idx < self.element_ranges.len() {
+            // New text plus its separator goes in front of the following element
+            let pos = self.element_ranges[idx].rope_start;
+            self.rope.insert(pos, &format!("{} ", text));
+            for range in &mut self.element_ranges[idx..] {
+                range.rope_start += text_len + 1;
+                range.rope_end += text_len + 1;
+            }
+            (pos, pos, pos + text_len + 1)
+        } else {
+            // Appended: the previous last element gains a separator
+            let pos = self.rope.len_chars();
+            let separator = if idx > 0 { " " } else { "" };
+            self.rope.insert(pos, &format!("{}{}", separator, text));
+            if let Some(last) = self.element_ranges.last_mut() {
+                last.rope_end += separator.len();
+            }
+            (pos, pos + separator.len(), pos + separator.len() + text_len)
+        };
+        
+        self.element_ranges.insert(idx, ElementRange {
+            rope_start,
+            rope_end,
+            element_id,
+            visual_bounds: bounds,
+            original_bounds: bounds,
+            original_text: text.to_string(),
+            overflow: false,
+            modified: true,
+            modified_at: Some(unix_now()),
+            pending_from: None,
+        });
+        self.spatial_index.rebuild(&self.element_ranges);
+        self.revision = next_revision();
+        (idx, rope_pos, rope_end - rope_pos)
+    }
+    
+    /// Split the element containing `rope_pos` in two, dividing its bounds in proportion
+    /// to the characters on each side. A space is inserted as separator unless one is
+    /// already there; returns (index of the second element, whether a space was inserted).
+    pub fn split_element(&mut self, rope_pos: usize) -> Option<(usize, bool)> {
+        let idx = self.element_ranges.iter()
+            .position(|e| rope_pos > e.rope_start && rope_pos < e.rope_end)?;
+        let text = self.element_text(idx)?;
+        let start = self.element_ranges[idx].rope_start;
+        let text_len = text.chars().count();
+        
+        // Split point measured in characters from the element start
+        let mut offset = rope_pos - start;
+        let chars: Vec<char> = text.chars().collect();
+        let inserted = if chars.get(offset) == Some(&' ') {
+            offset += 1;
+            false
+        } else if offset > 0 && chars.get(offset - 1) == Some(&' ') {
+            false
+        } else {
+            true
+        };
+        if offset == 0 || offset >= text_len {
+            return None;
+        }
+        if inserted {
+            self.insert_text(start + offset, " ");
+            offset += 1;
+        }
+        
+        let range = self.element_ranges[idx].clone();
+        let bounds = range.visual_bounds;
+        let ratio = offset as f32 / (text_len + inserted as usize) as f32;
+        let split_x = bounds.min.x + bounds.width() * ratio;
+        // Originals split at the same character count where they still line up
+        let original_count = range.original_text.chars().count();
+        let original_offset = offset.min(original_count);
+        let original_split = range.original_text.char_indices()
+            .nth(original_offset)
+            .map_or(range.original_text.len(), |(i, _)| i);
+        let original_bounds = range.original_bounds;
+        let original_x = original_bounds.min.x + original_bounds.width() * original_offset as f32 / original_count.max(1) as f32;
+        let (original_first, original_second) = range.original_text.split_at(original_split);
+        let (original_first, original_second) = (original_first.trim().to_string(), original_second.trim().to_string());
+        let mut first = range.clone();
+        first.rope_end = start + offset;
+        first.original_text = original_first;
+        first.visual_bounds = egui::Rect::from_min_max(bounds.min, egui::pos2(split_x, bounds.max.y));
+        first.original_bounds = egui::Rect::from_min_max(original_bounds.min, egui::pos2(original_x, original_bounds.max.y));
+        first.mark_modified();
+        first.overflow = false;
+        let mut second = range;
+        second.rope_start = start + offset;
+        second.original_text = original_second;
+        second.visual_bounds = egui::Rect::from_min_max(egui::pos2(split_x, bounds.min.y), bounds.max);
+        second.original_bounds = egui::Rect::from_min_max(egui::pos2(original_x, original_bounds.min.y), original_bounds.max);
+        second.mark_modified();
+        second.overflow = false;
+        
+        self.element_ranges[idx] = first;
+        self.element_ranges.insert(idx + 1, second);
+        self.spatial_index.rebuild(&self.element_ranges);
+        self.revision = next_revision();
+        Some((idx + 1, inserted))
+    }
+    
+    /// With track changes on, remember the text of elements an edit of `start..end` touches
+    fn record_pending(&mut self, start: usize, end: usize) {
+        if !self.track_changes {
+            return;
+        }
+        for idx in 0..self.element_ranges.len() {
+            let range = &self.element_ranges[idx];
+            if range.pending_from.is_none() && range.rope_start <= end && range.rope_end > start {
+                self.element_ranges[idx].pending_from = self.element_text(idx);
+            }
+        }
+    }
+    
+    /// Swap an element's text in the rope, shifting later ranges; returns (rope start, old length, new length)
+    fn replace_element_text(&mut self, element_idx: usize, text: &str) -> Option<(usize, usize, usize)> {
+        let current = self.element_text(element_idx)?;
+        let start = self.element_ranges[element_idx].rope_start;
+        let (old_len, new_len) = (current.chars().count(), text.chars().count());
+        self.revision = next_revision();
+        
+        self.rope.remove(start..start + old_len);
+        self.rope.insert(start, text);
+        for (i, range) in self.element_ranges.iter_mut().enumerate() {
+            if i == element_idx {
+                range.rope_end = range.rope_end + new_len - old_len;
+            } else if range.rope_start > start {
+                range.rope_start = range.rope_start + new_len - old_len;
+                range.rope_end = range.rope_end + new_len - old_len;
+            }
+        }
+        Some((start, old_len, new_len))
+    }
+    
+    /// Replace an element's text as an edit, keeping the extracted text to revert to
+    pub fn edit_element(&mut self, element_idx: usize, text: &str) {
+        if self.replace_element_text(element_idx, text).is_some() && text != self.element_ranges[element_idx].original_text {
+            self.element_ranges[element_idx].mark_modified();
+        }
+    }
+    
+    /// Restore an element's extracted text and bounds; returns (rope start, old length, new length)
+    pub fn revert_element(&mut self, element_idx: usize) -> Option<(usize, usize, usize)> {
+        let original = self.element_ranges.get(element_idx)?.original_text.clone();
+        let result = self.replace_element_text(element_idx, &original)?;
+        let range = &mut self.element_ranges[element_idx];
+        range.visual_bounds = range.original_bounds;
+        range.modified = false;
+        range.modified_at = None;
+        range.pending_from = None;
+        range.overflow = false;
+        self.spatial_index.rebuild(&self.element_ranges);
+        Some(result)
+    }
+    
+    /// Keep a tracked edit: the current text becomes the element's accepted state
+    pub fn accept_change(&mut self, element_idx: usize) {
+        if let Some(range) = self.element_ranges.get_mut(element_idx) {
+            range.pending_from = None;
+            self.revision = next_revision();
+        }
+    }
+    
+    /// Put back a saved tracked edit: the edited text, pending until accepted or rejected
+    pub fn restore_pending(&mut self, element_idx: usize, text: &str, pending_from: &str) {
+        if self.replace_element_text(element_idx, text).is_none() {
+            return;
+        }
+        let range = &mut self.element_ranges[element_idx];
+        range.pending_from = Some(pending_from.to_string());
+        if text != range.original_text {
+            range.mark_modified();
+        }
+        self.spatial_index.rebuild(&self.element_ranges);
+    }
+    
+    /// Undo a tracked edit back to the text it replaced; returns (rope start, old length, new length)
+    pub fn reject_change(&mut self, element_idx: usize) -> Option<(usize, usize, usize)> {
+        let before = self.element_ranges.get(element_idx)?.pending_from.clone()?;
+        let result = self.replace_element_text(element_idx, &before)?;
+        let range = &mut self.element_ranges[element_idx];
+        range.pending_from = None;
+        range.modified = before != range.original_text || range.visual_bounds != range.original_bounds;
+        if !range.modified {
+            range.modified_at = None;
+        }
+        self.spatial_index.rebuild(&self.element_ranges);
+        Some(result)
+    }
+    
+    /// Current text of an element without its trailing separator space
+    pub fn element_text(&self, element_idx: usize) -> Option<String> {
+        let element = self.element_ranges.get(element_idx)?;
+        let len = self.rope.len_chars();
+        let start = element.rope_start.min(len);
+        let end = element.rope_end.min(len).max(start);
+        Some(self.rope.slice(start..end).to_string().trim_end_matches(' ').to_string())
+    }
+    
     fn find_element_containing_position(&self, rope_pos: usize) -> Option<&ElementRange> {
         self.element_ranges.iter().find(|e| rope_pos >= e.rope_start && rope_pos < e.rope_end)
     }
     
     fn text_exceeds_bounds(&self, text: &str, bounds: &egui::Rect) -> bool {
-        // Simple width check - can be enhanced with cosmic-text measurement
-        let estimated_width = text.len() as f32 * 8.0; // Assume 8px per character
+        if self.vertical_cjk && cjk::is_vertical(*bounds, text) {
+            // Vertical blocks grow downwards, roughly one square cell per char
+            return text.trim_end().chars().count() as f32 * bounds.width() > bounds.height();
+        }
+        // Measured advances when we have them, otherwise 8px per monospace cell
+        let estimated_width = if self.proportional && !self.glyph_advances.is_empty() {
+            self.boundary_offsets(text).last().copied().unwrap_or(0.0)
+        } else {
+            cjk::cell_offsets(text).last().copied().unwrap_or(0) as f32 * 8.0
+        };
         estimated_width > bounds.width()
     }
 }
@@ -309,12 +807,12 @@ impl SpatialCursor {
         }
     }
     
-    pub fn render(&self, painter: &egui::Painter) {
+    pub fn render(&self, painter: &egui::Painter, color: egui::Color32) {
         if let Some(pos) = self.screen_pos {
             if self.visible {
                 painter.line_segment(
                     [pos, pos + egui::vec2(0.0, 15.0)],
-                    egui::Stroke::new(2.0, egui::Color32::from_rgb(40, 90, 200))
+                    egui::Stroke::new(2.0, color)
                 );
             }
         }
@@ -0,0 +1,37 @@
+// speech.rs - Text-to-speech read-back through the platform speech command
+use std::process::{Child, Command, Stdio};
+
+/// Speak text asynchronously; the returned child can be killed to stop
+pub fn speak(text: &str) -> std::io::Result<Child> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("say");
+        command.arg(text);
+        command
+    } else if cfg!(target_os = "windows") {
+        let script = format!(
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+            text.replace('\'', "''")
+        );
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command", &script]);
+        command
+    } else {
+        // speech-dispatcher, falling back to espeak when it isn't installed
+        let program = if Command::new("spd-say").arg("--version").output().is_ok() { "spd-say" } else { "espeak" };
+        let mut command = Command::new(program);
+        if program == "spd-say" {
+            command.arg("--wait");
+        }
+        command.arg(text);
+        command
+    };
+    command.stdout(Stdio::null()).stderr(Stdio::null()).spawn()
+}
+
+/// Stop any read-back in progress
+pub fn stop(child: &mut Option<Child>) {
+    if let Some(mut process) = child.take() {
+        let _ = process.kill();
+        let _ = process.wait();
+    }
+}
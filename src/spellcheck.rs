@@ -0,0 +1,112 @@
+// spellcheck.rs - Dictionary spellchecking over the rope with a per-project word list
+use crate::dictionary::{self, Dictionary};
+use ropey::Rope;
+use std::path::PathBuf;
+
+/// A misspelled word as a rope char range
+#[derive(Debug, Clone)]
+pub struct Misspelling {
+    pub start: usize,
+    pub end: usize,
+    pub word: String,
+}
+
+/// Right-click menu state for one misspelling
+#[derive(Debug, Clone)]
+pub struct SpellMenu {
+    pub misspelling: Misspelling,
+    pub suggestions: Vec<String>,
+}
+
+/// Words added during proofreading, stored next to the PDF
+#[derive(Debug, Default)]
+pub struct ProjectWords {
+    pub words: Dictionary,
+    path: PathBuf,
+}
+
+impl ProjectWords {
+    pub fn load(pdf_path: &str) -> Self {
+        let path = PathBuf::from(format!("{}.chonker-words", pdf_path));
+        let mut words = Dictionary::default();
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            words.add_words(content.lines());
+        }
+        Self { words, path }
+    }
+    
+    /// Add a word and append it to the project word file
+    pub fn add(&mut self, word: &str) -> std::io::Result<()> {
+        use std::io::Write;
+        self.words.add_words(std::iter::once(word));
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", word)
+    }
+}
+
+/// All words in the rope missing from both the system and project dictionaries
+pub fn find_misspellings(rope: &Rope, dictionary: &Dictionary, project: &ProjectWords) -> Vec<Misspelling> {
+    let text = rope.to_string();
+    let mut misspellings = Vec::new();
+    let mut char_pos = 0;
+    
+    for token in text.split(' ') {
+        for part in token.split('\n') {
+            if let Some(word) = dictionary::checkable_word(part) {
+                if !dictionary.contains(word) && !project.words.contains(word) {
+                    // Offset of the trimmed word inside its token, in chars
+                    let byte_offset = part.find(word).unwrap_or(0);
+                    let start = char_pos + part[..byte_offset].chars().count();
+                    misspellings.push(Misspelling {
+                        start,
+                        end: start + word.chars().count(),
+                        word: word.to_string(),
+                    });
+                }
+            }
+            char_pos += part.chars().count() + 1;
+        }
+    }
+    
+    misspellings
+}
+
+/// Closest dictionary words by edit distance (at most 2 edits)
+pub fn suggestions(word: &str, dictionary: &Dictionary, max: usize) -> Vec<String> {
+    let target: Vec<char> = word.to_lowercase().chars().collect();
+    let mut scored: Vec<(usize, &String)> = dictionary.words()
+        .filter(|candidate| candidate.chars().count().abs_diff(target.len()) <= 2)
+        .filter_map(|candidate| {
+            let distance = edit_distance(&target, &candidate.chars().collect::<Vec<char>>());
+            if distance <= 2 { Some((distance, candidate)) } else { None }
+        })
+        .collect();
+    scored.sort();
+    
+    let capitalized = word.chars().next().map_or(false, |c| c.is_uppercase());
+    scored.into_iter()
+        .take(max)
+        .map(|(_, candidate)| {
+            if capitalized {
+                let mut chars = candidate.chars();
+                chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+            } else {
+                candidate.clone()
+            }
+        })
+        .collect()
+}
+
+/// Levenshtein distance between two char slices
+pub fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
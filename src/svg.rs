@@ -0,0 +1,86 @@
+// svg.rs - SVG snapshot of the spatial view with selectable text
+use crate::SpatialElement;
+use eframe::egui;
+use std::fmt::Write;
+
+/// Canvas decoration carried into the snapshot
+pub enum Overlay {
+    Highlight(egui::Rect, egui::Color32), // Translucent fill under the text (tags, labels, matches)
+    Redaction(egui::Rect),                // Solid black over the text
+}
+
+fn rect_attrs(rect: &egui::Rect) -> String {
+    format!(r#"x="{}" y="{}" width="{}" height="{}""#, rect.min.x, rect.min.y, rect.width(), rect.height())
+}
+
+/// Escape text for SVG character data and attribute values
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Positioned <text> per element over its bounding box, optionally on top of a page image
+pub fn render(elements: &[SpatialElement], page_width: f32, page_height: f32, background: Option<&str>, overlays: &[Overlay]) -> String {
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = page_width,
+        h = page_height
+    );
+    let _ = writeln!(svg, r#"  <rect width="100%" height="100%" fill="white"/>"#);
+    if let Some(href) = background {
+        let _ = writeln!(
+            svg,
+            r#"  <image xlink:href="{}" x="0" y="0" width="{}" height="{}" opacity="0.35"/>"#,
+            escape(href), page_width, page_height
+        );
+    }
+
+    for overlay in overlays {
+        if let Overlay::Highlight(rect, color) = overlay {
+            let _ = writeln!(
+                svg,
+                r#"  <rect {} fill="rgb({},{},{})" fill-opacity="{:.2}"/>"#,
+                rect_attrs(rect), color.r(), color.g(), color.b(), color.a() as f32 / 255.0
+            );
+        }
+    }
+
+    let _ = writeln!(svg, r##"  <g fill="none" stroke="#7aa7d8" stroke-width="0.5">"##);
+    for element in elements {
+        let stroke = if element.modified { r##" stroke="#e0a030""## } else { "" };
+        let _ = writeln!(
+            svg,
+            r#"    <rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+            element.hpos, element.vpos, element.width, element.height, stroke
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+
+    // Font size follows element height so the text roughly fills its box
+    let _ = writeln!(svg, r#"  <g font-family="sans-serif" fill="black">"#);
+    for element in elements {
+        let font_size = (element.height * 0.8).max(4.0);
+        let _ = writeln!(
+            svg,
+            r#"    <text x="{}" y="{}" font-size="{:.1}" textLength="{}" lengthAdjust="spacingAndGlyphs">{}</text>"#,
+            element.hpos,
+            element.vpos + element.height * 0.8,
+            font_size,
+            element.width.max(1.0),
+            escape(element.content.trim())
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+
+    for overlay in overlays {
+        if let Overlay::Redaction(rect) = overlay {
+            let _ = writeln!(svg, r#"  <rect {} fill="black"/>"#, rect_attrs(rect));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
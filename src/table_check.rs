@@ -0,0 +1,179 @@
+// table_check.rs - Validate numeric columns of marked tables and their Total rows
+use crate::xlsx;
+
+/// What's wrong with a cell in a numeric column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellIssue {
+    NotNumeric,
+    LetterForDigit,  // O for 0, l/I for 1
+    StrayComma,      // Separator not followed by a group of three digits
+}
+
+impl CellIssue {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CellIssue::NotNumeric => "not a number",
+            CellIssue::LetterForDigit => "letter mixed with digits (O/0, l/1)",
+            CellIssue::StrayComma => "stray comma",
+        }
+    }
+}
+
+/// A printed total compared with the sum of the cells above it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TotalCheck {
+    pub row: usize,
+    pub printed: f64,
+    pub computed: f64,
+}
+
+impl TotalCheck {
+    /// Equal to the cent
+    pub fn passes(&self) -> bool {
+        (self.printed - self.computed).abs() < 0.005
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnCheck {
+    pub column: usize,
+    pub issues: Vec<(usize, CellIssue)>,  // (row, issue)
+    pub totals: Vec<TotalCheck>,
+}
+
+impl ColumnCheck {
+    /// Some(pass) when the column has Total rows
+    pub fn totals_pass(&self) -> Option<bool> {
+        (!self.totals.is_empty()).then(|| self.totals.iter().all(TotalCheck::passes))
+    }
+}
+
+const LOOKALIKES: &[char] = &['O', 'o', 'l', 'I'];
+
+fn cell_issue(cell: &str) -> Option<CellIssue> {
+    let has_digit = cell.chars().any(|c| c.is_ascii_digit());
+    if has_digit && cell.chars().any(|c| LOOKALIKES.contains(&c)) {
+        return Some(CellIssue::LetterForDigit);
+    }
+    if xlsx::parse_number(cell).is_none() {
+        return Some(CellIssue::NotNumeric);
+    }
+    // Every comma must start a three-digit group ending at another separator or the end
+    let chars: Vec<char> = cell.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ',' {
+            let group = chars[i + 1..].iter().take_while(|c| c.is_ascii_digit()).count();
+            let leading = i > 0 && chars[i - 1].is_ascii_digit();
+            if group != 3 || !leading {
+                return Some(CellIssue::StrayComma);
+            }
+        }
+    }
+    None
+}
+
+/// Label of a Total row, lowercased
+fn total_label(row: &[String]) -> Option<String> {
+    row.iter().find(|cell| !cell.trim().is_empty())
+        .map(|cell| cell.to_lowercase())
+        .filter(|label| label.contains("total"))
+}
+
+/// Check each column whose body cells are mostly numbers; row 0 is the header.
+/// A Total row is compared with the sum of the rows since the previous Total; a grand total
+/// (labelled "grand", or following other totals directly) with the subtotals since the last
+/// grand total plus any rows no subtotal covered
+pub fn validate(rows: &[Vec<String>]) -> Vec<ColumnCheck> {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut checks = Vec::new();
+    for column in 0..columns {
+        let body: Vec<(usize, &str)> = rows.iter().enumerate().skip(1)
+            .filter_map(|(r, row)| Some((r, row.get(column)?.trim())))
+            .filter(|(_, cell)| !cell.is_empty())
+            .collect();
+        let numeric = body.iter().filter(|(_, cell)| xlsx::parse_number(cell).is_some()).count();
+        if body.is_empty() || numeric * 2 <= body.len() {
+            continue;
+        }
+
+        let mut check = ColumnCheck { column, issues: Vec::new(), totals: Vec::new() };
+        let mut running = 0.0;    // Rows since the previous Total
+        let mut rows_since = 0;
+        let mut subtotals = 0.0;  // Printed subtotals since the previous grand total
+        let mut subtotal_count = 0;
+        for &(r, cell) in &body {
+            if let Some(issue) = cell_issue(cell) {
+                check.issues.push((r, issue));
+            }
+            let value = xlsx::parse_number(cell).unwrap_or(0.0);
+            let Some(label) = total_label(&rows[r]) else {
+                running += value;
+                rows_since += 1;
+                continue;
+            };
+            let grand = subtotal_count > 0 && (label.contains("grand") || rows_since == 0);
+            if grand {
+                check.totals.push(TotalCheck { row: r, printed: value, computed: subtotals + running });
+                subtotals = 0.0;
+                subtotal_count = 0;
+            } else {
+                check.totals.push(TotalCheck { row: r, printed: value, computed: running });
+                subtotals += value;
+                subtotal_count += 1;
+            }
+            running = 0.0;
+            rows_since = 0;
+        }
+        checks.push(check);
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[(&str, &str)]) -> Vec<Vec<String>> {
+        rows.iter().map(|(label, amount)| vec![label.to_string(), amount.to_string()]).collect()
+    }
+
+    #[test]
+    fn total_matches_the_rows_above() {
+        let checks = validate(&table(&[("Item", "Amount"), ("a", "10"), ("b", "20.50"), ("Total", "30.50")]));
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].column, 1);
+        assert!(checks[0].issues.is_empty());
+        assert_eq!(checks[0].totals, vec![TotalCheck { row: 3, printed: 30.5, computed: 30.5 }]);
+        assert_eq!(checks[0].totals_pass(), Some(true));
+    }
+
+    #[test]
+    fn grand_total_sums_subtotals_and_uncovered_rows() {
+        let checks = validate(&table(&[
+            ("Item", "Amount"), ("a", "10"), ("b", "20"), ("Subtotal", "30"),
+            ("c", "5"), ("Subtotal", "5"), ("d", "1"), ("Grand Total", "36"),
+        ]));
+        let totals: Vec<(usize, f64)> = checks[0].totals.iter().map(|t| (t.row, t.computed)).collect();
+        assert_eq!(totals, vec![(3, 30.0), (5, 5.0), (7, 36.0)]);
+        assert_eq!(checks[0].totals_pass(), Some(true));
+    }
+
+    #[test]
+    fn wrong_total_fails() {
+        let checks = validate(&table(&[("Item", "Amount"), ("a", "10"), ("b", "20"), ("Total", "31")]));
+        assert_eq!(checks[0].totals_pass(), Some(false));
+    }
+
+    #[test]
+    fn flags_lookalike_letters_and_stray_commas() {
+        let checks = validate(&table(&[("Item", "Amount"), ("a", "1O0"), ("b", "1,00"), ("c", "7"), ("d", "8"), ("e", "9")]));
+        assert_eq!(checks[0].issues, vec![(1, CellIssue::LetterForDigit), (2, CellIssue::StrayComma)]);
+        assert_eq!(checks[0].totals_pass(), None);
+    }
+
+    #[test]
+    fn text_columns_are_skipped() {
+        let checks = validate(&table(&[("Name", "City"), ("a", "Oslo"), ("b", "Rome"), ("c", "12")]));
+        assert!(checks.is_empty());
+    }
+}
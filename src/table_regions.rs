@@ -0,0 +1,107 @@
+// table_regions.rs - Hand-drawn table regions that override table detection
+use crate::{layout, SpatialElement};
+use eframe::egui;
+use std::path::PathBuf;
+
+/// A rectangle declared as a table with a fixed number of columns, in PDF points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TableRegion {
+    pub page: usize,
+    pub rect: egui::Rect,
+    pub columns: usize,
+}
+
+impl TableRegion {
+    /// Judged by the element's center, on the region's own page
+    pub fn contains(&self, element: &SpatialElement) -> bool {
+        element.page == self.page && self.rect.contains(egui::pos2(element.hpos + element.width / 2.0, element.vpos + element.height / 2.0))
+    }
+
+    /// x positions between columns: midpoints of the widest gaps between the enclosed words
+    pub fn column_dividers(&self, elements: &[&SpatialElement]) -> Vec<f32> {
+        let mut spans: Vec<(f32, f32)> = elements.iter()
+            .filter(|e| self.contains(e))
+            .map(|e| (e.hpos, e.hpos + e.width))
+            .collect();
+        spans.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        // Sweep left to right, recording gaps not covered by any word
+        let mut gaps: Vec<(f32, f32)> = Vec::new(); // (width, midpoint)
+        let mut reach = match spans.first() {
+            Some(&(_, right)) => right,
+            None => return Vec::new(),
+        };
+        for &(left, right) in &spans[1..] {
+            if left > reach {
+                gaps.push((left - reach, (left + reach) / 2.0));
+            }
+            reach = reach.max(right);
+        }
+        gaps.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let mut dividers: Vec<f32> = gaps.iter().take(self.columns.saturating_sub(1)).map(|&(_, mid)| mid).collect();
+        dividers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        dividers
+    }
+
+    /// Rows of exactly `columns` cells; words sharing a cell are joined with spaces
+    pub fn cells(&self, elements: &[&SpatialElement]) -> Vec<Vec<String>> {
+        let dividers = self.column_dividers(elements);
+        let inside: Vec<&SpatialElement> = elements.iter().copied().filter(|e| self.contains(e)).collect();
+        layout::group_into_lines(&inside).iter()
+            .map(|line| {
+                let mut row = vec![String::new(); self.columns.max(1)];
+                for element in line {
+                    let centre = element.hpos + element.width / 2.0;
+                    let column = dividers.iter().filter(|&&x| centre > x).count().min(row.len() - 1);
+                    if !row[column].is_empty() {
+                        row[column].push(' ');
+                    }
+                    row[column].push_str(element.content.trim());
+                }
+                row
+            })
+            .collect()
+    }
+}
+
+/// When any region is drawn on a page it replaces detection there: only enclosed elements are table cells
+pub fn is_table_element(regions: &[TableRegion], element: &SpatialElement) -> bool {
+    if regions.iter().any(|region| region.page == element.page) {
+        regions.iter().any(|region| region.contains(element))
+    } else {
+        layout::is_table_element(element)
+    }
+}
+
+/// Drop the region under a document point, returning whether one was removed
+pub fn remove_at(regions: &mut Vec<TableRegion>, pos: egui::Pos2) -> bool {
+    let before = regions.len();
+    regions.retain(|r| !r.rect.contains(pos));
+    regions.len() != before
+}
+
+fn sidecar_path(pdf_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.chonker-tables", pdf_path))
+}
+
+/// Regions saved for a page (JSON array of [x0, y0, x1, y1, columns])
+pub fn load(pdf_path: &str, page: usize) -> Vec<TableRegion> {
+    let regions: Vec<[f32; 5]> = std::fs::read_to_string(sidecar_path(pdf_path))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    regions.iter()
+        .map(|r| TableRegion {
+            page,
+            rect: egui::Rect::from_min_max(egui::pos2(r[0], r[1]), egui::pos2(r[2], r[3])),
+            columns: (r[4] as usize).max(1),
+        })
+        .collect()
+}
+
+pub fn save(pdf_path: &str, regions: &[TableRegion]) -> Result<(), Box<dyn std::error::Error>> {
+    let regions: Vec<[f32; 5]> = regions.iter()
+        .map(|r| [r.rect.min.x, r.rect.min.y, r.rect.max.x, r.rect.max.y, r.columns as f32])
+        .collect();
+    std::fs::write(sidecar_path(pdf_path), serde_json::to_string(&regions)?)?;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+// tabs.rs - Several documents open at once, one ChonkerApp per tab
+use crate::ChonkerApp;
+use eframe::egui;
+
+/// Tab bar over independent documents; only the active one is drawn
+pub struct DocumentTabs {
+    tabs: Vec<ChonkerApp>,
+    active: usize,
+    open_input: String, // Path typed into the "open" field
+    confirm_close: Option<String>, // Path of a tab with unsaved changes whose close button was pressed
+}
+
+impl DocumentTabs {
+    pub fn new(first: ChonkerApp) -> Self {
+        Self { tabs: vec![first], active: 0, open_input: String::new(), confirm_close: None }
+    }
+    
+    /// Load a PDF into a new tab and switch to it, or switch to it if it is already open
+    fn open(&mut self, path: &str) {
+        if let Some(existing) = self.tabs.iter().position(|tab| tab.pdf_path == path) {
+            self.active = existing;
+            return;
+        }
+        let mut app = ChonkerApp::default();
+        app.pdf_path = path.to_string();
+        match app.load_pdf() {
+            Ok(()) => {
+                self.tabs.push(app);
+                self.active = self.tabs.len() - 1;
+            }
+            Err(e) => eprintln!("Error opening {}: {}", path, e),
+        }
+    }
+    
+    fn title(tab: &ChonkerApp) -> String {
+        let name = std::path::Path::new(&tab.pdf_path)
+            .file_name()
+            .map_or(tab.pdf_path.clone(), |n| n.to_string_lossy().to_string());
+        if tab.modified { format!("{} •", name) } else { name }
+    }
+    
+    fn close(&mut self, i: usize) {
+        self.tabs.remove(i);
+        if self.active >= i && self.active > 0 {
+            self.active -= 1;
+        }
+    }
+    
+    /// Ask before a tab with unsaved changes is closed
+    fn render_close_confirm(&mut self, ctx: &egui::Context) {
+        // By path, since closing another tab meanwhile shifts the indices
+        let pending = self.confirm_close.as_ref().and_then(|path| self.tabs.iter().position(|tab| &tab.pdf_path == path));
+        let Some(i) = pending else {
+            self.confirm_close = None;
+            return;
+        };
+        let mut decision = None;
+        egui::Window::new("✖ Close tab")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{} has unsaved changes. Closing the tab discards them.", self.tabs[i].pdf_path));
+                ui.horizontal(|ui| {
+                    if ui.button("✖ Close, discard changes").clicked() {
+                        decision = Some(true);
+                    }
+                    if ui.button("✋ Keep the tab").clicked() {
+                        decision = Some(false);
+                    }
+                });
+            });
+        if let Some(discard) = decision {
+            self.confirm_close = None;
+            if discard {
+                self.close(i);
+            }
+        }
+    }
+    
+    fn render_tab_bar(&mut self, ctx: &egui::Context) {
+        let mut close = None;
+        let mut open = false;
+        egui::TopBottomPanel::top("document_tabs").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for (i, tab) in self.tabs.iter().enumerate() {
+                    ui.selectable_value(&mut self.active, i, Self::title(tab))
+                        .on_hover_text(&tab.pdf_path);
+                    if self.tabs.len() > 1 && ui.small_button("×").on_hover_text("Close tab").clicked() {
+                        close = Some(i);
+                    }
+                    ui.separator();
+                }
+                let response = ui.add(egui::TextEdit::singleline(&mut self.open_input)
+                    .hint_text("path/to/file.pdf")
+                    .desired_width(220.0));
+                open = ui.button("📂 Open").clicked()
+                    || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)));
+            });
+        });
+        
+        match close {
+            Some(i) if self.tabs[i].modified => self.confirm_close = Some(self.tabs[i].pdf_path.clone()),
+            Some(i) => self.close(i),
+            None => {}
+        }
+        self.render_close_confirm(ctx);
+        let path = self.open_input.trim().to_string();
+        if open && !path.is_empty() {
+            self.open(&path);
+            self.open_input.clear();
+        }
+    }
+}
+
+impl eframe::App for DocumentTabs {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // Ctrl+PageDown / Ctrl+PageUp cycle through tabs
+        let count = self.tabs.len();
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::PageDown)) {
+            self.active = (self.active + 1) % count;
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::PageUp)) {
+            self.active = (self.active + count - 1) % count;
+        }
+        
+        self.render_tab_bar(ctx);
+        eframe::App::update(&mut self.tabs[self.active], ctx, frame);
+    }
+}
@@ -0,0 +1,150 @@
+// tags.rs - Color tags for classifying elements, saved next to the PDF
+use eframe::egui;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::PathBuf;
+
+/// Something that can be attached to elements and saved by name
+pub trait TagKind: Copy + Eq + Hash {
+    const SIDECAR: &'static str; // Sidecar suffix after `.chonker-`
+    fn label(&self) -> &'static str;
+    fn from_label(label: &str) -> Option<Self>;
+}
+
+/// Palette of tags, like file labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorTag {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl TagKind for ColorTag {
+    const SIDECAR: &'static str = "tags";
+    
+    fn label(&self) -> &'static str {
+        ColorTag::label(self)
+    }
+    
+    fn from_label(label: &str) -> Option<Self> {
+        ColorTag::ALL.into_iter().find(|tag| tag.label() == label)
+    }
+}
+
+impl ColorTag {
+    pub const ALL: [ColorTag; 6] = [
+        ColorTag::Red, ColorTag::Orange, ColorTag::Yellow,
+        ColorTag::Green, ColorTag::Blue, ColorTag::Purple,
+    ];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColorTag::Red => "Red",
+            ColorTag::Orange => "Orange",
+            ColorTag::Yellow => "Yellow",
+            ColorTag::Green => "Green",
+            ColorTag::Blue => "Blue",
+            ColorTag::Purple => "Purple",
+        }
+    }
+    
+    pub fn color(&self) -> egui::Color32 {
+        match self {
+            ColorTag::Red => egui::Color32::from_rgb(230, 70, 70),
+            ColorTag::Orange => egui::Color32::from_rgb(240, 150, 50),
+            ColorTag::Yellow => egui::Color32::from_rgb(230, 210, 60),
+            ColorTag::Green => egui::Color32::from_rgb(80, 190, 90),
+            ColorTag::Blue => egui::Color32::from_rgb(70, 140, 235),
+            ColorTag::Purple => egui::Color32::from_rgb(170, 90, 220),
+        }
+    }
+}
+
+/// Tag per element (indexed like the buffer's element ranges; saved by extraction index)
+#[derive(Debug)]
+pub struct ElementTags<T = ColorTag> {
+    tags: HashMap<usize, T>,
+}
+
+impl<T> Default for ElementTags<T> {
+    fn default() -> Self {
+        Self { tags: HashMap::new() }
+    }
+}
+
+impl<T: TagKind> ElementTags<T> {
+    pub fn get(&self, element_idx: usize) -> Option<T> {
+        self.tags.get(&element_idx).copied()
+    }
+    
+    /// Set or clear (None) an element's tag
+    pub fn set(&mut self, element_idx: usize, tag: Option<T>) {
+        match tag {
+            Some(tag) => self.tags.insert(element_idx, tag),
+            None => self.tags.remove(&element_idx),
+        };
+    }
+    
+    /// Renumber after `count` elements were inserted at `at`
+    pub fn shift_for_insert(&mut self, at: usize, count: usize) {
+        self.tags = self.tags.drain()
+            .map(|(idx, tag)| (if idx >= at { idx + count } else { idx }, tag))
+            .collect();
+    }
+    
+    /// Renumber after `count` elements starting at `first` were removed
+    pub fn shift_for_removal(&mut self, first: usize, count: usize) {
+        self.tags = self.tags.drain()
+            .filter(|(idx, _)| *idx < first || *idx >= first + count)
+            .map(|(idx, tag)| (if idx >= first + count { idx - count } else { idx }, tag))
+            .collect();
+    }
+    
+    pub fn count(&self, tag: T) -> usize {
+        self.tags.values().filter(|&&t| t == tag).count()
+    }
+    
+    pub fn iter(&self) -> impl Iterator<Item = (usize, T)> + '_ {
+        self.tags.iter().map(|(&idx, &tag)| (idx, tag))
+    }
+    
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-{}", pdf_path, T::SIDECAR))
+    }
+    
+    /// Load tags saved for a PDF (`element<TAB>tag` per line)
+    pub fn load(pdf_path: &str) -> Self {
+        let mut tags = Self::default();
+        if let Ok(content) = std::fs::read_to_string(Self::sidecar_path(pdf_path)) {
+            for line in content.lines() {
+                if let Some((idx, label)) = line.split_once('\t') {
+                    if let (Ok(idx), Some(tag)) = (idx.parse(), T::from_label(label)) {
+                        tags.tags.insert(idx, tag);
+                    }
+                }
+            }
+        }
+        tags
+    }
+    
+    /// Save under each element's extraction index, which a fresh extraction numbers the same way,
+    /// so tags survive merges, splits and inserted boxes that are not saved with them.
+    /// `extracted_id` maps a buffer index to that index, None for elements a reload won't bring back
+    pub fn save(&self, pdf_path: &str, extracted_id: impl Fn(usize) -> Option<usize>) -> std::io::Result<()> {
+        let mut entries: Vec<(usize, T)> = self.iter()
+            .filter_map(|(idx, tag)| Some((extracted_id(idx)?, tag)))
+            .collect();
+        // Split halves share an id; keep the first half's tag
+        entries.sort_by_key(|&(id, _)| id);
+        entries.dedup_by_key(|&mut (id, _)| id);
+        let content: String = entries.iter()
+            .map(|(idx, tag)| format!("{}\t{}\n", idx, tag.label()))
+            .collect();
+        std::fs::write(Self::sidecar_path(pdf_path), content)
+    }
+}
@@ -0,0 +1,243 @@
+// theme.rs - Dark, light, accessible and custom color palettes for editor, overlays and XML panel
+use eframe::egui::{self, Color32};
+
+/// Selected preset and custom colors, one `key=value` per line (colors as `#rrggbb`)
+pub const THEME_FILE: &str = "chonker9_theme.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemePreset {
+    Dark,
+    Light,
+    HighContrast,
+    ColorBlind,   // Okabe-Ito colors, safe for protanopia/deuteranopia/tritanopia
+    Custom,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 5] = [
+        ThemePreset::Dark,
+        ThemePreset::Light,
+        ThemePreset::HighContrast,
+        ThemePreset::ColorBlind,
+        ThemePreset::Custom,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemePreset::Dark => "dark",
+            ThemePreset::Light => "light",
+            ThemePreset::HighContrast => "high-contrast",
+            ThemePreset::ColorBlind => "color-blind",
+            ThemePreset::Custom => "custom",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|preset| preset.label() == label)
+    }
+}
+
+/// Colors the canvas and panels paint with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub dark: bool,              // Base egui visuals for widgets
+    pub background: Color32,
+    pub text: Color32,
+    pub table_text: Color32,
+    pub cursor: Color32,
+    pub modified: Color32,
+    pub overflow: Color32,
+    pub selection: Color32,
+    pub xml_text: Color32,
+    pub shape_cues: bool,        // Underline/outline statuses so they don't rely on color alone
+}
+
+/// Semantic status that gets a shape cue alongside its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusCue {
+    Modified,   // Solid underline
+    Table,      // Dotted underline
+    Overflow,   // Dashed outline
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            dark: true,
+            background: Color32::from_rgb(27, 27, 27),
+            text: Color32::WHITE,
+            table_text: Color32::from_rgb(150, 255, 150),
+            cursor: Color32::from_rgb(40, 90, 200),
+            modified: Color32::from_rgb(255, 200, 100),
+            overflow: Color32::RED,
+            selection: Color32::from_rgb(255, 220, 80),
+            xml_text: Color32::from_rgb(200, 200, 200),
+            shape_cues: false,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            dark: false,
+            background: Color32::from_rgb(250, 250, 247),
+            text: Color32::from_rgb(20, 20, 20),
+            table_text: Color32::from_rgb(0, 120, 40),
+            cursor: Color32::from_rgb(200, 30, 30),
+            modified: Color32::from_rgb(190, 90, 0),
+            overflow: Color32::from_rgb(200, 0, 0),
+            selection: Color32::from_rgb(180, 130, 0),
+            xml_text: Color32::from_rgb(40, 40, 60),
+            shape_cues: false,
+        }
+    }
+
+    /// Pure black background, saturated statuses well above 7:1 contrast
+    pub fn high_contrast() -> Self {
+        Self {
+            dark: true,
+            background: Color32::BLACK,
+            text: Color32::WHITE,
+            table_text: Color32::from_rgb(0, 255, 255),
+            cursor: Color32::from_rgb(255, 255, 0),
+            modified: Color32::from_rgb(255, 255, 0),
+            overflow: Color32::from_rgb(255, 0, 255),
+            selection: Color32::from_rgb(0, 255, 255),
+            xml_text: Color32::WHITE,
+            shape_cues: true,
+        }
+    }
+
+    /// Okabe-Ito palette: blue/orange/vermillion instead of green/orange/red
+    pub fn color_blind() -> Self {
+        Self {
+            dark: true,
+            background: Color32::from_rgb(27, 27, 27),
+            text: Color32::WHITE,
+            table_text: Color32::from_rgb(86, 180, 233),
+            cursor: Color32::from_rgb(240, 228, 66),
+            modified: Color32::from_rgb(230, 159, 0),
+            overflow: Color32::from_rgb(213, 94, 0),
+            selection: Color32::from_rgb(204, 121, 167),
+            xml_text: Color32::from_rgb(200, 200, 200),
+            shape_cues: true,
+        }
+    }
+
+    /// Draw the shape cue for `status` under or around `rect` when cues are on
+    pub fn paint_cue(&self, painter: &egui::Painter, rect: egui::Rect, status: StatusCue) {
+        if !self.shape_cues {
+            return;
+        }
+        let (color, y) = match status {
+            StatusCue::Modified => (self.modified, rect.bottom() + 1.0),
+            StatusCue::Table => (self.table_text, rect.bottom() + 1.0),
+            StatusCue::Overflow => (self.overflow, rect.bottom()),
+        };
+        let underline = [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)];
+        match status {
+            StatusCue::Modified => {
+                painter.line_segment(underline, egui::Stroke::new(1.5, color));
+            }
+            StatusCue::Table => {
+                painter.extend(egui::Shape::dotted_line(&underline, color, 3.0, 0.8));
+            }
+            StatusCue::Overflow => {
+                let outline = [rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom(), rect.left_top()];
+                painter.extend(egui::Shape::dashed_line(&outline, egui::Stroke::new(1.0, color), 4.0, 3.0));
+            }
+        }
+    }
+
+    /// egui visuals matching the palette
+    pub fn visuals(&self) -> egui::Visuals {
+        let mut visuals = if self.dark { egui::Visuals::dark() } else { egui::Visuals::light() };
+        visuals.panel_fill = self.background;
+        visuals.extreme_bg_color = self.background;
+        visuals.text_cursor.stroke.color = self.cursor;
+        visuals
+    }
+
+    /// Named color slots, for the custom palette editor and the config file
+    pub fn slots_mut(&mut self) -> [(&'static str, &mut Color32); 8] {
+        [
+            ("background", &mut self.background),
+            ("text", &mut self.text),
+            ("table_text", &mut self.table_text),
+            ("cursor", &mut self.cursor),
+            ("modified", &mut self.modified),
+            ("overflow", &mut self.overflow),
+            ("selection", &mut self.selection),
+            ("xml_text", &mut self.xml_text),
+        ]
+    }
+}
+
+/// Preset in use plus the user's custom palette
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub custom: Theme,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self { preset: ThemePreset::Dark, custom: Theme::dark() }
+    }
+}
+
+impl ThemeConfig {
+    pub fn active(&self) -> Theme {
+        match self.preset {
+            ThemePreset::Dark => Theme::dark(),
+            ThemePreset::Light => Theme::light(),
+            ThemePreset::HighContrast => Theme::high_contrast(),
+            ThemePreset::ColorBlind => Theme::color_blind(),
+            ThemePreset::Custom => self.custom,
+        }
+    }
+
+    /// Config from THEME_FILE; `base=light` starts the custom palette from the light preset
+    pub fn load() -> Self {
+        let mut config = Self::default();
+        let content = std::fs::read_to_string(THEME_FILE).unwrap_or_default();
+        let lines: Vec<(&str, &str)> = content.lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect();
+        if lines.contains(&("base", "light")) {
+            config.custom = Theme::light();
+        }
+        for (key, value) in lines {
+            match key {
+                "preset" => config.preset = ThemePreset::from_label(value).unwrap_or(config.preset),
+                "base" => {}
+                "shape_cues" => config.custom.shape_cues = value == "true",
+                _ => {
+                    if let (Some((_, slot)), Some(color)) = (config.custom.slots_mut().into_iter().find(|(name, _)| *name == key), parse_hex(value)) {
+                        *slot = color;
+                    }
+                }
+            }
+        }
+        config
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut custom = self.custom;
+        let mut content = format!("preset={}\nbase={}\nshape_cues={}\n",
+                                  self.preset.label(), if custom.dark { "dark" } else { "light" }, custom.shape_cues);
+        for (name, color) in custom.slots_mut() {
+            content.push_str(&format!("{}=#{:02x}{:02x}{:02x}\n", name, color.r(), color.g(), color.b()));
+        }
+        std::fs::write(THEME_FILE, content)
+    }
+}
+
+fn parse_hex(value: &str) -> Option<Color32> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color32::from_rgb(channel(0)?, channel(2)?, channel(4)?))
+}
@@ -0,0 +1,54 @@
+// thumbnails.rs - Miniature page layouts for the page strip
+use crate::theme::Theme;
+use eframe::egui;
+
+/// One page's boxes in document coordinates, flagged when edited
+pub struct PageThumbnail {
+    pub page: usize,
+    pub boxes: Vec<(egui::Rect, bool)>,
+    pub quality: Option<f32>,  // Extraction quality score, once the page has been parsed
+}
+
+impl PageThumbnail {
+    pub fn modified_count(&self) -> usize {
+        self.boxes.iter().filter(|(_, modified)| *modified).count()
+    }
+}
+
+/// Draw a page as a sheet with its words as bars; edited words in the modified color
+pub fn paint(painter: &egui::Painter, rect: egui::Rect, page_size: egui::Vec2, thumbnail: &PageThumbnail,
+             theme: &Theme, current: bool) {
+    painter.rect_filled(rect, 2.0, theme.background);
+    let border = if current { theme.cursor } else { theme.text.gamma_multiply(0.3) };
+    painter.rect_stroke(rect, 2.0, egui::Stroke::new(if current { 2.0 } else { 1.0 }, border));
+
+    let scale = (rect.width() / page_size.x.max(1.0)).min(rect.height() / page_size.y.max(1.0));
+    for (bounds, modified) in &thumbnail.boxes {
+        let min = rect.min + bounds.min.to_vec2() * scale;
+        // Keep tiny words visible
+        let size = egui::vec2((bounds.width() * scale).max(1.0), (bounds.height() * scale * 0.6).max(1.0));
+        let color = if *modified { theme.modified } else { theme.text.gamma_multiply(0.5) };
+        painter.rect_filled(egui::Rect::from_min_size(min, size), 0.0, color);
+    }
+
+    let modified = thumbnail.modified_count();
+    if modified > 0 {
+        painter.text(rect.right_top() + egui::vec2(-4.0, 4.0), egui::Align2::RIGHT_TOP,
+                     format!("✎{}", modified), egui::FontId::proportional(11.0), theme.modified);
+    }
+
+    // Quality badge, red for pages that likely need another backend
+    if let Some(score) = thumbnail.quality {
+        let color = if score < 50.0 {
+            egui::Color32::from_rgb(220, 70, 60)
+        } else if score < 75.0 {
+            egui::Color32::from_rgb(230, 160, 40)
+        } else {
+            egui::Color32::from_rgb(70, 170, 90)
+        };
+        let galley = painter.layout_no_wrap(format!("Q{:.0}", score), egui::FontId::proportional(10.0), egui::Color32::WHITE);
+        let badge = egui::Rect::from_min_size(rect.left_bottom() + egui::vec2(4.0, -4.0 - galley.size().y), galley.size());
+        painter.rect_filled(badge.expand(2.0), 3.0, color);
+        painter.galley(badge.min, galley, egui::Color32::WHITE);
+    }
+}
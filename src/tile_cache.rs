@@ -0,0 +1,153 @@
+// tile_cache.rs - Rasterized page images cached per zoom level with an LRU budget
+use eframe::egui;
+use std::collections::HashMap;
+use std::process::Command;
+
+/// A page rendered at one quantized zoom level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileKey {
+    pub page: usize,
+    pub zoom_step: u32, // Zoom in quarter steps, so nearby zooms share a tile
+}
+
+impl TileKey {
+    pub fn new(page: usize, zoom: f32) -> Self {
+        Self { page, zoom_step: ((zoom * 4.0).round() as u32).max(1) }
+    }
+    
+    pub fn dpi(&self) -> f32 {
+        72.0 * self.zoom_step as f32 / 4.0
+    }
+}
+
+struct Tile {
+    texture: egui::TextureHandle,
+    page_size: egui::Vec2, // Page extent in PDF points
+    bytes: usize,
+    last_used: u64,
+}
+
+/// LRU cache of page textures bounded by a memory budget
+pub struct TileCache {
+    tiles: HashMap<TileKey, Tile>,
+    pub budget_mb: usize,
+    clock: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl TileCache {
+    pub fn new(budget_mb: usize) -> Self {
+        Self { tiles: HashMap::new(), budget_mb, clock: 0, hits: 0, misses: 0, evictions: 0 }
+    }
+    
+    pub fn used_bytes(&self) -> usize {
+        self.tiles.values().map(|t| t.bytes).sum()
+    }
+    
+    /// Texture and page size for a tile, rendering it on a miss
+    pub fn get_or_render(&mut self, ctx: &egui::Context, pdf_path: &str, key: TileKey)
+        -> Result<(egui::TextureHandle, egui::Vec2), Box<dyn std::error::Error>> {
+        self.clock += 1;
+        if let Some(tile) = self.tiles.get_mut(&key) {
+            tile.last_used = self.clock;
+            self.hits += 1;
+            return Ok((tile.texture.clone(), tile.page_size));
+        }
+        self.misses += 1;
+        
+        let image = render_page(pdf_path, key.page, key.dpi())?;
+        let page_size = egui::vec2(image.size[0] as f32, image.size[1] as f32) * 72.0 / key.dpi();
+        let bytes = image.size[0] * image.size[1] * 4;
+        let texture = ctx.load_texture(format!("page{}@{}", key.page, key.zoom_step), image, egui::TextureOptions::LINEAR);
+        self.tiles.insert(key, Tile { texture: texture.clone(), page_size, bytes, last_used: self.clock });
+        self.evict(Some(key));
+        Ok((texture, page_size))
+    }
+    
+    /// Drop least recently used tiles until under budget (never the one just added)
+    pub fn evict(&mut self, keep: Option<TileKey>) {
+        let budget = self.budget_mb * 1024 * 1024;
+        while self.used_bytes() > budget {
+            let oldest = self.tiles.iter()
+                .filter(|(key, _)| Some(**key) != keep)
+                .min_by_key(|(_, tile)| tile.last_used)
+                .map(|(key, _)| *key);
+            match oldest {
+                Some(key) => {
+                    self.tiles.remove(&key);
+                    self.evictions += 1;
+                }
+                None => break,
+            }
+        }
+    }
+    
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+    }
+    
+    pub fn stats(&self) -> String {
+        format!(
+            "tiles {} · {:.1}/{} MB · hits {} · misses {} · evicted {}",
+            self.tiles.len(),
+            self.used_bytes() as f32 / (1024.0 * 1024.0),
+            self.budget_mb,
+            self.hits,
+            self.misses,
+            self.evictions
+        )
+    }
+}
+
+/// Rasterize one page with pdftoppm (PPM output, so no image decoder is needed)
+pub fn render_page(pdf_path: &str, page: usize, dpi: f32) -> Result<egui::ColorImage, Box<dyn std::error::Error>> {
+    let page_arg = page.to_string();
+    let prefix = crate::temp_path(&format!("chonker9_tile_p{}", page));
+    let prefix_str = prefix.to_string_lossy().to_string();
+    let status = Command::new("pdftoppm")
+        .args(["-r", &format!("{:.0}", dpi), "-f", &page_arg, "-l", &page_arg, "-singlefile", pdf_path, &prefix_str])
+        .status()?;
+    if !status.success() {
+        return Err("pdftoppm failed".into());
+    }
+    let ppm_path = format!("{}.ppm", prefix_str);
+    let data = std::fs::read(&ppm_path);
+    let _ = std::fs::remove_file(&ppm_path);
+    parse_ppm(&data?)
+}
+
+/// Binary PPM (P6, 8-bit) to an egui image
+pub fn parse_ppm(data: &[u8]) -> Result<egui::ColorImage, Box<dyn std::error::Error>> {
+    // Header: magic, width, height, maxval separated by whitespace (comments skipped)
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while fields.len() < 4 {
+        while pos < data.len() && (data[pos].is_ascii_whitespace() || data[pos] == b'#') {
+            if data[pos] == b'#' {
+                while pos < data.len() && data[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            pos += 1;
+        }
+        let start = pos;
+        while pos < data.len() && !data[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if start == pos {
+            return Err("truncated PPM header".into());
+        }
+        fields.push(std::str::from_utf8(&data[start..pos])?.to_string());
+    }
+    pos += 1; // Single whitespace before pixel data
+    
+    if fields[0] != "P6" || fields[3] != "255" {
+        return Err("unsupported PPM format".into());
+    }
+    let width: usize = fields[1].parse()?;
+    let height: usize = fields[2].parse()?;
+    let pixels = data.get(pos..pos + width * height * 3).ok_or("truncated PPM data")?;
+    Ok(egui::ColorImage::from_rgb([width, height], pixels))
+}
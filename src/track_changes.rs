@@ -0,0 +1,57 @@
+// track_changes.rs - Tracked edits awaiting accept/reject, saved per page so a review survives a restart
+use crate::spatial_text::SpatialTextBuffer;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// An element's edited text and the text it replaced, under the element's extraction index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingEdit {
+    pub element: usize,
+    pub text: String,
+    pub pending_from: String,
+}
+
+fn sidecar_path(key: &str) -> PathBuf {
+    PathBuf::from(format!("{}.chonker-edits", key))
+}
+
+/// Pending edits saved for a page
+pub fn load(key: &str) -> Vec<PendingEdit> {
+    std::fs::read_to_string(sidecar_path(key))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Save the pending edits; the sidecar is removed once none are left
+pub fn save(key: &str, edits: &[PendingEdit]) -> Result<(), Box<dyn std::error::Error>> {
+    if edits.is_empty() {
+        return match std::fs::remove_file(sidecar_path(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        };
+    }
+    std::fs::write(sidecar_path(key), serde_json::to_string_pretty(edits)?)?;
+    Ok(())
+}
+
+/// Elements with a pending edit. `extracted_id` maps a buffer index to its extraction index,
+/// None for elements a reload won't bring back
+pub fn collect(buffer: &SpatialTextBuffer, extracted_id: impl Fn(usize) -> Option<usize>) -> Vec<PendingEdit> {
+    buffer.element_ranges.iter().enumerate()
+        .filter_map(|(idx, range)| Some(PendingEdit {
+            element: extracted_id(idx)?,
+            text: buffer.element_text(idx)?,
+            pending_from: range.pending_from.clone()?,
+        }))
+        .collect()
+}
+
+/// Re-apply saved edits to a freshly extracted page
+pub fn restore(buffer: &mut SpatialTextBuffer, edits: &[PendingEdit]) {
+    for edit in edits {
+        if let Some(idx) = buffer.element_ranges.iter().position(|range| range.element_id == edit.element) {
+            buffer.restore_pending(idx, &edit.text, &edit.pending_from);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// units.rs - ALTO measurement units, normalized to PDF points on load
+use quick_xml::events::BytesStart;
+
+/// Unit declared in ALTO Description/MeasurementUnit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeasurementUnit {
+    Pixel,      // Image pixels at the page DPI (pdfalto writes points as 72 dpi pixels)
+    Mm10,       // Tenths of a millimetre
+    Inch1200,   // 1/1200 inch
+}
+
+impl MeasurementUnit {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MeasurementUnit::Pixel => "pixel",
+            MeasurementUnit::Mm10 => "mm10",
+            MeasurementUnit::Inch1200 => "inch1200",
+        }
+    }
+
+    /// From a Description/MeasurementUnit value (or a `label()`); anything unknown is pixels
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "mm10" => MeasurementUnit::Mm10,
+            "inch1200" => MeasurementUnit::Inch1200,
+            _ => MeasurementUnit::Pixel,
+        }
+    }
+
+    /// Points per unit; pixels depend on the resolution
+    pub fn points_per_unit(&self, dpi: f32) -> f32 {
+        match self {
+            MeasurementUnit::Pixel => 72.0 / dpi,
+            MeasurementUnit::Mm10 => 72.0 / 254.0,
+            MeasurementUnit::Inch1200 => 72.0 / 1200.0,
+        }
+    }
+}
+
+/// Coordinate system of an ALTO file: declared unit and pixel resolution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceUnits {
+    pub unit: MeasurementUnit,
+    pub dpi: f32,
+}
+
+impl Default for SourceUnits {
+    fn default() -> Self {
+        Self { unit: MeasurementUnit::Pixel, dpi: DEFAULT_DPI }
+    }
+}
+
+impl SourceUnits {
+    pub fn points_per_unit(&self) -> f32 {
+        self.unit.points_per_unit(self.dpi)
+    }
+}
+
+/// Pixels are taken as points unless the file says otherwise
+pub const DEFAULT_DPI: f32 = 72.0;
+
+/// DPI of an ALTO Page when its producer writes one (ALTO has no standard attribute;
+/// RESOLUTION and DPI are both seen)
+pub fn page_dpi(page: &BytesStart) -> Option<f32> {
+    page.attributes().flatten()
+        .find(|attr| matches!(attr.key.as_ref(), b"RESOLUTION" | b"DPI"))
+        .and_then(|attr| String::from_utf8_lossy(&attr.value).parse::<f32>().ok())
+        .filter(|&dpi| dpi > 0.0)
+}
+
+/// Unit for showing coordinates in the inspector
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayUnit {
+    Points,
+    Millimeters,
+    Inches,
+    Pixels,   // At the source DPI
+}
+
+impl DisplayUnit {
+    pub const ALL: [DisplayUnit; 4] = [DisplayUnit::Points, DisplayUnit::Millimeters, DisplayUnit::Inches, DisplayUnit::Pixels];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayUnit::Points => "pt",
+            DisplayUnit::Millimeters => "mm",
+            DisplayUnit::Inches => "in",
+            DisplayUnit::Pixels => "px",
+        }
+    }
+
+    /// Display units per point
+    pub fn per_point(&self, dpi: f32) -> f32 {
+        match self {
+            DisplayUnit::Points => 1.0,
+            DisplayUnit::Millimeters => 25.4 / 72.0,
+            DisplayUnit::Inches => 1.0 / 72.0,
+            DisplayUnit::Pixels => dpi / 72.0,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+// watch.rs - Notice when the source PDF is rewritten on disk (e.g. re-scanned)
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the file's modification time is checked
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct FileWatch {
+    pub path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+fn modified_time(path: &PathBuf) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl FileWatch {
+    /// Start watching from the file's current state
+    pub fn new(path: &str) -> Self {
+        let path = PathBuf::from(path);
+        FileWatch { modified: modified_time(&path), path, last_check: Instant::now() }
+    }
+
+    /// Whether the file changed since the last poll; checks at most once per POLL_INTERVAL.
+    /// A file that disappears (mid-save) isn't a change until it's back
+    pub fn poll(&mut self) -> bool {
+        if self.last_check.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+        match modified_time(&self.path) {
+            Some(modified) if Some(modified) != self.modified => {
+                self.modified = Some(modified);
+                true
+            }
+            _ => false,
+        }
+    }
+}
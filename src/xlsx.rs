@@ -0,0 +1,139 @@
+// xlsx.rs - Tables as an Excel workbook, numeric cells typed as numbers
+use std::fmt::Write;
+
+/// One worksheet of rows
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<String>>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Value of a cell that reads as a number: `1,234.50`, `1.234,50`, `$12`, `(300)`, `45%`.
+/// Separators must group thousands as `numbers::value` checks them, so `555 1234` stays text,
+/// as do exponents (`1e5`), zero-padded codes like `00123` and out-of-range values
+pub fn parse_number(text: &str) -> Option<f64> {
+    // Zero-padded codes (ZIPs, account numbers) would lose their padding as numbers
+    let digits = text.trim_start_matches(|c: char| !c.is_ascii_digit());
+    if digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    crate::numbers::value(text).filter(|v| v.is_finite())
+}
+
+/// Column letters for a zero-based index (0 = A, 26 = AA)
+fn column_name(mut index: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.insert(0, (b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name
+}
+
+fn sheet_xml(sheet: &Sheet) -> String {
+    let mut rows = String::new();
+    for (r, row) in sheet.rows.iter().enumerate() {
+        let _ = write!(rows, "<row r=\"{}\">", r + 1);
+        for (c, cell) in row.iter().enumerate() {
+            let reference = format!("{}{}", column_name(c), r + 1);
+            match parse_number(cell) {
+                Some(value) => { let _ = write!(rows, "<c r=\"{}\"><v>{}</v></c>", reference, value); }
+                None => { let _ = write!(rows, "<c r=\"{}\" t=\"inlineStr\"><is><t>{}</t></is></c>", reference, escape(cell)); }
+            }
+        }
+        rows.push_str("</row>\n");
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>\n{}</sheetData></worksheet>\n",
+        rows
+    )
+}
+
+/// Sheet names must be unique, at most 31 characters and free of []:*?/\
+fn sheet_names(sheets: &[Sheet]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for sheet in sheets {
+        let base: String = sheet.name.chars()
+            .filter(|c| !"[]:*?/\\".contains(*c))
+            .take(28)
+            .collect();
+        let mut name = base.clone();
+        let mut n = 2;
+        while names.contains(&name) {
+            name = format!("{} {}", base, n);
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+pub fn write(path: &str, sheets: &[Sheet]) -> Result<(), Box<dyn std::error::Error>> {
+    if sheets.is_empty() {
+        return Err("no tables to export".into());
+    }
+    let names = sheet_names(sheets);
+    let mut content_types = String::new();
+    let mut workbook_sheets = String::new();
+    let mut relationships = String::new();
+    for (i, name) in names.iter().enumerate() {
+        let n = i + 1;
+        let _ = write!(content_types, "<Override PartName=\"/xl/worksheets/sheet{n}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>");
+        let _ = write!(workbook_sheets, "<sheet name=\"{}\" sheetId=\"{n}\" r:id=\"rId{n}\"/>", escape(name));
+        let _ = write!(relationships, "<Relationship Id=\"rId{n}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet{n}.xml\"/>");
+    }
+    
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("[Content_Types].xml".to_string(), format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"><Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/><Default Extension=\"xml\" ContentType=\"application/xml\"/><Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>{}</Types>\n",
+            content_types
+        ).into_bytes()),
+        ("_rels/.rels".to_string(), b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\"><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>\n".to_vec()),
+        ("xl/workbook.xml".to_string(), format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>{}</sheets></workbook>\n",
+            workbook_sheets
+        ).into_bytes()),
+        ("xl/_rels/workbook.xml.rels".to_string(), format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">{}</Relationships>\n",
+            relationships
+        ).into_bytes()),
+    ];
+    for (i, sheet) in sheets.iter().enumerate() {
+        files.push((format!("xl/worksheets/sheet{}.xml", i + 1), sheet_xml(sheet).into_bytes()));
+    }
+    std::fs::write(path, crate::zip::write_stored(&files))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_in_either_convention_are_numbers() {
+        assert_eq!(parse_number("1,234.50"), Some(1234.5));
+        assert_eq!(parse_number("1.234,50"), Some(1234.5));
+        assert_eq!(parse_number("12,34"), Some(12.34));
+        assert_eq!(parse_number("$12"), Some(12.0));
+        assert_eq!(parse_number("(300)"), Some(-300.0));
+        assert_eq!(parse_number("0.5"), Some(0.5));
+    }
+
+    #[test]
+    fn codes_and_loose_digits_stay_text() {
+        assert_eq!(parse_number("00123"), None);
+        assert_eq!(parse_number("555 1234"), None);
+        assert_eq!(parse_number("1e5"), None);
+        assert_eq!(parse_number("Total"), None);
+        assert_eq!(parse_number(""), None);
+    }
+}
@@ -0,0 +1,49 @@
+// zip.rs - Uncompressed zip archives for EPUB, XLSX and DOCX containers
+
+/// Zip archive without compression (EPUB's mimetype must be stored anyway)
+pub fn write_stored(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut zip = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let crc = crate::png::crc32(data);
+        let offset = zip.len() as u32;
+        let header = |signature: u32, central_entry: bool| {
+            let mut h = Vec::new();
+            h.extend_from_slice(&signature.to_le_bytes());
+            if central_entry {
+                h.extend_from_slice(&20u16.to_le_bytes()); // Version made by
+            }
+            h.extend_from_slice(&20u16.to_le_bytes()); // Version needed
+            h.extend_from_slice(&0u16.to_le_bytes());  // Flags
+            h.extend_from_slice(&0u16.to_le_bytes());  // Stored
+            h.extend_from_slice(&0u32.to_le_bytes());  // DOS time and date
+            h.extend_from_slice(&crc.to_le_bytes());
+            h.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            h.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            h.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            h.extend_from_slice(&0u16.to_le_bytes()); // Extra length
+            if central_entry {
+                h.extend_from_slice(&0u16.to_le_bytes()); // Comment length
+                h.extend_from_slice(&0u16.to_le_bytes()); // Disk number
+                h.extend_from_slice(&0u16.to_le_bytes()); // Internal attributes
+                h.extend_from_slice(&0u32.to_le_bytes()); // External attributes
+                h.extend_from_slice(&offset.to_le_bytes());
+            }
+            h.extend_from_slice(name.as_bytes());
+            h
+        };
+        zip.extend(header(0x0403_4b50, false));
+        zip.extend_from_slice(data);
+        central.extend(header(0x0201_4b50, true));
+    }
+    let central_offset = zip.len() as u32;
+    zip.extend_from_slice(&central);
+    zip.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    zip.extend_from_slice(&[0, 0, 0, 0]); // Disk numbers
+    zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    zip.extend_from_slice(&central_offset.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes()); // Comment length
+    zip
+}
@@ -0,0 +1,99 @@
+// zones.rs - Named rectangles on a recurring layout, saved as templates and applied to other PDFs
+use crate::{layout, SpatialElement};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Directory of saved zone templates (`name.json`)
+pub const ZONE_DIR: &str = "chonker9_zones";
+
+/// A named field area, in PDF points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    pub name: String,
+    pub page: usize,
+    pub rect: [f32; 4],  // x0, y0, x1, y1
+}
+
+impl Zone {
+    pub fn new(name: &str, page: usize, rect: egui::Rect) -> Self {
+        Zone { name: name.to_string(), page, rect: [rect.min.x, rect.min.y, rect.max.x, rect.max.y] }
+    }
+
+    pub fn rect(&self) -> egui::Rect {
+        egui::Rect::from_min_max(egui::pos2(self.rect[0], self.rect[1]), egui::pos2(self.rect[2], self.rect[3]))
+    }
+
+    /// Judged by the element's center, like table regions
+    pub fn contains(&self, element: &SpatialElement) -> bool {
+        element.page == self.page
+            && self.rect().contains(egui::pos2(element.hpos + element.width / 2.0, element.vpos + element.height / 2.0))
+    }
+
+    /// Enclosed words in reading order, lines joined with spaces
+    pub fn text(&self, elements: &[SpatialElement]) -> String {
+        let inside: Vec<&SpatialElement> = elements.iter().filter(|e| self.contains(e)).collect();
+        layout::group_into_lines(&inside).iter()
+            .map(|line| layout::line_text(line))
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+/// Unused "Zone N" name for a new zone
+pub fn next_name(zones: &[Zone]) -> String {
+    (1..).map(|n| format!("Zone {}", n)).find(|name| !zones.iter().any(|z| &z.name == name)).unwrap_or_default()
+}
+
+/// Drop the zone under a document point, returning whether one was removed
+pub fn remove_at(zones: &mut Vec<Zone>, page: usize, pos: egui::Pos2) -> bool {
+    let before = zones.len();
+    zones.retain(|z| z.page != page || !z.rect().contains(pos));
+    zones.len() != before
+}
+
+/// `[{ "zone": "name", "text": "..." }, ...]` in zone order, keeping zones that share a name
+pub fn extract_json(zones: &[Zone], elements: &[SpatialElement]) -> Result<String, serde_json::Error> {
+    let fields: Vec<serde_json::Value> = zones.iter()
+        .map(|zone| serde_json::json!({ "zone": zone.name, "text": zone.text(elements) }))
+        .collect();
+    serde_json::to_string_pretty(&fields)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneTemplate {
+    pub name: String,
+    pub zones: Vec<Zone>,
+}
+
+impl ZoneTemplate {
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from(ZONE_DIR).join(format!("{}.json", layout::slugify(name)))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(ZONE_DIR)?;
+        std::fs::write(Self::path(&self.name), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(Self::path(name))?)?)
+    }
+
+    /// Names of the saved templates, sorted
+    pub fn list() -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(ZONE_DIR)
+            .map(|entries| {
+                entries.filter_map(|entry| entry.ok())
+                    .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+                    .filter_map(|json| serde_json::from_str::<ZoneTemplate>(&json).ok())
+                    .map(|template| template.name)
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+}
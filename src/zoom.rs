@@ -0,0 +1,70 @@
+// zoom.rs - Zoom presets, fit modes and per-document zoom memory
+use eframe::egui;
+use std::path::PathBuf;
+
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 4.0;
+const STEP: f32 = 1.25;
+
+/// How the canvas zoom is chosen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZoomMode {
+    Actual,        // 100%
+    FitWidth,
+    FitPage,
+    Custom(f32),
+}
+
+impl ZoomMode {
+    pub fn label(&self) -> String {
+        match self {
+            ZoomMode::Actual => "100%".to_string(),
+            ZoomMode::FitWidth => "Fit Width".to_string(),
+            ZoomMode::FitPage => "Fit Page".to_string(),
+            ZoomMode::Custom(zoom) => format!("{:.0}%", zoom * 100.0),
+        }
+    }
+
+    /// Zoom factor for a page of `page_size` document points in a `viewport` of screen pixels
+    pub fn resolve(&self, page_size: egui::Vec2, viewport: egui::Vec2) -> f32 {
+        let zoom = match self {
+            ZoomMode::Actual => 1.0,
+            ZoomMode::FitWidth => viewport.x / page_size.x.max(1.0),
+            ZoomMode::FitPage => (viewport.x / page_size.x.max(1.0)).min(viewport.y / page_size.y.max(1.0)),
+            ZoomMode::Custom(zoom) => *zoom,
+        };
+        zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+    }
+
+    /// Step one preset up (or down) from the zoom currently on screen
+    pub fn step(current: f32, zoom_in: bool) -> ZoomMode {
+        let zoom = if zoom_in { current * STEP } else { current / STEP };
+        ZoomMode::Custom(zoom.clamp(MIN_ZOOM, MAX_ZOOM))
+    }
+
+    fn sidecar_path(pdf_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.chonker-view", pdf_path))
+    }
+
+    /// Zoom remembered for a PDF (`actual`, `fit-width`, `fit-page` or a factor)
+    pub fn load(pdf_path: &str) -> ZoomMode {
+        let content = std::fs::read_to_string(Self::sidecar_path(pdf_path)).unwrap_or_default();
+        match content.trim() {
+            "fit-width" => ZoomMode::FitWidth,
+            "fit-page" => ZoomMode::FitPage,
+            value => value.parse::<f32>()
+                .map(|zoom| ZoomMode::Custom(zoom.clamp(MIN_ZOOM, MAX_ZOOM)))
+                .unwrap_or(ZoomMode::Actual),
+        }
+    }
+
+    pub fn save(&self, pdf_path: &str) -> std::io::Result<()> {
+        let content = match self {
+            ZoomMode::Actual => "actual".to_string(),
+            ZoomMode::FitWidth => "fit-width".to_string(),
+            ZoomMode::FitPage => "fit-page".to_string(),
+            ZoomMode::Custom(zoom) => zoom.to_string(),
+        };
+        std::fs::write(Self::sidecar_path(pdf_path), content)
+    }
+}